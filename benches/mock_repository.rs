@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dapp::application::{NotarizeConfig, NotarizeUseCase, VerifyUseCase};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+#[path = "../tests/common/mocks.rs"]
+mod mocks;
+
+use mocks::MockDocumentRepository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+/// One use case test: a fresh repository, a notarization and a verification
+fn notarize_and_verify(repo: &impl DocumentRepository) {
+    let receipt = NotarizeUseCase::new(repo, NotarizeConfig::default())
+        .execute(
+            b"benchmark document",
+            "bench.txt",
+            "text/plain",
+            SUBMITTER,
+            1,
+        )
+        .unwrap();
+    black_box(
+        VerifyUseCase::new(repo)
+            .execute(&receipt.content_hash)
+            .unwrap(),
+    );
+}
+
+fn bench_usecase_test_repository(c: &mut Criterion) {
+    let mut group = c.benchmark_group("usecase_test_repository");
+
+    group.bench_function("sqlite_in_memory", |b| {
+        b.iter(|| notarize_and_verify(&SqliteRepository::new_in_memory().unwrap()))
+    });
+
+    group.bench_function("mock", |b| {
+        b.iter(|| notarize_and_verify(&MockDocumentRepository::new()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_usecase_test_repository);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use dapp::application::{NotarizeConfig, NotarizeUseCase, VerifyUseCase};
+use dapp::domain::Document;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+const INSERTS: usize = 1000;
+
+/// Content sizes notarized per iteration, with the name of their benchmark
+const CONTENT_SIZES: [(&str, usize); 3] =
+    [("1KB", 1024), ("100KB", 100 * 1024), ("1MB", 1024 * 1024)];
+
+fn bench_notarize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("notarize");
+
+    for (name, size) in CONTENT_SIZES {
+        let content = vec![0x5a; size];
+        group.throughput(Throughput::Bytes(size as u64));
+
+        // A fresh repository per iteration, so the same content is never a duplicate
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || SqliteRepository::new_in_memory().unwrap(),
+                |repo| {
+                    let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+                    black_box(
+                        usecase
+                            .execute(&content, "bench.txt", "text/plain", SUBMITTER, 1)
+                            .unwrap(),
+                    );
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let doc = Document::new(b"benchmark document", "bench.txt", "text/plain", SUBMITTER);
+    repo.save_document(&doc).unwrap();
+    let usecase = VerifyUseCase::new(&repo);
+    let missing = "0".repeat(64);
+
+    let mut group = c.benchmark_group("verify");
+    group.bench_function("hit", |b| {
+        b.iter(|| {
+            black_box(
+                usecase
+                    .execute(black_box(doc.content_hash.as_str()))
+                    .unwrap(),
+            )
+        })
+    });
+    group.bench_function("miss", |b| {
+        b.iter(|| black_box(usecase.execute(black_box(&missing)).unwrap()))
+    });
+    group.finish();
+}
+
+fn bench_save_document(c: &mut Criterion) {
+    c.bench_function("save_document_1000_sequential", |b| {
+        b.iter_batched(
+            || {
+                let docs: Vec<Document> = (0..INSERTS)
+                    .map(|i| {
+                        Document::new(
+                            format!("document {}", i).as_bytes(),
+                            "bench.txt",
+                            "text/plain",
+                            SUBMITTER,
+                        )
+                    })
+                    .collect();
+                (SqliteRepository::new_in_memory().unwrap(), docs)
+            },
+            |(repo, docs)| {
+                for doc in &docs {
+                    repo.save_document(doc).unwrap();
+                }
+            },
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+criterion_group!(benches, bench_notarize, bench_verify, bench_save_document);
+criterion_main!(benches);
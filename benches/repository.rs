@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use dapp::domain::Document;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const LOOKUPS: usize = 1000;
+const INSERTS: usize = 100;
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+fn bench_find_by_hash(c: &mut Criterion) {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let doc = Document::new(b"benchmark document", "bench.txt", "text/plain", SUBMITTER);
+    repo.save_document(&doc).unwrap();
+
+    c.bench_function("find_by_hash_1000_lookups", |b| {
+        b.iter(|| {
+            for _ in 0..LOOKUPS {
+                black_box(repo.find_by_hash(black_box(&doc.content_hash)).unwrap());
+            }
+        })
+    });
+}
+
+fn bench_save_document(c: &mut Criterion) {
+    c.bench_function("save_document_100_inserts", |b| {
+        b.iter_batched(
+            || {
+                let docs: Vec<Document> = (0..INSERTS)
+                    .map(|i| {
+                        Document::new(
+                            format!("document {}", i).as_bytes(),
+                            "bench.txt",
+                            "text/plain",
+                            SUBMITTER,
+                        )
+                    })
+                    .collect();
+                (SqliteRepository::new_in_memory().unwrap(), docs)
+            },
+            |(repo, docs)| {
+                for doc in &docs {
+                    repo.save_document(doc).unwrap();
+                }
+            },
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+criterion_group!(benches, bench_find_by_hash, bench_save_document);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dapp::application::{LruVerifyCache, NotarizeConfig, NotarizeUseCase, VerifyUseCase};
+use dapp::infrastructure::database::SqliteRepository;
+
+const LOOKUPS: usize = 1000;
+
+fn bench_repeated_verification(c: &mut Criterion) {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let receipt = NotarizeUseCase::new(&repo, NotarizeConfig::default())
+        .execute(
+            b"benchmark document",
+            "bench.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            1,
+        )
+        .unwrap();
+    let hash = receipt.content_hash;
+
+    let mut group = c.benchmark_group("verify_1000_repeated_lookups");
+
+    group.bench_function("uncached", |b| {
+        let usecase = VerifyUseCase::new(&repo);
+        b.iter(|| {
+            for _ in 0..LOOKUPS {
+                black_box(usecase.execute(black_box(&hash)).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("lru_cached", |b| {
+        let cache = LruVerifyCache::new(VerifyUseCase::new(&repo), 128);
+        b.iter(|| {
+            for _ in 0..LOOKUPS {
+                black_box(cache.execute(black_box(&hash)).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_repeated_verification);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+#![no_main]
+
+use dapp::handlers::parse_advance_payload;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // As the rollup server would deliver it, so hex, UTF-8 and JSON decoding are all exercised
+    let request = json::object! {
+        "request_type" => "advance_state",
+        "data" => json::object! {
+            "payload" => hex::encode(data),
+            "metadata" => json::object! {
+                "msg_sender" => "0x0000000000000000000000000000000000000123",
+                "block_number" => 1,
+                "timestamp" => 0,
+            }
+        }
+    };
+    if let Some(payload_hex) = request["data"]["payload"].as_str() {
+        let _ = parse_advance_payload(payload_hex);
+    }
+
+    // The raw input as the hex string itself, for malformed hex
+    if let Ok(payload_hex) = std::str::from_utf8(data) {
+        let _ = parse_advance_payload(payload_hex);
+    }
+});
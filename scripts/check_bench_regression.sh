@@ -0,0 +1,61 @@
+#!/bin/bash
+#
+# Compare criterion results against benches/baseline.json
+#
+# Each baseline entry maps a benchmark id to its mean time in nanoseconds.
+# Fails when any benchmark is more than MAX_REGRESSION_PERCENT slower than
+# its baseline. Entries with a null baseline are reported but not checked.
+#
+# Usage:
+#   cargo bench --bench notarize_benchmark
+#   scripts/check_bench_regression.sh            # check against the baseline
+#   scripts/check_bench_regression.sh --update   # record current means as the baseline
+#
+# Requires jq.
+#
+
+set -euo pipefail
+
+BASELINE="${BASELINE:-benches/baseline.json}"
+CRITERION_DIR="${CRITERION_DIR:-target/criterion}"
+MAX_REGRESSION_PERCENT="${MAX_REGRESSION_PERCENT:-20}"
+
+mean_of() {
+    local estimates="$CRITERION_DIR/$1/new/estimates.json"
+    if [ ! -f "$estimates" ]; then
+        echo "No results for $1 at $estimates; run cargo bench first" >&2
+        exit 1
+    fi
+    jq '.mean.point_estimate' "$estimates"
+}
+
+if [ "${1:-}" = "--update" ]; then
+    updated="{}"
+    for id in $(jq -r 'keys[]' "$BASELINE"); do
+        updated=$(jq --arg id "$id" --argjson mean "$(mean_of "$id")" '. + {($id): $mean}' <<<"$updated")
+    done
+    jq '.' <<<"$updated" > "$BASELINE"
+    echo "Updated $BASELINE"
+    exit 0
+fi
+
+failed=0
+for id in $(jq -r 'keys[]' "$BASELINE"); do
+    current=$(mean_of "$id")
+    baseline=$(jq --arg id "$id" '.[$id]' "$BASELINE")
+
+    if [ "$baseline" = "null" ]; then
+        printf '%-32s %14.0f ns  (no baseline)\n' "$id" "$current"
+        continue
+    fi
+
+    change=$(jq -n --argjson c "$current" --argjson b "$baseline" '($c - $b) / $b * 100')
+    printf '%-32s %14.0f ns  %+7.1f%%\n' "$id" "$current" "$change"
+
+    if jq -en --argjson change "$change" --argjson max "$MAX_REGRESSION_PERCENT" '$change > $max' > /dev/null; then
+        echo "  regressed by more than ${MAX_REGRESSION_PERCENT}%" >&2
+        failed=1
+    fi
+done
+
+exit "$failed"
@@ -0,0 +1,136 @@
+use super::search::{DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT};
+use crate::domain::{is_valid_address, AuditEntry};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("Invalid submitter address: {0}")]
+    InvalidSubmitter(String),
+
+    #[error("Invalid block range: from_block {from} is after to_block {to}")]
+    InvalidBlockRange { from: u64, to: u64 },
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// One page of the audit log, oldest first
+#[derive(Debug)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// Id of the last entry, to pass as the cursor of the following page; `None` on the last page
+    pub next_cursor: Option<i64>,
+}
+
+/// Entries of the audit log addressed by offset, oldest first
+#[derive(Debug)]
+pub struct AuditListing {
+    pub entries: Vec<AuditEntry>,
+    /// Number of entries skipped before the first one
+    pub offset: usize,
+    /// Whether entries follow this page
+    pub has_more: bool,
+}
+
+/// Filters for paging through the audit log
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery<'q> {
+    pub submitter: Option<&'q str>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    /// Only entries with a larger id are returned
+    pub after_id: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+pub struct AuditUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> AuditUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Append the outcome of an advance input to the log, assigning its id
+    /// `submitter` is stored in lowercase, as other addresses are
+    pub fn record(&self, mut entry: AuditEntry) -> Result<AuditEntry, DatabaseError> {
+        entry.submitter = entry.submitter.to_lowercase();
+        entry.id = self.repository.append_audit_entry(&entry)?;
+        Ok(entry)
+    }
+
+    /// Entries matching `query`, oldest first
+    /// `limit` defaults to `DEFAULT_SEARCH_LIMIT` and is capped at `MAX_SEARCH_LIMIT`
+    pub fn page(&self, query: &AuditQuery) -> Result<AuditPage, Box<dyn Error>> {
+        if let Some(submitter) = query.submitter {
+            if !is_valid_address(submitter) {
+                return Err(Box::new(AuditError::InvalidSubmitter(
+                    submitter.to_string(),
+                )));
+            }
+        }
+
+        if let (Some(from), Some(to)) = (query.from_block, query.to_block) {
+            if from > to {
+                return Err(Box::new(AuditError::InvalidBlockRange { from, to }));
+            }
+        }
+
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .min(MAX_SEARCH_LIMIT);
+
+        // One extra entry tells whether another page follows
+        let mut entries = self
+            .repository
+            .find_audit_entries(
+                query.submitter,
+                query.from_block,
+                query.to_block,
+                query.after_id,
+                limit + 1,
+            )
+            .map_err(|e| Box::new(AuditError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        let next_cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|entry| entry.id)
+        } else {
+            None
+        };
+
+        Ok(AuditPage {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Page of the whole log, oldest first, skipping the first `offset` entries
+    /// `limit` defaults to `DEFAULT_SEARCH_LIMIT` and is capped at `MAX_SEARCH_LIMIT`
+    pub fn list(
+        &self,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<AuditListing, Box<dyn Error>> {
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+
+        // One extra entry tells whether another page follows
+        let mut entries = self
+            .repository
+            .list_audit_entries(limit + 1, offset)
+            .map_err(|e| Box::new(AuditError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        let has_more = entries.len() > limit;
+        entries.truncate(limit);
+
+        Ok(AuditListing {
+            entries,
+            offset,
+            has_more,
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use crate::domain::Deposit;
+use crate::infrastructure::database::DocumentRepository;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BalanceError {
+    #[error("Insufficient balance: fee is {required} of token {token}, balance is {available}")]
+    Insufficient {
+        token: String,
+        required: u128,
+        available: u128,
+    },
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Credits portal deposits and charges notarization fees against them
+pub struct BalanceUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> BalanceUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Credit a deposit to its depositor and return the new balance
+    pub fn deposit(&self, deposit: &Deposit) -> Result<u128, BalanceError> {
+        self.repository
+            .credit_balance(
+                deposit.depositor.as_str(),
+                deposit.token.as_str(),
+                deposit.amount,
+            )
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))
+    }
+
+    /// Check that `owner` can pay `amount` of `token` without charging it yet
+    pub fn ensure_funds(&self, owner: &str, token: &str, amount: u128) -> Result<(), BalanceError> {
+        let available = self
+            .repository
+            .find_balance(owner, token)
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+
+        if available < amount {
+            return Err(BalanceError::Insufficient {
+                token: token.to_lowercase(),
+                required: amount,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Take `amount` of `token` from `owner`, failing if the balance does not cover it
+    pub fn charge(&self, owner: &str, token: &str, amount: u128) -> Result<(), BalanceError> {
+        let charged = self
+            .repository
+            .debit_balance(owner, token, amount)
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+
+        if !charged {
+            let available = self
+                .repository
+                .find_balance(owner, token)
+                .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+            return Err(BalanceError::Insufficient {
+                token: token.to_lowercase(),
+                required: amount,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use super::notarize::{
+    validate_filename, NotarizeConfig, NotarizeError, NotarizeOptions, NotarizeUseCase,
+};
+use crate::domain::{NotarizationReceipt, UploadSession};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ChunkedUploadError {
+    #[error("Empty upload_id not allowed")]
+    EmptyUploadId,
+
+    #[error("Upload {0} already exists")]
+    UploadExists(String),
+
+    #[error("Upload {0} not found")]
+    UploadNotFound(String),
+
+    #[error("Only the submitter who started an upload can continue it")]
+    Unauthorized,
+
+    #[error("Empty chunk not allowed")]
+    EmptyChunk,
+
+    #[error("Chunk {0} was already received")]
+    DuplicateChunk(u32),
+
+    #[error("Expected chunk {expected}, got {got}")]
+    ChunkOutOfOrder { expected: u32, got: u32 },
+
+    #[error("Upload has no chunks")]
+    NoChunks,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Assembles a document from several inputs, then notarizes it like a single-shot upload
+pub struct ChunkedUploadUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    config: NotarizeConfig,
+}
+
+impl<'a> ChunkedUploadUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository, config: NotarizeConfig) -> Self {
+        Self { repository, config }
+    }
+
+    /// Open an upload; filename and MIME type are checked now rather than on finish
+    /// `started_at` is the block timestamp of the input opening it
+    pub fn start(
+        &self,
+        upload_id: &str,
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        started_at: i64,
+    ) -> Result<UploadSession, Box<dyn Error>> {
+        if upload_id.trim().is_empty() {
+            return Err(Box::new(ChunkedUploadError::EmptyUploadId));
+        }
+
+        validate_filename(file_name)?;
+
+        if !self.config.is_mime_allowed(mime_type) {
+            return Err(Box::new(NotarizeError::DisallowedMimeType(
+                mime_type.to_string(),
+            )));
+        }
+
+        let session = UploadSession {
+            upload_id: upload_id.to_string(),
+            submitted_by: submitted_by.to_string(),
+            file_name: file_name.to_string(),
+            mime_type: mime_type.to_string(),
+            started_at,
+            chunk_count: 0,
+            total_bytes: 0,
+        };
+
+        self.repository.start_upload(&session).map_err(|e| {
+            if matches!(e, DatabaseError::DuplicateUpload) {
+                Box::new(ChunkedUploadError::UploadExists(upload_id.to_string())) as Box<dyn Error>
+            } else {
+                Box::new(ChunkedUploadError::DatabaseError(e.to_string()))
+            }
+        })?;
+
+        Ok(session)
+    }
+
+    /// Stage the next chunk; indexes start at 0 and must arrive without gaps or repeats
+    pub fn append(
+        &self,
+        upload_id: &str,
+        index: u32,
+        chunk: &[u8],
+        submitted_by: &str,
+    ) -> Result<UploadSession, Box<dyn Error>> {
+        let mut session = self.find_own_upload(upload_id, submitted_by)?;
+
+        if index < session.chunk_count {
+            return Err(Box::new(ChunkedUploadError::DuplicateChunk(index)));
+        }
+        if index > session.chunk_count {
+            return Err(Box::new(ChunkedUploadError::ChunkOutOfOrder {
+                expected: session.chunk_count,
+                got: index,
+            }));
+        }
+
+        if chunk.is_empty() {
+            return Err(Box::new(ChunkedUploadError::EmptyChunk));
+        }
+
+        // The assembled document is subject to the same limit as a single-shot upload
+        let total_bytes = session.total_bytes + chunk.len() as u64;
+        self.config
+            .check_content_size(usize::try_from(total_bytes).unwrap_or(usize::MAX))?;
+
+        self.repository
+            .append_chunk(upload_id, index, chunk)
+            .map_err(|e| {
+                Box::new(ChunkedUploadError::DatabaseError(e.to_string())) as Box<dyn Error>
+            })?;
+
+        session.chunk_count += 1;
+        session.total_bytes = total_bytes;
+        Ok(session)
+    }
+
+    /// Notarize the concatenated chunks exactly like a single-shot notarization
+    /// The upload is consumed whether or not notarization succeeds
+    pub fn finish(
+        &self,
+        upload_id: &str,
+        submitted_by: &str,
+        block_number: u64,
+        options: &NotarizeOptions,
+    ) -> Result<NotarizationReceipt, Box<dyn Error>> {
+        let session = self.find_own_upload(upload_id, submitted_by)?;
+
+        if session.chunk_count == 0 {
+            return Err(Box::new(ChunkedUploadError::NoChunks));
+        }
+
+        let content = self.repository.upload_content(upload_id).map_err(|e| {
+            Box::new(ChunkedUploadError::DatabaseError(e.to_string())) as Box<dyn Error>
+        })?;
+
+        self.repository.delete_upload(upload_id).map_err(|e| {
+            Box::new(ChunkedUploadError::DatabaseError(e.to_string())) as Box<dyn Error>
+        })?;
+
+        NotarizeUseCase::new(self.repository, self.config.clone())
+            .execute_with_options(
+                &content,
+                &session.file_name,
+                &session.mime_type,
+                &session.submitted_by,
+                block_number,
+                options,
+            )
+            .map_err(Into::into)
+    }
+
+    fn find_own_upload(
+        &self,
+        upload_id: &str,
+        submitted_by: &str,
+    ) -> Result<UploadSession, Box<dyn Error>> {
+        let session = self
+            .repository
+            .find_upload(upload_id)
+            .map_err(|e| {
+                Box::new(ChunkedUploadError::DatabaseError(e.to_string())) as Box<dyn Error>
+            })?
+            .ok_or_else(|| ChunkedUploadError::UploadNotFound(upload_id.to_string()))?;
+
+        if !session.submitted_by.eq_ignore_ascii_case(submitted_by) {
+            return Err(Box::new(ChunkedUploadError::Unauthorized));
+        }
+
+        Ok(session)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::domain::{Commitment, ContentHash};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CommitError {
+    #[error("Invalid hash format: must be 64 hexadecimal characters")]
+    InvalidHashFormat,
+
+    #[error("Hash {content_hash} was already committed at block {block_number}")]
+    AlreadyCommitted {
+        content_hash: String,
+        block_number: u64,
+    },
+
+    #[error("Document with this content hash is already notarized")]
+    AlreadyNotarized,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Records a document's SHA-256 hash before the document itself is revealed
+/// Notarizing the content later attaches the commitment block to its receipt
+pub struct CommitUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> CommitUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Commit `content_hash` at `block_number`; only the first commitment to a hash counts
+    pub fn execute(
+        &self,
+        content_hash: &str,
+        committed_by: &str,
+        block_number: u64,
+        timestamp: i64,
+    ) -> Result<Commitment, Box<dyn Error>> {
+        if content_hash.len() != 64 || !content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Box::new(CommitError::InvalidHashFormat));
+        }
+
+        // Stored SHA-256 hashes are lowercase hex
+        let content_hash = ContentHash::from(content_hash.to_lowercase());
+
+        // A commitment only means something while the content is still unrevealed
+        match self.repository.find_by_hash(&content_hash) {
+            Ok(_) => return Err(Box::new(CommitError::AlreadyNotarized)),
+            Err(DatabaseError::NotFound) => {}
+            Err(e) => return Err(database_error(e)),
+        }
+
+        if let Some(existing) = self
+            .repository
+            .find_commitment(&content_hash)
+            .map_err(database_error)?
+        {
+            return Err(Box::new(CommitError::AlreadyCommitted {
+                content_hash: existing.content_hash,
+                block_number: existing.block_number,
+            }));
+        }
+
+        let commitment = Commitment {
+            content_hash: content_hash.into(),
+            committed_by: committed_by.to_string(),
+            block_number,
+            timestamp,
+        };
+        self.repository
+            .save_commitment(&commitment)
+            .map_err(database_error)?;
+
+        Ok(commitment)
+    }
+}
+
+fn database_error(e: DatabaseError) -> Box<dyn Error> {
+    Box::new(CommitError::DatabaseError(e.to_string()))
+}
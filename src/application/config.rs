@@ -0,0 +1,770 @@
+use super::cosign::DEFAULT_COSIGN_WINDOW_SECS;
+use super::notarize::{AllowDenyList, AllowDenyMode, DEFAULT_MAX_CONTENT_BYTES};
+use super::rate_limit::RateLimiter;
+use crate::domain::{Deposit, DepositError, EthAddress, ETHER_TOKEN};
+use crate::infrastructure::database::{
+    SqliteOptions, DEFAULT_BUSY_TIMEOUT_MS, DEFAULT_CACHE_SIZE_KIB, DEFAULT_MMAP_SIZE_BYTES,
+};
+use crate::infrastructure::signing::NoticeSigningKey;
+use thiserror::Error;
+
+/// Default persistent database location inside the Cartesi machine
+pub const DEFAULT_DB_PATH: &str = "/var/lib/notary/notary.db";
+
+/// Default Cartesi rollup HTTP server endpoint
+pub const DEFAULT_ROLLUP_SERVER_URL: &str = "http://127.0.0.1:5004";
+
+/// Default DID named as the issuer of verifiable credentials
+pub const DEFAULT_ISSUER_DID: &str = "did:web:notary.local";
+
+const DEFAULT_MAX_PAYLOAD_KB: usize = 2048;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("{name} must be a positive integer, got \"{value}\"")]
+    InvalidNumber { name: String, value: String },
+
+    #[error("{name} must be true or false, got \"{value}\"")]
+    InvalidBool { name: String, value: String },
+
+    #[error("{name} is not a valid Ethereum address: \"{value}\"")]
+    InvalidAddress { name: String, value: String },
+
+    #[error("{0} must be greater than zero")]
+    ZeroValue(String),
+
+    #[error("{0} cannot be empty")]
+    EmptyValue(String),
+
+    #[error("{name} is required when {dependent} is set")]
+    MissingValue { name: String, dependent: String },
+
+    #[error("{0} and {1} cannot both be set")]
+    Conflicting(String, String),
+}
+
+/// Storage backend holding the documents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryKind {
+    /// SQLite database file path (NOTARY_DB_PATH)
+    Sqlite(String),
+    /// PostgreSQL connection string, as a URL or `key=value` settings (NOTARY_DATABASE_URL)
+    Postgres(String),
+}
+
+/// Application configuration, loaded once at startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Document storage; SQLite at NOTARY_DB_PATH unless NOTARY_DATABASE_URL names a PostgreSQL server
+    pub repository: RepositoryKind,
+    /// How long a database write waits for a lock, in milliseconds (NOTARY_DB_BUSY_TIMEOUT_MS)
+    pub db_busy_timeout_ms: u64,
+    /// SQLite page cache per connection, in KiB (NOTARY_DB_CACHE_SIZE_KIB)
+    pub db_cache_size_kib: u64,
+    /// Bytes of the SQLite file read through memory-mapped I/O; 0 disables it (NOTARY_DB_MMAP_SIZE_BYTES)
+    pub db_mmap_size_bytes: u64,
+    /// Cartesi rollup HTTP server endpoint (ROLLUP_HTTP_SERVER_URL)
+    pub rollup_server_url: String,
+    /// Path prefix for rollup endpoints, e.g. `/rollup` (ROLLUP_PATH_PREFIX)
+    pub rollup_path_prefix: String,
+    /// Maximum decoded input payload size in kilobytes (NOTARY_MAX_PAYLOAD_KB)
+    pub max_payload_kb: usize,
+    /// Optional MIME type allowlist, comma-separated (NOTARY_ALLOWED_MIME_TYPES)
+    /// Unset uses the default document allowlist; `*` accepts any type
+    pub allowed_mime_types: Option<Vec<String>>,
+    /// Only these addresses may notarize, comma-separated (NOTARY_ALLOWED_SUBMITTERS)
+    /// An empty list restricts no one
+    pub allowed_submitters: Option<Vec<String>>,
+    /// These addresses may not notarize, comma-separated (NOTARY_DENIED_SUBMITTERS)
+    pub denied_submitters: Option<Vec<String>>,
+    /// Maximum decoded document size in bytes (NOTARY_MAX_CONTENT_BYTES)
+    /// NOTARY_MAX_DOCUMENT_SIZE_BYTES is still read when the former is unset
+    pub max_content_bytes: usize,
+    /// Keep the original document bytes alongside the hash (NOTARY_STORE_CONTENT)
+    pub store_content: bool,
+    /// Record the IPFS CIDv1 of each notarized document (NOTARY_GENERATE_IPFS_CID)
+    pub generate_ipfs_cid: bool,
+    /// Record the Blake2b-256 digest of each notarized document (NOTARY_ENABLE_BLAKE2B)
+    pub enable_blake2b: bool,
+    /// Require a strictly increasing per-submitter nonce on state-changing inputs (NOTARY_REQUIRE_NONCE)
+    pub require_nonce: bool,
+    /// Address allowed to purge documents; unset disables purging (NOTARY_ADMIN_ADDRESS)
+    pub admin_address: Option<String>,
+    /// GIO domain of the provenance oracle; unset disables provenance checks (NOTARY_GIO_DOMAIN)
+    pub gio_domain: Option<u16>,
+    /// How long cosigners have to approve a co-notarization, in seconds (NOTARY_COSIGN_WINDOW_SECS)
+    pub cosign_window_secs: u64,
+    /// HMAC key for signing notarization notices; unset sends unsigned notices (NOTARY_NOTICE_SIGNING_KEY)
+    pub notice_signing_key: Option<NoticeSigningKey>,
+    /// DID named as the issuer of verifiable credentials (NOTARY_ISSUER_DID)
+    pub issuer_did: String,
+    /// Per-sender limit on state-changing inputs in one block; unset disables it (NOTARY_MAX_INPUTS_PER_BLOCK)
+    /// Clones of the config share the limiter's counters
+    pub rate_limiter: Option<RateLimiter>,
+    /// ERC20Portal contract whose inputs are deposits; unset ignores ERC-20 deposits (NOTARY_ERC20_PORTAL_ADDRESS)
+    pub erc20_portal_address: Option<String>,
+    /// EtherPortal contract whose inputs are deposits; unset ignores Ether deposits (NOTARY_ETHER_PORTAL_ADDRESS)
+    pub ether_portal_address: Option<String>,
+    /// Amount debited from the submitter's balance per notarization; zero is free (NOTARY_NOTARIZATION_FEE)
+    pub notarization_fee: u128,
+    /// Token the fee is paid in; the zero address means Ether (NOTARY_FEE_TOKEN)
+    pub fee_token: Option<String>,
+    /// Documents one submitter may notarize per epoch; unset disables it (NOTARY_MAX_PER_SUBMITTER_PER_EPOCH)
+    pub max_per_submitter_per_epoch: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            repository: RepositoryKind::Sqlite(DEFAULT_DB_PATH.to_string()),
+            db_busy_timeout_ms: DEFAULT_BUSY_TIMEOUT_MS,
+            db_cache_size_kib: DEFAULT_CACHE_SIZE_KIB,
+            db_mmap_size_bytes: DEFAULT_MMAP_SIZE_BYTES,
+            rollup_server_url: DEFAULT_ROLLUP_SERVER_URL.to_string(),
+            rollup_path_prefix: String::new(),
+            max_payload_kb: DEFAULT_MAX_PAYLOAD_KB,
+            allowed_mime_types: None,
+            allowed_submitters: None,
+            denied_submitters: None,
+            max_content_bytes: DEFAULT_MAX_CONTENT_BYTES,
+            store_content: false,
+            generate_ipfs_cid: false,
+            enable_blake2b: false,
+            require_nonce: false,
+            admin_address: None,
+            gio_domain: None,
+            cosign_window_secs: DEFAULT_COSIGN_WINDOW_SECS,
+            notice_signing_key: None,
+            issuer_did: DEFAULT_ISSUER_DID.to_string(),
+            rate_limiter: None,
+            erc20_portal_address: None,
+            ether_portal_address: None,
+            notarization_fee: 0,
+            fee_token: None,
+            max_per_submitter_per_epoch: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from process environment variables
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Load configuration using an arbitrary variable lookup
+    /// Unset variables fall back to defaults
+    pub fn from_lookup<F>(lookup: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let defaults = Self::default();
+
+        let config = Self {
+            repository: match (lookup("NOTARY_DB_PATH"), lookup("NOTARY_DATABASE_URL")) {
+                (Some(_), Some(_)) => {
+                    return Err(ConfigError::Conflicting(
+                        "NOTARY_DB_PATH".to_string(),
+                        "NOTARY_DATABASE_URL".to_string(),
+                    ))
+                }
+                (Some(path), None) => RepositoryKind::Sqlite(path),
+                (None, Some(url)) => RepositoryKind::Postgres(url),
+                (None, None) => defaults.repository,
+            },
+            db_busy_timeout_ms: parse_number(&lookup, "NOTARY_DB_BUSY_TIMEOUT_MS")?
+                .unwrap_or(defaults.db_busy_timeout_ms),
+            db_cache_size_kib: parse_number(&lookup, "NOTARY_DB_CACHE_SIZE_KIB")?
+                .unwrap_or(defaults.db_cache_size_kib),
+            db_mmap_size_bytes: parse_number(&lookup, "NOTARY_DB_MMAP_SIZE_BYTES")?
+                .unwrap_or(defaults.db_mmap_size_bytes),
+            rollup_server_url: lookup("ROLLUP_HTTP_SERVER_URL")
+                .unwrap_or(defaults.rollup_server_url),
+            rollup_path_prefix: lookup("ROLLUP_PATH_PREFIX").unwrap_or(defaults.rollup_path_prefix),
+            max_payload_kb: parse_usize(&lookup, "NOTARY_MAX_PAYLOAD_KB")?
+                .unwrap_or(defaults.max_payload_kb),
+            allowed_mime_types: parse_list(&lookup, "NOTARY_ALLOWED_MIME_TYPES"),
+            allowed_submitters: parse_list(&lookup, "NOTARY_ALLOWED_SUBMITTERS"),
+            denied_submitters: parse_list(&lookup, "NOTARY_DENIED_SUBMITTERS"),
+            max_content_bytes: match parse_usize(&lookup, "NOTARY_MAX_CONTENT_BYTES")? {
+                Some(limit) => limit,
+                None => parse_usize(&lookup, "NOTARY_MAX_DOCUMENT_SIZE_BYTES")?
+                    .unwrap_or(defaults.max_content_bytes),
+            },
+            store_content: parse_bool(&lookup, "NOTARY_STORE_CONTENT")?
+                .unwrap_or(defaults.store_content),
+            generate_ipfs_cid: parse_bool(&lookup, "NOTARY_GENERATE_IPFS_CID")?
+                .unwrap_or(defaults.generate_ipfs_cid),
+            enable_blake2b: parse_bool(&lookup, "NOTARY_ENABLE_BLAKE2B")?
+                .unwrap_or(defaults.enable_blake2b),
+            require_nonce: parse_bool(&lookup, "NOTARY_REQUIRE_NONCE")?
+                .unwrap_or(defaults.require_nonce),
+            admin_address: lookup("NOTARY_ADMIN_ADDRESS").map(|value| value.trim().to_string()),
+            gio_domain: parse_number(&lookup, "NOTARY_GIO_DOMAIN")?,
+            cosign_window_secs: parse_number(&lookup, "NOTARY_COSIGN_WINDOW_SECS")?
+                .unwrap_or(defaults.cosign_window_secs),
+            notice_signing_key: match lookup("NOTARY_NOTICE_SIGNING_KEY") {
+                Some(key) if key.is_empty() => {
+                    return Err(ConfigError::EmptyValue(
+                        "NOTARY_NOTICE_SIGNING_KEY".to_string(),
+                    ))
+                }
+                Some(key) => Some(NoticeSigningKey::new(key)),
+                None => None,
+            },
+            issuer_did: lookup("NOTARY_ISSUER_DID")
+                .map(|value| value.trim().to_string())
+                .unwrap_or(defaults.issuer_did),
+            rate_limiter: parse_number(&lookup, "NOTARY_MAX_INPUTS_PER_BLOCK")?
+                .map(RateLimiter::new),
+            erc20_portal_address: lookup("NOTARY_ERC20_PORTAL_ADDRESS")
+                .map(|value| value.trim().to_string()),
+            ether_portal_address: lookup("NOTARY_ETHER_PORTAL_ADDRESS")
+                .map(|value| value.trim().to_string()),
+            notarization_fee: parse_number(&lookup, "NOTARY_NOTARIZATION_FEE")?
+                .unwrap_or(defaults.notarization_fee),
+            fee_token: lookup("NOTARY_FEE_TOKEN").map(|value| value.trim().to_string()),
+            max_per_submitter_per_epoch: parse_number(
+                &lookup,
+                "NOTARY_MAX_PER_SUBMITTER_PER_EPOCH",
+            )?,
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject values that would leave the DApp unable to serve requests
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match &self.repository {
+            RepositoryKind::Sqlite(path) if path.trim().is_empty() => {
+                return Err(ConfigError::EmptyValue("NOTARY_DB_PATH".to_string()));
+            }
+            RepositoryKind::Postgres(url) if url.trim().is_empty() => {
+                return Err(ConfigError::EmptyValue("NOTARY_DATABASE_URL".to_string()));
+            }
+            _ => {}
+        }
+
+        if self.rollup_server_url.trim().is_empty() {
+            return Err(ConfigError::EmptyValue(
+                "ROLLUP_HTTP_SERVER_URL".to_string(),
+            ));
+        }
+
+        if self.db_cache_size_kib == 0 {
+            return Err(ConfigError::ZeroValue(
+                "NOTARY_DB_CACHE_SIZE_KIB".to_string(),
+            ));
+        }
+
+        if self.max_payload_kb == 0 {
+            return Err(ConfigError::ZeroValue("NOTARY_MAX_PAYLOAD_KB".to_string()));
+        }
+
+        if self.max_content_bytes == 0 {
+            return Err(ConfigError::ZeroValue(
+                "NOTARY_MAX_CONTENT_BYTES".to_string(),
+            ));
+        }
+
+        if self.cosign_window_secs == 0 {
+            return Err(ConfigError::ZeroValue(
+                "NOTARY_COSIGN_WINDOW_SECS".to_string(),
+            ));
+        }
+
+        if matches!(&self.rate_limiter, Some(limiter) if limiter.max_per_block() == 0) {
+            return Err(ConfigError::ZeroValue(
+                "NOTARY_MAX_INPUTS_PER_BLOCK".to_string(),
+            ));
+        }
+
+        if self.max_per_submitter_per_epoch == Some(0) {
+            return Err(ConfigError::ZeroValue(
+                "NOTARY_MAX_PER_SUBMITTER_PER_EPOCH".to_string(),
+            ));
+        }
+
+        if self.issuer_did.is_empty() {
+            return Err(ConfigError::EmptyValue("NOTARY_ISSUER_DID".to_string()));
+        }
+
+        if matches!(&self.allowed_mime_types, Some(types) if types.is_empty()) {
+            return Err(ConfigError::EmptyValue(
+                "NOTARY_ALLOWED_MIME_TYPES".to_string(),
+            ));
+        }
+
+        if self.allowed_submitters.is_some() && self.denied_submitters.is_some() {
+            return Err(ConfigError::Conflicting(
+                "NOTARY_ALLOWED_SUBMITTERS".to_string(),
+                "NOTARY_DENIED_SUBMITTERS".to_string(),
+            ));
+        }
+
+        for (name, addresses) in [
+            ("NOTARY_ALLOWED_SUBMITTERS", &self.allowed_submitters),
+            ("NOTARY_DENIED_SUBMITTERS", &self.denied_submitters),
+        ] {
+            let invalid = addresses
+                .iter()
+                .flatten()
+                .find(|address| EthAddress::parse(address).is_err());
+            if let Some(address) = invalid {
+                return Err(ConfigError::InvalidAddress {
+                    name: name.to_string(),
+                    value: address.clone(),
+                });
+            }
+        }
+
+        for (name, address) in [
+            ("NOTARY_ADMIN_ADDRESS", &self.admin_address),
+            ("NOTARY_ERC20_PORTAL_ADDRESS", &self.erc20_portal_address),
+            ("NOTARY_ETHER_PORTAL_ADDRESS", &self.ether_portal_address),
+            ("NOTARY_FEE_TOKEN", &self.fee_token),
+        ] {
+            if let Some(address) = address {
+                if EthAddress::parse(address).is_err() {
+                    return Err(ConfigError::InvalidAddress {
+                        name: name.to_string(),
+                        value: address.clone(),
+                    });
+                }
+            }
+        }
+
+        // A fee nobody can deposit for would reject every notarization
+        if let Some((token, _)) = self.notarization_fee() {
+            let portal = if token.eq_ignore_ascii_case(ETHER_TOKEN) {
+                ("NOTARY_ETHER_PORTAL_ADDRESS", &self.ether_portal_address)
+            } else {
+                ("NOTARY_ERC20_PORTAL_ADDRESS", &self.erc20_portal_address)
+            };
+            if portal.1.is_none() {
+                return Err(ConfigError::MissingValue {
+                    name: portal.0.to_string(),
+                    dependent: "NOTARY_NOTARIZATION_FEE".to_string(),
+                });
+            }
+        } else if self.notarization_fee > 0 {
+            return Err(ConfigError::MissingValue {
+                name: "NOTARY_FEE_TOKEN".to_string(),
+                dependent: "NOTARY_NOTARIZATION_FEE".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Submitter access control for notarization, when either list is configured
+    pub fn submitter_access(&self) -> Option<AllowDenyList> {
+        match (&self.allowed_submitters, &self.denied_submitters) {
+            (Some(allowed), _) => Some(AllowDenyList::new(AllowDenyMode::AllowOnly, allowed)),
+            (None, Some(denied)) => Some(AllowDenyList::new(AllowDenyMode::DenyAll, denied)),
+            (None, None) => None,
+        }
+    }
+
+    /// Fee token and amount charged per notarization, when notarization is not free
+    pub fn notarization_fee(&self) -> Option<(&str, u128)> {
+        match &self.fee_token {
+            Some(token) if self.notarization_fee > 0 => Some((token, self.notarization_fee)),
+            _ => None,
+        }
+    }
+
+    /// Parse an input from a configured portal contract as a deposit
+    /// Returns `None` when `sender` is not a deposit portal
+    pub fn portal_deposit(
+        &self,
+        sender: &str,
+        payload: &[u8],
+    ) -> Option<Result<Deposit, DepositError>> {
+        let is_sender =
+            |portal: &Option<String>| matches!(portal, Some(p) if p.eq_ignore_ascii_case(sender));
+
+        if is_sender(&self.erc20_portal_address) {
+            Some(Deposit::from_erc20_portal(payload))
+        } else if is_sender(&self.ether_portal_address) {
+            Some(Deposit::from_ether_portal(payload))
+        } else {
+            None
+        }
+    }
+
+    /// Connection settings for a SQLite database
+    pub fn sqlite_options(&self) -> SqliteOptions {
+        SqliteOptions {
+            busy_timeout_ms: self.db_busy_timeout_ms,
+            cache_size_kib: self.db_cache_size_kib,
+            mmap_size_bytes: self.db_mmap_size_bytes,
+        }
+    }
+
+    /// Maximum decoded input payload size in bytes
+    pub fn max_payload_bytes(&self) -> usize {
+        self.max_payload_kb.saturating_mul(1024)
+    }
+}
+
+/// Comma-separated values, trimmed and lowercased, with empty entries dropped
+fn parse_list<F>(lookup: &F, name: &str) -> Option<Vec<String>>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    lookup(name).map(|value| {
+        value
+            .split(',')
+            .map(|item| item.trim().to_lowercase())
+            .filter(|item| !item.is_empty())
+            .collect()
+    })
+}
+
+fn parse_usize<F>(lookup: &F, name: &str) -> Result<Option<usize>, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    parse_number(lookup, name)
+}
+
+fn parse_number<T, F>(lookup: &F, name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: std::str::FromStr,
+    F: Fn(&str) -> Option<String>,
+{
+    match lookup(name) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::InvalidNumber {
+                name: name.to_string(),
+                value,
+            }),
+        None => Ok(None),
+    }
+}
+
+fn parse_bool<F>(lookup: &F, name: &str) -> Result<Option<bool>, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match lookup(name) {
+        Some(value) => match value.trim().to_lowercase().as_str() {
+            "1" | "true" | "yes" => Ok(Some(true)),
+            "0" | "false" | "no" => Ok(Some(false)),
+            _ => Err(ConfigError::InvalidBool {
+                name: name.to_string(),
+                value,
+            }),
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults_when_unset() {
+        let config = load(&[]).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(
+            config.repository,
+            RepositoryKind::Sqlite(DEFAULT_DB_PATH.to_string())
+        );
+        assert_eq!(config.rollup_server_url, DEFAULT_ROLLUP_SERVER_URL);
+        assert!(config.allowed_mime_types.is_none());
+    }
+
+    #[test]
+    fn test_reads_all_variables() {
+        let config = load(&[
+            ("NOTARY_DB_PATH", "/tmp/notary.db"),
+            ("NOTARY_DB_BUSY_TIMEOUT_MS", "250"),
+            ("NOTARY_DB_CACHE_SIZE_KIB", "8192"),
+            ("NOTARY_DB_MMAP_SIZE_BYTES", "0"),
+            ("ROLLUP_HTTP_SERVER_URL", "http://localhost:9000"),
+            ("ROLLUP_PATH_PREFIX", "/rollup"),
+            ("NOTARY_MAX_PAYLOAD_KB", "64"),
+            ("NOTARY_ALLOWED_MIME_TYPES", "application/pdf, Text/Plain,"),
+            ("NOTARY_MAX_CONTENT_BYTES", "4096"),
+            ("NOTARY_STORE_CONTENT", "yes"),
+            ("NOTARY_GENERATE_IPFS_CID", "true"),
+            ("NOTARY_ENABLE_BLAKE2B", "true"),
+            ("NOTARY_REQUIRE_NONCE", "true"),
+            ("NOTARY_GIO_DOMAIN", "45"),
+            ("NOTARY_NOTICE_SIGNING_KEY", "notary-key"),
+            ("NOTARY_COSIGN_WINDOW_SECS", "3600"),
+            ("NOTARY_ISSUER_DID", "did:web:notary.example"),
+            ("NOTARY_MAX_INPUTS_PER_BLOCK", "5"),
+            ("NOTARY_MAX_PER_SUBMITTER_PER_EPOCH", "20"),
+            (
+                "NOTARY_ERC20_PORTAL_ADDRESS",
+                "0x00000000000000000000000000000000000000e2",
+            ),
+            (
+                "NOTARY_ETHER_PORTAL_ADDRESS",
+                "0x00000000000000000000000000000000000000e1",
+            ),
+            ("NOTARY_NOTARIZATION_FEE", "1000000000000000000"),
+            (
+                "NOTARY_FEE_TOKEN",
+                "0x00000000000000000000000000000000000000f0",
+            ),
+            (
+                "NOTARY_ADMIN_ADDRESS",
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            config.repository,
+            RepositoryKind::Sqlite("/tmp/notary.db".to_string())
+        );
+        assert_eq!(config.db_busy_timeout_ms, 250);
+        assert_eq!(
+            config.sqlite_options(),
+            SqliteOptions {
+                busy_timeout_ms: 250,
+                cache_size_kib: 8192,
+                mmap_size_bytes: 0,
+            }
+        );
+        assert_eq!(config.rollup_server_url, "http://localhost:9000");
+        assert_eq!(config.rollup_path_prefix, "/rollup");
+        assert_eq!(config.max_payload_kb, 64);
+        assert_eq!(config.max_payload_bytes(), 64 * 1024);
+        assert_eq!(
+            config.allowed_mime_types,
+            Some(vec![
+                "application/pdf".to_string(),
+                "text/plain".to_string()
+            ])
+        );
+        assert_eq!(config.max_content_bytes, 4096);
+        assert!(config.store_content);
+        assert!(config.generate_ipfs_cid);
+        assert!(config.enable_blake2b);
+        assert!(config.require_nonce);
+        assert_eq!(config.gio_domain, Some(45));
+        assert_eq!(config.cosign_window_secs, 3600);
+        assert_eq!(config.issuer_did, "did:web:notary.example");
+        assert_eq!(config.rate_limiter, Some(RateLimiter::new(5)));
+        assert_eq!(config.max_per_submitter_per_epoch, Some(20));
+        assert_eq!(
+            config.notarization_fee(),
+            Some((
+                "0x00000000000000000000000000000000000000f0",
+                1_000_000_000_000_000_000
+            ))
+        );
+        assert!(config.portal_deposit(ETHER_TOKEN, &[]).is_none());
+        assert!(config
+            .portal_deposit("0x00000000000000000000000000000000000000E2", &[0; 72])
+            .is_some_and(|deposit| deposit.is_ok()));
+        assert!(config
+            .portal_deposit("0x00000000000000000000000000000000000000e1", &[0; 52])
+            .is_some_and(|deposit| deposit.is_ok()));
+        assert_eq!(
+            config.notice_signing_key,
+            Some(NoticeSigningKey::new("notary-key"))
+        );
+        assert_eq!(
+            config.admin_address.as_deref(),
+            Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+    }
+
+    #[test]
+    fn test_postgres_repository() {
+        let config = load(&[("NOTARY_DATABASE_URL", "postgres://notary@db/notary")]).unwrap();
+        assert_eq!(
+            config.repository,
+            RepositoryKind::Postgres("postgres://notary@db/notary".to_string())
+        );
+
+        assert!(matches!(
+            load(&[
+                ("NOTARY_DB_PATH", "/tmp/notary.db"),
+                ("NOTARY_DATABASE_URL", "postgres://notary@db/notary"),
+            ]),
+            Err(ConfigError::Conflicting(..))
+        ));
+    }
+
+    #[test]
+    fn test_legacy_document_size_variable() {
+        let config = load(&[("NOTARY_MAX_DOCUMENT_SIZE_BYTES", "2048")]).unwrap();
+        assert_eq!(config.max_content_bytes, 2048);
+
+        // The new name wins when both are set
+        let config = load(&[
+            ("NOTARY_MAX_DOCUMENT_SIZE_BYTES", "2048"),
+            ("NOTARY_MAX_CONTENT_BYTES", "512"),
+        ])
+        .unwrap();
+        assert_eq!(config.max_content_bytes, 512);
+    }
+
+    #[test]
+    fn test_rejects_zero_sizes() {
+        assert!(matches!(
+            load(&[("NOTARY_MAX_PAYLOAD_KB", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_MAX_DOCUMENT_SIZE_BYTES", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_COSIGN_WINDOW_SECS", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_MAX_INPUTS_PER_BLOCK", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_MAX_PER_SUBMITTER_PER_EPOCH", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_DB_CACHE_SIZE_KIB", "0")]),
+            Err(ConfigError::ZeroValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_non_numeric_sizes() {
+        let err = load(&[("NOTARY_MAX_PAYLOAD_KB", "lots")]).unwrap_err();
+        assert!(err.to_string().contains("NOTARY_MAX_PAYLOAD_KB"));
+
+        // GIO domains are 16-bit
+        let err = load(&[("NOTARY_GIO_DOMAIN", "70000")]).unwrap_err();
+        assert!(err.to_string().contains("NOTARY_GIO_DOMAIN"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_admin_address() {
+        assert!(matches!(
+            load(&[("NOTARY_ADMIN_ADDRESS", "admin")]),
+            Err(ConfigError::InvalidAddress { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fee_requires_token_and_portal() {
+        assert!(matches!(
+            load(&[("NOTARY_NOTARIZATION_FEE", "10")]),
+            Err(ConfigError::MissingValue { name, .. }) if name == "NOTARY_FEE_TOKEN"
+        ));
+        assert!(matches!(
+            load(&[
+                ("NOTARY_NOTARIZATION_FEE", "10"),
+                ("NOTARY_FEE_TOKEN", ETHER_TOKEN),
+            ]),
+            Err(ConfigError::MissingValue { name, .. }) if name == "NOTARY_ETHER_PORTAL_ADDRESS"
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_ERC20_PORTAL_ADDRESS", "portal")]),
+            Err(ConfigError::InvalidAddress { .. })
+        ));
+
+        // A zero fee leaves notarization free whatever token is named
+        let config = load(&[("NOTARY_FEE_TOKEN", ETHER_TOKEN)]).unwrap();
+        assert_eq!(config.notarization_fee(), None);
+    }
+
+    #[test]
+    fn test_submitter_access_lists() {
+        let alice = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let bob = "0x00000000000000000000000000000000000000b0";
+
+        let config = load(&[("NOTARY_ALLOWED_SUBMITTERS", &format!("{alice}, {bob},"))]).unwrap();
+        let access = config.submitter_access().unwrap();
+        assert_eq!(access.mode, AllowDenyMode::AllowOnly);
+        assert!(access.addresses.contains(&alice.to_lowercase()));
+        assert!(access.addresses.contains(bob));
+
+        let config = load(&[("NOTARY_DENIED_SUBMITTERS", bob)]).unwrap();
+        assert_eq!(
+            config.submitter_access().unwrap().mode,
+            AllowDenyMode::DenyAll
+        );
+
+        // An empty list is accepted and restricts no one
+        let config = load(&[("NOTARY_ALLOWED_SUBMITTERS", " ")]).unwrap();
+        assert!(config.submitter_access().unwrap().permits(bob));
+
+        assert_eq!(load(&[]).unwrap().submitter_access(), None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_submitter_lists() {
+        assert!(matches!(
+            load(&[("NOTARY_DENIED_SUBMITTERS", "0x00000000000000000000000000000000000000b0,bob")]),
+            Err(ConfigError::InvalidAddress { name, value })
+                if name == "NOTARY_DENIED_SUBMITTERS" && value == "bob"
+        ));
+        assert!(matches!(
+            load(&[
+                (
+                    "NOTARY_ALLOWED_SUBMITTERS",
+                    "0x00000000000000000000000000000000000000a0"
+                ),
+                (
+                    "NOTARY_DENIED_SUBMITTERS",
+                    "0x00000000000000000000000000000000000000b0"
+                ),
+            ]),
+            Err(ConfigError::Conflicting(..))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_bool() {
+        assert!(matches!(
+            load(&[("NOTARY_REQUIRE_NONCE", "maybe")]),
+            Err(ConfigError::InvalidBool { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_empty_values() {
+        assert!(matches!(
+            load(&[("NOTARY_DB_PATH", "  ")]),
+            Err(ConfigError::EmptyValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_DATABASE_URL", "")]),
+            Err(ConfigError::EmptyValue(name)) if name == "NOTARY_DATABASE_URL"
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_ALLOWED_MIME_TYPES", " , ")]),
+            Err(ConfigError::EmptyValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_NOTICE_SIGNING_KEY", "")]),
+            Err(ConfigError::EmptyValue(_))
+        ));
+        assert!(matches!(
+            load(&[("NOTARY_ISSUER_DID", " ")]),
+            Err(ConfigError::EmptyValue(_))
+        ));
+    }
+}
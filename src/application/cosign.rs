@@ -0,0 +1,271 @@
+use super::notarize::{NotarizeConfig, NotarizeOptions, NotarizeUseCase};
+use crate::domain::{Cosigner, EthAddress, NotarizationReceipt, PendingNotarization};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+/// Time cosigners have to approve a co-notarization when none is configured (7 days)
+pub const DEFAULT_COSIGN_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Most cosigners a single notarization can require
+pub const MAX_COSIGNERS: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum CosignError {
+    #[error("At least one cosigner is required")]
+    NoCosigners,
+
+    #[error("{count} cosigners exceed limit of {limit}")]
+    TooManyCosigners { count: usize, limit: usize },
+
+    #[error("Invalid cosigner address '{address}': {reason}")]
+    InvalidCosigner { address: String, reason: String },
+
+    #[error("Document is already awaiting cosignatures")]
+    AlreadyPending,
+
+    #[error("No pending notarization for this content hash")]
+    NotFound,
+
+    #[error("Cosigning window closed; the pending notarization was discarded")]
+    Expired,
+
+    #[error("Sender is not a required cosigner")]
+    NotACosigner,
+
+    #[error("Sender has already cosigned")]
+    AlreadySigned,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Result of a cosignature: still waiting, or notarized now that everyone approved
+#[derive(Debug)]
+pub enum CosignOutcome {
+    Pending {
+        pending: PendingNotarization,
+        cosigners: Vec<Cosigner>,
+    },
+    Completed {
+        receipt: NotarizationReceipt,
+        cosigners: Vec<Cosigner>,
+        /// Earlier version the notarized document amends, if any
+        supersedes: Option<String>,
+    },
+}
+
+/// Holds notarizations until all required cosigners approve them with their own inputs
+/// Approval is authenticated by the input's sender; the submitted signature is kept as given
+pub struct CosignUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    config: NotarizeConfig,
+    window_secs: u64,
+    epoch_index: u64,
+    input_index: u64,
+}
+
+impl<'a> CosignUseCase<'a> {
+    pub fn new(
+        repository: &'a dyn DocumentRepository,
+        config: NotarizeConfig,
+        window_secs: u64,
+    ) -> Self {
+        Self {
+            repository,
+            config,
+            window_secs,
+            epoch_index: 0,
+            input_index: 0,
+        }
+    }
+
+    /// Record the advance input whose cosignature completes a notarization on the document
+    pub fn with_input(mut self, epoch_index: u64, input_index: u64) -> Self {
+        self.epoch_index = epoch_index;
+        self.input_index = input_index;
+        self
+    }
+
+    /// Validate a notarization like a regular one, then hold it for `cosigners`
+    /// Cosigner addresses are stored in lowercase and repeated addresses are kept once
+    /// `timestamp` is the input's block timestamp, which opens the cosigning window
+    #[allow(clippy::too_many_arguments)]
+    pub fn request(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        options: &NotarizeOptions,
+        cosigners: &[String],
+        timestamp: i64,
+    ) -> Result<(PendingNotarization, Vec<Cosigner>), Box<dyn Error>> {
+        let cosigners = normalize_cosigners(cosigners)?;
+
+        let content_hash = NotarizeUseCase::new(self.repository, self.config.clone()).validate(
+            content,
+            file_name,
+            mime_type,
+            submitted_by,
+            options,
+        )?;
+
+        // An expired request no longer blocks the same content
+        if let Some(existing) = self.find_pending(&content_hash)? {
+            if !existing.is_expired_at(timestamp) {
+                return Err(Box::new(CosignError::AlreadyPending));
+            }
+            self.discard(&content_hash)?;
+        }
+
+        let pending = PendingNotarization {
+            content_hash,
+            submitted_by: submitted_by.to_string(),
+            file_name: file_name.to_string(),
+            mime_type: mime_type.to_string(),
+            content: content.to_vec(),
+            expires_at: options.expires_at,
+            expiration_blocks: options.expiration_blocks,
+            tags: options.tags.clone(),
+            supersedes: options.supersedes.clone(),
+            description: options.description.clone(),
+            signature: options.signature.clone(),
+            signed_message: options.signed_message.clone(),
+            created_at: timestamp,
+            deadline: timestamp.saturating_add(i64::try_from(self.window_secs).unwrap_or(i64::MAX)),
+        };
+
+        let addresses: Vec<&str> = cosigners.iter().map(String::as_str).collect();
+        self.repository
+            .save_pending_notarization(&pending, &addresses)
+            .map_err(|e| {
+                if matches!(e, DatabaseError::DuplicateHash) {
+                    Box::new(CosignError::AlreadyPending) as Box<dyn Error>
+                } else {
+                    Box::new(CosignError::DatabaseError(e.to_string()))
+                }
+            })?;
+
+        let cosigners = self.cosigners(&pending.content_hash)?;
+        Ok((pending, cosigners))
+    }
+
+    /// Record the sender's approval; the last approval notarizes the document
+    /// `timestamp` is the input's block timestamp, used for the window and the notarization
+    pub fn cosign(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        block_number: u64,
+        timestamp: i64,
+    ) -> Result<CosignOutcome, Box<dyn Error>> {
+        let pending = self
+            .find_pending(content_hash)?
+            .ok_or(CosignError::NotFound)?;
+
+        if pending.is_expired_at(timestamp) {
+            self.discard(content_hash)?;
+            return Err(Box::new(CosignError::Expired));
+        }
+
+        let cosigner = cosigner.to_lowercase();
+        let cosigners = self.cosigners(content_hash)?;
+        if !cosigners.iter().any(|c| c.address == cosigner) {
+            return Err(Box::new(CosignError::NotACosigner));
+        }
+
+        let recorded = self
+            .repository
+            .record_cosignature(content_hash, &cosigner, signature, timestamp)
+            .map_err(|e| Box::new(CosignError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+        if !recorded {
+            return Err(Box::new(CosignError::AlreadySigned));
+        }
+
+        let cosigners = self.cosigners(content_hash)?;
+        if !cosigners.iter().all(Cosigner::has_signed) {
+            return Ok(CosignOutcome::Pending { pending, cosigners });
+        }
+
+        // Everyone approved: notarize exactly as a regular request, then release the content
+        let options = NotarizeOptions {
+            expires_at: pending.expires_at,
+            expiration_blocks: pending.expiration_blocks,
+            tags: pending.tags.clone(),
+            supersedes: pending.supersedes.clone(),
+            description: pending.description.clone(),
+            signature: pending.signature.clone(),
+            signed_message: pending.signed_message.clone(),
+            notarized_at: Some(timestamp),
+            epoch_index: self.epoch_index,
+            input_index: self.input_index,
+            ..NotarizeOptions::default()
+        };
+        let result = NotarizeUseCase::new(self.repository, self.config.clone())
+            .execute_with_options(
+                &pending.content,
+                &pending.file_name,
+                &pending.mime_type,
+                &pending.submitted_by,
+                block_number,
+                &options,
+            );
+        self.discard(content_hash)?;
+
+        Ok(CosignOutcome::Completed {
+            receipt: result?,
+            cosigners,
+            supersedes: pending.supersedes,
+        })
+    }
+
+    fn find_pending(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, Box<dyn Error>> {
+        self.repository
+            .find_pending_notarization(content_hash)
+            .map_err(|e| Box::new(CosignError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    fn cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, Box<dyn Error>> {
+        self.repository
+            .find_cosigners(content_hash)
+            .map_err(|e| Box::new(CosignError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    fn discard(&self, content_hash: &str) -> Result<(), Box<dyn Error>> {
+        self.repository
+            .delete_pending_notarization(content_hash)
+            .map_err(|e| Box::new(CosignError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+}
+
+fn normalize_cosigners(cosigners: &[String]) -> Result<Vec<String>, CosignError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(cosigners.len());
+    for address in cosigners {
+        let parsed =
+            EthAddress::parse(address.trim()).map_err(|e| CosignError::InvalidCosigner {
+                address: address.clone(),
+                reason: e.to_string(),
+            })?;
+        let address = parsed.as_str().to_string();
+        if !normalized.contains(&address) {
+            normalized.push(address);
+        }
+    }
+
+    if normalized.is_empty() {
+        return Err(CosignError::NoCosigners);
+    }
+    if normalized.len() > MAX_COSIGNERS {
+        return Err(CosignError::TooManyCosigners {
+            count: normalized.len(),
+            limit: MAX_COSIGNERS,
+        });
+    }
+
+    Ok(normalized)
+}
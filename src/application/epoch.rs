@@ -0,0 +1,80 @@
+use crate::domain::{ContentHash, EpochRoot, MerkleProof};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EpochError {
+    #[error("Invalid hash format: must be 64 hexadecimal characters")]
+    InvalidHashFormat,
+
+    #[error("Epoch {0} has no Merkle root")]
+    EpochNotSealed(u64),
+
+    #[error("Document is not committed to the root of epoch {0}")]
+    NotInEpoch(u64),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Commits the documents notarized in each rollup epoch to a Merkle root
+pub struct EpochUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> EpochUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Track the epoch of an advance input, before the input is processed
+    /// When a new epoch begins, the previous one is sealed and its root returned
+    pub fn observe(&self, epoch_index: u64) -> Result<Option<EpochRoot>, Box<dyn Error>> {
+        let to_db_error =
+            |e: DatabaseError| Box::new(EpochError::DatabaseError(e.to_string())) as Box<dyn Error>;
+
+        match self.repository.find_open_epoch().map_err(to_db_error)? {
+            Some(open) if epoch_index > open => self
+                .repository
+                .seal_epoch(open, epoch_index)
+                .map_err(to_db_error),
+            Some(_) => Ok(None),
+            None => {
+                self.repository
+                    .open_epoch(epoch_index)
+                    .map_err(to_db_error)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Proof that the document with `content_hash` was notarized during `epoch`
+    pub fn inclusion_proof(
+        &self,
+        content_hash: &str,
+        epoch: u64,
+    ) -> Result<MerkleProof, Box<dyn Error>> {
+        if content_hash.len() != 64 || !content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Box::new(EpochError::InvalidHashFormat));
+        }
+
+        let sealed = self
+            .repository
+            .find_epoch_root(epoch)
+            .map_err(|e| Box::new(EpochError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+        if sealed.is_none() {
+            return Err(Box::new(EpochError::EpochNotSealed(epoch)));
+        }
+
+        self.repository
+            .generate_inclusion_proof(&ContentHash::from(content_hash), epoch)
+            .map_err(|e| {
+                if matches!(e, DatabaseError::NotFound) {
+                    Box::new(EpochError::NotInEpoch(epoch)) as Box<dyn Error>
+                } else {
+                    Box::new(EpochError::DatabaseError(e.to_string()))
+                }
+            })
+    }
+}
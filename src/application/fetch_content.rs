@@ -0,0 +1,78 @@
+use crate::domain::DocumentId;
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FetchContentError {
+    #[error("Document not found")]
+    NotFound,
+
+    #[error("Only the document owner can fetch its content")]
+    Unauthorized,
+
+    #[error(
+        "Content is only served to an authenticated sender; submit fetch_content via advance_state"
+    )]
+    SenderRequired,
+
+    #[error("Content was not retained for this document")]
+    ContentNotStored,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Original bytes of a document, with the metadata needed to save them again
+#[derive(Debug, Clone)]
+pub struct StoredContent {
+    pub document_id: String,
+    pub content_hash: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+}
+
+pub struct FetchContentUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> FetchContentUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Return the retained content of a document to its current owner
+    pub fn execute(
+        &self,
+        document_id: &str,
+        requester: &str,
+    ) -> Result<StoredContent, Box<dyn Error>> {
+        let document_id = DocumentId::from(document_id);
+        let document = self
+            .repository
+            .find_by_id(&document_id)
+            .map_err(|_| Box::new(FetchContentError::NotFound) as Box<dyn Error>)?;
+
+        if !document.submitted_by.eq_ignore_ascii_case(requester) {
+            return Err(Box::new(FetchContentError::Unauthorized));
+        }
+
+        // Documents notarized in hash-only mode have no content row
+        let content = self.repository.get_content(&document_id).map_err(|e| {
+            if matches!(e, DatabaseError::NotFound) {
+                Box::new(FetchContentError::ContentNotStored) as Box<dyn Error>
+            } else {
+                Box::new(FetchContentError::DatabaseError(e.to_string()))
+            }
+        })?;
+
+        Ok(StoredContent {
+            document_id: document.id.into(),
+            content_hash: document.content_hash.into(),
+            file_name: document.file_name,
+            mime_type: document.mime_type,
+            content,
+        })
+    }
+}
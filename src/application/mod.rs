@@ -1,7 +1,76 @@
+mod audit;
+mod balance;
+mod chunked_upload;
+mod commit;
+pub mod config;
+mod cosign;
+mod epoch;
+mod fetch_content;
+mod nonce;
 mod notarize;
+mod provenance;
+mod purge;
+mod rate_limit;
+mod rehash;
+mod search;
+mod transfer;
 pub mod types;
+mod update_metadata;
 mod verify;
 
-pub use notarize::{NotarizeError, NotarizeUseCase};
-pub use types::{InputAction, NotarizeRequest, NoticeResponse, ReportResponse, VerifyRequest};
-pub use verify::{VerificationResult, VerifyError, VerifyUseCase};
+pub use audit::{AuditError, AuditListing, AuditPage, AuditQuery, AuditUseCase};
+pub use balance::{BalanceError, BalanceUseCase};
+pub use chunked_upload::{ChunkedUploadError, ChunkedUploadUseCase};
+pub use commit::{CommitError, CommitUseCase};
+pub use config::{Config, ConfigError, RepositoryKind};
+pub use cosign::{
+    CosignError, CosignOutcome, CosignUseCase, DEFAULT_COSIGN_WINDOW_SECS, MAX_COSIGNERS,
+};
+pub use epoch::{EpochError, EpochUseCase};
+pub use fetch_content::{FetchContentError, FetchContentUseCase, StoredContent};
+pub use nonce::{NonceError, NonceUseCase};
+pub use notarize::{
+    normalize_tag, validate_filename, AllowDenyList, AllowDenyMode, NotarizeConfig, NotarizeError,
+    NotarizeOptions, NotarizePreview, NotarizeUseCase, OriginalNotarization,
+    DEFAULT_ALLOWED_MIME_TYPES, DEFAULT_MAX_CONTENT_BYTES, MAX_DESCRIPTION_LENGTH,
+    MAX_FILENAME_LENGTH, MAX_TAGS_PER_DOCUMENT, MAX_TAG_LENGTH, SECONDS_PER_BLOCK,
+};
+pub use provenance::{ProvenanceError, ProvenanceResult, ProvenanceUseCase};
+pub use purge::{PurgeError, PurgeRecord, PurgeUseCase};
+pub use rate_limit::{RateLimitError, RateLimiter};
+pub use rehash::{
+    RehashError, RehashProgress, RehashUseCase, DEFAULT_REHASH_BATCH, MAX_REHASH_BATCH,
+};
+pub use search::{
+    DocumentCursor, DocumentPage, RegistryPage, SearchError, SearchUseCase, SyncBatch,
+    DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT,
+};
+pub use transfer::{TransferError, TransferOwnershipUseCase};
+pub use types::{
+    duplicate_of, duplicate_report, error_report, exception_report, supported_versions,
+    AuditLogRequest, AuditLogResponse, BatchVerifyEntry, BatchVerifyRequest, BatchVerifyResponse,
+    Blake2bVerifyRequest, BuilderError, CertificateResponse, ChunkAppendRequest,
+    ChunkFinishRequest, ChunkStartRequest, CommitRequest, CommitmentNotice, ContentResponse,
+    CosignRequest, DateRangeRequest, DateRangeResponse, DayCount, DepositNotice,
+    DocumentVersionNotice, EpochRootNotice, ErrorCode, ExpiredDocumentsResponse,
+    ExportRegistryRequest, ExportRegistryResponse, FetchContentRequest, FieldError,
+    FindByTagRequest, GetDocumentRequest, HealthResponse, InclusionProofRequest,
+    InclusionProofResponse, InputAction, ListAuditLogRequest, ListAuditLogResponse,
+    ListBySubmitterRequest, ListDocumentsRequest, ListDocumentsResponse, ListExpiredRequest,
+    MetadataUpdateNotice, NotarizeRequest, NotarizeRequestBuilder, NoticeResponse,
+    OwnershipTransferNotice, PendingCosignResponse, ProofResponse, ProtocolVersionError,
+    ProvenanceReport, PurgeNotice, PurgeRequest, RehashRequest, ReportResponse, ResponseEncoding,
+    SearchDocumentsRequest, SearchDocumentsResponse, StatisticsResponse, SubmitterStats,
+    SubmitterStatsRequest, SubmitterStatsResponse, SyncSinceRequest, SyncSinceResponse,
+    TaggedDocumentsResponse, TimeseriesRequest, TimeseriesResponse, TransferOwnershipRequest,
+    UnknownActionResponse, UpdateMetadataRequest, UploadProgressResponse, ValidationReport,
+    VerifyByContentRequest, VerifyContentRequest, VerifyRequest, CBOR_PAYLOAD_PREFIX,
+    DEFAULT_PROTOCOL_VERSION, SUPPORTED_ACTIONS,
+};
+pub use update_metadata::{
+    MetadataChange, UpdateMetadataError, UpdateMetadataUseCase, MAX_METADATA_BYTES,
+};
+pub use verify::{
+    LruVerifyCache, MatchedHash, VerificationResult, VerifyError, VerifyUseCase,
+    MAX_BATCH_VERIFY_SIZE,
+};
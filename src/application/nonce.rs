@@ -0,0 +1,65 @@
+use crate::infrastructure::database::DocumentRepository;
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NonceError {
+    #[error("Nonce is required")]
+    Missing,
+
+    #[error("Nonce {nonce} must be greater than last used nonce {last}")]
+    Replayed { nonce: u64, last: u64 },
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Rejects replayed advance inputs using a per-submitter monotonic nonce
+pub struct NonceUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> NonceUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Accept `nonce` only if it is strictly greater than the submitter's last nonce
+    pub fn execute(&self, submitter: &str, nonce: Option<u64>) -> Result<(), Box<dyn Error>> {
+        let nonce = nonce.ok_or(NonceError::Missing)?;
+
+        let accepted = self
+            .repository
+            .advance_nonce(submitter, nonce)
+            .map_err(|e| Box::new(NonceError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        if !accepted {
+            let last = self
+                .repository
+                .find_last_nonce(submitter)
+                .map_err(|e| Box::new(NonceError::DatabaseError(e.to_string())) as Box<dyn Error>)?
+                .unwrap_or_default();
+            return Err(Box::new(NonceError::Replayed { nonce, last }));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::SqliteRepository;
+
+    #[test]
+    fn test_nonce_is_required() {
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        let usecase = NonceUseCase::new(&repo);
+
+        let result = usecase.execute("0x123", None);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Nonce is required"));
+    }
+}
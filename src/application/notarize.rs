@@ -1,8 +1,60 @@
-use crate::domain::{Document, NotarizationReceipt};
-use crate::infrastructure::database::DocumentRepository;
-use std::error::Error;
+use super::config::Config;
+use crate::domain::{
+    compute_blake2b, compute_cid, recover_personal_signer, Clock, ContentHash, Document,
+    DocumentId, EthAddress, NotarizationReceipt, SignatureError, SystemClock,
+};
+use crate::error::NotaryError;
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use crate::infrastructure::metrics;
+use serde::Serialize;
+use std::collections::HashSet;
 use thiserror::Error;
 
+/// Largest decoded document accepted when no limit is configured
+pub const DEFAULT_MAX_CONTENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Most tags a single document can carry
+pub const MAX_TAGS_PER_DOCUMENT: usize = 10;
+
+/// Longest accepted tag, in characters
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// Longest accepted document description, in characters
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+/// Longest accepted filename, in characters
+pub const MAX_FILENAME_LENGTH: usize = 255;
+
+/// Estimated seconds per block, used to turn `expiration_blocks` into a timestamp
+pub const SECONDS_PER_BLOCK: i64 = 12;
+
+/// MIME types accepted when no explicit allowlist is configured
+pub const DEFAULT_ALLOWED_MIME_TYPES: &[&str] = &[
+    // Documents
+    "application/pdf",
+    "application/rtf",
+    "text/plain",
+    "text/markdown",
+    "text/csv",
+    "application/json",
+    // Images
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/tiff",
+    // Office formats
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/vnd.oasis.opendocument.presentation",
+];
+
 #[derive(Error, Debug)]
 pub enum NotarizeError {
     #[error("Content cannot be empty")]
@@ -11,20 +63,449 @@ pub enum NotarizeError {
     #[error("Filename cannot be empty")]
     EmptyFilename,
 
+    #[error("Invalid filename: {0}")]
+    InvalidFilename(String),
+
+    #[error("Invalid submitter address {address}: {reason}")]
+    InvalidSubmitter { address: String, reason: String },
+
     #[error("Document with this content hash already exists")]
-    DuplicateDocument,
+    DuplicateDocument { original: OriginalNotarization },
+
+    #[error("Expiry timestamp must be in the future")]
+    ExpiryInPast,
+
+    #[error("Superseded document not found: {0}")]
+    SupersededNotFound(String),
+
+    #[error("Document {0} is already superseded by a later version")]
+    AlreadySuperseded(String),
+
+    #[error("Set either expires_at or expiration_blocks, not both")]
+    ConflictingExpiry,
+
+    #[error("Expiration of {0} blocks is out of range")]
+    ExpirationOutOfRange(u64),
+
+    #[error("Idempotency key {0} was already used for a different document")]
+    IdempotencyKeyReused(String),
+
+    #[error("MIME type not allowed: {0}")]
+    DisallowedMimeType(String),
+
+    #[error("Content size {actual} bytes exceeds limit of {limit} bytes")]
+    ContentTooLarge { actual: usize, limit: usize },
+
+    #[error("Invalid tag '{0}': use 1-32 letters, digits, '-' or '_'")]
+    InvalidTag(String),
+
+    #[error("{count} tags exceed limit of {limit}")]
+    TooManyTags { count: usize, limit: usize },
+
+    #[error("Submitter {0} is not allowed to notarize")]
+    Unauthorized(String),
+
+    #[error("Description of {length} characters exceeds limit of {limit}")]
+    DescriptionTooLong { length: usize, limit: usize },
+
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(#[from] SignatureError),
+
+    #[error("Signature was made by {recovered}, not the submitter {expected}")]
+    SignatureMismatch { recovered: String, expected: String },
+
+    #[error("Signed message does not contain the content hash {content_hash}")]
+    SignedMessageMismatch { content_hash: String },
+
+    #[error("Submitter {submitter} already notarized {used} of {limit} documents allowed in epoch {epoch}")]
+    QuotaExceeded {
+        submitter: String,
+        epoch: u64,
+        used: u64,
+        limit: u64,
+    },
 
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
 
-pub struct NotarizeUseCase {
-    repository: Box<dyn DocumentRepository>,
+/// How an `AllowDenyList` treats the addresses it names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowDenyMode {
+    /// Only the listed addresses may notarize
+    AllowOnly,
+    /// Every address may notarize except the listed ones
+    DenyAll,
+}
+
+/// Submitter access control for notarization; an empty list restricts no one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowDenyList {
+    pub mode: AllowDenyMode,
+    /// Lowercase addresses
+    pub addresses: HashSet<String>,
 }
 
-impl NotarizeUseCase {
-    pub fn new(repository: Box<dyn DocumentRepository>) -> Self {
-        Self { repository }
+impl AllowDenyList {
+    pub fn new<I, S>(mode: AllowDenyMode, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            mode,
+            addresses: addresses
+                .into_iter()
+                .map(|address| address.as_ref().trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Whether `address` may notarize; compared case-insensitively
+    pub fn permits(&self, address: &str) -> bool {
+        if self.addresses.is_empty() {
+            return true;
+        }
+
+        let listed = self.addresses.contains(&address.to_lowercase());
+        match self.mode {
+            AllowDenyMode::AllowOnly => listed,
+            AllowDenyMode::DenyAll => !listed,
+        }
+    }
+}
+
+/// Validation policy applied to every notarization
+#[derive(Debug, Clone)]
+pub struct NotarizeConfig {
+    /// Accepted MIME types (lowercase, without parameters); `None` accepts any type
+    pub allowed_mime_types: Option<HashSet<String>>,
+    /// Maximum decoded content size in bytes; `None` disables the check
+    pub max_content_bytes: Option<usize>,
+    /// Persist the raw content so the owner can download it again
+    pub store_content: bool,
+    /// Record the IPFS CIDv1 of each document's content
+    pub generate_ipfs_cid: bool,
+    /// Record the Blake2b-256 digest of each document's content
+    pub enable_blake2b: bool,
+    /// Addresses allowed or denied notarization; `None` lets anyone notarize
+    pub submitter_access: Option<AllowDenyList>,
+    /// Documents one submitter may notarize per epoch; `None` disables the quota
+    pub max_per_submitter_per_epoch: Option<u64>,
+}
+
+impl Default for NotarizeConfig {
+    fn default() -> Self {
+        Self {
+            allowed_mime_types: Some(
+                DEFAULT_ALLOWED_MIME_TYPES
+                    .iter()
+                    .map(|mime| mime.to_string())
+                    .collect(),
+            ),
+            max_content_bytes: Some(DEFAULT_MAX_CONTENT_BYTES),
+            store_content: false,
+            generate_ipfs_cid: false,
+            enable_blake2b: false,
+            submitter_access: None,
+            max_per_submitter_per_epoch: None,
+        }
+    }
+}
+
+impl NotarizeConfig {
+    /// Accept documents of any MIME type
+    pub fn allow_all() -> Self {
+        Self {
+            allowed_mime_types: None,
+            ..Self::default()
+        }
+    }
+
+    /// Build from a configured allowlist; `None` keeps the default list and `*` allows any type
+    pub fn from_allowlist(allowlist: Option<&[String]>) -> Self {
+        match allowlist {
+            None => Self::default(),
+            Some(types) if types.iter().any(|mime| mime == "*") => Self::allow_all(),
+            Some(types) => Self {
+                allowed_mime_types: Some(types.iter().map(|mime| normalize_mime(mime)).collect()),
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Replace the content size limit
+    pub fn with_max_content_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_content_bytes = limit;
+        self
+    }
+
+    /// Enable or disable retention of the raw content
+    pub fn with_store_content(mut self, store_content: bool) -> Self {
+        self.store_content = store_content;
+        self
+    }
+
+    /// Enable or disable IPFS CID generation
+    pub fn with_generate_ipfs_cid(mut self, generate_ipfs_cid: bool) -> Self {
+        self.generate_ipfs_cid = generate_ipfs_cid;
+        self
+    }
+
+    /// Enable or disable Blake2b-256 digests
+    pub fn with_enable_blake2b(mut self, enable_blake2b: bool) -> Self {
+        self.enable_blake2b = enable_blake2b;
+        self
+    }
+
+    /// Restrict which submitters may notarize
+    pub fn with_submitter_access(mut self, submitter_access: Option<AllowDenyList>) -> Self {
+        self.submitter_access = submitter_access;
+        self
+    }
+
+    /// Cap the documents one submitter may notarize per epoch
+    pub fn with_max_per_submitter_per_epoch(mut self, limit: Option<u64>) -> Self {
+        self.max_per_submitter_per_epoch = limit;
+        self
+    }
+
+    /// Reject content larger than the configured limit
+    pub fn check_content_size(&self, actual: usize) -> Result<(), NotarizeError> {
+        match self.max_content_bytes {
+            Some(limit) if actual > limit => Err(NotarizeError::ContentTooLarge { actual, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the size limit to base64 content before decoding it
+    /// Uses the decoded length implied by the encoding (3 bytes per 4 characters)
+    pub fn check_encoded_content_size(&self, encoded: &str) -> Result<(), NotarizeError> {
+        let unpadded = encoded.trim_end_matches('=').len();
+        self.check_content_size(unpadded * 3 / 4)
+    }
+
+    pub(crate) fn is_mime_allowed(&self, mime_type: &str) -> bool {
+        match &self.allowed_mime_types {
+            Some(allowed) => allowed.contains(&normalize_mime(mime_type)),
+            None => true,
+        }
+    }
+}
+
+impl From<&Config> for NotarizeConfig {
+    fn from(config: &Config) -> Self {
+        Self::from_allowlist(config.allowed_mime_types.as_deref())
+            .with_max_content_bytes(Some(config.max_content_bytes))
+            .with_store_content(config.store_content)
+            .with_generate_ipfs_cid(config.generate_ipfs_cid)
+            .with_enable_blake2b(config.enable_blake2b)
+            .with_submitter_access(config.submitter_access())
+            .with_max_per_submitter_per_epoch(config.max_per_submitter_per_epoch)
+    }
+}
+
+/// Lowercase and trim a tag, as it is stored and looked up
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normalize tags, dropping duplicates while keeping the first occurrence order
+fn validate_tags(tags: &[String]) -> Result<Vec<String>, NotarizeError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+
+    for tag in tags {
+        let tag = normalize_tag(tag);
+        let valid = !tag.is_empty()
+            && tag.chars().count() <= MAX_TAG_LENGTH
+            && tag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if !valid {
+            return Err(NotarizeError::InvalidTag(tag));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    if normalized.len() > MAX_TAGS_PER_DOCUMENT {
+        return Err(NotarizeError::TooManyTags {
+            count: normalized.len(),
+            limit: MAX_TAGS_PER_DOCUMENT,
+        });
+    }
+
+    Ok(normalized)
+}
+
+/// Reject filenames that are blank, overlong, or could be read as a path
+/// Any other Unicode is kept as submitted
+pub fn validate_filename(file_name: &str) -> Result<(), NotarizeError> {
+    if file_name.trim().is_empty() {
+        return Err(NotarizeError::EmptyFilename);
+    }
+
+    let length = file_name.chars().count();
+    if length > MAX_FILENAME_LENGTH {
+        return Err(NotarizeError::InvalidFilename(format!(
+            "{} characters exceed limit of {}",
+            length, MAX_FILENAME_LENGTH
+        )));
+    }
+
+    if file_name.contains(['/', '\\']) {
+        return Err(NotarizeError::InvalidFilename(
+            "path separators are not allowed".to_string(),
+        ));
+    }
+
+    if file_name.contains('\0') {
+        return Err(NotarizeError::InvalidFilename(
+            "null bytes are not allowed".to_string(),
+        ));
+    }
+
+    if let Some(c) = file_name.chars().find(|c| c.is_control()) {
+        return Err(NotarizeError::InvalidFilename(format!(
+            "control character U+{:04X} is not allowed",
+            c as u32
+        )));
+    }
+
+    if matches!(file_name.trim(), "." | "..") {
+        return Err(NotarizeError::InvalidFilename(
+            "relative path components are not allowed".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Trim a description, treating a blank one as absent
+fn validate_description(description: Option<&str>) -> Result<Option<String>, NotarizeError> {
+    let Some(description) = description.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+
+    let length = description.chars().count();
+    if length > MAX_DESCRIPTION_LENGTH {
+        return Err(NotarizeError::DescriptionTooLong {
+            length,
+            limit: MAX_DESCRIPTION_LENGTH,
+        });
+    }
+
+    Ok(Some(description.to_string()))
+}
+
+/// Pick the expiry timestamp from either an explicit time or a block count
+fn resolve_expiry(
+    options: &NotarizeOptions,
+    created_at: i64,
+) -> Result<Option<i64>, NotarizeError> {
+    match (options.expires_at, options.expiration_blocks) {
+        (Some(_), Some(_)) => Err(NotarizeError::ConflictingExpiry),
+        (Some(expires_at), None) => Ok(Some(expires_at)),
+        (None, Some(blocks)) => i64::try_from(blocks)
+            .ok()
+            .and_then(|b| b.checked_mul(SECONDS_PER_BLOCK))
+            .and_then(|secs| created_at.checked_add(secs))
+            .map(Some)
+            .ok_or(NotarizeError::ExpirationOutOfRange(blocks)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Lowercase a MIME type and strip parameters such as `; charset=utf-8`
+fn normalize_mime(mime_type: &str) -> String {
+    mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// The earlier notarization a duplicate submission collides with
+/// Metadata only: the retained content and description are never included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OriginalNotarization {
+    pub document_id: DocumentId,
+    pub submitted_by: String,
+    #[serde(with = "crate::domain::rfc3339")]
+    pub created_at: i64,
+}
+
+impl From<&Document> for OriginalNotarization {
+    fn from(document: &Document) -> Self {
+        Self {
+            document_id: document.id.clone(),
+            submitted_by: document.submitted_by.clone(),
+            created_at: document.created_at,
+        }
+    }
+}
+
+/// Outcome of previewing a notarization, sent as a Cartesi Report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NotarizePreview {
+    pub content_hash: ContentHash,
+    /// True when a document with this hash is already notarized
+    pub would_duplicate: bool,
+}
+
+/// Optional attributes of a single notarization
+#[derive(Debug, Clone, Default)]
+pub struct NotarizeOptions {
+    /// Unix timestamp after which the notarization expires
+    pub expires_at: Option<i64>,
+    /// Lifetime in blocks, converted to `expires_at` with [`SECONDS_PER_BLOCK`]
+    pub expiration_blocks: Option<u64>,
+    /// Category tags, e.g. `contract` or `invoice`
+    pub tags: Vec<String>,
+    /// Content hash of the earlier version this document amends
+    pub supersedes: Option<String>,
+    /// Client-chosen key; a retry with the same key returns the original receipt
+    pub idempotency_key: Option<String>,
+    /// Human-readable description stored with the document
+    pub description: Option<String>,
+    /// Time recorded for the notarization, normally the advance input's block timestamp
+    /// Falls back to the use case's clock, the host clock unless `with_clock` replaced it,
+    /// which is only acceptable outside the rollup
+    pub notarized_at: Option<i64>,
+    /// Record an already notarized content under `file_name` as an alias, returning the
+    /// original receipt, instead of rejecting it as a duplicate
+    pub allow_alias: bool,
+    /// Epoch and index of the advance input carrying the notarization; 0 outside the rollup
+    pub epoch_index: u64,
+    pub input_index: u64,
+    /// Hex `personal_sign` signature of `signed_message` by the submitter's wallet
+    /// Checked only when both are given; the message must contain the content hash
+    pub signature: Option<String>,
+    pub signed_message: Option<String>,
+}
+
+pub struct NotarizeUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    config: NotarizeConfig,
+    clock: &'a dyn Clock,
+}
+
+impl<'a> NotarizeUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository, config: NotarizeConfig) -> Self {
+        Self {
+            repository,
+            config,
+            clock: &SystemClock,
+        }
+    }
+
+    /// Read the time from `clock` when `NotarizeOptions::notarized_at` is not set
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = clock;
+        self
     }
 
     pub fn execute(
@@ -34,39 +515,402 @@ impl NotarizeUseCase {
         mime_type: &str,
         submitted_by: &str,
         block_number: u64,
-    ) -> Result<NotarizationReceipt, Box<dyn Error>> {
-        // Validate inputs
-        if content.is_empty() {
-            return Err(Box::new(NotarizeError::EmptyContent));
+    ) -> Result<NotarizationReceipt, NotaryError> {
+        self.execute_with_expiry(
+            content,
+            file_name,
+            mime_type,
+            submitted_by,
+            block_number,
+            None,
+        )
+    }
+
+    /// Notarize a document that is only valid until `expires_at` (Unix timestamp)
+    pub fn execute_with_expiry(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        block_number: u64,
+        expires_at: Option<i64>,
+    ) -> Result<NotarizationReceipt, NotaryError> {
+        let options = NotarizeOptions {
+            expires_at,
+            ..NotarizeOptions::default()
+        };
+
+        self.execute_with_options(
+            content,
+            file_name,
+            mime_type,
+            submitted_by,
+            block_number,
+            &options,
+        )
+    }
+
+    /// Notarize a document with an optional expiry, tags and earlier version
+    /// Tags are stored after the document, normalized to lowercase
+    pub fn execute_with_options(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        block_number: u64,
+        options: &NotarizeOptions,
+    ) -> Result<NotarizationReceipt, NotaryError> {
+        if let Some(key) = &options.idempotency_key {
+            if let Some(receipt) = self.find_prior_receipt(key, content, submitted_by)? {
+                return Ok(receipt);
+            }
         }
 
-        if file_name.trim().is_empty() {
-            return Err(Box::new(NotarizeError::EmptyFilename));
+        if options.allow_alias {
+            if let Some(receipt) = self.record_alias(content, file_name, submitted_by, options)? {
+                return Ok(receipt);
+            }
         }
 
-        // Create document entity (generates hash and ID)
-        let document = Document::new(content, file_name, mime_type, submitted_by);
+        let (document, tags) =
+            self.prepare(content, file_name, mime_type, submitted_by, options)?;
 
-        // Check for duplicate hash
-        if self.repository.find_by_hash(&document.content_hash).is_ok() {
-            return Err(Box::new(NotarizeError::DuplicateDocument));
+        // Save document to repository, with its content when retention is enabled
+        let saved = if self.config.store_content {
+            self.repository
+                .save_document_with_content(&document, content)
+        } else {
+            self.repository.save_document(&document)
+        };
+
+        saved.map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
+
+        if self.config.max_per_submitter_per_epoch.is_some() {
+            self.repository
+                .increment_quota_usage(submitted_by, options.epoch_index)
+                .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
         }
 
-        // Save document to repository
-        self.repository
-            .save_document(&document)
-            .map_err(|e| Box::new(NotarizeError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+        if !tags.is_empty() {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            self.repository
+                .add_tags(&document.id, &tags)
+                .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
+        }
+
+        if let Some(key) = &options.idempotency_key {
+            self.repository
+                .save_idempotency_key(key, submitted_by, &document.id, block_number)
+                .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
+        }
+
+        metrics::NOTARIZATIONS_TOTAL.inc();
 
         // Generate notarization receipt
         let receipt = NotarizationReceipt::new(
-            document.id.clone(),
-            document.content_hash.clone(),
+            document.id.to_string(),
+            document.content_hash.to_string(),
             document.created_at,
             block_number,
-        );
+        )
+        .with_committed_at(self.committed_at(&document.content_hash)?);
 
         Ok(receipt)
     }
+
+    /// Block at which `content_hash` was committed ahead of notarization, if it was
+    fn committed_at(&self, content_hash: &ContentHash) -> Result<Option<u64>, NotaryError> {
+        self.repository
+            .find_commitment(content_hash)
+            .map(|commitment| commitment.map(|c| c.block_number))
+            .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))
+    }
+
+    /// Hash `content` and check it against stored documents, without saving anything
+    /// Only emptiness and size are validated; the sender is unknown to an inspect
+    pub fn preview(&self, content: &[u8]) -> Result<NotarizePreview, NotaryError> {
+        if content.is_empty() {
+            return Err(NotaryError::from(NotarizeError::EmptyContent));
+        }
+        self.config.check_content_size(content.len())?;
+
+        let content_hash = Document::hash_content(content);
+        let would_duplicate = match self.repository.find_by_hash(&content_hash) {
+            Ok(_) => true,
+            Err(DatabaseError::NotFound) => false,
+            Err(e) => {
+                return Err(NotaryError::from(NotarizeError::DatabaseError(
+                    e.to_string(),
+                )))
+            }
+        };
+
+        Ok(NotarizePreview {
+            content_hash,
+            would_duplicate,
+        })
+    }
+
+    /// Run every check `execute_with_options` applies, without saving anything
+    /// Returns the document's content hash
+    pub fn validate(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        options: &NotarizeOptions,
+    ) -> Result<String, NotaryError> {
+        self.prepare(content, file_name, mime_type, submitted_by, options)
+            .map(|(document, _)| document.content_hash.into())
+    }
+
+    /// Validate a notarization and build its document, with the normalized tags
+    fn prepare(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        options: &NotarizeOptions,
+    ) -> Result<(Document, Vec<String>), NotaryError> {
+        // Validate inputs
+        if content.is_empty() {
+            return Err(NotaryError::from(NotarizeError::EmptyContent));
+        }
+
+        self.check_submission(file_name, submitted_by)?;
+
+        // Reject oversized content before hashing it
+        self.config.check_content_size(content.len())?;
+
+        if !self.config.is_mime_allowed(mime_type) {
+            return Err(NotaryError::from(NotarizeError::DisallowedMimeType(
+                mime_type.to_string(),
+            )));
+        }
+
+        let tags = validate_tags(&options.tags)?;
+        let description = validate_description(options.description.as_deref())?;
+
+        // Create document entity (generates hash and ID)
+        let notarized_at = options.notarized_at.unwrap_or_else(|| self.clock.now());
+        let document = Document::new_at(content, file_name, mime_type, submitted_by, notarized_at);
+        let signed = self.check_signature(submitted_by, document.content_hash.as_str(), options)?;
+        let expires_at = resolve_expiry(options, document.created_at)?;
+        let document = document
+            .with_expiry(expires_at)
+            .with_supersedes(options.supersedes.clone())
+            .with_description(description)
+            .with_wallet_signature(signed)
+            .with_input(options.epoch_index, options.input_index)
+            .with_ipfs_cid(self.config.generate_ipfs_cid.then(|| compute_cid(content)))
+            .with_blake2b_hash(self.config.enable_blake2b.then(|| compute_blake2b(content)));
+
+        // Reject expiries that have already passed
+        if document.is_expired_at(document.created_at) {
+            return Err(NotaryError::from(NotarizeError::ExpiryInPast));
+        }
+
+        // Check for duplicate hash
+        if let Ok(original) = self.repository.find_by_hash(&document.content_hash) {
+            metrics::DUPLICATE_REJECTIONS_TOTAL.inc();
+            return Err(NotaryError::from(NotarizeError::DuplicateDocument {
+                original: OriginalNotarization::from(&original),
+            }));
+        }
+
+        if let Some(previous) = &options.supersedes {
+            self.check_supersedes(previous)?;
+        }
+
+        self.check_quota(submitted_by, options.epoch_index)?;
+
+        Ok((document, tags))
+    }
+
+    /// Reject a new document once `submitted_by` used up their quota for `epoch`
+    fn check_quota(&self, submitted_by: &str, epoch: u64) -> Result<(), NotaryError> {
+        let Some(limit) = self.config.max_per_submitter_per_epoch else {
+            return Ok(());
+        };
+
+        let used = self
+            .repository
+            .find_quota_usage(submitted_by, epoch)
+            .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
+
+        if used >= limit {
+            return Err(NotaryError::from(NotarizeError::QuotaExceeded {
+                submitter: submitted_by.to_lowercase(),
+                epoch,
+                used,
+                limit,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Reject a wallet signature that was not made by `submitted_by` over a message naming
+    /// `content_hash`, so a signature of something else cannot be replayed onto this document
+    /// Returns the checked signature and message, to be kept with the document
+    fn check_signature(
+        &self,
+        submitted_by: &str,
+        content_hash: &str,
+        options: &NotarizeOptions,
+    ) -> Result<Option<(String, String)>, NotaryError> {
+        let (Some(signature), Some(message)) = (&options.signature, &options.signed_message) else {
+            return Ok(None);
+        };
+
+        if !message.to_lowercase().contains(content_hash) {
+            return Err(NotaryError::from(NotarizeError::SignedMessageMismatch {
+                content_hash: content_hash.to_string(),
+            }));
+        }
+
+        let recovered = recover_personal_signer(message, signature).map_err(NotarizeError::from)?;
+        let expected = submitted_by.to_lowercase();
+        if recovered.as_str() != expected {
+            return Err(NotaryError::from(NotarizeError::SignatureMismatch {
+                recovered: recovered.as_str().to_string(),
+                expected,
+            }));
+        }
+
+        Ok(Some((signature.clone(), message.clone())))
+    }
+
+    /// Filename and submitter checks shared by new documents and aliases
+    fn check_submission(&self, file_name: &str, submitted_by: &str) -> Result<(), NotaryError> {
+        validate_filename(file_name)?;
+
+        if let Err(e) = EthAddress::parse(submitted_by) {
+            return Err(NotaryError::from(NotarizeError::InvalidSubmitter {
+                address: submitted_by.to_string(),
+                reason: e.to_string(),
+            }));
+        }
+
+        if let Some(access) = &self.config.submitter_access {
+            if !access.permits(submitted_by) {
+                return Err(NotaryError::from(NotarizeError::Unauthorized(
+                    submitted_by.to_string(),
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Link `file_name` to the document already notarizing `content` and return its receipt
+    /// `None` when the content is not notarized yet. The original block number is not
+    /// stored with the document, so the receipt carries 0 as verification results do.
+    fn record_alias(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        submitted_by: &str,
+        options: &NotarizeOptions,
+    ) -> Result<Option<NotarizationReceipt>, NotaryError> {
+        let to_db_error =
+            |e: DatabaseError| NotaryError::from(NotarizeError::DatabaseError(e.to_string()));
+
+        let document = match self
+            .repository
+            .find_by_hash(&Document::hash_content(content))
+        {
+            Ok(document) => document,
+            Err(DatabaseError::NotFound) => return Ok(None),
+            Err(e) => return Err(to_db_error(e)),
+        };
+
+        self.check_submission(file_name, submitted_by)?;
+        self.check_signature(submitted_by, document.content_hash.as_str(), options)?;
+
+        let aliased_at = options.notarized_at.unwrap_or_else(|| self.clock.now());
+        // Resubmitting under the document's own name records nothing new
+        if file_name != document.file_name {
+            self.repository
+                .add_alias(&document.id, file_name, submitted_by, aliased_at)
+                .map_err(to_db_error)?;
+        }
+
+        let committed_at = self.committed_at(&document.content_hash)?;
+        Ok(Some(
+            NotarizationReceipt::new(
+                document.id.into(),
+                document.content_hash.into(),
+                document.created_at,
+                0,
+            )
+            .with_committed_at(committed_at),
+        ))
+    }
+
+    /// Rebuild the receipt of a request already processed under `key` for this submitter
+    /// The key must have been used for the same content, so it cannot return a stranger's receipt
+    fn find_prior_receipt(
+        &self,
+        key: &str,
+        content: &[u8],
+        submitted_by: &str,
+    ) -> Result<Option<NotarizationReceipt>, NotaryError> {
+        let to_db_error =
+            |e: DatabaseError| NotaryError::from(NotarizeError::DatabaseError(e.to_string()));
+
+        let Some((document_id, block_number)) = self
+            .repository
+            .find_idempotency_key(key, submitted_by)
+            .map_err(to_db_error)?
+        else {
+            return Ok(None);
+        };
+
+        let document = self
+            .repository
+            .find_by_id(&document_id)
+            .map_err(to_db_error)?;
+        if document.content_hash != Document::hash_content(content) {
+            return Err(NotaryError::from(NotarizeError::IdempotencyKeyReused(
+                key.to_string(),
+            )));
+        }
+
+        let committed_at = self.committed_at(&document.content_hash)?;
+        Ok(Some(
+            NotarizationReceipt::new(
+                document.id.into(),
+                document.content_hash.into(),
+                document.created_at,
+                block_number,
+            )
+            .with_committed_at(committed_at),
+        ))
+    }
+
+    /// A new version may only amend an existing document that has no later version yet,
+    /// which keeps every chain linear and free of cycles
+    fn check_supersedes(&self, previous: &str) -> Result<(), NotaryError> {
+        let versions = self
+            .repository
+            .find_versions(&ContentHash::from(previous))
+            .map_err(|e| NotaryError::from(NotarizeError::DatabaseError(e.to_string())))?;
+
+        match versions.len() {
+            0 => Err(NotaryError::from(NotarizeError::SupersededNotFound(
+                previous.to_string(),
+            ))),
+            1 => Ok(()),
+            _ => Err(NotaryError::from(NotarizeError::AlreadySuperseded(
+                previous.to_string(),
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -77,6 +921,31 @@ mod tests {
     #[test]
     fn test_notarize_usecase_creation() {
         let repo = SqliteRepository::new_in_memory().unwrap();
-        let _usecase = NotarizeUseCase::new(Box::new(repo));
+        let _usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+    }
+
+    #[test]
+    fn test_mime_allowlist_normalizes_types() {
+        let config = NotarizeConfig::default();
+        assert!(config.is_mime_allowed("application/pdf"));
+        assert!(config.is_mime_allowed("Text/Plain; charset=utf-8"));
+        assert!(!config.is_mime_allowed("text/html"));
+
+        assert!(NotarizeConfig::allow_all().is_mime_allowed("text/html"));
+        assert!(NotarizeConfig::from_allowlist(Some(&["*".to_string()])).is_mime_allowed("x/y"));
+    }
+
+    #[test]
+    fn test_encoded_size_matches_decoded_length() {
+        use base64::Engine;
+        let config = NotarizeConfig::default().with_max_content_bytes(Some(5));
+
+        for len in 0..=8 {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(vec![0u8; len]);
+            assert_eq!(
+                config.check_encoded_content_size(&encoded).is_ok(),
+                len <= 5
+            );
+        }
     }
 }
@@ -0,0 +1,75 @@
+use super::verify::{VerificationResult, VerifyUseCase};
+use crate::infrastructure::cartesi::{send_gio, Endpoints, GioResponse};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProvenanceError {
+    #[error("Provenance oracle is not configured")]
+    NotConfigured,
+
+    #[error("Oracle request failed: {0}")]
+    OracleUnavailable(String),
+
+    #[error("Oracle returned code {0}")]
+    OracleRejected(u16),
+}
+
+/// Local verification result together with the oracle's answer
+#[derive(Debug, Clone)]
+pub struct ProvenanceResult {
+    pub verification: VerificationResult,
+    pub oracle: Option<GioResponse>,
+    /// Set when the oracle could not be reached or answered with a non-success code
+    pub error: Option<String>,
+}
+
+/// Checks a document against an external provenance oracle reached through GIO
+pub struct ProvenanceUseCase<'a> {
+    verify: VerifyUseCase<'a>,
+    client: &'a hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &'a Endpoints,
+    domain: Option<u16>,
+}
+
+impl<'a> ProvenanceUseCase<'a> {
+    pub fn new(
+        verify: VerifyUseCase<'a>,
+        client: &'a hyper::Client<hyper::client::HttpConnector>,
+        endpoints: &'a Endpoints,
+        domain: Option<u16>,
+    ) -> Self {
+        Self {
+            verify,
+            client,
+            endpoints,
+            domain,
+        }
+    }
+
+    pub async fn execute(&self, content_hash: &str) -> Result<ProvenanceResult, Box<dyn Error>> {
+        let domain = self.domain.ok_or(ProvenanceError::NotConfigured)?;
+
+        // Validates the hash format before anything leaves the machine
+        let verification = self.verify.execute(content_hash)?;
+
+        let id_hex = format!("0x{}", content_hash.to_lowercase());
+        let (oracle, error) = match send_gio(self.client, self.endpoints, domain, &id_hex).await {
+            Ok(response) if response.is_success() => (Some(response), None),
+            Ok(response) => {
+                let error = ProvenanceError::OracleRejected(response.code).to_string();
+                (Some(response), Some(error))
+            }
+            Err(e) => (
+                None,
+                Some(ProvenanceError::OracleUnavailable(e.to_string()).to_string()),
+            ),
+        };
+
+        Ok(ProvenanceResult {
+            verification,
+            oracle,
+            error,
+        })
+    }
+}
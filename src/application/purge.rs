@@ -0,0 +1,95 @@
+use crate::domain::{ContentHash, EthAddress};
+use crate::infrastructure::database::DocumentRepository;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PurgeError {
+    #[error("Purge is disabled: no admin address configured")]
+    AdminNotConfigured,
+
+    #[error("Only the admin can purge documents")]
+    Unauthorized,
+
+    #[error("Document not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Record of a hard-deleted document, emitted as a notice
+/// Only the hash is kept so the erasure is auditable without retaining metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeRecord {
+    pub content_hash: String,
+    pub purged_by: String,
+    pub purged_at: i64,
+    pub block_number: u64,
+}
+
+pub struct PurgeUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    admin_address: Option<&'a str>,
+}
+
+impl<'a> PurgeUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository, admin_address: Option<&'a str>) -> Self {
+        Self {
+            repository,
+            admin_address,
+        }
+    }
+
+    pub fn execute(
+        &self,
+        content_hash: &str,
+        caller: &str,
+        block_number: u64,
+        timestamp: i64,
+    ) -> Result<PurgeRecord, Box<dyn Error>> {
+        let admin = self.admin_address.ok_or(PurgeError::AdminNotConfigured)?;
+
+        // Compare parsed addresses so checksummed and lowercase forms match
+        let is_admin = match (EthAddress::parse(admin), EthAddress::parse(caller)) {
+            (Ok(admin), Ok(caller)) => admin == caller,
+            _ => false,
+        };
+
+        if !is_admin {
+            return Err(Box::new(PurgeError::Unauthorized));
+        }
+
+        let content_hash = ContentHash::from(content_hash);
+        if self.repository.find_by_hash(&content_hash).is_err() {
+            return Err(Box::new(PurgeError::NotFound));
+        }
+
+        self.repository
+            .delete_by_hash(&content_hash)
+            .map_err(|e| Box::new(PurgeError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        Ok(PurgeRecord {
+            content_hash: content_hash.into(),
+            purged_by: caller.to_string(),
+            purged_at: timestamp,
+            block_number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::SqliteRepository;
+
+    #[test]
+    fn test_purge_disabled_without_admin() {
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        let usecase = PurgeUseCase::new(&repo, None);
+
+        let result = usecase.execute("hash", "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", 1, 0);
+        assert!(result.unwrap_err().to_string().contains("disabled"));
+    }
+}
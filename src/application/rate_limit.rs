@@ -0,0 +1,76 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RateLimitError {
+    #[error("RATE_LIMITED: {sender} exceeded {max_per_block} inputs in block {block_number}")]
+    Exceeded {
+        sender: String,
+        max_per_block: u32,
+        block_number: u64,
+    },
+}
+
+/// Sliding-window limit on advance inputs per `msg_sender`
+/// Each sender's accepted inputs are remembered by block number; inputs from
+/// earlier blocks slide out of the window. Clones share the same counters
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    max_per_block: u32,
+    windows: Arc<Mutex<HashMap<String, VecDeque<u64>>>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_block: u32) -> Self {
+        Self {
+            max_per_block,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn max_per_block(&self) -> u32 {
+        self.max_per_block
+    }
+
+    /// Record an input from `sender` in `block_number` if the sender is still under the limit
+    /// Rejected inputs are not recorded
+    pub fn allow(&self, sender: &str, block_number: u64) -> bool {
+        // Counters stay consistent even if another thread panicked while holding the lock
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        let window = windows.entry(sender.to_string()).or_default();
+
+        while window.front().is_some_and(|&block| block < block_number) {
+            window.pop_front();
+        }
+
+        if window.len() >= self.max_per_block as usize {
+            return false;
+        }
+
+        window.push_back(block_number);
+        true
+    }
+
+    /// Like `allow`, but explains a rejection
+    pub fn check(&self, sender: &str, block_number: u64) -> Result<(), RateLimitError> {
+        if self.allow(sender, block_number) {
+            Ok(())
+        } else {
+            Err(RateLimitError::Exceeded {
+                sender: sender.to_string(),
+                max_per_block: self.max_per_block,
+                block_number,
+            })
+        }
+    }
+}
+
+/// Limiters are equal when configured alike; recorded inputs are not compared
+impl PartialEq for RateLimiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_per_block == other.max_per_block
+    }
+}
+
+impl Eq for RateLimiter {}
@@ -0,0 +1,172 @@
+use super::notarize::NotarizeConfig;
+use crate::domain::{compute_blake2b, compute_cid, Document, DocumentId, EthAddress};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use serde::Serialize;
+use std::error::Error;
+use thiserror::Error;
+
+/// Documents visited by one rehash input unless the request asks for fewer
+pub const DEFAULT_REHASH_BATCH: usize = 100;
+
+/// Most documents one rehash input visits, so a single input stays cheap to execute
+pub const MAX_REHASH_BATCH: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum RehashError {
+    #[error("Rehash is disabled: no admin address configured")]
+    AdminNotConfigured,
+
+    #[error("Only the admin can rehash documents")]
+    Unauthorized,
+
+    #[error("Rehash needs retained content; enable NOTARY_STORE_CONTENT")]
+    ContentNotRetained,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Result of one rehash batch, reported to the admin
+#[derive(Debug, Clone, Serialize)]
+pub struct RehashProgress {
+    /// Documents whose missing digests were computed and stored
+    pub rehashed: usize,
+    /// Content hashes of documents left as they were because no content was retained
+    pub skipped: Vec<String>,
+    /// `after` of the next batch; absent once every document lacking a digest was visited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_after: Option<String>,
+}
+
+/// Computes the digests that documents notarized before they existed are missing
+/// Keccak-256 is always filled in; Blake2b-256 and the IPFS CID only when enabled,
+/// mirroring what a notarization records today
+pub struct RehashUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    admin_address: Option<&'a str>,
+    config: NotarizeConfig,
+}
+
+impl<'a> RehashUseCase<'a> {
+    pub fn new(
+        repository: &'a dyn DocumentRepository,
+        admin_address: Option<&'a str>,
+        config: NotarizeConfig,
+    ) -> Self {
+        Self {
+            repository,
+            admin_address,
+            config,
+        }
+    }
+
+    /// Rehash up to `limit` documents ordered by id, starting after the document `after`
+    pub fn execute(
+        &self,
+        caller: &str,
+        after: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<RehashProgress, Box<dyn Error>> {
+        let admin = self.admin_address.ok_or(RehashError::AdminNotConfigured)?;
+
+        // Compare parsed addresses so checksummed and lowercase forms match
+        let is_admin = match (EthAddress::parse(admin), EthAddress::parse(caller)) {
+            (Ok(admin), Ok(caller)) => admin == caller,
+            _ => false,
+        };
+
+        if !is_admin {
+            return Err(Box::new(RehashError::Unauthorized));
+        }
+
+        if !self.config.store_content {
+            return Err(Box::new(RehashError::ContentNotRetained));
+        }
+
+        let limit = limit
+            .unwrap_or(DEFAULT_REHASH_BATCH)
+            .clamp(1, MAX_REHASH_BATCH);
+        let after = after.map(DocumentId::from);
+        let documents = self
+            .repository
+            .find_missing_digests(
+                self.config.enable_blake2b,
+                self.config.generate_ipfs_cid,
+                after.as_ref(),
+                limit,
+            )
+            .map_err(database_error)?;
+
+        let mut progress = RehashProgress {
+            rehashed: 0,
+            skipped: Vec::new(),
+            // A short batch means nothing lacking a digest is left after it
+            next_after: (documents.len() == limit)
+                .then(|| documents.last().map(|doc| doc.id.to_string()))
+                .flatten(),
+        };
+
+        for document in &documents {
+            if self.rehash(document)? {
+                progress.rehashed += 1;
+            } else {
+                progress.skipped.push(document.content_hash.to_string());
+            }
+        }
+
+        Ok(progress)
+    }
+
+    /// Store the digests `document` is missing; `false` when its content was not retained
+    fn rehash(&self, document: &Document) -> Result<bool, Box<dyn Error>> {
+        let content = match self.repository.get_content(&document.id) {
+            Ok(content) => content,
+            Err(DatabaseError::NotFound) => return Ok(false),
+            Err(e) => return Err(database_error(e)),
+        };
+
+        let keccak = document
+            .content_hash_keccak
+            .is_none()
+            .then(|| Document::hash_content_keccak(&content));
+        let blake2b = (self.config.enable_blake2b && document.blake2b_hash.is_none())
+            .then(|| compute_blake2b(&content));
+        let ipfs_cid = (self.config.generate_ipfs_cid && document.ipfs_cid.is_none())
+            .then(|| compute_cid(&content));
+
+        self.repository
+            .update_digests(
+                &document.id,
+                keccak.as_deref(),
+                blake2b.as_deref(),
+                ipfs_cid.as_deref(),
+            )
+            .map_err(database_error)?;
+
+        Ok(true)
+    }
+}
+
+fn database_error(e: DatabaseError) -> Box<dyn Error> {
+    Box::new(RehashError::DatabaseError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::SqliteRepository;
+
+    const ADMIN: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+    #[test]
+    fn test_rehash_requires_retained_content() {
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        let usecase = RehashUseCase::new(&repo, Some(ADMIN), NotarizeConfig::default());
+
+        let result = usecase.execute(ADMIN, None, None);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("NOTARY_STORE_CONTENT"));
+    }
+}
@@ -0,0 +1,341 @@
+use super::notarize::normalize_tag;
+use crate::domain::{is_valid_address, Document, DocumentId};
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::error::Error;
+use thiserror::Error;
+
+/// Results returned when a search does not specify a limit
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Largest number of results a single search can return
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Error, Debug)]
+pub enum SearchError {
+    #[error("Search query cannot be empty")]
+    EmptyQuery,
+
+    #[error("Tag cannot be empty")]
+    EmptyTag,
+
+    #[error("Invalid date range: start {start} is after end {end}")]
+    InvalidDateRange { start: i64, end: i64 },
+
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    #[error("Invalid submitter address: {0}")]
+    InvalidSubmitter(String),
+
+    #[error("Page size must be between 1 and {max}, got {page_size}")]
+    InvalidPageSize { page_size: usize, max: usize },
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Position in the newest-first document listing: the key of the last document seen
+/// Clients treat the encoded form as opaque
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentCursor {
+    pub created_at: i64,
+    pub id: DocumentId,
+}
+
+impl DocumentCursor {
+    pub fn from_document(document: &Document) -> Self {
+        Self {
+            created_at: document.created_at,
+            id: document.id.clone(),
+        }
+    }
+
+    /// Unpadded URL-safe base64 of `<created_at>:<id>`
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.created_at, self.id))
+    }
+
+    /// Parse a cursor produced by `encode`; the id must be a UUID
+    pub fn decode(encoded: &str) -> Result<Self, SearchError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| SearchError::InvalidCursor)?;
+        let text = String::from_utf8(bytes).map_err(|_| SearchError::InvalidCursor)?;
+        let (created_at, id) = text.split_once(':').ok_or(SearchError::InvalidCursor)?;
+
+        let created_at = created_at.parse().map_err(|_| SearchError::InvalidCursor)?;
+        uuid::Uuid::parse_str(id).map_err(|_| SearchError::InvalidCursor)?;
+
+        Ok(Self {
+            created_at,
+            id: DocumentId::from(id),
+        })
+    }
+}
+
+/// One page of the document listing
+#[derive(Debug)]
+pub struct DocumentPage {
+    pub documents: Vec<Document>,
+    /// Cursor for the following page, `None` on the last page
+    pub next_cursor: Option<DocumentCursor>,
+}
+
+impl DocumentPage {
+    /// Build a page from up to `limit + 1` fetched documents; the extra one only
+    /// signals that another page follows
+    fn from_fetched(mut documents: Vec<Document>, limit: usize) -> Self {
+        let next_cursor = if documents.len() > limit {
+            documents.truncate(limit);
+            documents.last().map(DocumentCursor::from_document)
+        } else {
+            None
+        };
+
+        Self {
+            documents,
+            next_cursor,
+        }
+    }
+}
+
+/// Documents created after a sync checkpoint, oldest first
+#[derive(Debug)]
+pub struct SyncBatch {
+    pub documents: Vec<Document>,
+    /// Newest `created_at` in the batch, or the requested checkpoint when the batch is empty
+    pub next_since: i64,
+    /// Whether documents after `next_since` may remain
+    pub has_more: bool,
+}
+
+/// One page of the full registry export, oldest first
+#[derive(Debug)]
+pub struct RegistryPage {
+    pub documents: Vec<Document>,
+    /// Number of documents in the whole registry
+    pub total_count: usize,
+    pub page: usize,
+    pub page_size: usize,
+    /// Whether later pages hold more documents
+    pub has_more: bool,
+}
+
+pub struct SearchUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> SearchUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Find documents by words in their filename, MIME type or tags, best matches first
+    /// `limit` defaults to `DEFAULT_SEARCH_LIMIT` and is capped at `MAX_SEARCH_LIMIT`
+    pub fn execute(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Document>, Box<dyn Error>> {
+        if query.trim().is_empty() {
+            return Err(Box::new(SearchError::EmptyQuery));
+        }
+
+        self.repository
+            .full_text_search(query, clamp_limit(limit))
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    /// Documents carrying `tag` (case-insensitive), newest first
+    /// Uses the same limit defaults as `execute`
+    pub fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<Document>, Box<dyn Error>> {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            return Err(Box::new(SearchError::EmptyTag));
+        }
+
+        self.repository
+            .find_by_tag(&tag, clamp_limit(limit), offset)
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    /// Documents created within `[start, end]` (Unix timestamps), oldest first
+    /// Uses the same limit defaults as `execute`
+    pub fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<Document>, Box<dyn Error>> {
+        if start > end {
+            return Err(Box::new(SearchError::InvalidDateRange { start, end }));
+        }
+
+        self.repository
+            .find_by_date_range(start, end, clamp_limit(limit), offset)
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    /// Notarizations per UTC day within `[start, end]`, as `(midnight, count)` pairs
+    /// Days without notarizations are omitted
+    pub fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, Box<dyn Error>> {
+        if start > end {
+            return Err(Box::new(SearchError::InvalidDateRange { start, end }));
+        }
+
+        self.repository
+            .count_by_day(start, end)
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+
+    /// All documents newest first, one page at a time
+    /// `after` is the encoded cursor of the previous page; uses the same limit defaults as `execute`
+    pub fn list_documents(
+        &self,
+        after: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<DocumentPage, Box<dyn Error>> {
+        let after = after.map(DocumentCursor::decode).transpose()?;
+        let limit = clamp_limit(limit);
+
+        // Fetch one extra row to learn whether another page follows
+        let documents = self
+            .repository
+            .list_documents(
+                after.as_ref().map(|cursor| (cursor.created_at, &cursor.id)),
+                limit + 1,
+            )
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        Ok(DocumentPage::from_fetched(documents, limit))
+    }
+
+    /// Documents owned by `submitter`, newest first, one page at a time
+    /// Paged like `list_documents`, so documents notarized between page fetches are
+    /// neither returned twice nor cause others to be skipped
+    pub fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<DocumentPage, Box<dyn Error>> {
+        if !is_valid_address(submitter) {
+            return Err(Box::new(SearchError::InvalidSubmitter(
+                submitter.to_string(),
+            )));
+        }
+        let after = after.map(DocumentCursor::decode).transpose()?;
+        let limit = clamp_limit(limit);
+
+        let documents = self
+            .repository
+            .list_by_submitter(
+                submitter,
+                after.as_ref().map(|cursor| (cursor.created_at, &cursor.id)),
+                limit + 1,
+            )
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        Ok(DocumentPage::from_fetched(documents, limit))
+    }
+
+    /// Documents created strictly after `since`, ordered by `(created_at, id)` ascending
+    /// Uses the same limit defaults as `execute`. A batch never ends partway through the
+    /// documents of one `created_at`, so syncing again from `next_since` misses none of them;
+    /// when a single timestamp holds more documents than the limit, all of them are returned
+    pub fn sync_since(
+        &self,
+        since: i64,
+        limit: Option<usize>,
+    ) -> Result<SyncBatch, Box<dyn Error>> {
+        let limit = clamp_limit(limit);
+        let created_between = |start: i64, end: i64, limit: usize| {
+            self.repository
+                .find_by_date_range(start, end, limit, 0)
+                .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+        };
+
+        // Fetch one extra row to learn whether the batch cuts through a timestamp
+        let mut documents = created_between(since.saturating_add(1), i64::MAX, limit + 1)?;
+
+        let has_more = documents.len() > limit;
+        if has_more {
+            let boundary = documents[limit].created_at;
+            documents.truncate(limit);
+            documents.retain(|document| document.created_at < boundary);
+
+            if documents.is_empty() {
+                documents = created_between(boundary, boundary, i64::MAX as usize)?;
+            }
+        }
+
+        let next_since = documents
+            .last()
+            .map_or(since, |document| document.created_at);
+
+        Ok(SyncBatch {
+            documents,
+            next_since,
+            has_more,
+        })
+    }
+
+    /// Page `page` (from 0) of every document, ordered by `(created_at, id)` ascending
+    /// `page_size` is not clamped: anything outside `1..=MAX_SEARCH_LIMIT` is an error,
+    /// so an export never silently returns less than was asked for
+    pub fn export_registry(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<RegistryPage, Box<dyn Error>> {
+        if page_size == 0 || page_size > MAX_SEARCH_LIMIT {
+            return Err(Box::new(SearchError::InvalidPageSize {
+                page_size,
+                max: MAX_SEARCH_LIMIT,
+            }));
+        }
+
+        let database_error = |e: DatabaseError| {
+            Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>
+        };
+        let total_count = self.repository.count_documents().map_err(database_error)?;
+        let offset = page.saturating_mul(page_size);
+        let documents = self
+            .repository
+            .list_all(page_size, offset)
+            .map_err(database_error)?;
+
+        Ok(RegistryPage {
+            has_more: offset.saturating_add(documents.len()) < total_count,
+            documents,
+            total_count,
+            page,
+            page_size,
+        })
+    }
+
+    /// Documents that expired at or before `as_of` (defaults to now), earliest expiry first
+    /// Uses the same limit defaults as `execute`
+    pub fn find_expired(
+        &self,
+        as_of: i64,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<Document>, Box<dyn Error>> {
+        self.repository
+            .find_expired(as_of, clamp_limit(limit), offset)
+            .map_err(|e| Box::new(SearchError::DatabaseError(e.to_string())) as Box<dyn Error>)
+    }
+}
+
+fn clamp_limit(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT)
+}
@@ -0,0 +1,96 @@
+use crate::domain::{is_valid_address, ContentHash, OwnershipTransfer};
+use crate::infrastructure::database::DocumentRepository;
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error("Document not found")]
+    NotFound,
+
+    #[error("Only the current owner can transfer a document")]
+    Unauthorized,
+
+    #[error("Invalid new owner address: {0}")]
+    InvalidAddress(String),
+
+    #[error("New owner is already the current owner")]
+    SameOwner,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+pub struct TransferOwnershipUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> TransferOwnershipUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// `timestamp` is the input's block timestamp, recorded as the time of the transfer
+    pub fn execute(
+        &self,
+        content_hash: &str,
+        new_owner: &str,
+        caller: &str,
+        block_number: u64,
+        timestamp: i64,
+    ) -> Result<OwnershipTransfer, Box<dyn Error>> {
+        // Validate inputs
+        if !is_valid_address(new_owner) {
+            return Err(Box::new(TransferError::InvalidAddress(
+                new_owner.to_string(),
+            )));
+        }
+
+        let document = self
+            .repository
+            .find_by_hash(&ContentHash::from(content_hash))
+            .map_err(|_| Box::new(TransferError::NotFound) as Box<dyn Error>)?;
+
+        // Only the current owner may hand the document over
+        if !document.submitted_by.eq_ignore_ascii_case(caller) {
+            return Err(Box::new(TransferError::Unauthorized));
+        }
+
+        if document.submitted_by.eq_ignore_ascii_case(new_owner) {
+            return Err(Box::new(TransferError::SameOwner));
+        }
+
+        let transfer = OwnershipTransfer {
+            document_id: document.id.into(),
+            content_hash: document.content_hash.into(),
+            previous_owner: document.submitted_by,
+            new_owner: new_owner.to_string(),
+            transferred_at: timestamp,
+            block_number,
+        };
+
+        self.repository
+            .transfer_ownership(&transfer)
+            .map_err(|e| Box::new(TransferError::DatabaseError(e.to_string())) as Box<dyn Error>)?;
+
+        Ok(transfer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::SqliteRepository;
+
+    #[test]
+    fn test_transfer_rejects_malformed_address() {
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        let usecase = TransferOwnershipUseCase::new(&repo);
+
+        let result = usecase.execute("hash", "0x1234", "0xowner", 1, 0);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid new owner"));
+    }
+}
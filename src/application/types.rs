@@ -1,5 +1,70 @@
-use crate::domain::{Document, NotarizationReceipt};
+use super::audit::{AuditError, AuditListing, AuditPage};
+use super::balance::BalanceError;
+use super::chunked_upload::ChunkedUploadError;
+use super::commit::CommitError;
+use super::cosign::CosignError;
+use super::epoch::EpochError;
+use super::fetch_content::{FetchContentError, StoredContent};
+use super::nonce::NonceError;
+use super::notarize::{validate_filename, NotarizeError, OriginalNotarization};
+use super::provenance::ProvenanceError;
+use super::provenance::ProvenanceResult;
+use super::purge::PurgeError;
+use super::purge::PurgeRecord;
+use super::rate_limit::RateLimitError;
+use super::rehash::RehashError;
+use super::search::SearchError;
+use super::search::{DocumentPage, RegistryPage, SyncBatch, DEFAULT_SEARCH_LIMIT};
+use super::transfer::TransferError;
+use super::update_metadata::MetadataChange;
+use super::update_metadata::UpdateMetadataError;
+use super::verify::MatchedHash;
+use super::verify::VerifyError;
+use crate::domain::{
+    AddressError, AuditEntry, Commitment, Cosigner, Deposit, DepositError, Document, EpochRoot,
+    MerkleProof, NotarizationReceipt, OtsError, OwnershipTransfer, PendingNotarization,
+    UploadSession,
+};
+use crate::infrastructure::cartesi::GioResponse;
+use crate::infrastructure::database::DatabaseError;
+use crate::infrastructure::signing::{verify_json_signature, NoticeSigningKey};
+use crate::NotaryError;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+/// Prefix marking a notice payload as CBOR rather than JSON
+pub const CBOR_PAYLOAD_PREFIX: &[u8] = b"cbor:";
+
+/// How the receipt notice of a notarization is encoded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseEncoding {
+    #[default]
+    Json,
+    /// CBOR bytes behind `CBOR_PAYLOAD_PREFIX`, smaller than the JSON text
+    Cbor,
+}
+
+impl ResponseEncoding {
+    pub fn is_json(&self) -> bool {
+        *self == ResponseEncoding::Json
+    }
+
+    /// Encode `value` as a notice payload
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, Box<dyn StdError>> {
+        match self {
+            ResponseEncoding::Json => Ok(serde_json::to_vec(value)?),
+            ResponseEncoding::Cbor => {
+                let mut payload = CBOR_PAYLOAD_PREFIX.to_vec();
+                ciborium::into_writer(value, &mut payload)?;
+                Ok(payload)
+            }
+        }
+    }
+}
 
 /// Request to notarize a document
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -10,69 +75,2156 @@ pub struct NotarizeRequest {
     pub file_name: String,
     /// MIME type (e.g., "application/pdf", "text/plain")
     pub mime_type: String,
+    /// Optional Unix timestamp after which the notarization expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Optional lifetime in blocks, estimated at 12 seconds per block; excludes `expires_at`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_blocks: Option<u64>,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Optional category tags, e.g. "contract" or "invoice"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Optional content hash of the earlier version this document amends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// Addresses that must each approve with a `cosign` input before the document is notarized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_cosigners: Option<Vec<String>>,
+    /// Client-chosen retry key; resubmitting with the same key returns the original receipt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Optional L1 contract called with `onNotarized` through a voucher once notarized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_address: Option<String>,
+    /// Optional human-readable description, up to 1024 characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Encoding of the receipt notice; `cbor` sends a binary notice
+    #[serde(default, skip_serializing_if = "ResponseEncoding::is_json")]
+    pub response_encoding: ResponseEncoding,
+    /// Record already notarized content under this filename as an alias instead of
+    /// rejecting it; the original receipt is returned
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_alias: bool,
+    /// Optional 65-byte hex `personal_sign` signature of `signed_message` by the submitter's
+    /// wallet, proving the submitter holds its key; must come with `signed_message`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The exact text that was signed, normally the lowercase hex content hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_message: Option<String>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BuilderError {
+    #[error("Missing required field: {0}")]
+    MissingField(&'static str),
+}
+
+/// Builds a `NotarizeRequest` from raw content, encoding it to base64
+///
+/// Every setter is a `const fn`, so fixtures can be declared as constants:
+///
+/// ```
+/// use dapp::application::NotarizeRequestBuilder;
+///
+/// const CONTRACT: NotarizeRequestBuilder = NotarizeRequestBuilder::new()
+///     .content(b"signed contract")
+///     .file_name("contract.pdf")
+///     .mime_type("application/pdf");
+///
+/// let request = CONTRACT.tags(&["contract"]).build().unwrap();
+/// assert_eq!(request.content, "c2lnbmVkIGNvbnRyYWN0");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NotarizeRequestBuilder<'a> {
+    content: Option<&'a [u8]>,
+    file_name: Option<&'a str>,
+    mime_type: &'a str,
+    expires_at: Option<i64>,
+    expiration_blocks: Option<u64>,
+    nonce: Option<u64>,
+    tags: &'a [&'a str],
+    supersedes: Option<&'a str>,
+    required_cosigners: Option<&'a [&'a str]>,
+    idempotency_key: Option<&'a str>,
+    callback_address: Option<&'a str>,
+    description: Option<&'a str>,
+    response_encoding: ResponseEncoding,
+    allow_alias: bool,
+    signature: Option<(&'a str, &'a str)>,
+}
+
+impl<'a> NotarizeRequestBuilder<'a> {
+    /// MIME type used unless `mime_type` is set; accepted by the default allowlist
+    pub const DEFAULT_MIME_TYPE: &'static str = "text/plain";
+
+    pub const fn new() -> Self {
+        Self {
+            content: None,
+            file_name: None,
+            mime_type: Self::DEFAULT_MIME_TYPE,
+            expires_at: None,
+            expiration_blocks: None,
+            nonce: None,
+            tags: &[],
+            supersedes: None,
+            required_cosigners: None,
+            idempotency_key: None,
+            callback_address: None,
+            description: None,
+            response_encoding: ResponseEncoding::Json,
+            allow_alias: false,
+            signature: None,
+        }
+    }
+
+    /// Raw document bytes; required
+    pub const fn content(mut self, content: &'a [u8]) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    /// Required
+    pub const fn file_name(mut self, file_name: &'a str) -> Self {
+        self.file_name = Some(file_name);
+        self
+    }
+
+    pub const fn mime_type(mut self, mime_type: &'a str) -> Self {
+        self.mime_type = mime_type;
+        self
+    }
+
+    pub const fn expires_at(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub const fn expiration_blocks(mut self, expiration_blocks: u64) -> Self {
+        self.expiration_blocks = Some(expiration_blocks);
+        self
+    }
+
+    pub const fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub const fn tags(mut self, tags: &'a [&'a str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub const fn supersedes(mut self, content_hash: &'a str) -> Self {
+        self.supersedes = Some(content_hash);
+        self
+    }
+
+    pub const fn required_cosigners(mut self, cosigners: &'a [&'a str]) -> Self {
+        self.required_cosigners = Some(cosigners);
+        self
+    }
+
+    pub const fn idempotency_key(mut self, key: &'a str) -> Self {
+        self.idempotency_key = Some(key);
+        self
+    }
+
+    pub const fn callback_address(mut self, address: &'a str) -> Self {
+        self.callback_address = Some(address);
+        self
+    }
+
+    pub const fn description(mut self, description: &'a str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub const fn response_encoding(mut self, encoding: ResponseEncoding) -> Self {
+        self.response_encoding = encoding;
+        self
+    }
+
+    pub const fn allow_alias(mut self, allow_alias: bool) -> Self {
+        self.allow_alias = allow_alias;
+        self
+    }
+
+    /// Wallet `signature` of `signed_message`, checked against the submitter
+    pub const fn signature(mut self, signature: &'a str, signed_message: &'a str) -> Self {
+        self.signature = Some((signature, signed_message));
+        self
+    }
+
+    /// Fails with `MissingField` when `content` or `file_name` was not set
+    pub fn build(&self) -> Result<NotarizeRequest, BuilderError> {
+        let content = self.content.ok_or(BuilderError::MissingField("content"))?;
+        let file_name = self
+            .file_name
+            .ok_or(BuilderError::MissingField("file_name"))?;
+        let owned = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        Ok(NotarizeRequest {
+            content: base64::engine::general_purpose::STANDARD.encode(content),
+            file_name: file_name.to_string(),
+            mime_type: self.mime_type.to_string(),
+            expires_at: self.expires_at,
+            expiration_blocks: self.expiration_blocks,
+            nonce: self.nonce,
+            tags: owned(self.tags),
+            supersedes: self.supersedes.map(str::to_string),
+            required_cosigners: self.required_cosigners.map(owned),
+            idempotency_key: self.idempotency_key.map(str::to_string),
+            callback_address: self.callback_address.map(str::to_string),
+            description: self.description.map(str::to_string),
+            response_encoding: self.response_encoding,
+            allow_alias: self.allow_alias,
+            signature: self.signature.map(|(signature, _)| signature.to_string()),
+            signed_message: self.signature.map(|(_, message)| message.to_string()),
+        })
+    }
+}
+
+impl Default for NotarizeRequestBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<NotarizeRequestBuilder<'_>> for NotarizeRequest {
+    type Error = BuilderError;
+
+    fn try_from(builder: NotarizeRequestBuilder<'_>) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
+/// Request to open a chunked upload for a document too large for one input
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChunkStartRequest {
+    /// Client-chosen identifier referenced by the following chunks
+    pub upload_id: String,
+    /// Document filename
+    pub file_name: String,
+    /// MIME type (e.g., "application/pdf")
+    pub mime_type: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to stage one chunk of an open upload
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChunkAppendRequest {
+    pub upload_id: String,
+    /// Position of the chunk, starting at 0
+    pub index: u32,
+    /// Base64-encoded chunk bytes
+    pub content: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to notarize the assembled chunks; options match `NotarizeRequest`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChunkFinishRequest {
+    pub upload_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_blocks: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Approval of a pending co-notarization by one of its cosigners (the input sender)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CosignRequest {
+    /// SHA-256 hash of the pending document
+    pub content_hash: String,
+    /// Attestation kept with the approval, e.g. an off-chain signature; not verified
+    pub signature: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to verify a document by hash
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VerifyRequest {
+    /// SHA-256 hash (64 hex characters)
+    pub content_hash: String,
+}
+
+impl NotarizeRequest {
+    /// Every field-level problem with the request, checked before it reaches a use case
+    /// Content is only checked for base64 characters here; decoding still happens later
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.content.is_empty() {
+            errors.push(FieldError::new(
+                "content",
+                ErrorCode::EmptyContent,
+                NotarizeError::EmptyContent.to_string(),
+            ));
+        } else if let Some((offset, c)) = self
+            .content
+            .char_indices()
+            .find(|(_, c)| !is_base64_char(*c))
+        {
+            errors.push(FieldError::new(
+                "content",
+                ErrorCode::InvalidBase64,
+                format!(
+                    "Invalid base64 content: invalid character {:?} at offset {}",
+                    c, offset
+                ),
+            ));
+        }
+
+        if let Err(e) = validate_filename(&self.file_name) {
+            errors.push(FieldError::new(
+                "file_name",
+                ErrorCode::InvalidPayload,
+                e.to_string(),
+            ));
+        }
+
+        if !is_plausible_mime(&self.mime_type) {
+            errors.push(FieldError::new(
+                "mime_type",
+                ErrorCode::InvalidPayload,
+                format!(
+                    "Invalid mime_type {:?}: expected type/subtype",
+                    self.mime_type
+                ),
+            ));
+        }
+
+        match (&self.signature, &self.signed_message) {
+            (Some(_), None) => errors.push(FieldError::new(
+                "signed_message",
+                ErrorCode::InvalidPayload,
+                "signed_message is required with signature",
+            )),
+            (None, Some(_)) => errors.push(FieldError::new(
+                "signature",
+                ErrorCode::InvalidPayload,
+                "signature is required with signed_message",
+            )),
+            _ => {}
+        }
+
+        errors
+    }
+}
+
+impl VerifyRequest {
+    /// Every field-level problem with the request, checked before it reaches a use case
+    pub fn validate(&self) -> Vec<FieldError> {
+        let hash = &self.content_hash;
+        if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Vec::new();
+        }
+
+        vec![FieldError::new(
+            "content_hash",
+            ErrorCode::InvalidHash,
+            VerifyError::InvalidHashFormat.to_string(),
+        )]
+    }
+}
+
+/// Characters of the standard and URL-safe base64 alphabets, padding included
+fn is_base64_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '-' | '_' | '=')
+}
+
+/// `type/subtype`, optionally followed by `; parameters`, without whitespace in either part
+fn is_plausible_mime(mime_type: &str) -> bool {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim();
+    let is_token =
+        |part: &str| !part.is_empty() && !part.contains(|c: char| c.is_whitespace() || c == '/');
+    match essence.split_once('/') {
+        Some((kind, subtype)) => is_token(kind) && is_token(subtype),
+        None => false,
+    }
+}
+
+/// Request to verify a document by its Blake2b-256 digest
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Blake2bVerifyRequest {
+    /// Blake2b-256 hash (64 hex characters)
+    pub hash: String,
+}
+
+/// Request to check original content against a notarized hash
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VerifyContentRequest {
+    /// Base64-encoded document content
+    pub content: String,
+    /// SHA-256 hash (64 hex characters) the content is expected to match
+    pub content_hash: String,
+}
+
+/// Request to look up a document by its content, hashed by the DApp
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VerifyByContentRequest {
+    /// Base64-encoded document content
+    pub content: String,
+}
+
+/// Request to fetch a document by the id from its notarization notice
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GetDocumentRequest {
+    /// Document UUID
+    pub id: String,
+}
+
+/// Request to verify several documents at once
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BatchVerifyRequest {
+    /// SHA-256 hashes (64 hex characters each)
+    pub hashes: Vec<String>,
+}
+
+/// Request to change the metadata of an existing document
+/// The content hash identifies the document and can never be changed
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateMetadataRequest {
+    /// SHA-256 hash of the document to update
+    pub content_hash: String,
+    /// Replacement filename, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_file_name: Option<String>,
+    /// Replacement MIME type, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_mime_type: Option<String>,
+    /// Keys to merge into the document's metadata object; `null` removes a key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to hand a document over to a new owner
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransferOwnershipRequest {
+    /// SHA-256 hash of the document to transfer
+    pub content_hash: String,
+    /// 0x-prefixed Ethereum address of the new owner
+    pub new_owner: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to permanently delete a document (admin only)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PurgeRequest {
+    /// SHA-256 hash of the document to delete
+    pub content_hash: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request to commit to a document's hash before revealing its content
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommitRequest {
+    /// SHA-256 hash of the document (64 hex characters)
+    pub content_hash: String,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+impl CommitRequest {
+    /// Every field-level problem with the request, checked before it reaches a use case
+    pub fn validate(&self) -> Vec<FieldError> {
+        let hash = &self.content_hash;
+        if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Vec::new();
+        }
+
+        vec![FieldError::new(
+            "content_hash",
+            ErrorCode::InvalidHash,
+            CommitError::InvalidHashFormat.to_string(),
+        )]
+    }
+}
+
+/// Request to compute digests missing from older documents (admin only)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RehashRequest {
+    /// `next_after` of the previous batch; omitted to start from the first document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Documents to visit in this batch, up to `MAX_REHASH_BATCH`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Per-submitter replay protection nonce, required when NOTARY_REQUIRE_NONCE is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+}
+
+/// Request for per-submitter document counts
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SubmitterStatsRequest {
+    /// Restrict the report to one address; omit to list every submitter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<String>,
+}
+
+/// Request for documents carrying a tag
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FindByTagRequest {
+    /// Tag to look up (case-insensitive)
+    pub tag: String,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of matching documents to skip
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Request for documents notarized within a time window
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DateRangeRequest {
+    /// Earliest `created_at` to include (Unix timestamp)
+    pub start: i64,
+    /// Latest `created_at` to include (Unix timestamp)
+    pub end: i64,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of matching documents to skip
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Request for notarization counts per UTC day
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TimeseriesRequest {
+    /// Earliest `created_at` to count (Unix timestamp)
+    pub start: i64,
+    /// Latest `created_at` to count (Unix timestamp)
+    pub end: i64,
+}
+
+/// Request for documents whose expiry has passed
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ListExpiredRequest {
+    /// Reference time (Unix timestamp); defaults to the current time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<i64>,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of matching documents to skip
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Request for one page of all documents, newest first
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ListDocumentsRequest {
+    /// `next_cursor` of the previous page; omit for the first page
+    #[serde(default, alias = "cursor", skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Request for one page of the full registry export, oldest first
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportRegistryRequest {
+    /// Page number, starting at 0
+    #[serde(default)]
+    pub page: usize,
+    /// Documents per page (default 20); more than 100 is rejected rather than capped
+    #[serde(default = "default_export_page_size")]
+    pub page_size: usize,
+}
+
+fn default_export_page_size() -> usize {
+    DEFAULT_SEARCH_LIMIT
+}
+
+impl Default for ExportRegistryRequest {
+    fn default() -> Self {
+        Self {
+            page: 0,
+            page_size: default_export_page_size(),
+        }
+    }
+}
+
+/// Request for one page of a submitter's documents, newest first
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListBySubmitterRequest {
+    /// Ethereum address of the submitter
+    pub submitter: String,
+    /// `next_cursor` of the previous page; omit for the first page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Request for the documents created since an indexer's last checkpoint
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SyncSinceRequest {
+    /// `next_since` of the previous sync; omit or pass 0 to start from the beginning
+    #[serde(default)]
+    pub since_created_at: i64,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Request for a page of the audit log, oldest first
+/// Filters combine; omitting all of them pages through every entry
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AuditLogRequest {
+    /// Only entries sent by this address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<String>,
+    /// Only entries from this block onwards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<u64>,
+    /// Only entries up to and including this block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<u64>,
+    /// `next_cursor` of the previous page; omit for the first page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<i64>,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Request for a page of the whole audit log by offset, oldest first
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ListAuditLogRequest {
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of entries to skip
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Request for a Merkle proof that a document was notarized during an epoch
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InclusionProofRequest {
+    /// SHA-256 hash (64 hex characters)
+    pub content_hash: String,
+    /// Rollup epoch the document was notarized in
+    pub epoch: u64,
+}
+
+/// Request for the retained content of a document (owner only, checked against `msg_sender`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FetchContentRequest {
+    /// Id of the document to download
+    pub document_id: String,
+}
+
+/// Request to find documents by filename or MIME type words
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchDocumentsRequest {
+    /// Words to match, e.g. "acme contract" or "application/pdf"
+    pub query: String,
+    /// Maximum number of results (default 20, at most 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Protocol version of payloads without a `version` field
+pub const DEFAULT_PROTOCOL_VERSION: u8 = 1;
+
+/// Payload protocol versions this build accepts
+pub fn supported_versions() -> RangeInclusive<u8> {
+    DEFAULT_PROTOCOL_VERSION..=1
+}
+
+#[derive(Error, Debug)]
+pub enum ProtocolVersionError {
+    #[error(
+        "Unsupported protocol version {0}; supported versions are {}..={}",
+        supported_versions().start(),
+        supported_versions().end()
+    )]
+    Unsupported(u8),
+
+    #[error("Invalid protocol version: {0}")]
+    Invalid(#[from] serde_json::Error),
+}
+
+/// The fields every JSON payload may carry next to its action
+#[derive(Debug, Deserialize)]
+struct ProtocolEnvelope {
+    #[serde(default = "default_protocol_version")]
+    version: u8,
+}
+
+fn default_protocol_version() -> u8 {
+    DEFAULT_PROTOCOL_VERSION
+}
+
+/// Every `action` tag `InputAction` accepts, in declaration order
+pub const SUPPORTED_ACTIONS: &[&str] = &[
+    "notarize",
+    "verify",
+    "metrics",
+    "health",
+    "update_metadata",
+    "transfer_ownership",
+    "batch_verify",
+    "verify_provenance",
+    "get_submitter_stats",
+    "purge",
+    "search_documents",
+    "find_by_tag",
+    "list_by_date_range",
+    "cosign",
+    "notarize_chunk_start",
+    "notarize_chunk_append",
+    "notarize_chunk_finish",
+    "timeseries",
+    "list_expired",
+    "list_documents",
+    "list_by_submitter",
+    "sync_since",
+    "fetch_content",
+    "get_inclusion_proof",
+    "get_verifiable_credential",
+    "get_proof",
+    "certificate",
+    "preview",
+    "verify_by_blake2b",
+    "get_document_by_id",
+    "get_statistics",
+    "verify_content",
+    "audit_log",
+    "rehash",
+    "commit",
+    "verify_by_content",
+    "export_registry",
+    "list_audit_log",
+];
+
+/// Input action types that can be sent to the DApp
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum InputAction {
+    /// Notarize a new document (state-changing operation)
+    Notarize { data: NotarizeRequest },
+
+    /// Verify an existing document (can be query or state-changing)
+    Verify { data: VerifyRequest },
+
+    /// Export operational metrics in Prometheus text format (query operation)
+    Metrics,
+
+    /// Report liveness and database accessibility (query operation)
+    Health,
+
+    /// Change filename/MIME type of a document (state-changing, submitter only)
+    #[serde(rename = "update_metadata")]
+    UpdateMetadata { data: UpdateMetadataRequest },
+
+    /// Transfer ownership of a document (state-changing, current owner only)
+    #[serde(rename = "transfer_ownership")]
+    TransferOwnership { data: TransferOwnershipRequest },
+
+    /// Verify several documents in one request (query operation)
+    #[serde(rename = "batch_verify")]
+    BatchVerify { data: BatchVerifyRequest },
+
+    /// Verify a document and check it against the provenance oracle (query operation)
+    #[serde(rename = "verify_provenance")]
+    VerifyProvenance { data: VerifyRequest },
+
+    /// Report document counts per submitter address (query operation)
+    #[serde(rename = "get_submitter_stats")]
+    GetSubmitterStats {
+        #[serde(default)]
+        data: SubmitterStatsRequest,
+    },
+
+    /// Hard-delete a document, e.g. for erasure requests (state-changing, admin only)
+    Purge { data: PurgeRequest },
+
+    /// Full-text search over filenames and MIME types (query operation)
+    #[serde(rename = "search_documents")]
+    SearchDocuments { data: SearchDocumentsRequest },
+
+    /// List documents carrying a tag (query operation)
+    #[serde(rename = "find_by_tag", alias = "list_by_tag")]
+    FindByTag { data: FindByTagRequest },
+
+    /// List documents notarized within a time window (query operation)
+    #[serde(rename = "list_by_date_range")]
+    ListByDateRange { data: DateRangeRequest },
+
+    /// Approve a pending co-notarization (state-changing operation)
+    #[serde(rename = "cosign")]
+    Cosign { data: CosignRequest },
+
+    /// Open a chunked upload (state-changing operation)
+    #[serde(rename = "notarize_chunk_start")]
+    NotarizeChunkStart { data: ChunkStartRequest },
+
+    /// Stage the next chunk of an upload (state-changing operation)
+    #[serde(rename = "notarize_chunk_append")]
+    NotarizeChunkAppend { data: ChunkAppendRequest },
+
+    /// Notarize the assembled chunks (state-changing operation)
+    #[serde(rename = "notarize_chunk_finish")]
+    NotarizeChunkFinish { data: ChunkFinishRequest },
+
+    /// Count notarizations per UTC day (query operation)
+    Timeseries { data: TimeseriesRequest },
+
+    /// List documents whose expiry has passed (query operation)
+    #[serde(rename = "list_expired")]
+    ListExpired {
+        #[serde(default)]
+        data: ListExpiredRequest,
+    },
+
+    /// Page through all documents with a keyset cursor (query operation)
+    #[serde(rename = "list_documents")]
+    ListDocuments {
+        #[serde(default)]
+        data: ListDocumentsRequest,
+    },
+
+    /// Page through one submitter's documents with a keyset cursor (query operation)
+    #[serde(rename = "list_by_submitter")]
+    ListBySubmitter { data: ListBySubmitterRequest },
+
+    /// Documents created since a checkpoint, for off-chain indexers (query operation)
+    #[serde(rename = "sync_since")]
+    SyncSince {
+        #[serde(default)]
+        data: SyncSinceRequest,
+    },
+
+    /// Download the retained content of a document (query operation, owner only)
+    #[serde(rename = "fetch_content")]
+    FetchContent { data: FetchContentRequest },
+
+    /// Prove a document is committed to an epoch's Merkle root (query operation)
+    #[serde(rename = "get_inclusion_proof")]
+    GetInclusionProof { data: InclusionProofRequest },
+
+    /// Export a notarization as a W3C Verifiable Credential (query operation)
+    #[serde(rename = "get_verifiable_credential")]
+    GetVerifiableCredential { data: VerifyRequest },
+
+    /// Return only the receipt proof string of a document (query operation)
+    #[serde(rename = "get_proof")]
+    GetProof { data: VerifyRequest },
+
+    /// Return a signed certificate of a notarization, for printing or offline checks
+    /// (query operation)
+    Certificate { data: VerifyRequest },
+
+    /// Hash a document and check for duplicates without notarizing it (query operation)
+    Preview { data: NotarizeRequest },
+
+    /// Verify a document by its Blake2b-256 digest (query operation)
+    #[serde(rename = "verify_by_blake2b")]
+    VerifyByBlake2b { data: Blake2bVerifyRequest },
+
+    /// Fetch a document record by its id (query operation)
+    #[serde(rename = "get_document_by_id")]
+    GetDocumentById { data: GetDocumentRequest },
+
+    /// Summarize document totals and recent activity (query operation)
+    #[serde(rename = "get_statistics")]
+    GetStatistics,
+
+    /// Rehash submitted content and compare it to a notarized hash (query operation)
+    #[serde(rename = "verify_content")]
+    VerifyContent { data: VerifyContentRequest },
+
+    /// Page through the append-only log of advance inputs (query operation)
+    #[serde(rename = "audit_log")]
+    AuditLog {
+        #[serde(default)]
+        data: AuditLogRequest,
+    },
+
+    /// Compute digests missing from documents notarized before they were recorded, one
+    /// batch per input (state-changing, admin only)
+    Rehash {
+        #[serde(default)]
+        data: RehashRequest,
+    },
+
+    /// Commit to a content hash ahead of notarizing the document (state-changing operation)
+    Commit { data: CommitRequest },
+
+    /// Hash submitted content and verify the document it belongs to (query operation)
+    #[serde(rename = "verify_by_content")]
+    VerifyByContent { data: VerifyByContentRequest },
+
+    /// Dump the registry one page at a time for archival (query operation)
+    #[serde(rename = "export_registry")]
+    ExportRegistry {
+        #[serde(default)]
+        data: ExportRegistryRequest,
+    },
+
+    /// Page through the whole audit log by offset (query operation)
+    #[serde(rename = "list_audit_log")]
+    ListAuditLog {
+        #[serde(default)]
+        data: ListAuditLogRequest,
+    },
+}
+
+impl InputAction {
+    /// Protocol version a JSON payload declares, `DEFAULT_PROTOCOL_VERSION` when absent
+    /// Fails when the version is not a number from 0 to 255 or is not supported
+    pub fn protocol_version(payload: &str) -> Result<u8, ProtocolVersionError> {
+        let envelope: ProtocolEnvelope = serde_json::from_str(payload)?;
+        if !supported_versions().contains(&envelope.version) {
+            return Err(ProtocolVersionError::Unsupported(envelope.version));
+        }
+        Ok(envelope.version)
+    }
+
+    /// The `action` tag of a JSON payload when it names no supported action
+    /// Payloads without a string `action` field yield `None`
+    pub fn unknown_action(payload: &str) -> Option<String> {
+        let value: serde_json::Value = serde_json::from_str(payload).ok()?;
+        let action = value.get("action")?.as_str()?;
+        let known = SUPPORTED_ACTIONS.contains(&action) || action == "list_by_tag";
+        (!known).then(|| action.to_string())
+    }
+
+    /// Content hash the request names, recorded in the audit log
+    /// A notarization's hash is only known once its content is decoded
+    pub fn content_hash(&self) -> Option<&str> {
+        match self {
+            InputAction::Verify { data }
+            | InputAction::VerifyProvenance { data }
+            | InputAction::GetVerifiableCredential { data }
+            | InputAction::GetProof { data }
+            | InputAction::Certificate { data } => Some(&data.content_hash),
+            InputAction::UpdateMetadata { data } => Some(&data.content_hash),
+            InputAction::TransferOwnership { data } => Some(&data.content_hash),
+            InputAction::Purge { data } => Some(&data.content_hash),
+            InputAction::Cosign { data } => Some(&data.content_hash),
+            InputAction::VerifyContent { data } => Some(&data.content_hash),
+            InputAction::Commit { data } => Some(&data.content_hash),
+            InputAction::GetInclusionProof { data } => Some(&data.content_hash),
+            _ => None,
+        }
+    }
+
+    /// Action name as it appears in the `action` tag of the payload
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputAction::Notarize { .. } => "notarize",
+            InputAction::Verify { .. } => "verify",
+            InputAction::Metrics => "metrics",
+            InputAction::Health => "health",
+            InputAction::UpdateMetadata { .. } => "update_metadata",
+            InputAction::TransferOwnership { .. } => "transfer_ownership",
+            InputAction::BatchVerify { .. } => "batch_verify",
+            InputAction::VerifyProvenance { .. } => "verify_provenance",
+            InputAction::GetSubmitterStats { .. } => "get_submitter_stats",
+            InputAction::Purge { .. } => "purge",
+            InputAction::SearchDocuments { .. } => "search_documents",
+            InputAction::FindByTag { .. } => "find_by_tag",
+            InputAction::ListByDateRange { .. } => "list_by_date_range",
+            InputAction::Cosign { .. } => "cosign",
+            InputAction::NotarizeChunkStart { .. } => "notarize_chunk_start",
+            InputAction::NotarizeChunkAppend { .. } => "notarize_chunk_append",
+            InputAction::NotarizeChunkFinish { .. } => "notarize_chunk_finish",
+            InputAction::Timeseries { .. } => "timeseries",
+            InputAction::ListExpired { .. } => "list_expired",
+            InputAction::ListDocuments { .. } => "list_documents",
+            InputAction::ListBySubmitter { .. } => "list_by_submitter",
+            InputAction::SyncSince { .. } => "sync_since",
+            InputAction::FetchContent { .. } => "fetch_content",
+            InputAction::GetInclusionProof { .. } => "get_inclusion_proof",
+            InputAction::GetVerifiableCredential { .. } => "get_verifiable_credential",
+            InputAction::GetProof { .. } => "get_proof",
+            InputAction::Certificate { .. } => "certificate",
+            InputAction::Preview { .. } => "preview",
+            InputAction::VerifyByBlake2b { .. } => "verify_by_blake2b",
+            InputAction::GetDocumentById { .. } => "get_document_by_id",
+            InputAction::GetStatistics => "get_statistics",
+            InputAction::VerifyContent { .. } => "verify_content",
+            InputAction::AuditLog { .. } => "audit_log",
+            InputAction::Rehash { .. } => "rehash",
+            InputAction::Commit { .. } => "commit",
+            InputAction::VerifyByContent { .. } => "verify_by_content",
+            InputAction::ExportRegistry { .. } => "export_registry",
+            InputAction::ListAuditLog { .. } => "list_audit_log",
+        }
+    }
+
+    /// Whether the action changes state and therefore needs an authenticated sender
+    pub fn changes_state(&self) -> bool {
+        matches!(
+            self,
+            InputAction::Notarize { .. }
+                | InputAction::UpdateMetadata { .. }
+                | InputAction::TransferOwnership { .. }
+                | InputAction::Purge { .. }
+                | InputAction::Rehash { .. }
+                | InputAction::Commit { .. }
+                | InputAction::Cosign { .. }
+                | InputAction::NotarizeChunkStart { .. }
+                | InputAction::NotarizeChunkAppend { .. }
+                | InputAction::NotarizeChunkFinish { .. }
+        )
+    }
+
+    /// Whether the action is subject to replay protection (every state-changing action)
+    pub fn requires_nonce(&self) -> bool {
+        self.changes_state()
+    }
+
+    /// Field-level problems with the action's request, for actions validated before dispatch
+    pub fn validate(&self) -> Vec<FieldError> {
+        match self {
+            InputAction::Notarize { data } => data.validate(),
+            InputAction::Verify { data } => data.validate(),
+            InputAction::Commit { data } => data.validate(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replay protection nonce carried by the action, if any
+    pub fn nonce(&self) -> Option<u64> {
+        match self {
+            InputAction::Notarize { data } => data.nonce,
+            InputAction::UpdateMetadata { data } => data.nonce,
+            InputAction::TransferOwnership { data } => data.nonce,
+            InputAction::Purge { data } => data.nonce,
+            InputAction::Rehash { data } => data.nonce,
+            InputAction::Commit { data } => data.nonce,
+            InputAction::Cosign { data } => data.nonce,
+            InputAction::NotarizeChunkStart { data } => data.nonce,
+            InputAction::NotarizeChunkAppend { data } => data.nonce,
+            InputAction::NotarizeChunkFinish { data } => data.nonce,
+            _ => None,
+        }
+    }
+}
+
+/// Response sent as a Cartesi Notice (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct NoticeResponse {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub receipt: NotarizationReceipt,
+    /// Hex HMAC-SHA256 of the canonical receipt JSON, when a signing key is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Cosigners whose approval completed a co-notarization
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cosigners: Vec<Cosigner>,
+}
+
+impl NoticeResponse {
+    pub fn notarization(receipt: NotarizationReceipt) -> Self {
+        Self {
+            response_type: "notarization_receipt".to_string(),
+            receipt,
+            signature: None,
+            cosigners: Vec::new(),
+        }
+    }
+
+    /// Attach the approvals of a completed co-notarization; the signature covers the receipt only
+    pub fn with_cosigners(mut self, cosigners: Vec<Cosigner>) -> Self {
+        self.cosigners = cosigners;
+        self
+    }
+
+    /// Sign the receipt with `key`; without a key the notice stays unsigned
+    pub fn signed(mut self, key: Option<&NoticeSigningKey>) -> Self {
+        self.signature = key.map(|key| key.sign_receipt(&self.receipt));
+        self
+    }
+}
+
+/// Notice linking a new document version to the one it supersedes (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct DocumentVersionNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub document_id: String,
+    pub previous_hash: String,
+    pub content_hash: String,
+}
+
+impl DocumentVersionNotice {
+    pub fn new(receipt: &NotarizationReceipt, previous_hash: &str) -> Self {
+        Self {
+            response_type: "document_version".to_string(),
+            document_id: receipt.document_id.clone(),
+            previous_hash: previous_hash.to_string(),
+            content_hash: receipt.content_hash.clone(),
+        }
+    }
+}
+
+/// Notice recording a metadata change (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct MetadataUpdateNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub change: MetadataChange,
+}
+
+impl MetadataUpdateNotice {
+    pub fn new(change: MetadataChange) -> Self {
+        Self {
+            response_type: "metadata_update".to_string(),
+            change,
+        }
+    }
+}
+
+/// Notice recording an ownership transfer (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct OwnershipTransferNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub transfer: OwnershipTransfer,
+}
+
+impl OwnershipTransferNotice {
+    pub fn new(transfer: OwnershipTransfer) -> Self {
+        Self {
+            response_type: "ownership_transfer".to_string(),
+            transfer,
+        }
+    }
+}
+
+/// Notice recording that a document was purged (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct PurgeNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub purge: PurgeRecord,
+}
+
+impl PurgeNotice {
+    pub fn new(purge: PurgeRecord) -> Self {
+        Self {
+            response_type: "purge".to_string(),
+            purge,
+        }
+    }
+}
+
+/// Notice recording a commitment to a content hash (verifiable on-chain)
+#[derive(Debug, Serialize)]
+pub struct CommitmentNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub commitment: Commitment,
+}
+
+impl CommitmentNotice {
+    pub fn new(commitment: Commitment) -> Self {
+        Self {
+            response_type: "commitment".to_string(),
+            commitment,
+        }
+    }
+}
+
+/// Notice recording a portal deposit and the depositor's new balance (verifiable on-chain)
+/// Amounts are decimal strings, since they can exceed JSON's safe integer range
+#[derive(Debug, Serialize)]
+pub struct DepositNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub depositor: String,
+    pub token: String,
+    pub amount: String,
+    pub balance: String,
+}
+
+impl DepositNotice {
+    pub fn new(deposit: &Deposit, balance: u128) -> Self {
+        Self {
+            response_type: "deposit".to_string(),
+            depositor: deposit.depositor.as_str().to_string(),
+            token: deposit.token.as_str().to_string(),
+            amount: deposit.amount.to_string(),
+            balance: balance.to_string(),
+        }
+    }
+}
+
+/// State of a co-notarization still waiting for approvals, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct PendingCosignResponse {
+    pub status: String,
+    pub content_hash: String,
+    pub deadline: i64,
+    pub signed: usize,
+    pub required: usize,
+    pub cosigners: Vec<Cosigner>,
+}
+
+impl PendingCosignResponse {
+    pub fn new(pending: &PendingNotarization, cosigners: Vec<Cosigner>) -> Self {
+        Self {
+            status: "pending_cosignatures".to_string(),
+            content_hash: pending.content_hash.clone(),
+            deadline: pending.deadline,
+            signed: cosigners.iter().filter(|c| c.has_signed()).count(),
+            required: cosigners.len(),
+            cosigners,
+        }
+    }
+}
+
+/// Progress of a chunked upload, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct UploadProgressResponse {
+    pub upload_id: String,
+    /// Chunks received so far, which is also the index of the next chunk
+    pub chunks: u32,
+    pub bytes: u64,
+}
+
+impl UploadProgressResponse {
+    pub fn new(session: &UploadSession) -> Self {
+        Self {
+            upload_id: session.upload_id.clone(),
+            chunks: session.chunk_count,
+            bytes: session.total_bytes,
+        }
+    }
+}
+
+/// Response sent as a Cartesi Report (not verifiable, for logs/queries)
+#[derive(Debug, Serialize)]
+pub struct ReportResponse {
+    pub exists: bool,
+    pub expired: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<Document>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<NotarizationReceipt>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_hash: Option<MatchedHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_matches: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed_hash: Option<String>,
+}
+
+impl ReportResponse {
+    pub fn from_verification(result: &crate::application::VerificationResult) -> Self {
+        Self {
+            exists: result.exists,
+            expired: result.expired,
+            document: result.document.clone(),
+            receipt: result.receipt.clone(),
+            tags: result.tags.clone(),
+            matched_hash: result.matched_hash,
+            content_matches: result.content_matches,
+            description: result.description.clone(),
+            aliases: result.aliases.clone(),
+            committed_at: result.committed_at,
+            computed_hash: result.computed_hash.clone(),
+        }
+    }
+
+    pub fn error(_message: &str) -> Self {
+        // For error cases, we could extend this with an error field
+        // For now, just return not found
+        Self {
+            exists: false,
+            expired: false,
+            document: None,
+            receipt: None,
+            tags: Vec::new(),
+            matched_hash: None,
+            content_matches: None,
+            description: None,
+            aliases: Vec::new(),
+            committed_at: None,
+            computed_hash: None,
+        }
+    }
+}
+
+/// Response to a health check, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    /// "ok" when the database answered, "unavailable" otherwise
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_document_count: Option<usize>,
+    /// Schema revision recorded in the database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    /// Time taken by the document count query, in milliseconds
+    pub db_latency_ms: f64,
+    /// Crate version of the running DApp
+    pub version: String,
+    /// Why the database could not be queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HealthResponse {
+    pub fn ok(db_document_count: usize, schema_version: u32, db_latency_ms: f64) -> Self {
+        Self {
+            status: "ok".to_string(),
+            db_document_count: Some(db_document_count),
+            schema_version: Some(schema_version),
+            db_latency_ms,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            error: None,
+        }
+    }
+
+    pub fn unavailable(error: &str, db_latency_ms: f64) -> Self {
+        Self {
+            status: "unavailable".to_string(),
+            db_document_count: None,
+            schema_version: None,
+            db_latency_ms,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Result for one hash of a batch verification
+#[derive(Debug, Serialize)]
+pub struct BatchVerifyEntry {
+    pub content_hash: String,
+    #[serde(flatten)]
+    pub verification: ReportResponse,
+    /// Set when this hash could not be checked, e.g. malformed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Batch verification results in request order, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct BatchVerifyResponse {
+    pub results: Vec<BatchVerifyEntry>,
+}
+
+impl BatchVerifyResponse {
+    pub fn new(
+        hashes: &[String],
+        results: Vec<
+            Result<crate::application::VerificationResult, crate::application::VerifyError>,
+        >,
+    ) -> Self {
+        Self {
+            results: hashes
+                .iter()
+                .zip(results)
+                .map(|(hash, result)| match result {
+                    Ok(verification) => BatchVerifyEntry {
+                        content_hash: hash.clone(),
+                        verification: ReportResponse::from_verification(&verification),
+                        error: None,
+                    },
+                    Err(e) => BatchVerifyEntry {
+                        content_hash: hash.clone(),
+                        verification: ReportResponse::error(&e.to_string()),
+                        error: Some(e.to_string()),
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Verification report extended with the provenance oracle's answer
+#[derive(Debug, Serialize)]
+pub struct ProvenanceReport {
+    #[serde(flatten)]
+    pub verification: ReportResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oracle: Option<GioResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ProvenanceReport {
+    pub fn from_result(result: &ProvenanceResult) -> Self {
+        Self {
+            verification: ReportResponse::from_verification(&result.verification),
+            oracle: result.oracle.clone(),
+            error: result.error.clone(),
+        }
+    }
+}
+
+/// Document count for one submitter address
+#[derive(Debug, Serialize)]
+pub struct SubmitterStats {
+    pub submitter: String,
+    pub document_count: usize,
+}
+
+impl From<(String, usize)> for SubmitterStats {
+    fn from((submitter, document_count): (String, usize)) -> Self {
+        Self {
+            submitter,
+            document_count,
+        }
+    }
+}
+
+/// Per-submitter audit report, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct SubmitterStatsResponse {
+    pub submitters: Vec<SubmitterStats>,
+}
+
+impl SubmitterStatsResponse {
+    pub fn new(submitters: Vec<(String, usize)>) -> Self {
+        Self {
+            submitters: submitters.into_iter().map(SubmitterStats::from).collect(),
+        }
+    }
+}
+
+/// System overview for administrators, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct StatisticsResponse {
+    pub total_documents: usize,
+    /// Submitters with the most documents, largest count first
+    pub top_submitters: Vec<SubmitterStats>,
+    pub documents_last_24h: usize,
+    pub documents_last_7d: usize,
+    pub documents_last_30d: usize,
+    /// Unix timestamp the recent-activity windows end at
+    pub generated_at: i64,
+}
+
+/// Documents matching a search, newest first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct SearchDocumentsResponse {
+    pub query: String,
+    pub count: usize,
+    pub documents: Vec<Document>,
+}
+
+impl SearchDocumentsResponse {
+    pub fn new(query: &str, documents: Vec<Document>) -> Self {
+        Self {
+            query: query.to_string(),
+            count: documents.len(),
+            documents,
+        }
+    }
+}
+
+/// Documents carrying a tag, newest first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct TaggedDocumentsResponse {
+    pub tag: String,
+    pub count: usize,
+    pub documents: Vec<Document>,
+}
+
+impl TaggedDocumentsResponse {
+    pub fn new(tag: &str, documents: Vec<Document>) -> Self {
+        Self {
+            tag: tag.to_string(),
+            count: documents.len(),
+            documents,
+        }
+    }
+}
+
+/// Documents notarized within a time window, oldest first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct DateRangeResponse {
+    pub start: i64,
+    pub end: i64,
+    pub count: usize,
+    pub documents: Vec<Document>,
+}
+
+impl DateRangeResponse {
+    pub fn new(start: i64, end: i64, documents: Vec<Document>) -> Self {
+        Self {
+            start,
+            end,
+            count: documents.len(),
+            documents,
+        }
+    }
+}
+
+/// Notarizations on one UTC day
+#[derive(Debug, Serialize)]
+pub struct DayCount {
+    /// Unix timestamp of the day's midnight (UTC)
+    pub day: i64,
+    pub count: usize,
+}
+
+/// Notarizations per UTC day, oldest first, sent as a Cartesi Report
+/// Days without notarizations are omitted
+#[derive(Debug, Serialize)]
+pub struct TimeseriesResponse {
+    pub start: i64,
+    pub end: i64,
+    pub days: Vec<DayCount>,
+}
+
+impl TimeseriesResponse {
+    pub fn new(start: i64, end: i64, days: Vec<(i64, usize)>) -> Self {
+        Self {
+            start,
+            end,
+            days: days
+                .into_iter()
+                .map(|(day, count)| DayCount { day, count })
+                .collect(),
+        }
+    }
+}
+
+/// Documents expired as of a point in time, earliest expiry first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct ExpiredDocumentsResponse {
+    pub as_of: i64,
+    pub count: usize,
+    pub documents: Vec<Document>,
+}
+
+impl ExpiredDocumentsResponse {
+    pub fn new(as_of: i64, documents: Vec<Document>) -> Self {
+        Self {
+            as_of,
+            count: documents.len(),
+            documents,
+        }
+    }
+}
+
+/// One page of a document listing, newest first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct ListDocumentsResponse {
+    pub count: usize,
+    pub documents: Vec<Document>,
+    /// Pass as the cursor to fetch the next page; null on the last page
+    pub next_cursor: Option<String>,
+    /// Whether another page follows
+    pub has_more: bool,
+}
+
+/// One page of the registry export, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct ExportRegistryResponse {
+    pub total_count: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub count: usize,
+    /// Document records without their content
+    pub documents: Vec<Document>,
+    /// Whether later pages hold more documents
+    pub has_more: bool,
+}
+
+impl From<RegistryPage> for ExportRegistryResponse {
+    fn from(page: RegistryPage) -> Self {
+        Self {
+            total_count: page.total_count,
+            page: page.page,
+            page_size: page.page_size,
+            count: page.documents.len(),
+            documents: page.documents,
+            has_more: page.has_more,
+        }
+    }
+}
+
+impl From<DocumentPage> for ListDocumentsResponse {
+    fn from(page: DocumentPage) -> Self {
+        Self {
+            count: page.documents.len(),
+            has_more: page.next_cursor.is_some(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+            documents: page.documents,
+        }
+    }
 }
 
-/// Request to verify a document by hash
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct VerifyRequest {
-    /// SHA-256 hash (64 hex characters)
-    pub content_hash: String,
+/// One page of the audit log, oldest first, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct AuditLogResponse {
+    pub count: usize,
+    pub entries: Vec<AuditEntry>,
+    /// Pass as the cursor to fetch the next page; null on the last page
+    pub next_cursor: Option<i64>,
+    /// Whether another page follows
+    pub has_more: bool,
 }
 
-/// Input action types that can be sent to the DApp
-#[derive(Debug, Deserialize)]
-#[serde(tag = "action", rename_all = "lowercase")]
-pub enum InputAction {
-    /// Notarize a new document (state-changing operation)
-    Notarize { data: NotarizeRequest },
+impl From<AuditPage> for AuditLogResponse {
+    fn from(page: AuditPage) -> Self {
+        Self {
+            count: page.entries.len(),
+            has_more: page.next_cursor.is_some(),
+            next_cursor: page.next_cursor,
+            entries: page.entries,
+        }
+    }
+}
 
-    /// Verify an existing document (can be query or state-changing)
-    Verify { data: VerifyRequest },
+/// Entries of the audit log addressed by offset, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct ListAuditLogResponse {
+    pub count: usize,
+    pub offset: usize,
+    pub entries: Vec<AuditEntry>,
+    /// Whether entries follow this page
+    pub has_more: bool,
 }
 
-/// Response sent as a Cartesi Notice (verifiable on-chain)
+impl From<AuditListing> for ListAuditLogResponse {
+    fn from(listing: AuditListing) -> Self {
+        Self {
+            count: listing.entries.len(),
+            offset: listing.offset,
+            entries: listing.entries,
+            has_more: listing.has_more,
+        }
+    }
+}
+
+/// Documents created since a sync checkpoint, oldest first, sent as a Cartesi Report
 #[derive(Debug, Serialize)]
-pub struct NoticeResponse {
+pub struct SyncSinceResponse {
+    pub count: usize,
+    pub documents: Vec<Document>,
+    /// Pass as `since_created_at` on the next sync
+    pub next_since: i64,
+    /// True when another sync from `next_since` may return more documents
+    pub has_more: bool,
+}
+
+impl From<SyncBatch> for SyncSinceResponse {
+    fn from(batch: SyncBatch) -> Self {
+        Self {
+            count: batch.documents.len(),
+            documents: batch.documents,
+            next_since: batch.next_since,
+            has_more: batch.has_more,
+        }
+    }
+}
+
+/// Merkle inclusion proof of a document in an epoch, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct InclusionProofResponse {
+    pub epoch: u64,
+    #[serde(flatten)]
+    pub proof: MerkleProof,
+}
+
+/// Stable, machine-readable category of an error report
+/// Clients key localized messages on these; the detail text may change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Malformed or semantically invalid input
+    InvalidPayload,
+    InvalidBase64,
+    DuplicateDocument,
+    EmptyContent,
+    /// Hash is not 64 hexadecimal characters
+    InvalidHash,
+    NotFound,
+    RateLimited,
+    /// Sender may not perform the action
+    Unauthorized,
+    /// Payload, document or batch exceeds a configured limit
+    PayloadTooLarge,
+    /// Nonce is missing or was already used
+    InvalidNonce,
+    /// Deposited balance does not cover the notarization fee
+    InsufficientBalance,
+    /// Submitter notarized as many documents as allowed in the current epoch
+    QuotaExceeded,
+    /// Wallet signature covers a message that does not contain the content hash
+    SignedMessageMismatch,
+    /// `action` names no supported action
+    UnknownAction,
+    /// `version` names no supported payload protocol version
+    UnsupportedVersion,
+    /// A dependency such as the provenance oracle is not available
+    Unavailable,
+    /// Storage or other failure on the DApp's side
+    InternalError,
+}
+
+impl ErrorCode {
+    /// Best-fitting code for an error raised while handling an input
+    /// Errors of unknown types are reported as `InternalError`
+    pub fn classify(error: &(dyn StdError + 'static)) -> Self {
+        if let Some(e) = error.downcast_ref::<NotaryError>() {
+            return match e {
+                NotaryError::Notarize(e) => Self::classify(e),
+                NotaryError::Verify(e) => Self::classify(e),
+                NotaryError::Database(e) => Self::classify(e),
+            };
+        }
+        if let Some(e) = error.downcast_ref::<NotarizeError>() {
+            return match e {
+                NotarizeError::EmptyContent => Self::EmptyContent,
+                NotarizeError::DuplicateDocument { .. } => Self::DuplicateDocument,
+                NotarizeError::SupersededNotFound(_) => Self::NotFound,
+                NotarizeError::ContentTooLarge { .. } => Self::PayloadTooLarge,
+                NotarizeError::Unauthorized(_) | NotarizeError::SignatureMismatch { .. } => {
+                    Self::Unauthorized
+                }
+                NotarizeError::QuotaExceeded { .. } => Self::QuotaExceeded,
+                NotarizeError::SignedMessageMismatch { .. } => Self::SignedMessageMismatch,
+                NotarizeError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<VerifyError>() {
+            return match e {
+                VerifyError::InvalidHashFormat => Self::InvalidHash,
+                VerifyError::InvalidDocumentId => Self::InvalidPayload,
+                VerifyError::BatchTooLarge { .. } => Self::PayloadTooLarge,
+                VerifyError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<SearchError>() {
+            return match e {
+                SearchError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<CosignError>() {
+            return match e {
+                CosignError::NotFound => Self::NotFound,
+                CosignError::NotACosigner => Self::Unauthorized,
+                CosignError::AlreadyPending => Self::DuplicateDocument,
+                CosignError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<ChunkedUploadError>() {
+            return match e {
+                ChunkedUploadError::UploadNotFound(_) => Self::NotFound,
+                ChunkedUploadError::Unauthorized => Self::Unauthorized,
+                ChunkedUploadError::EmptyChunk | ChunkedUploadError::NoChunks => Self::EmptyContent,
+                ChunkedUploadError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<EpochError>() {
+            return match e {
+                EpochError::InvalidHashFormat => Self::InvalidHash,
+                EpochError::EpochNotSealed(_) | EpochError::NotInEpoch(_) => Self::NotFound,
+                EpochError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<FetchContentError>() {
+            return match e {
+                FetchContentError::NotFound | FetchContentError::ContentNotStored => Self::NotFound,
+                FetchContentError::Unauthorized | FetchContentError::SenderRequired => {
+                    Self::Unauthorized
+                }
+                FetchContentError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<NonceError>() {
+            return match e {
+                NonceError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidNonce,
+            };
+        }
+        if error.downcast_ref::<ProvenanceError>().is_some() {
+            return Self::Unavailable;
+        }
+        if let Some(e) = error.downcast_ref::<PurgeError>() {
+            return match e {
+                PurgeError::AdminNotConfigured | PurgeError::Unauthorized => Self::Unauthorized,
+                PurgeError::NotFound => Self::NotFound,
+                PurgeError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<RehashError>() {
+            return match e {
+                RehashError::AdminNotConfigured | RehashError::Unauthorized => Self::Unauthorized,
+                RehashError::ContentNotRetained => Self::Unavailable,
+                RehashError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<CommitError>() {
+            return match e {
+                CommitError::InvalidHashFormat => Self::InvalidHash,
+                CommitError::AlreadyCommitted { .. } | CommitError::AlreadyNotarized => {
+                    Self::DuplicateDocument
+                }
+                CommitError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if error.downcast_ref::<RateLimitError>().is_some() {
+            return Self::RateLimited;
+        }
+        if let Some(e) = error.downcast_ref::<TransferError>() {
+            return match e {
+                TransferError::NotFound => Self::NotFound,
+                TransferError::Unauthorized => Self::Unauthorized,
+                TransferError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<UpdateMetadataError>() {
+            return match e {
+                UpdateMetadataError::NotFound => Self::NotFound,
+                UpdateMetadataError::Unauthorized => Self::Unauthorized,
+                UpdateMetadataError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<AuditError>() {
+            return match e {
+                AuditError::DatabaseError(_) => Self::InternalError,
+                _ => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<BalanceError>() {
+            return match e {
+                BalanceError::Insufficient { .. } => Self::InsufficientBalance,
+                BalanceError::DatabaseError(_) => Self::InternalError,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<ProtocolVersionError>() {
+            return match e {
+                ProtocolVersionError::Unsupported(_) => Self::UnsupportedVersion,
+                ProtocolVersionError::Invalid(_) => Self::InvalidPayload,
+            };
+        }
+        if let Some(e) = error.downcast_ref::<DatabaseError>() {
+            return match e {
+                DatabaseError::NotFound => Self::NotFound,
+                DatabaseError::DuplicateHash => Self::DuplicateDocument,
+                _ => Self::InternalError,
+            };
+        }
+        if error.downcast_ref::<base64::DecodeError>().is_some() {
+            return Self::InvalidBase64;
+        }
+        if error.downcast_ref::<serde_json::Error>().is_some()
+            || error.downcast_ref::<AddressError>().is_some()
+            || error.downcast_ref::<DepositError>().is_some()
+            || error.downcast_ref::<OtsError>().is_some()
+        {
+            return Self::InvalidPayload;
+        }
+        Self::InternalError
+    }
+}
+
+/// Body of an error report, e.g. `{"error_code":"DUPLICATE_DOCUMENT","error_detail":"..."}`
+#[derive(Debug, Serialize)]
+struct ErrorReport<'a> {
+    error_code: ErrorCode,
+    error_detail: &'a str,
+}
+
+/// Serialize an error report with a stable `code` and human-readable `detail`
+pub fn error_report(code: ErrorCode, detail: &str) -> String {
+    serde_json::to_string(&ErrorReport {
+        error_code: code,
+        error_detail: detail,
+    })
+    .expect("error reports always serialize")
+}
+
+/// Error report for content that is already notarized, naming who notarized it first
+#[derive(Debug, Serialize)]
+struct DuplicateReport<'a> {
+    error_code: ErrorCode,
+    error_detail: &'a str,
+    original: &'a OriginalNotarization,
+}
+
+/// Serialize a `DUPLICATE_DOCUMENT` report carrying the original notarization
+pub fn duplicate_report(detail: &str, original: &OriginalNotarization) -> String {
+    serde_json::to_string(&DuplicateReport {
+        error_code: ErrorCode::DuplicateDocument,
+        error_detail: detail,
+        original,
+    })
+    .expect("error reports always serialize")
+}
+
+/// The earlier notarization `error` collides with, if it rejects a duplicate
+pub fn duplicate_of<'a>(error: &'a (dyn StdError + 'static)) -> Option<&'a OriginalNotarization> {
+    let error = match error.downcast_ref::<NotaryError>() {
+        Some(NotaryError::Notarize(e)) => e,
+        Some(_) => return None,
+        None => error.downcast_ref::<NotarizeError>()?,
+    };
+    match error {
+        NotarizeError::DuplicateDocument { original } => Some(original),
+        _ => None,
+    }
+}
+
+/// Body of a rollup exception, e.g. `{"error":"...","code":"INTERNAL_ERROR","causes":["..."]}`
+#[derive(Debug, Serialize)]
+struct ExceptionReport {
+    error: String,
+    code: ErrorCode,
+    /// Messages of the underlying errors, outermost first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    causes: Vec<String>,
+}
+
+/// Serialize an unrecoverable error for `/exception`, keeping its `source()` chain
+pub fn exception_report(error: &(dyn StdError + 'static)) -> String {
+    let causes = std::iter::successors(error.source(), |e| (*e).source())
+        .map(ToString::to_string)
+        .collect();
+    serde_json::to_string(&ExceptionReport {
+        error: error.to_string(),
+        code: ErrorCode::classify(error),
+        causes,
+    })
+    .expect("exception reports always serialize")
+}
+
+/// One request field that failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    /// Code the field's failure would be reported with on its own
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Report listing every validation failure of a request, so clients can fix them in one round trip
+/// A single failure keeps its own code and message; several are reported as `INVALID_PAYLOAD`
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub error_code: ErrorCode,
+    pub error_detail: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationReport {
+    /// Report for `errors`, or `None` when there are none
+    pub fn new(errors: Vec<FieldError>) -> Option<Self> {
+        let (error_code, error_detail) = match errors.as_slice() {
+            [] => return None,
+            [error] => (error.code, error.message.clone()),
+            _ => (
+                ErrorCode::InvalidPayload,
+                format!(
+                    "{} validation errors: {}",
+                    errors.len(),
+                    errors
+                        .iter()
+                        .map(|error| error.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+            ),
+        };
+
+        Some(Self {
+            error_code,
+            error_detail,
+            errors,
+        })
+    }
+}
+
+/// Report for a payload whose `action` tag is not recognized
+#[derive(Debug, Serialize)]
+pub struct UnknownActionResponse {
+    pub error_code: ErrorCode,
+    pub error_detail: String,
+    pub supported_actions: &'static [&'static str],
+}
+
+impl UnknownActionResponse {
+    pub fn new(action: &str) -> Self {
+        Self {
+            error_code: ErrorCode::UnknownAction,
+            error_detail: format!("Unknown action: {}", action),
+            supported_actions: SUPPORTED_ACTIONS,
+        }
+    }
+}
+
+/// Receipt proof of a document, a lighter alternative to a full verification report
+#[derive(Debug, Serialize)]
+pub struct ProofResponse {
+    pub exists: bool,
+    /// `sha256:<hash>@<timestamp>`, or null when the hash is not notarized
+    pub proof: Option<String>,
+}
+
+impl ProofResponse {
+    pub fn from_document(document: Option<&Document>) -> Self {
+        Self {
+            exists: document.is_some(),
+            proof: document.map(|doc| {
+                NotarizationReceipt::format_proof(doc.content_hash.as_str(), doc.created_at)
+            }),
+        }
+    }
+}
+
+/// Self-contained certificate of a notarization, signed so it can be checked offline
+/// The signature covers every other field, in the canonical JSON form of `signing`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateResponse {
     #[serde(rename = "type")]
     pub response_type: String,
+    pub document: Document,
+    /// Receipt of the notarization; the block number is not stored, so it is 0
     pub receipt: NotarizationReceipt,
+    /// Unix timestamp of the notarization, serialized as RFC 3339
+    #[serde(with = "crate::domain::rfc3339")]
+    pub notarized_at: i64,
+    /// Hex HMAC-SHA256 of the certificate without this field
+    pub signature: String,
 }
 
-impl NoticeResponse {
-    pub fn notarization(receipt: NotarizationReceipt) -> Self {
-        Self {
-            response_type: "notarization_receipt".to_string(),
+impl CertificateResponse {
+    pub fn new(document: Document, key: &NoticeSigningKey) -> Self {
+        let receipt = NotarizationReceipt::new(
+            document.id.to_string(),
+            document.content_hash.to_string(),
+            document.created_at,
+            0,
+        );
+        let mut certificate = Self {
+            response_type: "notarization_certificate".to_string(),
+            notarized_at: document.created_at,
+            document,
             receipt,
+            signature: String::new(),
+        };
+        certificate.signature = key.sign_json(&certificate.signed_fields());
+        certificate
+    }
+
+    /// Whether the certificate was signed with `key` and its receipt proof is intact
+    pub fn verify(&self, key: &[u8]) -> bool {
+        self.receipt.verify_proof()
+            && verify_json_signature(&self.signed_fields(), key, &self.signature)
+    }
+
+    /// The certificate as JSON, without its signature
+    fn signed_fields(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).expect("certificates always serialize");
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("signature");
         }
+        value
     }
 }
 
-/// Response sent as a Cartesi Report (not verifiable, for logs/queries)
+/// Notice publishing the Merkle root of a sealed epoch (verifiable on-chain)
 #[derive(Debug, Serialize)]
-pub struct ReportResponse {
-    pub exists: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub document: Option<Document>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub receipt: Option<NotarizationReceipt>,
+pub struct EpochRootNotice {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub epoch: u64,
+    pub root: String,
+    pub leaf_count: usize,
 }
 
-impl ReportResponse {
-    pub fn from_verification(result: &crate::application::VerificationResult) -> Self {
+impl EpochRootNotice {
+    pub fn new(sealed: EpochRoot) -> Self {
         Self {
-            exists: result.exists,
-            document: result.document.clone(),
-            receipt: result.receipt.clone(),
+            response_type: "epoch_root".to_string(),
+            epoch: sealed.epoch,
+            root: sealed.root,
+            leaf_count: sealed.leaf_count,
         }
     }
+}
+
+/// Retained document content, sent as a Cartesi Report
+#[derive(Debug, Serialize)]
+pub struct ContentResponse {
+    pub document_id: String,
+    pub content_hash: String,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Standard base64 encoding of the original bytes
+    pub content: String,
+}
+
+impl ContentResponse {
+    pub fn new(stored: StoredContent) -> Self {
+        use base64::Engine;
 
-    pub fn error(_message: &str) -> Self {
-        // For error cases, we could extend this with an error field
-        // For now, just return not found
         Self {
-            exists: false,
-            document: None,
-            receipt: None,
+            content: base64::engine::general_purpose::STANDARD.encode(&stored.content),
+            document_id: stored.document_id,
+            content_hash: stored.content_hash,
+            file_name: stored.file_name,
+            mime_type: stored.mime_type,
         }
     }
 }
@@ -81,6 +2233,24 @@ impl ReportResponse {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_unknown_action_names_unsupported_tag() {
+        assert_eq!(
+            InputAction::unknown_action(r#"{"action":"frobnicate"}"#),
+            Some("frobnicate".to_string())
+        );
+        assert_eq!(InputAction::unknown_action(r#"{"action":"metrics"}"#), None);
+        assert_eq!(
+            InputAction::unknown_action(r#"{"action":"list_by_tag"}"#),
+            None
+        );
+        assert_eq!(
+            InputAction::unknown_action(r#"{"content_hash":"abc"}"#),
+            None
+        );
+        assert_eq!(InputAction::unknown_action("not json"), None);
+    }
+
     #[test]
     fn test_input_action_deserialize_notarize() {
         let json = r#"{"action":"notarize","data":{"content":"SGVsbG8=","file_name":"test.txt","mime_type":"text/plain"}}"#;
@@ -95,6 +2265,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_notarize_request_builder_round_trips() {
+        const FIXTURE: NotarizeRequestBuilder = NotarizeRequestBuilder::new()
+            .content(b"Hello")
+            .file_name("test.txt");
+
+        let request = FIXTURE.nonce(3).tags(&["invoice"]).build().unwrap();
+        let json = serde_json::json!({ "action": "notarize", "data": request }).to_string();
+
+        match serde_json::from_str(&json).unwrap() {
+            InputAction::Notarize { data } => {
+                assert_eq!(data.content, "SGVsbG8=");
+                assert_eq!(data.mime_type, NotarizeRequestBuilder::DEFAULT_MIME_TYPE);
+                assert_eq!(data.nonce, Some(3));
+                assert_eq!(data.tags, vec!["invoice"]);
+                assert!(data.required_cosigners.is_none());
+            }
+            _ => panic!("Expected Notarize variant"),
+        }
+    }
+
+    #[test]
+    fn test_notarize_request_builder_requires_content_and_file_name() {
+        let missing_content = NotarizeRequestBuilder::default().file_name("test.txt");
+        assert_eq!(
+            NotarizeRequest::try_from(missing_content).unwrap_err(),
+            BuilderError::MissingField("content")
+        );
+
+        let missing_name = NotarizeRequestBuilder::new().content(b"Hello");
+        assert_eq!(
+            missing_name.build().unwrap_err().to_string(),
+            "Missing required field: file_name"
+        );
+    }
+
     #[test]
     fn test_input_action_deserialize_verify() {
         let json = r#"{"action":"verify","data":{"content_hash":"abc123"}}"#;
@@ -108,6 +2314,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_input_action_deserialize_metrics() {
+        let json = r#"{"action":"metrics"}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(action, InputAction::Metrics));
+    }
+
+    #[test]
+    fn test_input_action_deserialize_submitter_stats() {
+        let json = r#"{"action":"get_submitter_stats"}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            action,
+            InputAction::GetSubmitterStats {
+                data: SubmitterStatsRequest { submitter: None }
+            }
+        ));
+
+        let json = r#"{"action":"get_submitter_stats","data":{"submitter":"0xabc"}}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+        match action {
+            InputAction::GetSubmitterStats { data } => {
+                assert_eq!(data.submitter.as_deref(), Some("0xabc"));
+            }
+            _ => panic!("Expected GetSubmitterStats variant"),
+        }
+    }
+
+    #[test]
+    fn test_input_action_deserialize_health() {
+        let json = r#"{"action":"health"}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(action, InputAction::Health));
+    }
+
+    #[test]
+    fn test_input_action_deserialize_get_statistics() {
+        let json = r#"{"action":"get_statistics"}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(action, InputAction::GetStatistics));
+        assert_eq!(action.name(), "get_statistics");
+    }
+
+    #[test]
+    fn test_input_action_deserialize_update_metadata() {
+        let json = r#"{"action":"update_metadata","data":{"content_hash":"abc123","new_file_name":"renamed.txt"}}"#;
+        let action: InputAction = serde_json::from_str(json).unwrap();
+
+        match action {
+            InputAction::UpdateMetadata { data } => {
+                assert_eq!(data.content_hash, "abc123");
+                assert_eq!(data.new_file_name.as_deref(), Some("renamed.txt"));
+                assert!(data.new_mime_type.is_none());
+            }
+            _ => panic!("Expected UpdateMetadata variant"),
+        }
+    }
+
+    #[test]
+    fn test_unavailable_health_reports_error() {
+        let json =
+            serde_json::to_value(HealthResponse::unavailable("disk I/O error", 1.5)).unwrap();
+        assert_eq!(json["status"], "unavailable");
+        assert_eq!(json["error"], "disk I/O error");
+        assert!(json.get("db_document_count").is_none());
+        assert!(json.get("schema_version").is_none());
+    }
+
+    #[test]
+    fn test_response_encoding_defaults_to_json() {
+        let json = r#"{"content":"aGk=","file_name":"a.txt","mime_type":"text/plain"}"#;
+        let request: NotarizeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.response_encoding, ResponseEncoding::Json);
+        assert!(!serde_json::to_string(&request)
+            .unwrap()
+            .contains("response_encoding"));
+
+        let json = r#"{"content":"aGk=","file_name":"a.txt","mime_type":"text/plain","response_encoding":"cbor"}"#;
+        let request: NotarizeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.response_encoding, ResponseEncoding::Cbor);
+    }
+
+    #[test]
+    fn test_cbor_notice_payload_is_prefixed() {
+        use crate::domain::NotarizationReceipt;
+
+        let receipt =
+            NotarizationReceipt::new("doc-id".to_string(), "hash123".to_string(), 1234567890, 100);
+        let notice = NoticeResponse::notarization(receipt.clone());
+
+        let payload = ResponseEncoding::Cbor.encode(&notice).unwrap();
+        let body = payload.strip_prefix(CBOR_PAYLOAD_PREFIX).unwrap();
+        let decoded: serde_json::Value = ciborium::from_reader(body).unwrap();
+        assert_eq!(decoded["type"], "notarization_receipt");
+        assert_eq!(decoded["receipt"]["proof"], receipt.proof);
+
+        let payload = ResponseEncoding::Json.encode(&notice).unwrap();
+        assert_eq!(payload, serde_json::to_vec(&notice).unwrap());
+    }
+
     #[test]
     fn test_notice_response_serialize() {
         use crate::domain::NotarizationReceipt;
@@ -121,4 +2430,141 @@ mod tests {
         assert!(json.contains("notarization_receipt"));
         assert!(json.contains("doc-id"));
     }
+
+    #[test]
+    fn test_error_report_carries_code_and_detail() {
+        let report = error_report(ErrorCode::DuplicateDocument, "Already \"notarized\"");
+        let json: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(json["error_code"], "DUPLICATE_DOCUMENT");
+        assert_eq!(json["error_detail"], "Already \"notarized\"");
+    }
+
+    #[test]
+    fn test_exception_report_escapes_message_and_keeps_causes() {
+        let cause = rusqlite::Error::InvalidColumnName("{\"bad\"}".to_string());
+        let error = NotaryError::from(DatabaseError::from(cause));
+        let report = exception_report(&error);
+        let json: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(
+            json["error"],
+            "Database error: Invalid column name: {\"bad\"}"
+        );
+        assert_eq!(json["code"], "INTERNAL_ERROR");
+        assert_eq!(json["causes"][0], "Invalid column name: {\"bad\"}");
+    }
+
+    #[test]
+    fn test_classify_unwraps_library_errors() {
+        let wrapped = NotaryError::from(NotarizeError::DuplicateDocument {
+            original: OriginalNotarization {
+                document_id: "550e8400-e29b-41d4-a716-446655440000".into(),
+                submitted_by: "0x0000000000000000000000000000000000000123".to_string(),
+                created_at: 1_700_000_000,
+            },
+        });
+        assert_eq!(ErrorCode::classify(&wrapped), ErrorCode::DuplicateDocument);
+        assert_eq!(
+            duplicate_of(&wrapped).unwrap().submitted_by,
+            "0x0000000000000000000000000000000000000123"
+        );
+
+        let boxed: Box<dyn StdError> = Box::new(VerifyError::InvalidHashFormat);
+        assert_eq!(ErrorCode::classify(&*boxed), ErrorCode::InvalidHash);
+
+        let unknown: Box<dyn StdError> = "unexpected".into();
+        assert_eq!(ErrorCode::classify(&*unknown), ErrorCode::InternalError);
+    }
+
+    fn notarize_request(content: &str, file_name: &str, mime_type: &str) -> NotarizeRequest {
+        serde_json::from_value(serde_json::json!({
+            "content": content,
+            "file_name": file_name,
+            "mime_type": mime_type,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_notarize_request_has_no_field_errors() {
+        for mime_type in [
+            "application/pdf",
+            "text/plain; charset=utf-8",
+            "image/svg+xml",
+        ] {
+            let request = notarize_request("SGVsbG8_-w==", "doc.pdf", mime_type);
+            assert_eq!(request.validate(), Vec::new(), "{}", mime_type);
+        }
+    }
+
+    #[test]
+    fn test_notarize_request_collects_every_field_error() {
+        let request = notarize_request("SGVs bG8=", " ", "pdf");
+        let fields: Vec<_> = request
+            .validate()
+            .into_iter()
+            .map(|error| (error.field, error.code))
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("content", ErrorCode::InvalidBase64),
+                ("file_name", ErrorCode::InvalidPayload),
+                ("mime_type", ErrorCode::InvalidPayload),
+            ]
+        );
+
+        let errors = notarize_request("", "doc.pdf", "text/").validate();
+        assert_eq!(errors[0].code, ErrorCode::EmptyContent);
+        assert_eq!(errors[1].field, "mime_type");
+    }
+
+    #[test]
+    fn test_notarize_signature_needs_signed_message() {
+        let mut request = notarize_request("SGVsbG8=", "doc.pdf", "text/plain");
+        request.signature = Some(format!("0x{}", "ab".repeat(65)));
+        assert_eq!(request.validate()[0].field, "signed_message");
+
+        request.signed_message = Some("hello".to_string());
+        assert!(request.validate().is_empty());
+
+        request.signature = None;
+        assert_eq!(request.validate()[0].field, "signature");
+    }
+
+    #[test]
+    fn test_verify_request_checks_hash_length() {
+        let valid = VerifyRequest {
+            content_hash: "a".repeat(64),
+        };
+        assert!(valid.validate().is_empty());
+
+        let short = VerifyRequest {
+            content_hash: "a".repeat(63),
+        };
+        assert_eq!(short.validate()[0].code, ErrorCode::InvalidHash);
+    }
+
+    #[test]
+    fn test_validation_report_codes() {
+        assert!(ValidationReport::new(Vec::new()).is_none());
+
+        let single = ValidationReport::new(vec![FieldError::new(
+            "content",
+            ErrorCode::EmptyContent,
+            "Content cannot be empty",
+        )])
+        .unwrap();
+        assert_eq!(single.error_code, ErrorCode::EmptyContent);
+        assert_eq!(single.error_detail, "Content cannot be empty");
+
+        let several = ValidationReport::new(notarize_request("", "", "").validate()).unwrap();
+        assert_eq!(several.error_code, ErrorCode::InvalidPayload);
+        assert_eq!(several.errors.len(), 3);
+        assert!(several
+            .error_detail
+            .starts_with("3 validation errors: Content cannot be empty; Filename cannot be empty"));
+    }
 }
@@ -0,0 +1,168 @@
+use super::notarize::validate_filename;
+use crate::domain::ContentHash;
+use crate::infrastructure::database::DocumentRepository;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::error::Error;
+use thiserror::Error;
+
+/// Largest serialized metadata object a document may carry, in bytes
+pub const MAX_METADATA_BYTES: usize = 4096;
+
+#[derive(Error, Debug)]
+pub enum UpdateMetadataError {
+    #[error("Document not found")]
+    NotFound,
+
+    #[error("Only the original submitter can update document metadata")]
+    Unauthorized,
+
+    #[error("No metadata fields to update")]
+    NothingToUpdate,
+
+    #[error("Filename cannot be empty")]
+    EmptyFilename,
+
+    #[error("MIME type cannot be empty")]
+    EmptyMimeType,
+
+    #[error("Metadata must be a JSON object")]
+    MetadataNotObject,
+
+    #[error("Metadata is {size} bytes, exceeds limit of {limit} bytes")]
+    MetadataTooLarge { size: usize, limit: usize },
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Record of a metadata change, emitted as a notice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataChange {
+    pub document_id: String,
+    pub content_hash: String,
+    pub previous_file_name: String,
+    pub previous_mime_type: String,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Metadata object after the merge, present only when metadata was updated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    pub updated_by: String,
+}
+
+pub struct UpdateMetadataUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+}
+
+impl<'a> UpdateMetadataUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Rename, retype or annotate a document
+    /// `new_metadata` keys are merged into the stored metadata; a `null` value removes the key
+    pub fn execute(
+        &self,
+        content_hash: &str,
+        new_file_name: Option<&str>,
+        new_mime_type: Option<&str>,
+        new_metadata: Option<&Value>,
+        caller: &str,
+    ) -> Result<MetadataChange, Box<dyn Error>> {
+        // Validate inputs
+        if new_file_name.is_none() && new_mime_type.is_none() && new_metadata.is_none() {
+            return Err(Box::new(UpdateMetadataError::NothingToUpdate));
+        }
+
+        if new_file_name.is_some_and(|name| name.trim().is_empty()) {
+            return Err(Box::new(UpdateMetadataError::EmptyFilename));
+        }
+
+        if let Some(name) = new_file_name {
+            validate_filename(name)?;
+        }
+
+        if new_mime_type.is_some_and(|mime| mime.trim().is_empty()) {
+            return Err(Box::new(UpdateMetadataError::EmptyMimeType));
+        }
+
+        let patch = match new_metadata {
+            Some(Value::Object(patch)) => Some(patch),
+            Some(_) => return Err(Box::new(UpdateMetadataError::MetadataNotObject)),
+            None => None,
+        };
+
+        // Only the submitter may change metadata
+        let content_hash = ContentHash::from(content_hash);
+        let previous = self
+            .repository
+            .find_by_hash(&content_hash)
+            .map_err(|_| Box::new(UpdateMetadataError::NotFound) as Box<dyn Error>)?;
+
+        if !previous.submitted_by.eq_ignore_ascii_case(caller) {
+            return Err(Box::new(UpdateMetadataError::Unauthorized));
+        }
+
+        let merged = match patch {
+            Some(patch) => {
+                let merged = merge_metadata(&previous.metadata, patch);
+                let size = merged.to_string().len();
+                if size > MAX_METADATA_BYTES {
+                    return Err(Box::new(UpdateMetadataError::MetadataTooLarge {
+                        size,
+                        limit: MAX_METADATA_BYTES,
+                    }));
+                }
+                Some(merged)
+            }
+            None => None,
+        };
+
+        let updated = self
+            .repository
+            .update_metadata(&content_hash, new_file_name, new_mime_type, merged.as_ref())
+            .map_err(|e| {
+                Box::new(UpdateMetadataError::DatabaseError(e.to_string())) as Box<dyn Error>
+            })?;
+
+        Ok(MetadataChange {
+            document_id: updated.id.into(),
+            content_hash: updated.content_hash.into(),
+            previous_file_name: previous.file_name,
+            previous_mime_type: previous.mime_type,
+            file_name: updated.file_name,
+            mime_type: updated.mime_type,
+            metadata: merged.map(|_| updated.metadata),
+            updated_by: caller.to_string(),
+        })
+    }
+}
+
+/// Shallow merge of `patch` into `current`: top-level keys are replaced, `null` deletes
+fn merge_metadata(current: &Value, patch: &Map<String, Value>) -> Value {
+    let mut merged = current.as_object().cloned().unwrap_or_default();
+    for (key, value) in patch {
+        if value.is_null() {
+            merged.remove(key);
+        } else {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::SqliteRepository;
+
+    #[test]
+    fn test_update_metadata_requires_a_field() {
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        let usecase = UpdateMetadataUseCase::new(&repo);
+
+        let result = usecase.execute("hash", None, None, None, "0x123");
+        assert!(result.unwrap_err().to_string().contains("No metadata"));
+    }
+}
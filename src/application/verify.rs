@@ -1,81 +1,440 @@
-use crate::domain::{Document, NotarizationReceipt};
-use crate::infrastructure::database::DocumentRepository;
+use super::notarize::NotarizeError;
+use crate::domain::{
+    Clock, ContentHash, Document, DocumentId, HashAlgorithm, NotarizationReceipt, SystemClock,
+};
+use crate::error::NotaryError;
+use crate::infrastructure::database::{DatabaseError, DocumentRepository};
+use crate::infrastructure::metrics;
+use lru::LruCache;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::error::Error;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+/// Largest number of hashes accepted in a single batch verification
+pub const MAX_BATCH_VERIFY_SIZE: usize = 100;
+
 #[derive(Error, Debug)]
 pub enum VerifyError {
     #[error("Invalid hash format: must be 64 hexadecimal characters")]
     InvalidHashFormat,
 
+    #[error("Invalid document id: must be a UUID")]
+    InvalidDocumentId,
+
+    #[error("Batch of {count} hashes exceeds limit of {limit}")]
+    BatchTooLarge { count: usize, limit: usize },
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
 
+/// Which of a document's digests a verified hash matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchedHash {
+    Sha256,
+    Keccak256,
+    Blake2b256,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationResult {
     pub exists: bool,
+    /// True when the document exists but its expiry has passed
+    pub expired: bool,
     pub document: Option<Document>,
     pub receipt: Option<NotarizationReceipt>,
+    /// Category tags of the document, in alphabetical order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Digest the queried hash matched, when looked up by a single hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_hash: Option<MatchedHash>,
+    /// Whether submitted content hashes to the document's digest, when content was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_matches: Option<bool>,
+    /// Submitter's description of the document, when one was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Other filenames the same content was notarized under, in the order they were added
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Block at which the content hash was first committed, whether or not the
+    /// document has been notarized since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<u64>,
+    /// SHA-256 hash computed from submitted content, when the lookup started from content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub computed_hash: Option<String>,
 }
 
 impl VerificationResult {
     pub fn not_found() -> Self {
         Self {
             exists: false,
+            expired: false,
             document: None,
             receipt: None,
+            tags: Vec::new(),
+            matched_hash: None,
+            content_matches: None,
+            description: None,
+            aliases: Vec::new(),
+            committed_at: None,
+            computed_hash: None,
         }
     }
 
-    pub fn found(document: Document) -> Self {
+    pub fn found(document: Document, now: i64) -> Self {
         // Reconstruct receipt from document
         // Note: We don't have block_number stored in document yet
         // For MVP, we'll use 0 as placeholder or extend Document later
         let receipt = NotarizationReceipt::new(
-            document.id.clone(),
-            document.content_hash.clone(),
+            document.id.to_string(),
+            document.content_hash.to_string(),
             document.created_at,
             0, // Placeholder - we'd need to store this or retrieve it differently
         );
 
         Self {
             exists: true,
+            expired: document.is_expired_at(now),
+            description: document.description.clone(),
             document: Some(document),
             receipt: Some(receipt),
+            tags: Vec::new(),
+            matched_hash: None,
+            content_matches: None,
+            aliases: Vec::new(),
+            committed_at: None,
+            computed_hash: None,
         }
     }
+
+    /// Record which digest the queried hash matched
+    pub fn with_matched_hash(mut self, matched_hash: MatchedHash) -> Self {
+        self.matched_hash = Some(matched_hash);
+        self
+    }
+
+    /// Record whether submitted content matched the document's digest
+    pub fn with_content_matches(mut self, content_matches: bool) -> Self {
+        self.content_matches = Some(content_matches);
+        self
+    }
+
+    /// Attach the document's tags
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Attach the document's alias filenames
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Record the hash computed from submitted content
+    pub fn with_computed_hash(mut self, computed_hash: String) -> Self {
+        self.computed_hash = Some(computed_hash);
+        self
+    }
+
+    /// Attach the block of an earlier commitment to the hash, on the receipt as well
+    pub fn with_committed_at(mut self, committed_at: Option<u64>) -> Self {
+        self.committed_at = committed_at;
+        if let Some(receipt) = self.receipt.take() {
+            self.receipt = Some(receipt.with_committed_at(committed_at));
+        }
+        self
+    }
 }
 
-pub struct VerifyUseCase {
-    repository: Box<dyn DocumentRepository>,
+pub struct VerifyUseCase<'a> {
+    repository: &'a dyn DocumentRepository,
+    clock: &'a dyn Clock,
 }
 
-impl VerifyUseCase {
-    pub fn new(repository: Box<dyn DocumentRepository>) -> Self {
-        Self { repository }
+impl<'a> VerifyUseCase<'a> {
+    pub fn new(repository: &'a dyn DocumentRepository) -> Self {
+        Self {
+            repository,
+            clock: &SystemClock,
+        }
     }
 
-    pub fn execute(&self, content_hash: &str) -> Result<VerificationResult, Box<dyn Error>> {
+    /// Judge expiry by `clock` instead of the host clock
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn execute(&self, content_hash: &str) -> Result<VerificationResult, NotaryError> {
         // Validate hash format
         if !Self::is_valid_hash(content_hash) {
-            return Err(Box::new(VerifyError::InvalidHashFormat));
+            return Err(NotaryError::from(VerifyError::InvalidHashFormat));
+        }
+
+        metrics::VERIFICATIONS_TOTAL.inc();
+
+        // Query repository, by SHA-256 first and then by Keccak-256
+        let (document, matched_hash) = match self
+            .repository
+            .find_by_hash(&ContentHash::from(content_hash))
+        {
+            Ok(document) => (document, MatchedHash::Sha256),
+            Err(_) => match self.repository.find_by_keccak(content_hash) {
+                Ok(document) => (document, MatchedHash::Keccak256),
+                // A committed hash is reported even before its document is revealed
+                Err(_) => {
+                    let committed_at =
+                        self.committed_at(&ContentHash::from(content_hash.to_lowercase()))?;
+                    return Ok(VerificationResult::not_found().with_committed_at(committed_at));
+                }
+            },
+        };
+
+        Ok(self.found(document)?.with_matched_hash(matched_hash))
+    }
+
+    /// Verify a document by the Blake2b-256 digest recorded when Blake2b hashing is enabled
+    pub fn execute_blake2b(&self, blake2b_hash: &str) -> Result<VerificationResult, NotaryError> {
+        if !Self::is_valid_hash(blake2b_hash) {
+            return Err(NotaryError::from(VerifyError::InvalidHashFormat));
+        }
+
+        metrics::VERIFICATIONS_TOTAL.inc();
+
+        let document = match self
+            .repository
+            .find_by_blake2b_hash(&blake2b_hash.to_lowercase())
+        {
+            Ok(document) => document,
+            Err(DatabaseError::NotFound) => return Ok(VerificationResult::not_found()),
+            Err(e) => return Err(NotaryError::from(VerifyError::DatabaseError(e.to_string()))),
+        };
+
+        Ok(self
+            .found(document)?
+            .with_matched_hash(MatchedHash::Blake2b256))
+    }
+
+    /// Look up a document by SHA-256 hash and check that `content` rehashes to it
+    /// Unlike `execute`, this proves the caller holds the notarized content
+    pub fn execute_with_content(
+        &self,
+        content_hash: &str,
+        content: &[u8],
+    ) -> Result<VerificationResult, NotaryError> {
+        if !Self::is_valid_hash(content_hash) {
+            return Err(NotaryError::from(VerifyError::InvalidHashFormat));
+        }
+
+        metrics::VERIFICATIONS_TOTAL.inc();
+
+        // Stored SHA-256 hashes are lowercase hex
+        let content_hash = ContentHash::from(content_hash.to_lowercase());
+        let document = match self.repository.find_by_hash(&content_hash) {
+            Ok(document) => document,
+            Err(DatabaseError::NotFound) => {
+                return Ok(VerificationResult::not_found().with_content_matches(false))
+            }
+            Err(e) => return Err(NotaryError::from(VerifyError::DatabaseError(e.to_string()))),
+        };
+
+        let content_matches = document.verify_content(content, HashAlgorithm::Sha256);
+
+        Ok(self
+            .found(document)?
+            .with_matched_hash(MatchedHash::Sha256)
+            .with_content_matches(content_matches))
+    }
+
+    /// Hash `content` with SHA-256, the digest every document is notarized under, and look
+    /// the hash up, for clients that cannot hash content themselves
+    pub fn execute_by_content(&self, content: &[u8]) -> Result<VerificationResult, NotaryError> {
+        if content.is_empty() {
+            return Err(NotaryError::from(NotarizeError::EmptyContent));
         }
 
-        // Query repository
-        match self.repository.find_by_hash(content_hash) {
-            Ok(document) => Ok(VerificationResult::found(document)),
-            Err(_) => Ok(VerificationResult::not_found()),
+        let content_hash = Document::hash_content(content);
+        Ok(self
+            .execute(content_hash.as_str())?
+            .with_computed_hash(content_hash.into()))
+    }
+
+    /// Look up a document by the id given in its notarization notice
+    pub fn execute_by_id(&self, id: &str) -> Result<VerificationResult, NotaryError> {
+        // Stored ids are lowercase hyphenated UUIDs
+        let id = uuid::Uuid::parse_str(id)
+            .map_err(|_| NotaryError::from(VerifyError::InvalidDocumentId))?
+            .to_string();
+
+        let document = match self.repository.find_by_id(&DocumentId::from(id)) {
+            Ok(document) => document,
+            Err(DatabaseError::NotFound) => return Ok(VerificationResult::not_found()),
+            Err(e) => return Err(NotaryError::from(VerifyError::DatabaseError(e.to_string()))),
+        };
+
+        self.found(document)
+    }
+
+    /// Verify several hashes with a single database query
+    /// Each entry keeps the input position; malformed hashes get their own error
+    pub fn execute_batch(
+        &self,
+        content_hashes: &[String],
+    ) -> Result<Vec<Result<VerificationResult, VerifyError>>, NotaryError> {
+        if content_hashes.len() > MAX_BATCH_VERIFY_SIZE {
+            return Err(NotaryError::from(VerifyError::BatchTooLarge {
+                count: content_hashes.len(),
+                limit: MAX_BATCH_VERIFY_SIZE,
+            }));
         }
+
+        let valid: Vec<ContentHash> = content_hashes
+            .iter()
+            .filter(|hash| Self::is_valid_hash(hash))
+            .map(|hash| ContentHash::from(hash.as_str()))
+            .collect();
+
+        metrics::VERIFICATIONS_TOTAL.inc_by(valid.len() as u64);
+
+        // Repeated hashes are looked up once and share the same document
+        let unique: HashSet<&ContentHash> = valid.iter().collect();
+        let unique: Vec<&ContentHash> = unique.into_iter().collect();
+        let documents: HashSet<Document> = self
+            .repository
+            .find_many_by_hashes(&unique)
+            .map_err(|e| NotaryError::from(VerifyError::DatabaseError(e.to_string())))?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let now = self.clock.now();
+        Ok(content_hashes
+            .iter()
+            .map(|hash| {
+                if !Self::is_valid_hash(hash) {
+                    return Err(VerifyError::InvalidHashFormat);
+                }
+
+                Ok(match documents.get(&ContentHash::from(hash.as_str())) {
+                    Some(document) => VerificationResult::found(document.clone(), now),
+                    None => VerificationResult::not_found(),
+                })
+            })
+            .collect())
+    }
+
+    /// Result for a found document, with its tags and alias filenames
+    fn found(&self, document: Document) -> Result<VerificationResult, NotaryError> {
+        let to_db_error =
+            |e: DatabaseError| NotaryError::from(VerifyError::DatabaseError(e.to_string()));
+
+        let tags = self
+            .repository
+            .find_tags(&document.id)
+            .map_err(to_db_error)?;
+        // The first name is the document's own
+        let aliases = self
+            .repository
+            .find_all_by_content_hash(&document.content_hash)
+            .map_err(to_db_error)?
+            .into_iter()
+            .skip(1)
+            .collect();
+
+        let committed_at = self.committed_at(&document.content_hash)?;
+
+        Ok(VerificationResult::found(document, self.clock.now())
+            .with_tags(tags)
+            .with_aliases(aliases)
+            .with_committed_at(committed_at))
+    }
+
+    /// Block at which `content_hash` was committed, if it was
+    fn committed_at(&self, content_hash: &ContentHash) -> Result<Option<u64>, NotaryError> {
+        self.repository
+            .find_commitment(content_hash)
+            .map(|commitment| commitment.map(|c| c.block_number))
+            .map_err(|e| NotaryError::from(VerifyError::DatabaseError(e.to_string())))
     }
 
     fn is_valid_hash(hash: &str) -> bool {
-        // SHA-256 produces 64 hex characters
+        // SHA-256, Keccak-256 and Blake2b-256 all produce 64 hex characters
         hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
     }
 }
 
+/// `VerifyUseCase` wrapper that keeps recently found documents in memory
+/// Not-found results are never cached, so a freshly notarized document is seen
+/// on the next lookup. Callers that change or delete a document must `invalidate` it.
+pub struct LruVerifyCache<'a> {
+    usecase: VerifyUseCase<'a>,
+    cache: Arc<Mutex<LruCache<String, VerificationResult>>>,
+}
+
+impl<'a> LruVerifyCache<'a> {
+    /// A `capacity` of zero is treated as one
+    pub fn new(usecase: VerifyUseCase<'a>, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            usecase,
+            cache: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    pub fn execute(&self, content_hash: &str) -> Result<VerificationResult, Box<dyn Error>> {
+        if let Some(cached) = self.lock()?.get(content_hash) {
+            metrics::VERIFICATIONS_TOTAL.inc();
+
+            // Expiry depends on the use case's clock now, not on when the entry was cached
+            let mut result = cached.clone();
+            result.expired = result
+                .document
+                .as_ref()
+                .is_some_and(|doc| doc.is_expired_at(self.usecase.clock.now()));
+            return Ok(result);
+        }
+
+        let result = self.usecase.execute(content_hash)?;
+        if result.exists {
+            self.lock()?.put(content_hash.to_string(), result.clone());
+        }
+
+        Ok(result)
+    }
+
+    /// Drop a cached entry after the document was updated, transferred or purged
+    pub fn invalidate(&self, content_hash: &str) -> Result<(), Box<dyn Error>> {
+        self.lock()?.pop(content_hash);
+        Ok(())
+    }
+
+    /// Number of cached results
+    pub fn len(&self) -> usize {
+        self.cache.lock().map(|cache| cache.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, LruCache<String, VerificationResult>>, Box<dyn Error>>
+    {
+        self.cache
+            .lock()
+            .map_err(|_| "Verification cache lock poisoned".into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,7 +443,7 @@ mod tests {
     #[test]
     fn test_verify_usecase_creation() {
         let repo = SqliteRepository::new_in_memory().unwrap();
-        let _usecase = VerifyUseCase::new(Box::new(repo));
+        let _usecase = VerifyUseCase::new(&repo);
     }
 
     #[test]
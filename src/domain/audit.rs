@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether an advance input was accepted or rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Accept,
+    Reject,
+}
+
+impl AuditOutcome {
+    /// Outcome of an advance status; anything but `"accept"` is a rejection
+    pub fn from_status(status: &str) -> Self {
+        if status == "accept" {
+            Self::Accept
+        } else {
+            Self::Reject
+        }
+    }
+
+    /// The advance status this outcome was recorded from
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+/// One accepted advance input as recorded in the append-only audit log
+/// Rejected inputs are not logged: the node reverts their state, entry included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log, assigned when the entry is appended
+    pub id: i64,
+    /// Action name from the payload, or `deposit` for portal deposits
+    pub action: String,
+    /// Lowercase `msg_sender`, empty when the input carried none
+    pub submitter: String,
+    pub block_number: u64,
+    /// Unix timestamp of the block the input was included in
+    pub timestamp: i64,
+    pub outcome: AuditOutcome,
+    /// Content hash the input named or notarized, when there was one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
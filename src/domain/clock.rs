@@ -0,0 +1,25 @@
+/// Source of the current time as a Unix timestamp
+/// Advance inputs carry their own block timestamp; a clock only stands in where none is given
+pub trait Clock {
+    fn now(&self) -> i64;
+}
+
+/// The host's wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+}
+
+/// A clock stopped at one Unix timestamp, for reproducible tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// A content hash published ahead of its document, to prove the document existed
+/// before it is revealed by notarizing the content
+/// No filename, MIME type or content is recorded, so a commitment discloses nothing
+/// beyond the hash itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment {
+    pub content_hash: String,
+    pub committed_by: String,
+    pub block_number: u64,
+    pub timestamp: i64,
+}
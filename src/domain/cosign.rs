@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// A notarization held back until every required cosigner has approved it
+/// The content is kept only until the notarization completes or times out
+#[derive(Debug, Clone)]
+pub struct PendingNotarization {
+    pub content_hash: String,
+    pub submitted_by: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+    pub expires_at: Option<i64>,
+    pub expiration_blocks: Option<u64>,
+    pub tags: Vec<String>,
+    pub supersedes: Option<String>,
+    pub description: Option<String>,
+    /// Wallet signature of `signed_message` by the submitter, checked again on completion
+    pub signature: Option<String>,
+    pub signed_message: Option<String>,
+    pub created_at: i64,
+    /// Unix timestamp after which outstanding cosignatures are no longer accepted
+    pub deadline: i64,
+}
+
+impl PendingNotarization {
+    /// Whether the cosigning window has closed at the given Unix timestamp
+    pub fn is_expired_at(&self, now: i64) -> bool {
+        self.deadline <= now
+    }
+}
+
+/// A required cosigner of a pending notarization and its approval, if given
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cosigner {
+    pub address: String,
+    /// Attestation submitted with the approval; stored as given, not verified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_at: Option<i64>,
+}
+
+impl Cosigner {
+    pub fn has_signed(&self) -> bool {
+        self.signed_at.is_some()
+    }
+}
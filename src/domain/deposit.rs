@@ -0,0 +1,76 @@
+use super::EthAddress;
+use thiserror::Error;
+
+/// Token address under which Ether balances are kept
+pub const ETHER_TOKEN: &str = "0x0000000000000000000000000000000000000000";
+
+const ADDRESS_LEN: usize = 20;
+const AMOUNT_LEN: usize = 32;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DepositError {
+    #[error("Deposit payload must be at least {expected} bytes, got {actual}")]
+    TooShort { expected: usize, actual: usize },
+
+    #[error("Deposit amount does not fit in 128 bits")]
+    AmountTooLarge,
+}
+
+/// Funds forwarded to the DApp by a Cartesi portal contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    /// ERC-20 token contract, or `ETHER_TOKEN` for Ether
+    pub token: EthAddress,
+    /// Account whose balance is credited
+    pub depositor: EthAddress,
+    pub amount: u128,
+}
+
+impl Deposit {
+    /// Parse an ERC20Portal input: packed `token`, `sender`, `uint256 amount`, then exec-layer data
+    pub fn from_erc20_portal(payload: &[u8]) -> Result<Self, DepositError> {
+        check_len(payload, 2 * ADDRESS_LEN + AMOUNT_LEN)?;
+
+        Ok(Self {
+            token: read_address(&payload[..ADDRESS_LEN]),
+            depositor: read_address(&payload[ADDRESS_LEN..2 * ADDRESS_LEN]),
+            amount: read_amount(&payload[2 * ADDRESS_LEN..2 * ADDRESS_LEN + AMOUNT_LEN])?,
+        })
+    }
+
+    /// Parse an EtherPortal input: packed `sender`, `uint256 value`, then exec-layer data
+    pub fn from_ether_portal(payload: &[u8]) -> Result<Self, DepositError> {
+        check_len(payload, ADDRESS_LEN + AMOUNT_LEN)?;
+
+        Ok(Self {
+            token: EthAddress::from_bytes([0; ADDRESS_LEN]),
+            depositor: read_address(&payload[..ADDRESS_LEN]),
+            amount: read_amount(&payload[ADDRESS_LEN..ADDRESS_LEN + AMOUNT_LEN])?,
+        })
+    }
+}
+
+fn check_len(payload: &[u8], expected: usize) -> Result<(), DepositError> {
+    if payload.len() < expected {
+        return Err(DepositError::TooShort {
+            expected,
+            actual: payload.len(),
+        });
+    }
+    Ok(())
+}
+
+fn read_address(bytes: &[u8]) -> EthAddress {
+    EthAddress::from_bytes(bytes.try_into().expect("address slices are 20 bytes"))
+}
+
+/// Balances are kept as `u128`; larger uint256 amounts are refused
+fn read_amount(word: &[u8]) -> Result<u128, DepositError> {
+    let (high, low) = word.split_at(AMOUNT_LEN - 16);
+    if high.iter().any(|&byte| byte != 0) {
+        return Err(DepositError::AmountTooLarge);
+    }
+    Ok(u128::from_be_bytes(
+        low.try_into().expect("low half is 16 bytes"),
+    ))
+}
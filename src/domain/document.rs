@@ -1,29 +1,108 @@
+use super::clock::{Clock, SystemClock};
+use super::mime::detect_mime;
+use super::ots::HashAlgorithm;
+use super::types::{ContentHash, DocumentId};
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
+/// CIDv1 header for raw content addressed by SHA-256: version, `raw` codec,
+/// then the `sha2-256` multihash code and digest length
+const CID_V1_RAW_SHA256_PREFIX: [u8; 4] = [0x01, 0x55, 0x12, 0x20];
+
+/// RFC 4648 base32 alphabet in lowercase, as used by the `b` multibase prefix
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Documents are equal when they notarize the same content, whatever their id,
+/// so equality and hashing only consider `content_hash`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
-    pub id: String,
-    pub content_hash: String,
+    pub id: DocumentId,
+    pub content_hash: ContentHash,
     pub file_name: String,
     pub mime_type: String,
     pub submitted_by: String,
+    /// Unix timestamp, serialized as RFC 3339
+    #[serde(with = "super::timestamp::rfc3339")]
     pub created_at: i64,
+    /// Unix timestamp after which the notarization is no longer valid, serialized as RFC 3339
+    #[serde(
+        default,
+        with = "super::timestamp::rfc3339_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires_at: Option<i64>,
+    /// Content hash of the earlier version this document amends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// MIME type recognized from the content itself, which may differ from `mime_type`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_mime_type: Option<String>,
+    /// IPFS CIDv1 of the content, when CID generation is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipfs_cid: Option<String>,
+    /// Hex Keccak-256 of the content, for EVM integrations
+    /// Absent for documents notarized before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash_keccak: Option<String>,
+    /// Hex Blake2b-256 of the content, when Blake2b hashing is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blake2b_hash: Option<String>,
+    /// Free-form description supplied by the submitter, e.g. "Q3 2024 financial audit report"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Hex `personal_sign` signature of `signed_message` by the submitter's wallet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_signature: Option<String>,
+    /// EIP-191 message behind `wallet_signature`, which contains the content hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_message: Option<String>,
+    /// Rollup epoch of the advance input that notarized the document; 0 outside the rollup
+    #[serde(default)]
+    pub epoch_index: u64,
+    /// Index of that input within its epoch; 0 outside the rollup
+    #[serde(default)]
+    pub input_index: u64,
+    /// Caller-defined attributes as a JSON object, so new attributes need no schema change
+    #[serde(default = "empty_metadata", skip_serializing_if = "is_empty_metadata")]
+    pub metadata: serde_json::Value,
 }
 
 impl Document {
+    /// Document created now by the host clock
+    /// Advance inputs must use `new_at` with the input's timestamp, so every node records the same time
     pub fn new(content: &[u8], file_name: &str, mime_type: &str, submitted_by: &str) -> Self {
-        // Generate SHA-256 hash
-        let mut hasher = Sha256::new();
-        hasher.update(content);
-        let hash_bytes = hasher.finalize();
-        let content_hash = format!("{:x}", hash_bytes);
+        Self::new_with_clock(content, file_name, mime_type, submitted_by, &SystemClock)
+    }
 
-        // Generate unique ID
-        let id = uuid::Uuid::new_v4().to_string();
+    /// Document created at the time `clock` reads now
+    pub fn new_with_clock(
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        clock: &dyn Clock,
+    ) -> Self {
+        Self::new_at(content, file_name, mime_type, submitted_by, clock.now())
+    }
 
-        // Get current timestamp
-        let created_at = chrono::Utc::now().timestamp();
+    /// Document notarized at `created_at` (Unix timestamp)
+    pub fn new_at(
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitted_by: &str,
+        created_at: i64,
+    ) -> Self {
+        let content_hash = Self::hash_content(content);
+
+        // Generate unique ID
+        let id = DocumentId::from(uuid::Uuid::new_v4().to_string());
 
         Self {
             id,
@@ -32,8 +111,186 @@ impl Document {
             mime_type: mime_type.to_string(),
             submitted_by: submitted_by.to_string(),
             created_at,
+            expires_at: None,
+            supersedes: None,
+            detected_mime_type: detect_mime(content).map(str::to_string),
+            ipfs_cid: None,
+            content_hash_keccak: Some(Self::hash_content_keccak(content)),
+            blake2b_hash: None,
+            description: None,
+            wallet_signature: None,
+            signed_message: None,
+            epoch_index: 0,
+            input_index: 0,
+            metadata: empty_metadata(),
         }
     }
+
+    /// Hex SHA-256 of `content`, the hash documents are notarized under
+    pub fn hash_content(content: &[u8]) -> ContentHash {
+        let mut hasher = Sha256::new();
+        hasher.update(content);
+        ContentHash::from(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Hex Keccak-256 of `content`, recorded beside the SHA-256 hash
+    pub fn hash_content_keccak(content: &[u8]) -> String {
+        hex::encode(Keccak256::digest(content))
+    }
+
+    /// Whether `content` hashes to the digest recorded under `algorithm`
+    /// Digests are compared in constant time; false if no such digest was recorded
+    pub fn verify_content(&self, content: &[u8], algorithm: HashAlgorithm) -> bool {
+        let (recorded, computed) = match algorithm {
+            HashAlgorithm::Sha256 => (
+                Some(self.content_hash.as_str()),
+                Self::hash_content(content).into_inner(),
+            ),
+            HashAlgorithm::Keccak256 => (
+                self.content_hash_keccak.as_deref(),
+                Self::hash_content_keccak(content),
+            ),
+        };
+
+        recorded.is_some_and(|recorded| constant_time_eq(recorded.as_bytes(), computed.as_bytes()))
+    }
+
+    /// Set the expiry timestamp for a time-limited notarization
+    pub fn with_expiry(mut self, expires_at: Option<i64>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// Record the IPFS CID the content can be pinned under
+    pub fn with_ipfs_cid(mut self, ipfs_cid: Option<String>) -> Self {
+        self.ipfs_cid = ipfs_cid;
+        self
+    }
+
+    /// Record the Blake2b-256 digest of the content
+    pub fn with_blake2b_hash(mut self, blake2b_hash: Option<String>) -> Self {
+        self.blake2b_hash = blake2b_hash;
+        self
+    }
+
+    /// Attach the submitter's description of the document
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Keep the wallet signature checked at notarization, with the message it signs
+    pub fn with_wallet_signature(mut self, signed: Option<(String, String)>) -> Self {
+        (self.wallet_signature, self.signed_message) = signed.unzip();
+        self
+    }
+
+    /// Record the advance input that notarized the document
+    pub fn with_input(mut self, epoch_index: u64, input_index: u64) -> Self {
+        self.epoch_index = epoch_index;
+        self.input_index = input_index;
+        self
+    }
+
+    /// Attach caller-defined attributes, replacing any set before
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Link the document to the earlier version it amends
+    pub fn with_supersedes(mut self, supersedes: Option<String>) -> Self {
+        self.supersedes = supersedes;
+        self
+    }
+
+    /// Creation time as Unix seconds, the form stored in the database
+    /// JSON carries it as RFC 3339 text instead
+    pub fn created_at_unix(&self) -> i64 {
+        self.created_at
+    }
+
+    /// Whether the notarization has expired at the given Unix timestamp
+    pub fn is_expired_at(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Deterministic order by `created_at`, then `id`, for use with `sort_by`
+    /// Not an `Ord` impl, as equal documents may have different ids
+    pub fn cmp_by_creation(&self, other: &Self) -> Ordering {
+        self.created_at
+            .cmp(&other.created_at)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// Metadata of a document that has none: an empty JSON object
+pub fn empty_metadata() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+fn is_empty_metadata(metadata: &serde_json::Value) -> bool {
+    metadata.is_null() || metadata.as_object().is_some_and(serde_json::Map::is_empty)
+}
+
+/// IPFS CIDv1 of `content` stored as a single raw block, e.g. `bafkrei...`
+/// Uses the same SHA-256 digest as `content_hash`
+pub fn compute_cid(content: &[u8]) -> String {
+    let mut cid = CID_V1_RAW_SHA256_PREFIX.to_vec();
+    cid.extend_from_slice(&Sha256::digest(content));
+    format!("b{}", base32_lower(&cid))
+}
+
+/// Hex Blake2b-256 of `content` (BLAKE2b with a 32-byte digest)
+pub fn compute_blake2b(content: &[u8]) -> String {
+    hex::encode(Blake2b::<U32>::digest(content))
+}
+
+/// Byte equality that takes the same time wherever the inputs first differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Unpadded lowercase base32
+fn base32_lower(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    encoded
+}
+
+impl PartialEq for Document {
+    fn eq(&self, other: &Self) -> bool {
+        self.content_hash == other.content_hash
+    }
+}
+
+impl Eq for Document {}
+
+impl Hash for Document {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.content_hash.hash(state);
+    }
+}
+
+/// Lets hash-based collections of documents be queried by content hash
+impl Borrow<ContentHash> for Document {
+    fn borrow(&self) -> &ContentHash {
+        &self.content_hash
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Merkle root over the documents notarized during one rollup epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochRoot {
+    pub epoch: u64,
+    pub root: String,
+    /// Number of documents committed to by `root`
+    pub leaf_count: usize,
+}
@@ -0,0 +1,124 @@
+use sha3::{Digest, Keccak256};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("Address must start with 0x")]
+    MissingPrefix,
+
+    #[error("Address must have 40 hex characters, got {0}")]
+    InvalidLength(usize),
+
+    #[error("Address contains non-hex characters")]
+    InvalidHex,
+
+    #[error("Address checksum does not match")]
+    InvalidChecksum,
+}
+
+/// Ethereum account address, stored in lowercase form
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EthAddress(String);
+
+impl EthAddress {
+    /// Parse a `0x`-prefixed address
+    /// All-lowercase and all-uppercase forms carry no checksum; mixed case must be valid EIP-55
+    pub fn parse(s: &str) -> Result<Self, AddressError> {
+        let hex = s.strip_prefix("0x").ok_or(AddressError::MissingPrefix)?;
+
+        if hex.len() != 40 {
+            return Err(AddressError::InvalidLength(hex.len()));
+        }
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AddressError::InvalidHex);
+        }
+
+        let address = Self(format!("0x{}", hex.to_ascii_lowercase()));
+
+        let is_mixed_case = hex.chars().any(|c| c.is_ascii_lowercase())
+            && hex.chars().any(|c| c.is_ascii_uppercase());
+        if is_mixed_case && address.to_checksum() != s {
+            return Err(AddressError::InvalidChecksum);
+        }
+
+        Ok(address)
+    }
+
+    /// Address from its raw 20 bytes, as packed in ABI-encoded payloads
+    pub fn from_bytes(bytes: [u8; 20]) -> Self {
+        Self(format!("0x{}", hex::encode(bytes)))
+    }
+
+    /// Lowercase `0x`-prefixed form
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// EIP-55 mixed-case checksum form
+    pub fn to_checksum(&self) -> String {
+        let hex = &self.0[2..];
+        let hash = Keccak256::digest(hex.as_bytes());
+
+        let checksummed: String = hex
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                // Each hex character is uppercased when the matching hash nibble is >= 8
+                let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        format!("0x{}", checksummed)
+    }
+}
+
+impl fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Check that a string is a valid Ethereum address (see [`EthAddress::parse`])
+pub fn is_valid_address(address: &str) -> bool {
+    EthAddress::parse(address).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_address() {
+        assert!(is_valid_address(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+
+        assert!(!is_valid_address(
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+        assert!(!is_valid_address("0x742d35"));
+        assert!(!is_valid_address(
+            "0xZZ2d35Cc6634C0532925a3b844Bc9e7595f0bEb0"
+        ));
+    }
+
+    #[test]
+    fn test_checksum_round_trip() {
+        let address = EthAddress::parse("0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359").unwrap();
+        assert_eq!(
+            address.to_checksum(),
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+        );
+        assert_eq!(
+            address.as_str(),
+            "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359"
+        );
+    }
+}
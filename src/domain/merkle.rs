@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Prefixes that keep leaf and interior hashes apart, so an interior node
+/// can never be passed off as a leaf
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Proof that a document hash is among the leaves committed to by `root`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub root: String,
+    /// Content hash of the proven document
+    pub leaf: String,
+    /// Sibling hashes from the leaf level up to the root
+    pub path: Vec<String>,
+    /// Whether the proven node is the right child at each level of `path`;
+    /// informational, as pairs are hashed in sorted order
+    pub indices: Vec<bool>,
+}
+
+/// Merkle root over hex content hashes, in the given order
+/// Returns `None` for an empty or non-hex leaf set
+pub fn merkle_root(leaves: &[String]) -> Option<String> {
+    let levels = build_levels(leaves)?;
+    levels.last().map(|top| hex::encode(top[0]))
+}
+
+/// Inclusion proof for the leaf at `index`
+pub fn merkle_proof(leaves: &[String], index: usize) -> Option<MerkleProof> {
+    let levels = build_levels(leaves)?;
+    let leaf = leaves.get(index)?.to_lowercase();
+
+    let mut path = Vec::new();
+    let mut indices = Vec::new();
+    let mut position = index;
+
+    for level in &levels[..levels.len() - 1] {
+        let sibling = position ^ 1;
+        // A lone last node is promoted without a sibling
+        if let Some(hash) = level.get(sibling) {
+            path.push(hex::encode(hash));
+            indices.push(position % 2 == 1);
+        }
+        position /= 2;
+    }
+
+    Some(MerkleProof {
+        root: hex::encode(levels[levels.len() - 1][0]),
+        leaf,
+        path,
+        indices,
+    })
+}
+
+/// Check that `path` leads from `leaf` to `root`
+pub fn verify_merkle_proof(leaf: &str, path: &[String], root: &str) -> bool {
+    let Some(mut node) = decode(leaf).map(|leaf| hash_leaf(&leaf)) else {
+        return false;
+    };
+
+    for sibling in path {
+        match decode(sibling) {
+            Some(sibling) => node = hash_pair(&node, &sibling),
+            None => return false,
+        }
+    }
+
+    hex::encode(node).eq_ignore_ascii_case(root)
+}
+
+/// Every tree level from the hashed leaves up to the single root
+fn build_levels(leaves: &[String]) -> Option<Vec<Vec<[u8; 32]>>> {
+    if leaves.is_empty() {
+        return None;
+    }
+
+    let hashed = leaves
+        .iter()
+        .map(|leaf| decode(leaf).map(|leaf| hash_leaf(&leaf)))
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut levels = vec![hashed];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_pair(left, right),
+                [single] => *single,
+                _ => unreachable!("chunks(2) yields one or two nodes"),
+            })
+            .collect();
+        levels.push(next);
+    }
+
+    Some(levels)
+}
+
+fn decode(hash: &str) -> Option<Vec<u8>> {
+    hex::decode(hash).ok()
+}
+
+fn hash_leaf(leaf: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+/// Pairs are hashed smallest first, so verification needs no position bits
+fn hash_pair(a: &[u8], b: &[u8]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(first);
+    hasher.update(second);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| {
+                let mut hasher = Sha256::new();
+                hasher.update(i.to_le_bytes());
+                format!("{:x}", hasher.finalize())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_every_proof_verifies() {
+        for count in 1..=17 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves).unwrap();
+
+            for index in 0..count {
+                let proof = merkle_proof(&leaves, index).unwrap();
+                assert_eq!(proof.root, root);
+                assert_eq!(proof.path.len(), proof.indices.len());
+                assert!(verify_merkle_proof(&proof.leaf, &proof.path, &root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_leaf_has_empty_path() {
+        let leaves = leaves(1);
+        let proof = merkle_proof(&leaves, 0).unwrap();
+
+        assert!(proof.path.is_empty());
+        assert_eq!(
+            proof.root,
+            hex::encode(hash_leaf(&decode(&leaves[0]).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_tampered_proof_fails() {
+        let leaves = leaves(5);
+        let proof = merkle_proof(&leaves, 2).unwrap();
+
+        assert!(!verify_merkle_proof(&leaves[3], &proof.path, &proof.root));
+        assert!(!verify_merkle_proof(
+            &proof.leaf,
+            &proof.path[1..],
+            &proof.root
+        ));
+        assert!(!verify_merkle_proof("not hex", &proof.path, &proof.root));
+    }
+
+    #[test]
+    fn test_empty_or_invalid_leaves_have_no_root() {
+        assert!(merkle_root(&[]).is_none());
+        assert!(merkle_root(&["xyz".to_string()]).is_none());
+        assert!(merkle_proof(&leaves(3), 3).is_none());
+    }
+}
@@ -0,0 +1,81 @@
+/// Bytes inspected when deciding whether content is plain text
+const TEXT_SNIFF_LEN: usize = 8192;
+
+/// Entries that identify the Office Open XML format inside a ZIP container
+const OOXML_MARKERS: &[(&[u8], &str)] = &[
+    (
+        b"word/document.xml",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        b"xl/workbook.xml",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        b"ppt/presentation.xml",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+];
+
+/// MIME type recognized from the content's leading bytes, independent of the declared type
+/// Returns `None` when no known signature matches
+pub fn detect_mime(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+    if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if content.len() >= 12 && content.starts_with(b"RIFF") && &content[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if content.starts_with(b"PK\x03\x04") {
+        return Some(detect_zip_format(content));
+    }
+    if is_plain_text(content) {
+        return Some("text/plain");
+    }
+
+    None
+}
+
+/// Office documents are ZIP archives named by their main part
+fn detect_zip_format(content: &[u8]) -> &'static str {
+    OOXML_MARKERS
+        .iter()
+        .find(|(marker, _)| {
+            content
+                .windows(marker.len())
+                .any(|window| window == *marker)
+        })
+        .map(|(_, mime_type)| *mime_type)
+        .unwrap_or("application/zip")
+}
+
+/// UTF-8 without control characters other than common whitespace
+fn is_plain_text(content: &[u8]) -> bool {
+    if content.is_empty() {
+        return false;
+    }
+
+    let prefix = &content[..content.len().min(TEXT_SNIFF_LEN)];
+    let text = match std::str::from_utf8(prefix) {
+        Ok(text) => text,
+        // The prefix may end inside a multi-byte character
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&prefix[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r' | '\x0c'))
+}
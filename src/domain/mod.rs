@@ -1,5 +1,44 @@
+mod audit;
+mod clock;
+mod commitment;
+mod cosign;
+mod deposit;
 mod document;
+mod epoch;
+mod eth_address;
+mod merkle;
+mod mime;
+mod ots;
+mod ownership;
 mod receipt;
+mod timestamp;
+mod types;
+mod upload;
+mod vc;
+mod wallet_signature;
 
-pub use document::Document;
-pub use receipt::NotarizationReceipt;
+pub use audit::{AuditEntry, AuditOutcome};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use commitment::Commitment;
+pub use cosign::{Cosigner, PendingNotarization};
+pub use deposit::{Deposit, DepositError, ETHER_TOKEN};
+pub use document::{compute_blake2b, compute_cid, empty_metadata, Document};
+pub use epoch::EpochRoot;
+pub use eth_address::{is_valid_address, AddressError, EthAddress};
+pub use merkle::{merkle_proof, merkle_root, verify_merkle_proof, MerkleProof};
+pub use mime::detect_mime;
+pub use ots::{
+    verify_ots_proof, Attestation, HashAlgorithm, OpenTimestampsProof, OtsError,
+    CARTESI_ATTESTATION_TAG, OTS_HEADER_MAGIC,
+};
+pub use ownership::OwnershipTransfer;
+pub use receipt::{CborError, NotarizationReceipt};
+pub use timestamp::{format_rfc3339, parse_rfc3339, rfc3339, rfc3339_option};
+pub use types::{ContentHash, DocumentId};
+pub use upload::UploadSession;
+pub use vc::{
+    NotaryCredentialSubject, VerifiableCredential, NOTARY_CREDENTIAL_TYPE, W3C_CREDENTIALS_CONTEXT,
+};
+pub use wallet_signature::{
+    address_of, personal_message_hash, recover_personal_signer, SignatureError, SIGNATURE_LENGTH,
+};
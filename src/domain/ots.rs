@@ -0,0 +1,242 @@
+use thiserror::Error;
+
+/// Magic bytes opening every OpenTimestamps proof file
+pub const OTS_HEADER_MAGIC: &[u8] =
+    b"\x00OpenTimestamps\x00\x00Proof\x00\xbf\x89\xe2\xe8\x84\xe8\x92\x94";
+
+/// Proof format version written after the magic bytes
+pub const OTS_MAJOR_VERSION: u64 = 1;
+
+/// Tag that introduces an attestation, as opposed to an operation
+const ATTESTATION_MARKER: u8 = 0x00;
+
+/// Attestation tag identifying a notarization recorded by a Cartesi rollup
+/// OTS clients treat tags they do not know as unknown attestations and keep them
+pub const CARTESI_ATTESTATION_TAG: [u8; 8] = *b"CRTSNOTA";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum OtsError {
+    #[error("Not an OpenTimestamps proof")]
+    BadMagic,
+
+    #[error("Unsupported OpenTimestamps version {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("Unsupported hash operation 0x{0:02x}")]
+    UnsupportedHashAlgorithm(u8),
+
+    #[error("Unsupported attestation")]
+    UnsupportedAttestation,
+
+    #[error("Digest is not hexadecimal")]
+    NonHexDigest,
+
+    #[error("Digest must be {expected} bytes, got {actual}")]
+    InvalidDigest { expected: usize, actual: usize },
+
+    #[error("Proof ends unexpectedly")]
+    Truncated,
+
+    #[error("Unexpected bytes after the proof")]
+    TrailingBytes,
+}
+
+/// Hash operation a proof's digest was produced with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Keccak256,
+}
+
+impl HashAlgorithm {
+    /// OTS operation tag of the algorithm
+    pub fn tag(self) -> u8 {
+        match self {
+            HashAlgorithm::Sha256 => 0x08,
+            HashAlgorithm::Keccak256 => 0x67,
+        }
+    }
+
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 | HashAlgorithm::Keccak256 => 32,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, OtsError> {
+        match tag {
+            0x08 => Ok(HashAlgorithm::Sha256),
+            0x67 => Ok(HashAlgorithm::Keccak256),
+            other => Err(OtsError::UnsupportedHashAlgorithm(other)),
+        }
+    }
+}
+
+/// Who vouches that the digest existed at some point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attestation {
+    /// Recorded by the notary DApp in the given rollup input block
+    Cartesi { block_number: u64 },
+}
+
+impl Attestation {
+    fn tag(&self) -> [u8; 8] {
+        match self {
+            Attestation::Cartesi { .. } => CARTESI_ATTESTATION_TAG,
+        }
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        match self {
+            Attestation::Cartesi { block_number } => {
+                let mut payload = Vec::new();
+                write_varuint(&mut payload, *block_number);
+                payload
+            }
+        }
+    }
+
+    fn from_parts(tag: [u8; 8], payload: &[u8]) -> Result<Self, OtsError> {
+        if tag != CARTESI_ATTESTATION_TAG {
+            return Err(OtsError::UnsupportedAttestation);
+        }
+
+        let mut reader = Reader::new(payload);
+        let block_number = reader.varuint()?;
+        reader.finish()?;
+        Ok(Attestation::Cartesi { block_number })
+    }
+}
+
+/// A detached OpenTimestamps proof: a file digest attested directly, without
+/// intermediate commitment operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenTimestampsProof {
+    pub hash_algorithm: HashAlgorithm,
+    pub digest: Vec<u8>,
+    pub attestation: Attestation,
+}
+
+impl OpenTimestampsProof {
+    pub fn new(
+        hash_algorithm: HashAlgorithm,
+        digest: Vec<u8>,
+        attestation: Attestation,
+    ) -> Result<Self, OtsError> {
+        if digest.len() != hash_algorithm.digest_len() {
+            return Err(OtsError::InvalidDigest {
+                expected: hash_algorithm.digest_len(),
+                actual: digest.len(),
+            });
+        }
+
+        Ok(Self {
+            hash_algorithm,
+            digest,
+            attestation,
+        })
+    }
+
+    /// Encode in the `.ots` binary format
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = OTS_HEADER_MAGIC.to_vec();
+        write_varuint(&mut out, OTS_MAJOR_VERSION);
+        out.push(self.hash_algorithm.tag());
+        out.extend_from_slice(&self.digest);
+
+        out.push(ATTESTATION_MARKER);
+        out.extend_from_slice(&self.attestation.tag());
+        let payload = self.attestation.payload();
+        write_varuint(&mut out, payload.len() as u64);
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// Decode a proof produced by `serialize`
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, OtsError> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(OTS_HEADER_MAGIC.len()).ok() != Some(OTS_HEADER_MAGIC) {
+            return Err(OtsError::BadMagic);
+        }
+
+        let version = reader.varuint()?;
+        if version != OTS_MAJOR_VERSION {
+            return Err(OtsError::UnsupportedVersion(version));
+        }
+
+        let hash_algorithm = HashAlgorithm::from_tag(reader.byte()?)?;
+        let digest = reader.take(hash_algorithm.digest_len())?.to_vec();
+
+        if reader.byte()? != ATTESTATION_MARKER {
+            return Err(OtsError::UnsupportedAttestation);
+        }
+        let mut tag = [0u8; 8];
+        tag.copy_from_slice(reader.take(8)?);
+        let payload_len = usize::try_from(reader.varuint()?).map_err(|_| OtsError::Truncated)?;
+        let attestation = Attestation::from_parts(tag, reader.take(payload_len)?)?;
+        reader.finish()?;
+
+        Self::new(hash_algorithm, digest, attestation)
+    }
+}
+
+/// Whether `proof` is a well-formed OTS proof that re-encodes to the same bytes
+pub fn verify_ots_proof(proof: &[u8]) -> bool {
+    OpenTimestampsProof::deserialize(proof).is_ok_and(|decoded| decoded.serialize() == proof)
+}
+
+/// Unsigned LEB128, the integer encoding used throughout the OTS format
+fn write_varuint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OtsError> {
+        if self.bytes.len() < len {
+            return Err(OtsError::Truncated);
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, OtsError> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn varuint(&mut self) -> Result<u64, OtsError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(OtsError::Truncated)
+    }
+
+    fn finish(&self) -> Result<(), OtsError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(OtsError::TrailingBytes)
+        }
+    }
+}
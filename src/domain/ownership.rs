@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// A change of ownership of a notarized document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipTransfer {
+    pub document_id: String,
+    pub content_hash: String,
+    pub previous_owner: String,
+    pub new_owner: String,
+    pub transferred_at: i64,
+    pub block_number: u64,
+}
@@ -1,12 +1,27 @@
+use super::ots::{Attestation, HashAlgorithm, OpenTimestampsProof, OtsError};
+use super::vc::{NotaryCredentialSubject, VerifiableCredential};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Error, Debug)]
+pub enum CborError {
+    #[error("Invalid CBOR receipt: {0}")]
+    Decode(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NotarizationReceipt {
     pub document_id: String,
     pub content_hash: String,
+    /// Unix timestamp, serialized as RFC 3339
+    #[serde(with = "super::timestamp::rfc3339")]
     pub notarized_at: i64,
     pub block_number: u64,
     pub proof: String,
+    /// Block at which the content hash was first committed, when it was committed
+    /// before the document was notarized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<u64>,
 }
 
 impl NotarizationReceipt {
@@ -16,7 +31,7 @@ impl NotarizationReceipt {
         notarized_at: i64,
         block_number: u64,
     ) -> Self {
-        let proof = format!("sha256:{}@{}", content_hash, notarized_at);
+        let proof = Self::format_proof(&content_hash, notarized_at);
 
         Self {
             document_id,
@@ -24,6 +39,64 @@ impl NotarizationReceipt {
             notarized_at,
             block_number,
             proof,
+            committed_at: None,
         }
     }
+
+    /// Attach the block of an earlier commitment to the content hash
+    pub fn with_committed_at(mut self, committed_at: Option<u64>) -> Self {
+        self.committed_at = committed_at;
+        self
+    }
+
+    /// Encode as CBOR (RFC 8949), a binary form of the JSON receipt with the same field names
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        // Writing strings and integers into a Vec cannot fail
+        ciborium::into_writer(self, &mut bytes).expect("receipt encodes to CBOR");
+        bytes
+    }
+
+    /// Decode a receipt produced by `to_cbor`
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, CborError> {
+        ciborium::from_reader(bytes).map_err(|e| CborError::Decode(e.to_string()))
+    }
+
+    /// Compact proof string `sha256:<hash>@<timestamp>` carried by every receipt
+    pub fn format_proof(content_hash: &str, notarized_at: i64) -> String {
+        format!("sha256:{}@{}", content_hash, notarized_at)
+    }
+
+    /// Whether `proof` matches this receipt's hash and timestamp
+    pub fn verify_proof(&self) -> bool {
+        self.proof == Self::format_proof(&self.content_hash, self.notarized_at)
+    }
+
+    /// Express this receipt as an OpenTimestamps proof attested by the rollup block
+    /// Fails only if `content_hash` is not a hex SHA-256 digest
+    pub fn to_ots_proof(&self) -> Result<OpenTimestampsProof, OtsError> {
+        let digest = hex::decode(&self.content_hash).map_err(|_| OtsError::NonHexDigest)?;
+
+        OpenTimestampsProof::new(
+            HashAlgorithm::Sha256,
+            digest,
+            Attestation::Cartesi {
+                block_number: self.block_number,
+            },
+        )
+    }
+
+    /// Express this receipt as a W3C Verifiable Credential issued by `issuer_did`
+    /// The receipt does not carry the filename, so the caller supplies it
+    pub fn to_verifiable_credential(
+        &self,
+        issuer_did: &str,
+        file_name: &str,
+    ) -> VerifiableCredential {
+        VerifiableCredential::new(
+            issuer_did,
+            self.notarized_at,
+            NotaryCredentialSubject::new(&self.document_id, &self.content_hash, file_name),
+        )
+    }
 }
@@ -0,0 +1,72 @@
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Render a Unix timestamp as `YYYY-MM-DDTHH:MM:SSZ`
+/// Timestamps outside chrono's range fall back to the epoch
+pub fn format_rfc3339(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Unix timestamp of an RFC 3339 date such as `2024-01-15T12:34:56Z`
+pub fn parse_rfc3339(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+/// Either form a timestamp may arrive in: RFC 3339 text, or the Unix seconds
+/// written before timestamps were formatted
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Timestamp {
+    Unix(i64),
+    Text(String),
+}
+
+impl Timestamp {
+    fn into_unix<E: serde::de::Error>(self) -> Result<i64, E> {
+        match self {
+            Self::Unix(timestamp) => Ok(timestamp),
+            Self::Text(text) => parse_rfc3339(&text)
+                .ok_or_else(|| E::custom(format!("invalid RFC 3339 timestamp: {}", text))),
+        }
+    }
+}
+
+/// `#[serde(with = "rfc3339")]` for an `i64` Unix timestamp
+pub mod rfc3339 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(timestamp: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_rfc3339(*timestamp))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        Timestamp::deserialize(deserializer)?.into_unix()
+    }
+}
+
+/// `#[serde(with = "rfc3339_option")]` for an `Option<i64>` Unix timestamp
+pub mod rfc3339_option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<i64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_str(&format_rfc3339(*timestamp)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        Option::<Timestamp>::deserialize(deserializer)?
+            .map(Timestamp::into_unix)
+            .transpose()
+    }
+}
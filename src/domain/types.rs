@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Defines a string newtype, so one kind of identifier cannot be passed where another is expected
+macro_rules! string_newtype {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self(value.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+string_newtype!(
+    /// Unique id assigned to a document when it is notarized
+    ///
+    /// Repository lookups by id accept only a `DocumentId`:
+    ///
+    /// ```
+    /// use dapp::domain::DocumentId;
+    /// use dapp::{DocumentRepository, SqliteRepository};
+    ///
+    /// let repo = SqliteRepository::new_in_memory().unwrap();
+    /// assert!(repo.find_by_id(&DocumentId::from("550e8400")).is_err());
+    /// ```
+    ///
+    /// Passing a `ContentHash` instead does not compile; see `tests/compile_fail/`
+    DocumentId
+);
+
+string_newtype!(
+    /// Hex SHA-256 of a document's content
+    ///
+    /// Repository lookups by hash accept only a `ContentHash`:
+    ///
+    /// ```
+    /// use dapp::domain::ContentHash;
+    /// use dapp::{DocumentRepository, SqliteRepository};
+    ///
+    /// let repo = SqliteRepository::new_in_memory().unwrap();
+    /// assert!(repo.find_by_hash(&ContentHash::from("a591a6d4")).is_err());
+    /// ```
+    ///
+    /// Passing a `DocumentId` or a plain `&str` instead does not compile; see `tests/compile_fail/`
+    ContentHash
+);
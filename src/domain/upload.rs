@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// A document being assembled from chunks across several inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub upload_id: String,
+    pub submitted_by: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub started_at: i64,
+    /// Chunks received so far; the next chunk must carry this index
+    pub chunk_count: u32,
+    pub total_bytes: u64,
+}
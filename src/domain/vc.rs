@@ -0,0 +1,82 @@
+use super::document::Document;
+use super::timestamp::format_rfc3339;
+use serde::{Deserialize, Serialize};
+
+/// JSON-LD context every W3C Verifiable Credential must list first
+pub const W3C_CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+
+/// Credential type naming a notarization, listed after `VerifiableCredential`
+pub const NOTARY_CREDENTIAL_TYPE: &str = "NotaryCredential";
+
+/// A notarization expressed as a W3C Verifiable Credential (data model 1.1)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub credential_type: Vec<String>,
+    pub issuer: String,
+    /// ISO-8601 UTC timestamp of the notarization
+    pub issuance_date: String,
+    pub credential_subject: NotaryCredentialSubject,
+}
+
+/// What the credential attests: a document's content hash and filename
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotaryCredentialSubject {
+    /// `urn:uuid:` URI of the notarized document
+    pub id: String,
+    pub content_hash: String,
+    pub file_name: String,
+}
+
+impl NotaryCredentialSubject {
+    pub fn new(document_id: &str, content_hash: &str, file_name: &str) -> Self {
+        Self {
+            id: format!("urn:uuid:{}", document_id),
+            content_hash: content_hash.to_string(),
+            file_name: file_name.to_string(),
+        }
+    }
+}
+
+impl From<&Document> for NotaryCredentialSubject {
+    fn from(document: &Document) -> Self {
+        Self::new(
+            document.id.as_str(),
+            document.content_hash.as_str(),
+            &document.file_name,
+        )
+    }
+}
+
+impl VerifiableCredential {
+    /// Credential issued by `issuer_did` for a notarization made at `issued_at` (Unix timestamp)
+    pub fn new(
+        issuer_did: &str,
+        issued_at: i64,
+        credential_subject: NotaryCredentialSubject,
+    ) -> Self {
+        Self {
+            context: vec![W3C_CREDENTIALS_CONTEXT.to_string()],
+            credential_type: vec![
+                "VerifiableCredential".to_string(),
+                NOTARY_CREDENTIAL_TYPE.to_string(),
+            ],
+            issuer: issuer_did.to_string(),
+            issuance_date: format_rfc3339(issued_at),
+            credential_subject,
+        }
+    }
+
+    /// Credential for a stored document, issued at its notarization time
+    pub fn for_document(issuer_did: &str, document: &Document) -> Self {
+        Self::new(
+            issuer_did,
+            document.created_at,
+            NotaryCredentialSubject::from(document),
+        )
+    }
+}
@@ -0,0 +1,82 @@
+use super::eth_address::EthAddress;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+/// Length of an Ethereum signature: `r` and `s` of 32 bytes each, then the recovery byte `v`
+pub const SIGNATURE_LENGTH: usize = 65;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("Signature contains non-hex characters")]
+    InvalidHex,
+
+    #[error("Signature must be {SIGNATURE_LENGTH} bytes, got {0}")]
+    InvalidLength(usize),
+
+    #[error("Signature recovery byte {0} is not 0, 1, 27 or 28")]
+    InvalidRecoveryId(u8),
+
+    #[error("Signature does not recover to a public key")]
+    Unrecoverable,
+}
+
+/// Digest a wallet signs for `personal_sign`: Keccak-256 of the message behind the
+/// EIP-191 prefix `"\x19Ethereum Signed Message:\n" + length`
+pub fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Address of the account that signed `message` with `personal_sign`
+/// `signature` is the hex `r || s || v` a wallet returns, with or without `0x`
+pub fn recover_personal_signer(
+    message: &str,
+    signature: &str,
+) -> Result<EthAddress, SignatureError> {
+    let hex = signature.strip_prefix("0x").unwrap_or(signature);
+    let bytes = hex::decode(hex).map_err(|_| SignatureError::InvalidHex)?;
+    if bytes.len() != SIGNATURE_LENGTH {
+        return Err(SignatureError::InvalidLength(bytes.len()));
+    }
+
+    // Wallets send `v` as 27 or 28; raw recovery ids are 0 or 1
+    let v = bytes[64];
+    let recovery_id = match v {
+        0 | 1 => v,
+        27 | 28 => v - 27,
+        _ => return Err(SignatureError::InvalidRecoveryId(v)),
+    };
+    let mut recovery_id =
+        RecoveryId::from_byte(recovery_id).ok_or(SignatureError::InvalidRecoveryId(v))?;
+
+    let mut signature =
+        Signature::from_slice(&bytes[..64]).map_err(|_| SignatureError::Unrecoverable)?;
+    // Recovery only accepts low-s signatures; negating s flips the parity of the point
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+
+    let key = VerifyingKey::recover_from_prehash(
+        &personal_message_hash(message.as_bytes()),
+        &signature,
+        recovery_id,
+    )
+    .map_err(|_| SignatureError::Unrecoverable)?;
+
+    Ok(address_of(&key))
+}
+
+/// Ethereum address of a public key: the last 20 bytes of the Keccak-256 of its
+/// uncompressed coordinates
+pub fn address_of(key: &VerifyingKey) -> EthAddress {
+    let point = key.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    EthAddress::from_bytes(address)
+}
@@ -0,0 +1,17 @@
+use crate::application::{NotarizeError, VerifyError};
+use crate::infrastructure::database::DatabaseError;
+use thiserror::Error;
+
+/// Error returned by the library API, so callers can match on the failure
+/// instead of inspecting its message
+#[derive(Error, Debug)]
+pub enum NotaryError {
+    #[error(transparent)]
+    Notarize(#[from] NotarizeError),
+
+    #[error(transparent)]
+    Verify(#[from] VerifyError),
+
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
@@ -1,37 +1,1353 @@
 // This module exposes the handlers for integration testing
 // In production, these are only used from main.rs
 
+use crate::application::config::DEFAULT_DB_PATH;
 use crate::application::{
-    InputAction, NotarizeUseCase, NoticeResponse, ReportResponse, VerifyUseCase,
+    duplicate_of, duplicate_report, error_report, exception_report, normalize_tag, AuditLogRequest,
+    AuditLogResponse, AuditQuery, AuditUseCase, BalanceUseCase, BatchVerifyResponse,
+    CertificateResponse, ChunkedUploadUseCase, CommitUseCase, CommitmentNotice, Config,
+    ContentResponse, CosignOutcome, CosignUseCase, DateRangeRequest, DateRangeResponse,
+    DepositNotice, DocumentVersionNotice, EpochRootNotice, EpochUseCase, ErrorCode,
+    ExpiredDocumentsResponse, ExportRegistryRequest, ExportRegistryResponse, FetchContentError,
+    FetchContentUseCase, FindByTagRequest, HealthResponse, InclusionProofRequest,
+    InclusionProofResponse, InputAction, ListAuditLogRequest, ListAuditLogResponse,
+    ListBySubmitterRequest, ListDocumentsRequest, ListDocumentsResponse, ListExpiredRequest,
+    MetadataUpdateNotice, NonceUseCase, NotarizeConfig, NotarizeOptions, NotarizeRequest,
+    NotarizeUseCase, NoticeResponse, OwnershipTransferNotice, PendingCosignResponse, ProofResponse,
+    ProtocolVersionError, ProvenanceReport, ProvenanceUseCase, PurgeNotice, PurgeUseCase,
+    RehashUseCase, ReportResponse, RepositoryKind, ResponseEncoding, SearchDocumentsResponse,
+    SearchUseCase, StatisticsResponse, SubmitterStats, SubmitterStatsResponse, SyncSinceRequest,
+    SyncSinceResponse, TaggedDocumentsResponse, TimeseriesRequest, TimeseriesResponse,
+    TransferOwnershipUseCase, UnknownActionResponse, UpdateMetadataUseCase, UploadProgressResponse,
+    ValidationReport, VerifyByContentRequest, VerifyContentRequest, VerifyUseCase,
+};
+use crate::domain::{
+    AuditEntry, AuditOutcome, Clock, Cosigner, Deposit, DepositError, Document, EthAddress,
+    FixedClock, NotarizationReceipt, SystemClock, UploadSession, VerifiableCredential,
 };
 use crate::infrastructure::{
-    cartesi::{send_notice, send_report},
-    database::{DocumentRepository, SqliteRepository},
+    abi::encode_notarized_callback,
+    cartesi::{send_notice, send_report, send_voucher, throw_exception, Endpoints},
+    database::{DatabaseError, DocumentRepository, SqliteRepository},
+    metrics,
+    postgres_repository::PostgresRepository,
 };
 use json::JsonValue;
+use std::error::Error;
+use std::time::Instant;
+use thiserror::Error;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+
+/// Error report for `e`, coded by `ErrorCode::classify`
+/// Duplicates also name the original notarization
+fn coded_error(e: &(dyn Error + 'static)) -> String {
+    match duplicate_of(e) {
+        Some(original) => duplicate_report(&e.to_string(), original),
+        None => error_report(ErrorCode::classify(e), &e.to_string()),
+    }
+}
+
+/// What became of an advance input
+/// A rejection carries the report explaining it, which `handle_advance` always sends
+#[derive(Debug)]
+enum AdvanceOutcome {
+    Accept,
+    Reject {
+        /// Stable reason, the `error_code` of the report
+        code: ErrorCode,
+        /// JSON body of the report
+        report: String,
+    },
+}
+
+impl AdvanceOutcome {
+    /// Rejection reported as `{"error_code":...,"error_detail":...}`
+    fn reject(code: ErrorCode, detail: &str) -> Self {
+        Self::Reject {
+            code,
+            report: error_report(code, detail),
+        }
+    }
+
+    /// Rejection for `e`, coded by `ErrorCode::classify`
+    fn reject_error(e: &(dyn Error + 'static)) -> Self {
+        Self::Reject {
+            code: ErrorCode::classify(e),
+            report: coded_error(e),
+        }
+    }
+
+    /// Status string returned to the rollup server
+    fn status(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Reject { .. } => "reject",
+        }
+    }
+}
+
+/// Get a repository instance
+/// Called once at startup; the same repository is shared by every request
+/// In production, uses the database named by `config.repository`
+/// A SQLite repository falls back to in-memory if the default persistent path fails; an
+/// explicitly configured path or PostgreSQL server that cannot be opened is reported as an error
+pub fn get_repository(config: &Config) -> Result<Box<dyn DocumentRepository>, Box<dyn Error>> {
+    let path = match &config.repository {
+        RepositoryKind::Sqlite(path) => path,
+        RepositoryKind::Postgres(url) => return Ok(Box::new(PostgresRepository::new(url)?)),
+    };
+
+    let options = config.sqlite_options();
+    let (repository, in_memory) = if path == DEFAULT_DB_PATH {
+        // A corrupt database is reported rather than silently replaced
+        match SqliteRepository::open(path, &options) {
+            Ok(repository) => (repository, false),
+            Err(e @ DatabaseError::CorruptDatabase(_)) => return Err(e.into()),
+            Err(_) => (SqliteRepository::new_in_memory()?, true),
+        }
+    } else {
+        (SqliteRepository::open(path, &options)?, false)
+    };
+
+    // A damaged file would fail later and less clearly; nothing persists in memory anyway
+    if let Err(e) = repository.integrity_check() {
+        if !in_memory {
+            return Err(e.into());
+        }
+        warn!(error = %e, "In-memory database failed its integrity check");
+    }
+
+    Ok(Box::new(repository))
+}
+
+/// Why an advance payload could not be read as an action
+#[derive(Error, Debug)]
+pub enum PayloadError {
+    #[error("Invalid hex payload: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+
+    #[error("Payload is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+
+    #[error("Invalid input format: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    ProtocolVersion(#[from] ProtocolVersionError),
+}
+
+/// Decode a hex advance payload into the action it carries
+/// Needs no repository or rollup server, so it can be fuzzed on its own
+pub fn parse_advance_payload(payload_hex: &str) -> Result<InputAction, PayloadError> {
+    let payload_bytes = hex::decode(payload_hex)?;
+    parse_payload_bytes(&payload_bytes)
+}
+
+/// Parse decoded payload bytes as a JSON action
+fn parse_payload_bytes(payload_bytes: &[u8]) -> Result<InputAction, PayloadError> {
+    let payload_str = std::str::from_utf8(payload_bytes)?;
+
+    debug!(payload = %payload_str, "Decoded payload");
+
+    let input = serde_json::from_str(payload_str)?;
+    InputAction::protocol_version(payload_str)?;
+
+    Ok(input)
+}
+
+/// Decode document content sent by clients using any common base64 alphabet
+/// Tries standard and URL-safe alphabets, padded and unpadded; on failure the
+/// standard decoder's error is returned
+fn decode_base64_content(encoded: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+    use base64::Engine;
+
+    STANDARD.decode(encoded).or_else(|e| {
+        [STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+            .iter()
+            .find_map(|engine| engine.decode(encoded).ok())
+            .ok_or(e)
+    })
+}
+
+/// Send the Prometheus text exposition of all metrics as a report
+async fn report_metrics(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+) -> Result<(), Box<dyn Error>> {
+    if let Ok(count) = repository.count_documents() {
+        metrics::DOCUMENTS_STORED.set(count as i64);
+    }
+
+    let metrics_text = metrics::encode_text()?;
+    send_report(client, endpoints, &metrics_text).await
+}
+
+/// Time a document count query and report database health
+async fn report_health(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+) -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+    let count = repository.count_documents();
+    let db_latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let health = count.and_then(|count| Ok((count, repository.schema_version()?)));
+    let response = match health {
+        Ok((count, schema_version)) => HealthResponse::ok(count, schema_version, db_latency_ms),
+        Err(e) => {
+            error!(error = %e, "Health check database query failed");
+            HealthResponse::unavailable(&e.to_string(), db_latency_ms)
+        }
+    };
+
+    let report_json = serde_json::to_string(&response)?;
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Verify several hashes at once and report all results together
+async fn report_batch_verification(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    hashes: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let verify_usecase = VerifyUseCase::new(repository).with_clock(clock);
+
+    let report_json = match verify_usecase.execute_batch(hashes) {
+        Ok(results) => {
+            info!(count = hashes.len(), "Batch verification result");
+            serde_json::to_string(&BatchVerifyResponse::new(hashes, results))?
+        }
+        Err(e) => {
+            error!(error = %e, "Batch verification failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Verify a document against the provenance oracle and report both results
+async fn report_provenance(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    config: &Config,
+    content_hash: &str,
+) -> Result<(), Box<dyn Error>> {
+    let provenance_usecase = ProvenanceUseCase::new(
+        VerifyUseCase::new(repository).with_clock(clock),
+        client,
+        endpoints,
+        config.gio_domain,
+    );
+
+    let report_json = match provenance_usecase.execute(content_hash).await {
+        Ok(result) => {
+            if let Some(e) = &result.error {
+                warn!(error = %e, "Provenance oracle check failed");
+            }
+            serde_json::to_string(&ProvenanceReport::from_result(&result))?
+        }
+        Err(e) => {
+            error!(error = %e, "Provenance verification failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report document counts for one submitter, or for every submitter
+async fn report_submitter_stats(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    submitter: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let stats = match submitter {
+        Some(submitter) => repository
+            .count_by_submitter(submitter)
+            .map(|count| vec![(submitter.to_lowercase(), count)]),
+        None => repository.list_submitters(),
+    };
+
+    let report_json = match stats {
+        Ok(stats) => serde_json::to_string(&SubmitterStatsResponse::new(stats))?,
+        Err(e) => {
+            error!(error = %e, "Submitter stats query failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Number of submitters listed in the statistics overview
+const STATISTICS_TOP_SUBMITTERS: usize = 10;
+
+/// Gather the statistics overview with recent-activity windows ending at `now`
+fn collect_statistics(
+    repository: &dyn DocumentRepository,
+    now: i64,
+) -> Result<StatisticsResponse, DatabaseError> {
+    const DAY: i64 = 86_400;
+
+    Ok(StatisticsResponse {
+        total_documents: repository.count_documents()?,
+        top_submitters: repository
+            .count_by_submitter_top_n(STATISTICS_TOP_SUBMITTERS)?
+            .into_iter()
+            .map(SubmitterStats::from)
+            .collect(),
+        documents_last_24h: repository.count_documents_since(now - DAY)?,
+        documents_last_7d: repository.count_documents_since(now - 7 * DAY)?,
+        documents_last_30d: repository.count_documents_since(now - 30 * DAY)?,
+        generated_at: now,
+    })
+}
+
+/// Report document totals, the busiest submitters and recent notarization counts
+async fn report_statistics(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match collect_statistics(repository, clock.now()) {
+        Ok(statistics) => serde_json::to_string(&statistics)?,
+        Err(e) => {
+            error!(error = %e, "Statistics query failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Search documents by filename and MIME type and report the matches
+async fn report_search(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match SearchUseCase::new(repository).execute(query, limit) {
+        Ok(documents) => {
+            info!(count = documents.len(), "Search result");
+            serde_json::to_string(&SearchDocumentsResponse::new(query, documents))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Search failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report the documents carrying a tag
+async fn report_tagged_documents(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &FindByTagRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.find_by_tag(&request.tag, request.limit, request.offset)
+    {
+        Ok(documents) => {
+            info!(count = documents.len(), "Tag lookup result");
+            serde_json::to_string(&TaggedDocumentsResponse::new(
+                &normalize_tag(&request.tag),
+                documents,
+            ))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Tag lookup failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Send the receipt notice of a notarization, signed when a key is configured, followed by
+/// a version notice when it supersedes an earlier document
+/// Only the receipt notice follows `encoding`; the version notice is always JSON
+async fn send_notarization_notices(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    config: &Config,
+    receipt: NotarizationReceipt,
+    supersedes: Option<&str>,
+    cosigners: Vec<Cosigner>,
+    encoding: ResponseEncoding,
+) -> Result<(), Box<dyn Error>> {
+    let version_notice = supersedes.map(|previous| DocumentVersionNotice::new(&receipt, previous));
+
+    let response = NoticeResponse::notarization(receipt)
+        .signed(config.notice_signing_key.as_ref())
+        .with_cosigners(cosigners);
+    send_notice(client, endpoints, encoding.encode(&response)?).await?;
+
+    if let Some(version_notice) = version_notice {
+        send_notice(client, endpoints, &serde_json::to_string(&version_notice)?).await?;
+    }
+
+    Ok(())
+}
+
+/// Report the progress of a chunked upload, or the reason a step was refused
+/// Returns the outcome of the step
+async fn report_upload_progress(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    result: Result<UploadSession, Box<dyn Error>>,
+) -> Result<AdvanceOutcome, Box<dyn Error>> {
+    match result {
+        Ok(session) => {
+            info!(
+                upload_id = %session.upload_id,
+                chunks = session.chunk_count,
+                bytes = session.total_bytes,
+                "Upload progress"
+            );
+            let report_json = serde_json::to_string(&UploadProgressResponse::new(&session))?;
+            send_report(client, endpoints, &report_json).await?;
+            Ok(AdvanceOutcome::Accept)
+        }
+        Err(e) => {
+            warn!(error = %e, "Chunked upload step failed");
+            Ok(AdvanceOutcome::reject_error(&*e))
+        }
+    }
+}
+
+/// Report the documents notarized within a time window
+async fn report_date_range(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &DateRangeRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.find_by_date_range(
+        request.start,
+        request.end,
+        request.limit,
+        request.offset,
+    ) {
+        Ok(documents) => {
+            info!(count = documents.len(), "Date range result");
+            serde_json::to_string(&DateRangeResponse::new(
+                request.start,
+                request.end,
+                documents,
+            ))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Date range query failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report notarization counts per UTC day within the requested window
+async fn report_timeseries(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &TimeseriesRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.count_by_day(request.start, request.end) {
+        Ok(days) => {
+            info!(days = days.len(), "Timeseries result");
+            serde_json::to_string(&TimeseriesResponse::new(request.start, request.end, days))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Timeseries query failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report the documents whose expiry has passed as of the requested time
+async fn report_expired(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    request: &ListExpiredRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+    let as_of = request.as_of.unwrap_or_else(|| clock.now());
+
+    let report_json = match search_usecase.find_expired(as_of, request.limit, request.offset) {
+        Ok(documents) => {
+            info!(count = documents.len(), "Expired documents result");
+            serde_json::to_string(&ExpiredDocumentsResponse::new(as_of, documents))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Expired documents query failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report one page of all documents, newest first, with the cursor of the next page
+async fn report_document_page(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &ListDocumentsRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.list_documents(request.after.as_deref(), request.limit) {
+        Ok(page) => {
+            info!(count = page.documents.len(), "Document page result");
+            serde_json::to_string(&ListDocumentsResponse::from(page))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Document listing failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report one page of a submitter's documents
+async fn report_submitter_page(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &ListBySubmitterRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let page = search_usecase.list_by_submitter(
+        &request.submitter,
+        request.cursor.as_deref(),
+        request.limit,
+    );
+    let report_json = match page {
+        Ok(page) => {
+            info!(count = page.documents.len(), "Submitter page result");
+            serde_json::to_string(&ListDocumentsResponse::from(page))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Submitter listing failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report one page of the whole registry, oldest first, with the total document count
+async fn report_registry_export(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &ExportRegistryRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.export_registry(request.page, request.page_size) {
+        Ok(page) => {
+            info!(
+                count = page.documents.len(),
+                total = page.total_count,
+                "Registry export result"
+            );
+            serde_json::to_string(&ExportRegistryResponse::from(page))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Registry export failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report one page of the audit log
+async fn report_audit_log(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &AuditLogRequest,
+) -> Result<(), Box<dyn Error>> {
+    let query = AuditQuery {
+        submitter: request.submitter.as_deref(),
+        from_block: request.from_block,
+        to_block: request.to_block,
+        after_id: request.cursor,
+        limit: request.limit,
+    };
+
+    let report_json = match AuditUseCase::new(repository).page(&query) {
+        Ok(page) => {
+            info!(count = page.entries.len(), "Audit log page result");
+            serde_json::to_string(&AuditLogResponse::from(page))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Audit log query failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report entries of the whole audit log by offset
+async fn report_audit_listing(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &ListAuditLogRequest,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match AuditUseCase::new(repository).list(request.limit, request.offset) {
+        Ok(listing) => {
+            info!(count = listing.entries.len(), "Audit log listing result");
+            serde_json::to_string(&ListAuditLogResponse::from(listing))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Audit log listing failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report the documents created since an indexer's checkpoint
+async fn report_sync_batch(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &SyncSinceRequest,
+) -> Result<(), Box<dyn Error>> {
+    let search_usecase = SearchUseCase::new(repository);
+
+    let report_json = match search_usecase.sync_since(request.since_created_at, request.limit) {
+        Ok(batch) => {
+            info!(
+                count = batch.documents.len(),
+                next_since = batch.next_since,
+                "Sync batch result"
+            );
+            serde_json::to_string(&SyncSinceResponse::from(batch))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Sync failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report a Merkle proof that a document was notarized during an epoch
+async fn report_inclusion_proof(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    request: &InclusionProofRequest,
+) -> Result<(), Box<dyn Error>> {
+    let epoch_usecase = EpochUseCase::new(repository);
+
+    let report_json = match epoch_usecase.inclusion_proof(&request.content_hash, request.epoch) {
+        Ok(proof) => serde_json::to_string(&InclusionProofResponse {
+            epoch: request.epoch,
+            proof,
+        })?,
+        Err(e) => {
+            warn!(error = %e, "Inclusion proof failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report the hash a notarization would record and whether it is already taken
+async fn report_preview(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    request: &NotarizeRequest,
+) -> Result<(), Box<dyn Error>> {
+    let notarize_config = NotarizeConfig::from(config);
+
+    // Reject oversized content before allocating the decoded buffer
+    let preview = match notarize_config.check_encoded_content_size(&request.content) {
+        Err(e) => Err(coded_error(&e)),
+        Ok(()) => match decode_base64_content(&request.content) {
+            Ok(content) => NotarizeUseCase::new(repository, notarize_config)
+                .preview(&content)
+                .map_err(|e| coded_error(&e)),
+            Err(e) => Err(error_report(
+                ErrorCode::InvalidBase64,
+                &format!("Invalid base64 content: {}", e),
+            )),
+        },
+    };
+
+    let report_json = match preview {
+        Ok(preview) => serde_json::to_string(&preview)?,
+        Err(report) => {
+            warn!(error = %report, "Notarization preview failed");
+            report
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report an unrecognized action tag together with the actions that are supported
+async fn report_unknown_action(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    action: &str,
+) -> Result<(), Box<dyn Error>> {
+    warn!(action = %action, "Unknown input action");
+    let report_json = serde_json::to_string(&UnknownActionResponse::new(action))?;
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Verify a document by its Blake2b-256 digest and report the result
+async fn report_blake2b_verification(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    blake2b_hash: &str,
+) -> Result<(), Box<dyn Error>> {
+    let verify_usecase = VerifyUseCase::new(repository).with_clock(clock);
+
+    let report_json = match verify_usecase.execute_blake2b(blake2b_hash) {
+        Ok(result) => {
+            info!(exists = result.exists, "Blake2b verification result");
+            serde_json::to_string(&ReportResponse::from_verification(&result))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Blake2b verification failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Rehash submitted content and report whether it matches the notarized hash
+async fn report_content_verification(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    config: &Config,
+    request: &VerifyContentRequest,
+) -> Result<(), Box<dyn Error>> {
+    // Reject oversized content before allocating the decoded buffer
+    let verification =
+        match NotarizeConfig::from(config).check_encoded_content_size(&request.content) {
+            Err(e) => Err(coded_error(&e)),
+            Ok(()) => match decode_base64_content(&request.content) {
+                Ok(content) => VerifyUseCase::new(repository)
+                    .with_clock(clock)
+                    .execute_with_content(&request.content_hash, &content)
+                    .map_err(|e| coded_error(&e)),
+                Err(e) => Err(error_report(
+                    ErrorCode::InvalidBase64,
+                    &format!("Invalid base64 content: {}", e),
+                )),
+            },
+        };
+
+    let report_json = match verification {
+        Ok(result) => {
+            info!(
+                exists = result.exists,
+                content_matches = result.content_matches,
+                "Content verification result"
+            );
+            serde_json::to_string(&ReportResponse::from_verification(&result))?
+        }
+        Err(report) => {
+            warn!(error = %report, "Content verification failed");
+            report
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Hash submitted content and report the verification of the resulting hash
+async fn report_content_lookup(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    config: &Config,
+    request: &VerifyByContentRequest,
+) -> Result<(), Box<dyn Error>> {
+    // Reject oversized content before allocating the decoded buffer
+    let verification =
+        match NotarizeConfig::from(config).check_encoded_content_size(&request.content) {
+            Err(e) => Err(coded_error(&e)),
+            Ok(()) => match decode_base64_content(&request.content) {
+                Ok(content) => VerifyUseCase::new(repository)
+                    .with_clock(clock)
+                    .execute_by_content(&content)
+                    .map_err(|e| coded_error(&e)),
+                Err(e) => Err(error_report(
+                    ErrorCode::InvalidBase64,
+                    &format!("Invalid base64 content: {}", e),
+                )),
+            },
+        };
+
+    let report_json = match verification {
+        Ok(result) => {
+            info!(
+                exists = result.exists,
+                computed_hash = result.computed_hash.as_deref(),
+                "Content lookup result"
+            );
+            serde_json::to_string(&ReportResponse::from_verification(&result))?
+        }
+        Err(report) => {
+            warn!(error = %report, "Content lookup failed");
+            report
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Look up a document by id and report it like a verification
+async fn report_document_by_id(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    clock: &dyn Clock,
+    id: &str,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match VerifyUseCase::new(repository)
+        .with_clock(clock)
+        .execute_by_id(id)
+    {
+        Ok(result) => {
+            info!(exists = result.exists, "Document lookup result");
+            serde_json::to_string(&ReportResponse::from_verification(&result))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Document lookup failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report only the receipt proof of a document
+async fn report_proof(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    content_hash: &str,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match VerifyUseCase::new(repository).execute(content_hash) {
+        Ok(result) => {
+            serde_json::to_string(&ProofResponse::from_document(result.document.as_ref()))?
+        }
+        Err(e) => {
+            warn!(error = %e, "Proof lookup failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report a notarized document as a W3C Verifiable Credential
+async fn report_verifiable_credential(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    content_hash: &str,
+) -> Result<(), Box<dyn Error>> {
+    let verified = VerifyUseCase::new(repository)
+        .execute(content_hash)
+        .map(|result| result.document);
+
+    let report_json = match verified {
+        Ok(Some(document)) => serde_json::to_string(&VerifiableCredential::for_document(
+            &config.issuer_did,
+            &document,
+        ))?,
+        Ok(None) => error_report(ErrorCode::NotFound, "Document not found"),
+        Err(e) => {
+            warn!(error = %e, "Verifiable credential export failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report a signed certificate of a notarized document
+/// Certificates are signed with the notice signing key, so they need one configured
+async fn report_certificate(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    content_hash: &str,
+) -> Result<(), Box<dyn Error>> {
+    let Some(key) = &config.notice_signing_key else {
+        let error_msg = error_report(
+            ErrorCode::Unavailable,
+            "Certificates are disabled: no NOTARY_NOTICE_SIGNING_KEY configured",
+        );
+        return send_report(client, endpoints, &error_msg).await;
+    };
+
+    let verified = VerifyUseCase::new(repository)
+        .execute(content_hash)
+        .map(|result| result.document);
+
+    let report_json = match verified {
+        Ok(Some(document)) => serde_json::to_string(&CertificateResponse::new(document, key))?,
+        Ok(None) => error_report(ErrorCode::NotFound, "Document not found"),
+        Err(e) => {
+            warn!(error = %e, "Certificate export failed");
+            coded_error(&e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Report the retained content of a document if `requester` owns it
+async fn report_content(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    document_id: &str,
+    requester: &str,
+) -> Result<(), Box<dyn Error>> {
+    let report_json = match FetchContentUseCase::new(repository).execute(document_id, requester) {
+        Ok(stored) => serde_json::to_string(&ContentResponse::new(stored))?,
+        Err(e) => {
+            warn!(error = %e, "Content fetch failed");
+            coded_error(&*e)
+        }
+    };
+
+    send_report(client, endpoints, &report_json).await
+}
+
+/// Check that the submitter can pay the notarization fee
+/// Returns the rejection reporting the shortfall when they cannot
+fn check_notarization_fee(
+    repository: &dyn DocumentRepository,
+    submitter: &str,
+    fee: Option<(&str, u128)>,
+) -> Option<AdvanceOutcome> {
+    let (token, amount) = fee?;
+
+    match BalanceUseCase::new(repository).ensure_funds(submitter, token, amount) {
+        Ok(()) => None,
+        Err(e) => {
+            warn!(error = %e, "Notarization fee not covered");
+            Some(AdvanceOutcome::reject_error(&e))
+        }
+    }
+}
+
+/// Credit a portal deposit and announce the depositor's new balance in a notice
+async fn credit_deposit(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    deposit: Result<Deposit, DepositError>,
+) -> Result<AdvanceOutcome, Box<dyn Error>> {
+    let deposit = match deposit {
+        Ok(deposit) => deposit,
+        Err(e) => {
+            warn!(error = %e, "Malformed portal deposit");
+            return Ok(AdvanceOutcome::reject(
+                ErrorCode::InvalidPayload,
+                &format!("Invalid deposit: {}", e),
+            ));
+        }
+    };
+
+    info!(
+        depositor = %deposit.depositor,
+        token = %deposit.token,
+        amount = %deposit.amount,
+        "Crediting deposit"
+    );
+
+    match BalanceUseCase::new(repository).deposit(&deposit) {
+        Ok(balance) => {
+            let notice = DepositNotice::new(&deposit, balance);
+            send_notice(client, endpoints, &serde_json::to_string(&notice)?).await?;
+            Ok(AdvanceOutcome::Accept)
+        }
+        Err(e) => {
+            error!(error = %e, "Deposit failed");
+            Ok(AdvanceOutcome::reject_error(&e))
+        }
+    }
+}
+
+/// Answer a read-only query with a report, the same way for advance and inspect requests
+/// `clock` is the input's block time for advance requests and the node's clock for inspect
+/// Returns false, without reporting anything, for actions that are not shared queries
+async fn dispatch_query(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    clock: &dyn Clock,
+    input: &InputAction,
+) -> Result<bool, Box<dyn Error>> {
+    match input {
+        InputAction::Metrics => {
+            info!("Exporting metrics");
+
+            report_metrics(client, endpoints, repository).await?;
+        }
+        InputAction::Health => {
+            info!("Reporting health");
+
+            report_health(client, endpoints, repository).await?;
+        }
+        InputAction::BatchVerify { data } => {
+            info!(count = data.hashes.len(), "Verifying document batch");
+
+            report_batch_verification(client, endpoints, repository, clock, &data.hashes).await?;
+        }
+        InputAction::VerifyProvenance { data } => {
+            info!(content_hash = %data.content_hash, "Verifying document provenance");
+
+            report_provenance(
+                client,
+                endpoints,
+                repository,
+                clock,
+                config,
+                &data.content_hash,
+            )
+            .await?;
+        }
+        InputAction::GetSubmitterStats { data } => {
+            info!("Reporting submitter stats");
+
+            report_submitter_stats(client, endpoints, repository, data.submitter.as_deref())
+                .await?;
+        }
+        InputAction::SearchDocuments { data } => {
+            info!(query = %data.query, "Searching documents");
+
+            report_search(client, endpoints, repository, &data.query, data.limit).await?;
+        }
+        InputAction::FindByTag { data } => {
+            info!(tag = %data.tag, "Finding documents by tag");
+
+            report_tagged_documents(client, endpoints, repository, data).await?;
+        }
+        InputAction::ListByDateRange { data } => {
+            info!(
+                start = data.start,
+                end = data.end,
+                "Listing documents by date range"
+            );
+
+            report_date_range(client, endpoints, repository, data).await?;
+        }
+        InputAction::Timeseries { data } => {
+            info!(
+                start = data.start,
+                end = data.end,
+                "Reporting notarization timeseries"
+            );
+
+            report_timeseries(client, endpoints, repository, data).await?;
+        }
+        InputAction::ListExpired { data } => {
+            info!(as_of = ?data.as_of, "Listing expired documents");
+
+            report_expired(client, endpoints, repository, clock, data).await?;
+        }
+        InputAction::ListDocuments { data } => {
+            info!(after = ?data.after, "Listing documents");
+
+            report_document_page(client, endpoints, repository, data).await?;
+        }
+        InputAction::ListBySubmitter { data } => {
+            info!(submitter = %data.submitter, "Listing submitter documents");
+
+            report_submitter_page(client, endpoints, repository, data).await?;
+        }
+        InputAction::ExportRegistry { data } => {
+            info!(
+                page = data.page,
+                page_size = data.page_size,
+                "Exporting registry"
+            );
+
+            report_registry_export(client, endpoints, repository, data).await?;
+        }
+        InputAction::SyncSince { data } => {
+            info!(since = data.since_created_at, "Syncing documents");
+
+            report_sync_batch(client, endpoints, repository, data).await?;
+        }
+        InputAction::GetInclusionProof { data } => {
+            info!(content_hash = %data.content_hash, epoch = data.epoch, "Generating inclusion proof");
 
-// Database path - use persistent DB in production, in-memory for fallback
-const DB_PATH: &str = "/var/lib/notary/notary.db";
+            report_inclusion_proof(client, endpoints, repository, data).await?;
+        }
+        InputAction::GetVerifiableCredential { data } => {
+            info!(content_hash = %data.content_hash, "Exporting verifiable credential");
 
-/// Get a repository instance
-/// In production, uses persistent SQLite database
-/// Can be overridden via NOTARY_DB_PATH environment variable (for testing)
-/// Falls back to in-memory if persistent fails
-pub fn get_repository() -> Box<dyn DocumentRepository> {
-    let db_path = std::env::var("NOTARY_DB_PATH").unwrap_or_else(|_| DB_PATH.to_string());
-    Box::new(
-        SqliteRepository::new(&db_path)
-            .or_else(|_| SqliteRepository::new_in_memory())
-            .expect("Failed to initialize database"),
-    )
+            report_verifiable_credential(client, endpoints, repository, config, &data.content_hash)
+                .await?;
+        }
+        InputAction::GetProof { data } => {
+            info!(content_hash = %data.content_hash, "Reporting receipt proof");
+
+            report_proof(client, endpoints, repository, &data.content_hash).await?;
+        }
+        InputAction::Certificate { data } => {
+            info!(content_hash = %data.content_hash, "Issuing notarization certificate");
+
+            report_certificate(client, endpoints, repository, config, &data.content_hash).await?;
+        }
+        InputAction::Preview { data } => {
+            info!(file_name = %data.file_name, "Previewing notarization");
+
+            report_preview(client, endpoints, repository, config, data).await?;
+        }
+        InputAction::VerifyByBlake2b { data } => {
+            info!(blake2b_hash = %data.hash, "Verifying Blake2b hash");
+
+            report_blake2b_verification(client, endpoints, repository, clock, &data.hash).await?;
+        }
+        InputAction::GetDocumentById { data } => {
+            info!(document_id = %data.id, "Fetching document by id");
+
+            report_document_by_id(client, endpoints, repository, clock, &data.id).await?;
+        }
+        InputAction::GetStatistics => {
+            info!("Reporting statistics");
+
+            report_statistics(client, endpoints, repository, clock).await?;
+        }
+        InputAction::VerifyContent { data } => {
+            info!(content_hash = %data.content_hash, "Verifying document content");
+
+            report_content_verification(client, endpoints, repository, clock, config, data).await?;
+        }
+        InputAction::VerifyByContent { data } => {
+            info!("Looking up document by content");
+
+            report_content_lookup(client, endpoints, repository, clock, config, data).await?;
+        }
+        InputAction::AuditLog { data } => {
+            info!("Paging through audit log");
+
+            report_audit_log(client, endpoints, repository, data).await?;
+        }
+        InputAction::ListAuditLog { data } => {
+            info!(offset = data.offset, "Listing audit log");
+
+            report_audit_listing(client, endpoints, repository, data).await?;
+        }
+        // State changes, and queries whose answer depends on the request path
+        InputAction::Notarize { .. }
+        | InputAction::Verify { .. }
+        | InputAction::FetchContent { .. }
+        | InputAction::UpdateMetadata { .. }
+        | InputAction::TransferOwnership { .. }
+        | InputAction::Purge { .. }
+        | InputAction::Rehash { .. }
+        | InputAction::Commit { .. }
+        | InputAction::Cosign { .. }
+        | InputAction::NotarizeChunkStart { .. }
+        | InputAction::NotarizeChunkAppend { .. }
+        | InputAction::NotarizeChunkFinish { .. } => return Ok(false),
+    }
+
+    Ok(true)
 }
 
 pub async fn handle_advance(
     client: &hyper::Client<hyper::client::HttpConnector>,
     server_addr: &str,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    request: JsonValue,
+) -> Result<&'static str, Box<dyn Error>> {
+    let endpoints = Endpoints::new(server_addr, &config.rollup_path_prefix)?;
+
+    // Extract metadata; a missing sender is rejected for state-changing actions
+    let submitter = request["data"]["metadata"]["msg_sender"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let block_number = request["data"]["metadata"]["block_number"]
+        .as_u64()
+        .unwrap_or(0);
+
+    // Every node must record the same time, so the host clock cannot stand in for it
+    let timestamp = request["data"]["metadata"]["timestamp"].as_i64();
+
+    let span = info_span!(
+        "advance",
+        msg_sender = %submitter,
+        block_number,
+        action = tracing::field::Empty
+    );
+
+    let mut action = "unknown";
+    let mut content_hash = None;
+    let outcome = match timestamp {
+        Some(timestamp) => {
+            process_advance(
+                client,
+                &endpoints,
+                repository,
+                config,
+                request,
+                &submitter,
+                block_number,
+                timestamp,
+                &mut action,
+                &mut content_hash,
+            )
+            .instrument(span)
+            .await?
+        }
+        None => {
+            warn!("Rejected input without a block timestamp");
+            AdvanceOutcome::reject(
+                ErrorCode::InvalidPayload,
+                "Missing timestamp in input metadata",
+            )
+        }
+    };
+
+    // Every rejection is explained by a report carrying its stable error code
+    // The node reverts the state of a rejected input, so the report is its only record
+    if let AdvanceOutcome::Reject { code, report } = &outcome {
+        info!(action, reason = ?code, "Rejected advance input");
+        send_report(client, &endpoints, report).await?;
+        return Ok(outcome.status());
+    }
+
+    // Only accepted inputs are logged: an entry for a rejected one would be reverted with it
+    AuditUseCase::new(repository).record(AuditEntry {
+        id: 0,
+        action: action.to_string(),
+        submitter,
+        block_number,
+        timestamp: timestamp.unwrap_or_default(),
+        outcome: AuditOutcome::Accept,
+        content_hash,
+    })?;
+
+    Ok(outcome.status())
+}
+
+/// Process an advance request, escalating system failures to a rollup exception
+///
+/// Invalid inputs are reported by `handle_advance` and come back as `"reject"`.
+/// An `Err` means the request could not be processed at all (malformed request,
+/// database or rollup server failure), so the node operator is notified through
+/// `/exception` and the error is returned for the caller to stop on.
+pub async fn handle_advance_or_throw(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    server_addr: &str,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    request: JsonValue,
+) -> Result<&'static str, Box<dyn Error>> {
+    let error = match handle_advance(client, server_addr, repository, config, request).await {
+        Ok(status) => return Ok(status),
+        Err(e) => e,
+    };
+
+    error!(error = %error, "Unrecoverable failure while processing advance request");
+
+    let endpoints = Endpoints::new(server_addr, &config.rollup_path_prefix)?;
+    throw_exception(client, &endpoints, &exception_report(&*error)).await?;
+
+    Err(error)
+}
+
+/// `action` is set to the name of the input's action once it is known, and `content_hash`
+/// to the hash the input names or notarizes, for the audit log
+#[allow(clippy::too_many_arguments)]
+async fn process_advance(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
     request: JsonValue,
-) -> Result<&'static str, Box<dyn std::error::Error>> {
-    println!("Received advance request");
+    submitter: &str,
+    block_number: u64,
+    timestamp: i64,
+    action: &mut &'static str,
+    content_hash: &mut Option<String>,
+) -> Result<AdvanceOutcome, Box<dyn Error>> {
+    info!("Received advance request");
+
+    // Queries judge expiry and recent activity by the block's time, like every other node
+    let block_clock = FixedClock(timestamp);
+
+    // Recorded on every document this input notarizes
+    let epoch_index = request["data"]["metadata"]["epoch_index"]
+        .as_u64()
+        .unwrap_or(0);
+    let input_index = request["data"]["metadata"]["input_index"]
+        .as_u64()
+        .unwrap_or(0);
+
+    // The first input of a new epoch seals the previous one before it is processed
+    if let Some(epoch_index) = request["data"]["metadata"]["epoch_index"].as_u64() {
+        if let Some(sealed) = EpochUseCase::new(repository).observe(epoch_index)? {
+            info!(epoch = sealed.epoch, root = %sealed.root, "Sealed epoch");
+            let notice = EpochRootNotice::new(sealed);
+            send_notice(client, endpoints, &serde_json::to_string(&notice)?).await?;
+        }
+    }
 
     // Extract hex-encoded payload
     let payload_hex = request["data"]["payload"]
@@ -40,117 +1356,627 @@ pub async fn handle_advance(
 
     // Decode from hex to bytes
     let payload_bytes = hex::decode(payload_hex)?;
-    let payload_str = std::str::from_utf8(&payload_bytes)?;
 
-    println!("Decoded payload: {}", payload_str);
+    if payload_bytes.len() > config.max_payload_bytes() {
+        warn!(size = payload_bytes.len(), "Payload exceeds maximum size");
+        return Ok(AdvanceOutcome::reject(
+            ErrorCode::PayloadTooLarge,
+            &format!(
+                "Payload exceeds maximum size of {} KB",
+                config.max_payload_kb
+            ),
+        ));
+    }
+
+    // Inputs from a deposit portal carry packed ABI fields, not a JSON action
+    if let Some(deposit) = config.portal_deposit(submitter, &payload_bytes) {
+        tracing::Span::current().record("action", "deposit");
+        *action = "deposit";
+        return credit_deposit(client, endpoints, repository, deposit).await;
+    }
 
     // Parse input action
-    let input: InputAction = match serde_json::from_str(payload_str) {
+    let input = match parse_payload_bytes(&payload_bytes) {
         Ok(action) => action,
+        Err(PayloadError::InvalidJson(e)) => {
+            let payload_str = String::from_utf8_lossy(&payload_bytes);
+            if let Some(action) = InputAction::unknown_action(&payload_str) {
+                warn!(action = %action, "Unknown input action");
+                return Ok(AdvanceOutcome::Reject {
+                    code: ErrorCode::UnknownAction,
+                    report: serde_json::to_string(&UnknownActionResponse::new(&action))?,
+                });
+            }
+
+            warn!(error = %e, "Failed to parse input action");
+            return Ok(AdvanceOutcome::reject(
+                ErrorCode::InvalidPayload,
+                &format!("Invalid input format: {}", e),
+            ));
+        }
+        Err(PayloadError::ProtocolVersion(e)) => {
+            warn!(error = %e, "Rejected payload protocol version");
+            return Ok(AdvanceOutcome::reject_error(&e));
+        }
+        // Bytes that are not UTF-8 come from the sender, so they are rejected like bad JSON
         Err(e) => {
-            eprintln!("Failed to parse input action: {}", e);
-            let error_msg = format!("{{\"error\":\"Invalid input format: {}\"}}", e);
-            send_report(client, server_addr, &error_msg).await?;
-            return Ok("reject");
+            warn!(error = %e, "Undecodable payload");
+            return Ok(AdvanceOutcome::reject(
+                ErrorCode::InvalidPayload,
+                &e.to_string(),
+            ));
         }
     };
 
-    // Extract metadata
-    let submitter = request["data"]["metadata"]["msg_sender"]
-        .as_str()
-        .unwrap_or("0x0000000000000000000000000000000000000000");
+    tracing::Span::current().record("action", input.name());
+    *action = input.name();
+    *content_hash = input.content_hash().map(str::to_string);
 
-    let block_number = request["data"]["metadata"]["block_number"]
-        .as_u64()
-        .unwrap_or(0);
+    // Oversized content is rejected by its length alone, before validation scans it
+    // or the buffer is decoded
+    if let InputAction::Notarize { data } = &input {
+        if let Err(e) = NotarizeConfig::from(config).check_encoded_content_size(&data.content) {
+            warn!(error = %e, "Document content too large");
+            return Ok(AdvanceOutcome::reject_error(&e));
+        }
+    }
+
+    // Field-level problems are reported together, before any state is touched
+    if let Some(report) = ValidationReport::new(input.validate()) {
+        warn!(
+            errors = report.errors.len(),
+            "Rejected invalid request fields"
+        );
+        return Ok(AdvanceOutcome::Reject {
+            code: report.error_code,
+            report: serde_json::to_string(&report)?,
+        });
+    }
+
+    // State-changing actions need a well-formed sender, stored in lowercase
+    let submitter = if input.changes_state() {
+        match EthAddress::parse(submitter) {
+            Ok(address) => address.as_str().to_string(),
+            Err(e) => {
+                warn!(error = %e, "Rejected malformed msg_sender");
+                let detail = if submitter.is_empty() {
+                    "Missing msg_sender".to_string()
+                } else {
+                    format!("Invalid msg_sender: {}", e)
+                };
+                return Ok(AdvanceOutcome::reject(ErrorCode::InvalidPayload, &detail));
+            }
+        }
+    } else {
+        submitter.to_string()
+    };
+    let submitter = submitter.as_str();
+
+    // Per-sender rate limit for state-changing actions, when enabled
+    // Checked before the nonce so a throttled input does not consume one
+    if let Some(limiter) = config
+        .rate_limiter
+        .as_ref()
+        .filter(|_| input.changes_state())
+    {
+        if let Err(e) = limiter.check(submitter, block_number) {
+            warn!(error = %e, "Rate limited input");
+            return Ok(AdvanceOutcome::reject_error(&e));
+        }
+    }
+
+    // Replay protection for state-changing actions: a nonce is checked whenever one is sent,
+    // and required when enabled
+    if input.requires_nonce() && (config.require_nonce || input.nonce().is_some()) {
+        if let Err(e) = NonceUseCase::new(repository).execute(submitter, input.nonce()) {
+            warn!(error = %e, "Rejected input nonce");
+            return Ok(AdvanceOutcome::reject_error(&*e));
+        }
+    }
 
     // Handle different actions
     match input {
         InputAction::Notarize { data } => {
-            println!(
-                "Notarizing document: {} ({})",
-                data.file_name, data.mime_type
+            info!(
+                file_name = %data.file_name,
+                mime_type = %data.mime_type,
+                "Notarizing document"
             );
 
+            let notarize_config = NotarizeConfig::from(config);
+
             // Decode base64 content
-            use base64::Engine;
-            let content = match base64::engine::general_purpose::STANDARD.decode(&data.content) {
+            let content = match decode_base64_content(&data.content) {
                 Ok(c) => c,
                 Err(e) => {
-                    eprintln!("Failed to decode base64 content: {}", e);
-                    let error_msg = format!("{{\"error\":\"Invalid base64 content: {}\"}}", e);
-                    send_report(client, server_addr, &error_msg).await?;
-                    return Ok("reject");
+                    warn!(error = %e, "Failed to decode base64 content");
+                    return Ok(AdvanceOutcome::reject(
+                        ErrorCode::InvalidBase64,
+                        &format!("Invalid base64 content: {}", e),
+                    ));
+                }
+            };
+            *content_hash = Some(Document::hash_content(&content).to_string());
+
+            // Check the callback before notarizing, so a bad address cannot strand a receipt
+            let callback = match data.callback_address.as_deref().map(EthAddress::parse) {
+                Some(Err(e)) => {
+                    warn!(error = %e, "Rejected malformed callback_address");
+                    return Ok(AdvanceOutcome::reject(
+                        ErrorCode::InvalidPayload,
+                        &format!("Invalid callback_address: {}", e),
+                    ));
                 }
+                Some(Ok(_)) if data.required_cosigners.is_some() => {
+                    return Ok(AdvanceOutcome::reject(
+                        ErrorCode::InvalidPayload,
+                        "callback_address cannot be combined with required_cosigners",
+                    ));
+                }
+                parsed => parsed.and_then(Result::ok),
+            };
+
+            let options = NotarizeOptions {
+                expires_at: data.expires_at,
+                expiration_blocks: data.expiration_blocks,
+                tags: data.tags,
+                supersedes: data.supersedes,
+                idempotency_key: data.idempotency_key,
+                description: data.description,
+                notarized_at: Some(timestamp),
+                allow_alias: data.allow_alias,
+                epoch_index,
+                input_index,
+                signature: data.signature,
+                signed_message: data.signed_message,
+            };
+
+            // Retries return the original receipt, whose callback was already issued
+            let is_retry = match &options.idempotency_key {
+                Some(key) => repository.find_idempotency_key(key, submitter)?.is_some(),
+                None => false,
             };
 
-            // Create use case with repository
-            let notarize_usecase = NotarizeUseCase::new(get_repository());
+            // So do aliases, which are still charged since they record a new filename
+            let is_alias = options.allow_alias
+                && repository
+                    .find_by_hash(&Document::hash_content(&content))
+                    .is_ok();
+
+            // The fee is checked now and charged once the input is accepted; retries are free
+            let fee = config.notarization_fee().filter(|_| !is_retry);
+            if let Some(rejection) = check_notarization_fee(repository, submitter, fee) {
+                return Ok(rejection);
+            }
+
+            // Co-notarizations wait for their cosigners instead of being saved now
+            if let Some(cosigners) = &data.required_cosigners {
+                let cosign_usecase =
+                    CosignUseCase::new(repository, notarize_config, config.cosign_window_secs);
+                return match cosign_usecase.request(
+                    &content,
+                    &data.file_name,
+                    &data.mime_type,
+                    submitter,
+                    &options,
+                    cosigners,
+                    timestamp,
+                ) {
+                    Ok((pending, cosigners)) => {
+                        info!(content_hash = %pending.content_hash, "Awaiting cosignatures");
+                        if let Some((token, amount)) = fee {
+                            BalanceUseCase::new(repository).charge(submitter, token, amount)?;
+                        }
+                        let report = PendingCosignResponse::new(&pending, cosigners);
+                        send_report(client, endpoints, &serde_json::to_string(&report)?).await?;
+                        Ok(AdvanceOutcome::Accept)
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Co-notarization request failed");
+                        Ok(AdvanceOutcome::reject_error(&*e))
+                    }
+                };
+            }
+
+            // Create use case with shared repository
+            let notarize_usecase = NotarizeUseCase::new(repository, notarize_config);
 
             // Execute notarization
-            match notarize_usecase.execute(
+
+            match notarize_usecase.execute_with_options(
                 &content,
                 &data.file_name,
                 &data.mime_type,
                 submitter,
                 block_number,
+                &options,
             ) {
                 Ok(receipt) => {
-                    println!("Document notarized successfully: {}", receipt.document_id);
+                    info!(document_id = %receipt.document_id, "Document notarized successfully");
+                    if let Some((token, amount)) = fee {
+                        BalanceUseCase::new(repository).charge(submitter, token, amount)?;
+                    }
 
-                    // Send notice with receipt
-                    let response = NoticeResponse::notarization(receipt);
-                    let notice_json = serde_json::to_string(&response)?;
-                    send_notice(client, server_addr, &notice_json).await?;
+                    let callback_payload = match &callback {
+                        Some(_) if !is_retry && !is_alias => Some(format!(
+                            "0x{}",
+                            hex::encode(encode_notarized_callback(
+                                &receipt,
+                                &EthAddress::parse(submitter)?
+                            )?)
+                        )),
+                        _ => None,
+                    };
+
+                    send_notarization_notices(
+                        client,
+                        endpoints,
+                        config,
+                        receipt,
+                        options.supersedes.as_deref(),
+                        Vec::new(),
+                        data.response_encoding,
+                    )
+                    .await?;
+
+                    if let (Some(destination), Some(payload)) = (&callback, callback_payload) {
+                        let voucher =
+                            send_voucher(client, endpoints, destination.as_str(), 0, &payload)
+                                .await?;
+                        info!(index = voucher.index, destination = %destination, "Callback voucher sent");
+                    }
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Notarization failed");
+                    Ok(AdvanceOutcome::reject_error(&e))
+                }
+            }
+        }
+        InputAction::Cosign { data } => {
+            info!(content_hash = %data.content_hash, "Recording cosignature");
+
+            let cosign_usecase = CosignUseCase::new(
+                repository,
+                NotarizeConfig::from(config),
+                config.cosign_window_secs,
+            )
+            .with_input(epoch_index, input_index);
+
+            match cosign_usecase.cosign(
+                &data.content_hash,
+                submitter,
+                &data.signature,
+                block_number,
+                timestamp,
+            ) {
+                Ok(CosignOutcome::Pending { pending, cosigners }) => {
+                    let report = PendingCosignResponse::new(&pending, cosigners);
+                    info!(
+                        signed = report.signed,
+                        required = report.required,
+                        "Cosignature recorded"
+                    );
+                    send_report(client, endpoints, &serde_json::to_string(&report)?).await?;
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Ok(CosignOutcome::Completed {
+                    receipt,
+                    cosigners,
+                    supersedes,
+                }) => {
+                    info!(document_id = %receipt.document_id, "Co-notarized document notarized successfully");
+
+                    send_notarization_notices(
+                        client,
+                        endpoints,
+                        config,
+                        receipt,
+                        supersedes.as_deref(),
+                        cosigners,
+                        ResponseEncoding::Json,
+                    )
+                    .await?;
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    warn!(error = %e, "Cosignature rejected");
+                    Ok(AdvanceOutcome::reject_error(&*e))
+                }
+            }
+        }
+        InputAction::NotarizeChunkStart { data } => {
+            info!(
+                upload_id = %data.upload_id,
+                file_name = %data.file_name,
+                "Starting chunked upload"
+            );
+
+            let upload_usecase =
+                ChunkedUploadUseCase::new(repository, NotarizeConfig::from(config));
+            let result = upload_usecase.start(
+                &data.upload_id,
+                &data.file_name,
+                &data.mime_type,
+                submitter,
+                timestamp,
+            );
+
+            report_upload_progress(client, endpoints, result).await
+        }
+        InputAction::NotarizeChunkAppend { data } => {
+            info!(upload_id = %data.upload_id, index = data.index, "Appending upload chunk");
+
+            let notarize_config = NotarizeConfig::from(config);
+
+            // A chunk larger than the whole-document limit can never be appended
+            if let Err(e) = notarize_config.check_encoded_content_size(&data.content) {
+                warn!(error = %e, "Upload chunk too large");
+                return Ok(AdvanceOutcome::reject_error(&e));
+            }
+
+            let chunk = match decode_base64_content(&data.content) {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!(error = %e, "Failed to decode base64 chunk");
+                    return Ok(AdvanceOutcome::reject(
+                        ErrorCode::InvalidBase64,
+                        &format!("Invalid base64 content: {}", e),
+                    ));
+                }
+            };
+
+            let upload_usecase = ChunkedUploadUseCase::new(repository, notarize_config);
+            let result = upload_usecase.append(&data.upload_id, data.index, &chunk, submitter);
+
+            report_upload_progress(client, endpoints, result).await
+        }
+        InputAction::NotarizeChunkFinish { data } => {
+            info!(upload_id = %data.upload_id, "Finishing chunked upload");
+
+            let upload_usecase =
+                ChunkedUploadUseCase::new(repository, NotarizeConfig::from(config));
+            let options = NotarizeOptions {
+                expires_at: data.expires_at,
+                expiration_blocks: data.expiration_blocks,
+                tags: data.tags,
+                supersedes: data.supersedes,
+                notarized_at: Some(timestamp),
+                epoch_index,
+                input_index,
+                ..NotarizeOptions::default()
+            };
+
+            let fee = config.notarization_fee();
+            if let Some(rejection) = check_notarization_fee(repository, submitter, fee) {
+                return Ok(rejection);
+            }
+
+            match upload_usecase.finish(&data.upload_id, submitter, block_number, &options) {
+                Ok(receipt) => {
+                    info!(document_id = %receipt.document_id, "Chunked document notarized successfully");
+                    if let Some((token, amount)) = fee {
+                        BalanceUseCase::new(repository).charge(submitter, token, amount)?;
+                    }
+
+                    send_notarization_notices(
+                        client,
+                        endpoints,
+                        config,
+                        receipt,
+                        options.supersedes.as_deref(),
+                        Vec::new(),
+                        ResponseEncoding::Json,
+                    )
+                    .await?;
 
-                    Ok("accept")
+                    Ok(AdvanceOutcome::Accept)
                 }
                 Err(e) => {
-                    eprintln!("Notarization failed: {}", e);
-                    let error_msg = format!("{{\"error\":\"{}\"}}", e);
-                    send_report(client, server_addr, &error_msg).await?;
-                    Ok("reject")
+                    error!(error = %e, "Chunked notarization failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
                 }
             }
         }
         InputAction::Verify { data } => {
-            println!("Verifying document hash: {}", data.content_hash);
+            info!(content_hash = %data.content_hash, "Verifying document hash");
 
             // Create use case
-            let verify_usecase = VerifyUseCase::new(get_repository());
+            let verify_usecase = VerifyUseCase::new(repository).with_clock(&block_clock);
 
             // Execute verification
             match verify_usecase.execute(&data.content_hash) {
                 Ok(result) => {
-                    println!(
-                        "Verification result: {}",
-                        if result.exists { "found" } else { "not found" }
-                    );
+                    info!(exists = result.exists, "Verification result");
 
                     // Send report with result
                     let response = ReportResponse::from_verification(&result);
                     let report_json = serde_json::to_string(&response)?;
-                    send_report(client, server_addr, &report_json).await?;
+                    send_report(client, endpoints, &report_json).await?;
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Verification failed");
+                    Ok(AdvanceOutcome::reject_error(&e))
+                }
+            }
+        }
+        InputAction::FetchContent { data } => {
+            info!(document_id = %data.document_id, "Fetching document content");
+
+            report_content(client, endpoints, repository, &data.document_id, submitter).await?;
+
+            Ok(AdvanceOutcome::Accept)
+        }
+        InputAction::UpdateMetadata { data } => {
+            info!(content_hash = %data.content_hash, "Updating document metadata");
+
+            let update_usecase = UpdateMetadataUseCase::new(repository);
+
+            match update_usecase.execute(
+                &data.content_hash,
+                data.new_file_name.as_deref(),
+                data.new_mime_type.as_deref(),
+                data.metadata.as_ref(),
+                submitter,
+            ) {
+                Ok(change) => {
+                    info!(document_id = %change.document_id, "Document metadata updated");
+
+                    // Send notice recording the change
+                    let response = MetadataUpdateNotice::new(change);
+                    let notice_json = serde_json::to_string(&response)?;
+                    send_notice(client, endpoints, &notice_json).await?;
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Metadata update failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
+                }
+            }
+        }
+        InputAction::TransferOwnership { data } => {
+            info!(
+                content_hash = %data.content_hash,
+                new_owner = %data.new_owner,
+                "Transferring document ownership"
+            );
+
+            let transfer_usecase = TransferOwnershipUseCase::new(repository);
+
+            match transfer_usecase.execute(
+                &data.content_hash,
+                &data.new_owner,
+                submitter,
+                block_number,
+                timestamp,
+            ) {
+                Ok(transfer) => {
+                    info!(document_id = %transfer.document_id, "Document ownership transferred");
+
+                    // Send notice recording old and new owner
+                    let response = OwnershipTransferNotice::new(transfer);
+                    let notice_json = serde_json::to_string(&response)?;
+                    send_notice(client, endpoints, &notice_json).await?;
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Ownership transfer failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
+                }
+            }
+        }
+        InputAction::Purge { data } => {
+            info!(content_hash = %data.content_hash, "Purging document");
 
-                    Ok("accept")
+            let purge_usecase = PurgeUseCase::new(repository, config.admin_address.as_deref());
+
+            match purge_usecase.execute(&data.content_hash, submitter, block_number, timestamp) {
+                Ok(purge) => {
+                    warn!(content_hash = %purge.content_hash, "Document purged");
+
+                    // Send notice so the erasure itself stays auditable
+                    let response = PurgeNotice::new(purge);
+                    let notice_json = serde_json::to_string(&response)?;
+                    send_notice(client, endpoints, &notice_json).await?;
+
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Purge failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
+                }
+            }
+        }
+        InputAction::Rehash { data } => {
+            info!(after = ?data.after, "Rehashing documents");
+
+            let rehash_usecase = RehashUseCase::new(
+                repository,
+                config.admin_address.as_deref(),
+                NotarizeConfig::from(config),
+            );
+
+            match rehash_usecase.execute(submitter, data.after.as_deref(), data.limit) {
+                Ok(progress) => {
+                    info!(
+                        rehashed = progress.rehashed,
+                        skipped = progress.skipped.len(),
+                        "Rehash batch finished"
+                    );
+                    send_report(client, endpoints, &serde_json::to_string(&progress)?).await?;
+                    Ok(AdvanceOutcome::Accept)
+                }
+                Err(e) => {
+                    error!(error = %e, "Rehash failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
+                }
+            }
+        }
+        InputAction::Commit { data } => {
+            info!(content_hash = %data.content_hash, "Committing document hash");
+
+            let commit_usecase = CommitUseCase::new(repository);
+
+            match commit_usecase.execute(&data.content_hash, submitter, block_number, timestamp) {
+                Ok(commitment) => {
+                    info!(
+                        block_number = commitment.block_number,
+                        "Document hash committed"
+                    );
+
+                    // Send notice so the commitment can be shown on-chain before the reveal
+                    let response = CommitmentNotice::new(commitment);
+                    let notice_json = serde_json::to_string(&response)?;
+                    send_notice(client, endpoints, &notice_json).await?;
+
+                    Ok(AdvanceOutcome::Accept)
                 }
                 Err(e) => {
-                    eprintln!("Verification failed: {}", e);
-                    let error_msg = format!("{{\"error\":\"{}\"}}", e);
-                    send_report(client, server_addr, &error_msg).await?;
-                    Ok("reject")
+                    error!(error = %e, "Commit failed");
+                    Ok(AdvanceOutcome::reject_error(&*e))
                 }
             }
         }
+        // Every other action is a read-only query, answered as it would be over inspect
+        query => {
+            if !dispatch_query(client, endpoints, repository, config, &block_clock, &query).await? {
+                return Err(format!("No handler for action {}", query.name()).into());
+            }
+
+            Ok(AdvanceOutcome::Accept)
+        }
     }
 }
 
 pub async fn handle_inspect(
     client: &hyper::Client<hyper::client::HttpConnector>,
     server_addr: &str,
+    repository: &dyn DocumentRepository,
+    config: &Config,
+    request: JsonValue,
+) -> Result<&'static str, Box<dyn Error>> {
+    let endpoints = Endpoints::new(server_addr, &config.rollup_path_prefix)?;
+    let span = info_span!("inspect", action = tracing::field::Empty);
+
+    process_inspect(client, &endpoints, repository, config, request)
+        .instrument(span)
+        .await
+}
+
+async fn process_inspect(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    repository: &dyn DocumentRepository,
+    config: &Config,
     request: JsonValue,
-) -> Result<&'static str, Box<dyn std::error::Error>> {
-    println!("Received inspect request");
+) -> Result<&'static str, Box<dyn Error>> {
+    info!("Received inspect request");
 
     // Extract hex-encoded payload
     let payload_hex = request["data"]["payload"]
@@ -159,46 +1985,154 @@ pub async fn handle_inspect(
 
     // Decode from hex to bytes
     let payload_bytes = hex::decode(payload_hex)?;
-    let payload_str = std::str::from_utf8(&payload_bytes)?;
 
-    println!("Decoded payload: {}", payload_str);
+    if payload_bytes.len() > config.max_payload_bytes() {
+        warn!(size = payload_bytes.len(), "Payload exceeds maximum size");
+        let error_msg = error_report(
+            ErrorCode::PayloadTooLarge,
+            &format!(
+                "Payload exceeds maximum size of {} KB",
+                config.max_payload_kb
+            ),
+        );
+        send_report(client, endpoints, &error_msg).await?;
+        return Ok("accept"); // Inspect always accepts, errors go in reports
+    }
+
+    // The bytes come from the client, so undecodable ones get an error report, not an exit
+    let payload_str = match std::str::from_utf8(&payload_bytes) {
+        Ok(payload_str) => payload_str,
+        Err(e) => {
+            let e = PayloadError::InvalidUtf8(e);
+            warn!(error = %e, "Undecodable payload");
+            let error_msg = error_report(ErrorCode::InvalidPayload, &e.to_string());
+            send_report(client, endpoints, &error_msg).await?;
+            return Ok("accept");
+        }
+    };
+
+    debug!(payload = %payload_str, "Decoded payload");
+
+    // Payloads that are not JSON objects are left to the parsers below
+    if let Err(e @ ProtocolVersionError::Unsupported(_)) =
+        InputAction::protocol_version(payload_str)
+    {
+        warn!(error = %e, "Rejected payload protocol version");
+        send_report(client, endpoints, &coded_error(&e)).await?;
+        return Ok("accept");
+    }
+
+    // Action-tagged queries take precedence over the plain VerifyRequest format
+    if let Ok(input) = serde_json::from_str::<InputAction>(payload_str) {
+        tracing::Span::current().record("action", input.name());
+
+        if dispatch_query(client, endpoints, repository, config, &SystemClock, &input).await? {
+            return Ok("accept");
+        }
+
+        if let InputAction::FetchContent { data } = &input {
+            warn!(document_id = %data.document_id, "Content fetch needs an authenticated sender");
+
+            // Inspect requests carry no sender, so ownership cannot be checked here
+            let report_json = coded_error(&FetchContentError::SenderRequired);
+            send_report(client, endpoints, &report_json).await?;
+
+            return Ok("accept");
+        }
+    }
+
+    if let Some(action) = InputAction::unknown_action(payload_str) {
+        report_unknown_action(client, endpoints, &action).await?;
+        return Ok("accept");
+    }
 
     // Parse verify request
     let verify_req: crate::application::VerifyRequest = match serde_json::from_str(payload_str) {
         Ok(req) => req,
         Err(e) => {
-            eprintln!("Failed to parse verify request: {}", e);
-            let error_msg = format!("{{\"error\":\"Invalid request format: {}\"}}", e);
-            send_report(client, server_addr, &error_msg).await?;
+            warn!(error = %e, "Failed to parse verify request");
+            let error_msg = error_report(
+                ErrorCode::InvalidPayload,
+                &format!("Invalid request format: {}", e),
+            );
+            send_report(client, endpoints, &error_msg).await?;
             return Ok("accept"); // Inspect always accepts, errors go in reports
         }
     };
 
-    println!("Verifying hash: {}", verify_req.content_hash);
+    tracing::Span::current().record("action", "verify");
+    info!(content_hash = %verify_req.content_hash, "Verifying hash");
+
+    if let Some(report) = ValidationReport::new(verify_req.validate()) {
+        warn!(
+            errors = report.errors.len(),
+            "Rejected invalid request fields"
+        );
+        send_report(client, endpoints, &serde_json::to_string(&report)?).await?;
+        return Ok("accept"); // Inspect always accepts, errors go in reports
+    }
 
     // Create use case
-    let verify_usecase = VerifyUseCase::new(get_repository());
+    let verify_usecase = VerifyUseCase::new(repository);
 
     // Execute verification
     match verify_usecase.execute(&verify_req.content_hash) {
         Ok(result) => {
-            println!(
-                "Verification result: {}",
-                if result.exists { "found" } else { "not found" }
-            );
+            info!(exists = result.exists, "Verification result");
 
             // Send report with result
             let response = ReportResponse::from_verification(&result);
             let report_json = serde_json::to_string(&response)?;
-            send_report(client, server_addr, &report_json).await?;
+            send_report(client, endpoints, &report_json).await?;
 
             Ok("accept")
         }
         Err(e) => {
-            eprintln!("Verification failed: {}", e);
-            let error_msg = format!("{{\"error\":\"{}\"}}", e);
-            send_report(client, server_addr, &error_msg).await?;
+            error!(error = %e, "Verification failed");
+            let error_msg = coded_error(&e);
+            send_report(client, endpoints, &error_msg).await?;
             Ok("accept") // Inspect always accepts
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base64_content_accepts_all_alphabets() {
+        let expected = vec![0xfb, 0xff, 0xbf, 0x3e];
+
+        for encoded in ["+/+/Pg==", "+/+/Pg", "-_-_Pg==", "-_-_Pg"] {
+            assert_eq!(decode_base64_content(encoded).unwrap(), expected);
+        }
+
+        assert!(decode_base64_content("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_parse_advance_payload() {
+        let payload = hex::encode(r#"{"action":"verify","data":{"content_hash":"abc"}}"#);
+        assert_eq!(parse_advance_payload(&payload).unwrap().name(), "verify");
+
+        assert!(matches!(
+            parse_advance_payload("not hex"),
+            Err(PayloadError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            parse_advance_payload("ff00"),
+            Err(PayloadError::InvalidUtf8(_))
+        ));
+        assert!(matches!(
+            parse_advance_payload(&hex::encode("{")),
+            Err(PayloadError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse_advance_payload(&hex::encode(r#"{"action":"health","version":"1"}"#)),
+            Err(PayloadError::ProtocolVersion(
+                ProtocolVersionError::Invalid(_)
+            ))
+        ));
+    }
+}
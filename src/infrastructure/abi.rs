@@ -0,0 +1,73 @@
+use crate::domain::{EthAddress, NotarizationReceipt};
+use sha3::{Digest, Keccak256};
+use std::error::Error;
+
+/// Function called on a notarization's `callback_address` through a voucher
+pub const NOTARIZED_CALLBACK_SIGNATURE: &str = "onNotarized(bytes32,address,uint256,uint256)";
+
+/// First four bytes of the Keccak-256 hash of a Solidity function signature
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Calldata for `onNotarized(contentHash, submitter, notarizedAt, blockNumber)`
+pub fn encode_notarized_callback(
+    receipt: &NotarizationReceipt,
+    submitter: &EthAddress,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let content_hash: [u8; 32] = hex::decode(&receipt.content_hash)?
+        .try_into()
+        .map_err(|_| "Content hash must be 32 bytes")?;
+    let notarized_at = u64::try_from(receipt.notarized_at)?;
+
+    let mut calldata = function_selector(NOTARIZED_CALLBACK_SIGNATURE).to_vec();
+    calldata.extend_from_slice(&content_hash);
+    calldata.extend_from_slice(&encode_address(submitter)?);
+    calldata.extend_from_slice(&encode_uint(notarized_at));
+    calldata.extend_from_slice(&encode_uint(receipt.block_number));
+    Ok(calldata)
+}
+
+/// Addresses are left-padded to a 32-byte word
+fn encode_address(address: &EthAddress) -> Result<[u8; 32], Box<dyn Error>> {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&hex::decode(&address.as_str()[2..])?);
+    Ok(word)
+}
+
+/// Unsigned integers are big-endian, left-padded to a 32-byte word
+fn encode_uint(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_selector_matches_known_value() {
+        // transfer(address,uint256) is the ERC-20 transfer selector
+        assert_eq!(
+            function_selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+
+    #[test]
+    fn test_callback_is_selector_plus_four_words() {
+        let receipt =
+            NotarizationReceipt::new("id".to_string(), "ab".repeat(32), 1_700_000_000, 42);
+        let submitter = EthAddress::parse("0x00000000000000000000000000000000000000a1").unwrap();
+
+        let calldata = encode_notarized_callback(&receipt, &submitter).unwrap();
+
+        assert_eq!(calldata.len(), 4 + 4 * 32);
+        assert_eq!(&calldata[4..36], &[0xab; 32]);
+        assert_eq!(calldata[67], 0xa1);
+        assert_eq!(&calldata[68..100], &encode_uint(1_700_000_000));
+        assert_eq!(calldata[131], 42);
+    }
+}
@@ -1,5 +1,112 @@
-use hyper::{Body, Client, Method, Request};
+use hyper::{Body, Client, Method, Request, Uri};
+use serde::{Deserialize, Serialize};
 use std::error::Error;
+use tracing::debug;
+
+/// Full URLs of the rollup HTTP server endpoints
+///
+/// Built from the server base URL plus an optional path prefix, so
+/// `http://host:5004` with prefix `/rollup` sends notices to `http://host:5004/rollup/notice`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    notice: String,
+    voucher: String,
+    report: String,
+    finish: String,
+    gio: String,
+    exception: String,
+}
+
+impl Endpoints {
+    /// Build and validate the endpoint URLs; an empty prefix keeps the default paths
+    pub fn new(base_url: &str, path_prefix: &str) -> Result<Self, Box<dyn Error>> {
+        let base_url = base_url.trim_end_matches('/');
+        let prefix = path_prefix.trim().trim_matches('/');
+        let root = if prefix.is_empty() {
+            base_url.to_string()
+        } else {
+            format!("{}/{}", base_url, prefix)
+        };
+
+        let endpoints = Self {
+            notice: format!("{}/notice", root),
+            voucher: format!("{}/voucher", root),
+            report: format!("{}/report", root),
+            finish: format!("{}/finish", root),
+            gio: format!("{}/gio", root),
+            exception: format!("{}/exception", root),
+        };
+
+        for url in [
+            &endpoints.notice,
+            &endpoints.voucher,
+            &endpoints.report,
+            &endpoints.finish,
+            &endpoints.gio,
+            &endpoints.exception,
+        ] {
+            let uri: Uri = url
+                .parse()
+                .map_err(|e| format!("Invalid rollup endpoint URL {}: {}", url, e))?;
+            if uri.scheme().is_none() || uri.authority().is_none() {
+                return Err(format!(
+                    "Invalid rollup endpoint URL {}: missing scheme or host",
+                    url
+                )
+                .into());
+            }
+        }
+
+        Ok(endpoints)
+    }
+
+    pub fn notice_url(&self) -> &str {
+        &self.notice
+    }
+
+    pub fn voucher_url(&self) -> &str {
+        &self.voucher
+    }
+
+    pub fn report_url(&self) -> &str {
+        &self.report
+    }
+
+    pub fn finish_url(&self) -> &str {
+        &self.finish
+    }
+
+    pub fn gio_url(&self) -> &str {
+        &self.gio
+    }
+
+    pub fn exception_url(&self) -> &str {
+        &self.exception
+    }
+}
+
+/// Response to a generic I/O request, as returned by the rollup server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GioResponse {
+    /// Status code set by the handler of the GIO domain
+    pub code: u16,
+    /// Hex-encoded (`0x`-prefixed) response data
+    pub data: String,
+}
+
+impl GioResponse {
+    /// Domain handlers follow HTTP conventions: 2xx means success
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+}
+
+/// Rollup server acknowledgement of an emitted voucher
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoucherResponse {
+    /// Position of the voucher among the outputs of the current input
+    pub index: u64,
+}
 
 /// Send a notice to the Cartesi Rollup HTTP server
 ///
@@ -8,14 +115,14 @@ use std::error::Error;
 ///
 /// # Arguments
 /// * `client` - Hyper HTTP client
-/// * `server_url` - Base URL of the rollup server (e.g., "http://127.0.0.1:5004")
+/// * `endpoints` - Rollup server endpoint URLs
 /// * `payload` - JSON string to send (will be hex-encoded)
 pub async fn send_notice(
     client: &Client<hyper::client::HttpConnector>,
-    server_url: &str,
-    payload: &str,
+    endpoints: &Endpoints,
+    payload: impl AsRef<[u8]>,
 ) -> Result<(), Box<dyn Error>> {
-    // Hex-encode the JSON payload
+    // Hex-encode the JSON or binary payload
     let payload_hex = hex::encode(payload);
 
     // Build request body
@@ -23,10 +130,10 @@ pub async fn send_notice(
         "payload" => payload_hex
     };
 
-    // Send POST request to /notice endpoint
+    // Send POST request to the notice endpoint
     let request = Request::builder()
         .method(Method::POST)
-        .uri(format!("{}/notice", server_url))
+        .uri(endpoints.notice_url())
         .header("content-type", "application/json")
         .body(Body::from(body_json.dump()))?;
 
@@ -37,10 +144,56 @@ pub async fn send_notice(
         return Err(format!("Failed to send notice: HTTP {}", response.status()).into());
     }
 
-    println!("Notice sent successfully");
+    debug!("Notice sent successfully");
     Ok(())
 }
 
+/// Send a voucher to the Cartesi Rollup HTTP server
+///
+/// Vouchers are outputs that anyone can execute on the base layer once the
+/// epoch is finalized, calling `destination` with the given calldata.
+///
+/// # Arguments
+/// * `client` - Hyper HTTP client
+/// * `endpoints` - Rollup server endpoint URLs
+/// * `destination` - Address of the L1 contract to call
+/// * `value` - Wei sent with the call
+/// * `payload` - Hex-encoded (`0x`-prefixed) ABI calldata
+pub async fn send_voucher(
+    client: &Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    destination: &str,
+    value: u64,
+    payload: &str,
+) -> Result<VoucherResponse, Box<dyn Error>> {
+    // Value is a uint256, sent as 32 big-endian bytes
+    let body_json = json::object! {
+        "destination" => destination,
+        "value" => format!("0x{:064x}", value),
+        "payload" => payload
+    };
+
+    // Send POST request to the voucher endpoint
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(endpoints.voucher_url())
+        .header("content-type", "application/json")
+        .body(Body::from(body_json.dump()))?;
+
+    let response = client.request(request).await?;
+
+    // Check for success
+    if !response.status().is_success() {
+        return Err(format!("Failed to send voucher: HTTP {}", response.status()).into());
+    }
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let voucher_response: VoucherResponse = serde_json::from_slice(&body)?;
+
+    debug!(index = voucher_response.index, "Voucher sent successfully");
+    Ok(voucher_response)
+}
+
 /// Send a report to the Cartesi Rollup HTTP server
 ///
 /// Reports are non-verifiable outputs used for logging and query results.
@@ -48,11 +201,11 @@ pub async fn send_notice(
 ///
 /// # Arguments
 /// * `client` - Hyper HTTP client
-/// * `server_url` - Base URL of the rollup server
+/// * `endpoints` - Rollup server endpoint URLs
 /// * `payload` - JSON string to send (will be hex-encoded)
 pub async fn send_report(
     client: &Client<hyper::client::HttpConnector>,
-    server_url: &str,
+    endpoints: &Endpoints,
     payload: &str,
 ) -> Result<(), Box<dyn Error>> {
     // Hex-encode the JSON payload
@@ -63,10 +216,10 @@ pub async fn send_report(
         "payload" => payload_hex
     };
 
-    // Send POST request to /report endpoint
+    // Send POST request to the report endpoint
     let request = Request::builder()
         .method(Method::POST)
-        .uri(format!("{}/report", server_url))
+        .uri(endpoints.report_url())
         .header("content-type", "application/json")
         .body(Body::from(body_json.dump()))?;
 
@@ -77,12 +230,129 @@ pub async fn send_report(
         return Err(format!("Failed to send report: HTTP {}", response.status()).into());
     }
 
-    println!("Report sent successfully");
+    debug!("Report sent successfully");
+    Ok(())
+}
+
+/// Register an exception with the Cartesi Rollup HTTP server
+///
+/// Exceptions signal a fatal condition: the node stops processing inputs
+/// for the application and the operator must intervene. Use them only for
+/// system failures, never for inputs that are merely invalid.
+///
+/// # Arguments
+/// * `client` - Hyper HTTP client
+/// * `endpoints` - Rollup server endpoint URLs
+/// * `payload` - JSON string describing the failure (will be hex-encoded)
+pub async fn throw_exception(
+    client: &Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    payload: &str,
+) -> Result<(), Box<dyn Error>> {
+    // Hex-encode the JSON payload
+    let payload_hex = hex::encode(payload);
+
+    // Build request body
+    let body_json = json::object! {
+        "payload" => payload_hex
+    };
+
+    // Send POST request to the exception endpoint
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(endpoints.exception_url())
+        .header("content-type", "application/json")
+        .body(Body::from(body_json.dump()))?;
+
+    let response = client.request(request).await?;
+
+    // Check for success
+    if !response.status().is_success() {
+        return Err(format!("Failed to send exception: HTTP {}", response.status()).into());
+    }
+
+    debug!("Exception sent successfully");
     Ok(())
 }
 
+/// Issue a generic I/O (GIO) request to fetch external data
+///
+/// GIO lets the DApp ask the rollup node for data from outside the machine,
+/// such as an HTTP oracle. The request is routed by `domain`.
+///
+/// # Arguments
+/// * `client` - Hyper HTTP client
+/// * `endpoints` - Rollup server endpoint URLs
+/// * `domain` - GIO domain identifying the handler
+/// * `id_hex` - Hex-encoded (`0x`-prefixed) request id passed to the handler
+pub async fn send_gio(
+    client: &Client<hyper::client::HttpConnector>,
+    endpoints: &Endpoints,
+    domain: u16,
+    id_hex: &str,
+) -> Result<GioResponse, Box<dyn Error>> {
+    let body_json = json::object! {
+        "domain" => domain,
+        "id" => id_hex
+    };
+
+    // Send POST request to the gio endpoint
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(endpoints.gio_url())
+        .header("content-type", "application/json")
+        .body(Body::from(body_json.dump()))?;
+
+    let response = client.request(request).await?;
+
+    // Check for success
+    if !response.status().is_success() {
+        return Err(format!("Failed to send GIO request: HTTP {}", response.status()).into());
+    }
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let gio_response: GioResponse = serde_json::from_slice(&body)?;
+
+    debug!(domain, code = gio_response.code, "GIO response received");
+    Ok(gio_response)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_default_paths() {
+        let endpoints = Endpoints::new("http://127.0.0.1:5004", "").unwrap();
+        assert_eq!(endpoints.notice_url(), "http://127.0.0.1:5004/notice");
+        assert_eq!(endpoints.voucher_url(), "http://127.0.0.1:5004/voucher");
+        assert_eq!(endpoints.report_url(), "http://127.0.0.1:5004/report");
+        assert_eq!(endpoints.finish_url(), "http://127.0.0.1:5004/finish");
+        assert_eq!(endpoints.gio_url(), "http://127.0.0.1:5004/gio");
+        assert_eq!(endpoints.exception_url(), "http://127.0.0.1:5004/exception");
+    }
+
+    #[test]
+    fn test_endpoints_with_prefix() {
+        for prefix in ["/rollup", "rollup", "/rollup/"] {
+            let endpoints = Endpoints::new("http://localhost:8080/", prefix).unwrap();
+            assert_eq!(
+                endpoints.notice_url(),
+                "http://localhost:8080/rollup/notice"
+            );
+            assert_eq!(
+                endpoints.finish_url(),
+                "http://localhost:8080/rollup/finish"
+            );
+        }
+    }
+
+    #[test]
+    fn test_endpoints_reject_malformed_urls() {
+        assert!(Endpoints::new("127.0.0.1:5004", "").is_err());
+        assert!(Endpoints::new("http://localhost", "/bad prefix").is_err());
+    }
+
     #[test]
     fn test_hex_encoding() {
         let json_payload = r#"{"test":"data"}"#;
@@ -1,6 +1,15 @@
-use crate::domain::Document;
-use rusqlite::{params, Connection, OptionalExtension};
-use std::error::Error;
+use super::metrics;
+use crate::domain::{
+    empty_metadata, merkle_proof, merkle_root, AuditEntry, AuditOutcome, Commitment, ContentHash,
+    Cosigner, Document, DocumentId, EpochRoot, MerkleProof, OwnershipTransfer, PendingNotarization,
+    UploadSession,
+};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::{FromSql, FromSqlResult, ToSqlOutput, Type, ValueRef};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use std::collections::HashMap;
+use std::ops::Deref;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,38 +17,534 @@ pub enum DatabaseError {
     #[error("Database error: {0}")]
     SqliteError(#[from] rusqlite::Error),
 
+    #[error("Database error: {0}")]
+    PostgresError(#[from] postgres::Error),
+
     #[error("Document not found")]
     NotFound,
 
     #[error("Duplicate document hash")]
     DuplicateHash,
+
+    #[error("Duplicate upload id")]
+    DuplicateUpload,
+
+    #[error("Value out of range: {0}")]
+    OutOfRange(#[from] std::num::TryFromIntError),
+
+    #[error("Balance overflow")]
+    BalanceOverflow,
+
+    #[error("Connection pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+
+    #[error("Database is corrupt: {0}")]
+    CorruptDatabase(String),
+}
+
+impl DatabaseError {
+    /// `CorruptDatabase` for SQLite's malformed image failures; other errors are unchanged
+    fn flag_corruption(self) -> Self {
+        match self {
+            DatabaseError::SqliteError(rusqlite::Error::SqliteFailure(err, message))
+                if err.code == rusqlite::ErrorCode::DatabaseCorrupt =>
+            {
+                DatabaseError::CorruptDatabase(message.unwrap_or_else(|| err.to_string()))
+            }
+            e => e,
+        }
+    }
+}
+
+/// Typed identifiers are stored as plain TEXT columns
+macro_rules! text_column {
+    ($name:ty) => {
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                String::column_result(value).map(<$name>::from)
+            }
+        }
+    };
 }
 
+text_column!(DocumentId);
+text_column!(ContentHash);
+
+/// Columns selected for every document query, in `row_to_document` order
+pub(super) const DOCUMENT_COLUMNS: &str =
+    "id, content_hash, file_name, mime_type, submitted_by, created_at, expires_at, supersedes, detected_mime_type, ipfs_cid, content_hash_keccak, blake2b_hash, description, epoch_index, input_index, metadata, wallet_signature, signed_message";
+
+/// Columns of `audit_log` in the order `row_to_audit_entry` reads them
+pub(super) const AUDIT_COLUMNS: &str = "id, action, submitter, block_number, timestamp, outcome, content_hash";
+
 pub trait DocumentRepository {
-    fn save_document(&self, doc: &Document) -> Result<(), Box<dyn Error>>;
-    fn find_by_hash(&self, hash: &str) -> Result<Document, Box<dyn Error>>;
-    fn find_by_id(&self, id: &str) -> Result<Document, Box<dyn Error>>;
-    fn count_documents(&self) -> Result<usize, Box<dyn Error>>;
+    fn save_document(&self, doc: &Document) -> Result<(), DatabaseError>;
+
+    /// Store several documents in one transaction
+    /// Either every document is saved or, if any insert fails, none are
+    fn save_documents_atomic(&self, docs: &[&Document]) -> Result<(), DatabaseError>;
+
+    /// Store a document together with its raw content in one transaction
+    fn save_document_with_content(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(), DatabaseError>;
+
+    /// Raw content retained for a document id
+    /// Fails with `NotFound` when the document was stored as hash only
+    fn get_content(&self, id: &DocumentId) -> Result<Vec<u8>, DatabaseError>;
+    fn find_by_hash(&self, hash: &ContentHash) -> Result<Document, DatabaseError>;
+    fn find_by_id(&self, id: &DocumentId) -> Result<Document, DatabaseError>;
+
+    /// Document notarized by input `input` of epoch `epoch`
+    /// An input that notarized several documents yields the first one saved
+    fn find_by_input(&self, epoch: u64, input: u64) -> Result<Document, DatabaseError>;
+
+    /// Look up a document by the IPFS CID recorded when it was notarized
+    fn find_by_cid(&self, cid: &str) -> Result<Document, DatabaseError>;
+
+    /// Look up a document by the hex Keccak-256 of its content
+    fn find_by_keccak(&self, hash: &str) -> Result<Document, DatabaseError>;
+
+    /// Look up a document by the hex Blake2b-256 of its content
+    fn find_by_blake2b_hash(&self, hash: &str) -> Result<Document, DatabaseError>;
+
+    /// Look up several hashes in one query
+    /// Results follow the input order, with `None` for hashes that are not stored
+    fn find_many_by_hashes(
+        &self,
+        hashes: &[&ContentHash],
+    ) -> Result<Vec<Option<Document>>, DatabaseError>;
+    fn count_documents(&self) -> Result<usize, DatabaseError>;
+
+    /// Schema revision recorded in the database, see `SCHEMA_VERSION`
+    fn schema_version(&self) -> Result<u32, DatabaseError>;
+
+    /// Documents whose filename, MIME type or tags contain every word of `query`
+    /// Words match as prefixes; best matches first, at most `limit` results
+    fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Attach tags to a document; tags it already has are ignored
+    fn add_tags(&self, document_id: &DocumentId, tags: &[&str]) -> Result<(), DatabaseError>;
+
+    /// Tags attached to a document, in alphabetical order
+    fn find_tags(&self, document_id: &DocumentId) -> Result<Vec<String>, DatabaseError>;
+
+    /// Record `file_name` as another name for a document's content
+    /// A name the document already has as an alias is ignored
+    fn add_alias(
+        &self,
+        document_id: &DocumentId,
+        file_name: &str,
+        submitted_by: &str,
+        created_at: i64,
+    ) -> Result<(), DatabaseError>;
+
+    /// Every filename recorded for `hash`: the document's own first, then its aliases
+    /// in the order they were added; empty when the content is not notarized
+    fn find_all_by_content_hash(&self, hash: &ContentHash) -> Result<Vec<String>, DatabaseError>;
+
+    /// Documents carrying `tag`, newest first
+    fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Documents created between `start` and `end` inclusive (Unix timestamps), oldest first
+    fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Documents whose `expires_at` is at or before `as_of`, earliest expiry first
+    fn find_expired(
+        &self,
+        as_of: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Documents newest first, ordered by `(created_at, id)` descending
+    /// With `after`, only documents strictly before that `(created_at, id)` key are returned,
+    /// so pages stay stable while new documents are inserted
+    fn list_documents(
+        &self,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Documents owned by `submitter` (case-insensitive), in the order of `list_documents`
+    /// and resuming strictly after `after` in the same way
+    fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Every document oldest first, ordered by `(created_at, id)`, skipping the first `offset`
+    fn list_all(&self, limit: usize, offset: usize) -> Result<Vec<Document>, DatabaseError>;
+
+    /// The version chain starting at `root_hash`: the root itself, then each document
+    /// superseding the previous one, oldest first. Empty if `root_hash` is unknown
+    fn find_versions(&self, root_hash: &ContentHash) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Number of documents currently owned by `submitter` (case-insensitive)
+    fn count_by_submitter(&self, submitter: &str) -> Result<usize, DatabaseError>;
+
+    /// Documents created within `[start, end]` per UTC day, oldest day first
+    /// Each day is keyed by the Unix timestamp of its midnight; days without documents are omitted
+    fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, DatabaseError>;
+
+    /// Every submitter with its document count, largest count first
+    /// Addresses are returned in lowercase
+    fn list_submitters(&self) -> Result<Vec<(String, usize)>, DatabaseError>;
+
+    /// The `n` submitters with the most documents, largest count first
+    /// Addresses are returned in lowercase
+    fn count_by_submitter_top_n(&self, n: usize) -> Result<Vec<(String, usize)>, DatabaseError>;
+
+    /// Number of documents created at or after `ts` (Unix timestamp)
+    fn count_documents_since(&self, ts: i64) -> Result<usize, DatabaseError>;
+
+    /// Change the filename, MIME type or metadata object of a document; `None` leaves a
+    /// field unchanged, and `metadata` replaces the stored object as a whole
+    /// The content hash and id are never modified
+    fn update_metadata(
+        &self,
+        content_hash: &ContentHash,
+        file_name: Option<&str>,
+        mime_type: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Document, DatabaseError>;
+
+    /// Documents lacking their Keccak-256 digest or, when asked for, their Blake2b-256 digest
+    /// or IPFS CID; ordered by id and resuming strictly after `after`
+    fn find_missing_digests(
+        &self,
+        blake2b: bool,
+        ipfs_cid: bool,
+        after: Option<&DocumentId>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError>;
+
+    /// Record digests computed after a document was notarized; `None` leaves one unchanged
+    fn update_digests(
+        &self,
+        id: &DocumentId,
+        keccak: Option<&str>,
+        blake2b: Option<&str>,
+        ipfs_cid: Option<&str>,
+    ) -> Result<(), DatabaseError>;
+
+    /// Move a document to a new owner and append the change to its ownership history
+    fn transfer_ownership(&self, transfer: &OwnershipTransfer) -> Result<(), DatabaseError>;
+
+    /// Prior ownership changes for a document, oldest first
+    fn find_ownership_history(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Vec<OwnershipTransfer>, DatabaseError>;
+
+    /// Append an entry to the audit log and return its id
+    /// The log is append-only: the database refuses updates and deletes of its rows
+    fn append_audit_entry(&self, entry: &AuditEntry) -> Result<i64, DatabaseError>;
+
+    /// Audit log entries with an id above `after_id`, oldest first
+    /// `submitter` matches case-insensitively; the block bounds are inclusive
+    fn find_audit_entries(
+        &self,
+        submitter: Option<&str>,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        after_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError>;
+
+    /// One page of the whole audit log, oldest first, skipping the first `offset` entries
+    fn list_audit_entries(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError>;
+
+    /// Permanently remove a document, its retained content, tags and ownership history
+    fn delete_by_hash(&self, hash: &ContentHash) -> Result<(), DatabaseError>;
+
+    /// Open the staging area of a chunked upload; fails with `DuplicateUpload` if the id is taken
+    fn start_upload(&self, session: &UploadSession) -> Result<(), DatabaseError>;
+
+    /// An open chunked upload with its received chunk count and size
+    fn find_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError>;
+
+    /// Stage chunk `index` of an upload
+    fn append_chunk(&self, upload_id: &str, index: u32, data: &[u8]) -> Result<(), DatabaseError>;
+
+    /// Staged chunks of an upload concatenated in index order
+    fn upload_content(&self, upload_id: &str) -> Result<Vec<u8>, DatabaseError>;
+
+    /// Drop an upload and its staged chunks
+    fn delete_upload(&self, upload_id: &str) -> Result<(), DatabaseError>;
+
+    /// Hold a notarization until `cosigners` approve it; fails with `DuplicateHash` if the
+    /// same content is already pending
+    fn save_pending_notarization(
+        &self,
+        pending: &PendingNotarization,
+        cosigners: &[&str],
+    ) -> Result<(), DatabaseError>;
+
+    /// A pending notarization by content hash
+    fn find_pending_notarization(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, DatabaseError>;
+
+    /// Required cosigners of a pending notarization, in address order
+    fn find_cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, DatabaseError>;
+
+    /// Record the approval of `cosigner`; returns `false` if it is not a required cosigner
+    /// or has already signed
+    fn record_cosignature(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        signed_at: i64,
+    ) -> Result<bool, DatabaseError>;
+
+    /// Drop a pending notarization and its cosigners
+    fn delete_pending_notarization(&self, content_hash: &str) -> Result<(), DatabaseError>;
+
+    /// Highest nonce accepted so far for a submitter, if any
+    /// Remember that `key` from `submitter` notarized `document_id` in `block_number`
+    fn save_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+        document_id: &DocumentId,
+        block_number: u64,
+    ) -> Result<(), DatabaseError>;
+
+    /// Document id and block number recorded for `key` from `submitter`, if any
+    fn find_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+    ) -> Result<Option<(DocumentId, u64)>, DatabaseError>;
+
+    /// Epoch that newly notarized documents currently belong to, once one has been seen
+    fn find_open_epoch(&self) -> Result<Option<u64>, DatabaseError>;
+
+    /// Start assigning newly notarized documents to `epoch`
+    fn open_epoch(&self, epoch: u64) -> Result<(), DatabaseError>;
+
+    /// Commit every document not yet in an epoch to `epoch` under a Merkle root, then open
+    /// `next_epoch`; returns `None` when there was nothing to commit
+    fn seal_epoch(&self, epoch: u64, next_epoch: u64) -> Result<Option<EpochRoot>, DatabaseError>;
+
+    fn find_epoch_root(&self, epoch: u64) -> Result<Option<EpochRoot>, DatabaseError>;
+
+    /// Proof that `content_hash` is committed to by the root of `epoch`
+    fn generate_inclusion_proof(
+        &self,
+        content_hash: &ContentHash,
+        epoch: u64,
+    ) -> Result<MerkleProof, DatabaseError>;
+
+    fn find_last_nonce(&self, submitter: &str) -> Result<Option<u64>, DatabaseError>;
+
+    /// Record `nonce` for a submitter only if it is strictly greater than the last one
+    /// Returns `false` when the nonce was not accepted
+    fn advance_nonce(&self, submitter: &str, nonce: u64) -> Result<bool, DatabaseError>;
+
+    /// Funds `owner` holds in `token`; zero when nothing was ever deposited
+    fn find_balance(&self, owner: &str, token: &str) -> Result<u128, DatabaseError>;
+
+    /// Add `amount` to a balance and return the new balance
+    fn credit_balance(&self, owner: &str, token: &str, amount: u128)
+        -> Result<u128, DatabaseError>;
+
+    /// Subtract `amount` from a balance only if it covers it
+    /// Returns `false` when the balance was insufficient
+    fn debit_balance(&self, owner: &str, token: &str, amount: u128) -> Result<bool, DatabaseError>;
+
+    /// Documents `submitter` notarized in `epoch`; zero when none were counted
+    fn find_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError>;
+
+    /// Count one more notarization by `submitter` in `epoch` and return the new usage
+    fn increment_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError>;
+
+    /// Record a content hash committed ahead of its document; fails with `DuplicateHash`
+    /// if the hash was already committed
+    fn save_commitment(&self, commitment: &Commitment) -> Result<(), DatabaseError>;
+
+    /// The commitment to a content hash, if one was made
+    fn find_commitment(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Option<Commitment>, DatabaseError>;
+}
+
+/// Repository running every query on a single SQLite connection
+/// `C` holds the connection: owned by the repository, or checked out of the pool of a
+/// `PooledSqliteRepository` for the duration of one call
+pub struct SqliteRepository<C = Box<Connection>> {
+    conn: C,
+}
+
+/// Default time a write waits for a competing lock before failing
+pub const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+
+/// Default page cache of each connection, in KiB
+pub const DEFAULT_CACHE_SIZE_KIB: u64 = 64_000;
+
+/// Default part of a database file mapped into memory, in bytes
+pub const DEFAULT_MMAP_SIZE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Connection settings applied when a database is opened
+/// A larger cache and mmap window speed up reads at the cost of resident memory:
+/// the cache is allocated per connection as pages are read, and mapped pages count
+/// towards the process's memory like the page cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteOptions {
+    /// How long a write waits for a competing lock, in milliseconds
+    pub busy_timeout_ms: u64,
+    /// Page cache size in KiB
+    pub cache_size_kib: u64,
+    /// Bytes of the database file accessed through memory-mapped I/O; 0 disables it
+    /// In-memory databases have no file and ignore this setting
+    pub mmap_size_bytes: u64,
 }
 
-pub struct SqliteRepository {
-    conn: Connection,
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: DEFAULT_BUSY_TIMEOUT_MS,
+            cache_size_kib: DEFAULT_CACHE_SIZE_KIB,
+            mmap_size_bytes: DEFAULT_MMAP_SIZE_BYTES,
+        }
+    }
 }
 
+/// Schema revision written to `PRAGMA user_version` once `init_schema` completes
+/// Bump whenever `init_schema` changes the tables, columns or indexes
+pub const SCHEMA_VERSION: u32 = 13;
+
 impl SqliteRepository {
-    pub fn new(path: &str) -> Result<Self, Box<dyn Error>> {
+    /// Open a file-backed database; writers wait up to `busy_timeout_ms` for a lock
+    /// Cache and mmap sizes keep their defaults
+    pub fn new(path: &str, busy_timeout_ms: u64) -> Result<Self, DatabaseError> {
+        Self::open(
+            path,
+            &SqliteOptions {
+                busy_timeout_ms,
+                ..SqliteOptions::default()
+            },
+        )
+    }
+
+    /// Open a file-backed database with explicit connection settings
+    pub fn open(path: &str, options: &SqliteOptions) -> Result<Self, DatabaseError> {
         let conn = Connection::open(path)?;
-        Self::init_schema(&conn)?;
-        Ok(Self { conn })
+        Self::apply_options(&conn, options)
+            .and_then(|()| Self::init_schema(&conn))
+            .map_err(DatabaseError::flag_corruption)?;
+        Ok(Self {
+            conn: Box::new(conn),
+        })
     }
 
-    pub fn new_in_memory() -> Result<Self, Box<dyn Error>> {
+    /// In-memory database with the default cache size; there is no file to map
+    pub fn new_in_memory() -> Result<Self, DatabaseError> {
         let conn = Connection::open_in_memory()?;
+        Self::configure_connection(&conn, DEFAULT_CACHE_SIZE_KIB)?;
         Self::init_schema(&conn)?;
-        Ok(Self { conn })
+        Ok(Self {
+            conn: Box::new(conn),
+        })
+    }
+
+    /// Run `PRAGMA integrity_check`, failing with `CorruptDatabase` and SQLite's findings
+    /// unless it reports `ok`
+    /// Damage severe enough that the check cannot run is reported the same way
+    /// A file whose header no longer matches its size fails in `open` already, also with
+    /// `CorruptDatabase`
+    pub fn integrity_check(&self) -> Result<(), DatabaseError> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let findings = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>());
+
+        match findings {
+            Ok(findings) if findings == ["ok"] => Ok(()),
+            Ok(findings) => Err(DatabaseError::CorruptDatabase(findings.join("; "))),
+            Err(e) => Err(DatabaseError::from(e).flag_corruption()),
+        }
+    }
+
+    /// Settings currently in effect on the connection, as reported by SQLite
+    pub fn applied_options(&self) -> Result<SqliteOptions, DatabaseError> {
+        // mmap_size returns no row for in-memory databases, which never map
+        let pragma = |name: &str| -> Result<i64, DatabaseError> {
+            Ok(self
+                .conn
+                .query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+                .optional()?
+                .unwrap_or(0))
+        };
+
+        // A negative cache_size is in KiB, which is how it is always set
+        Ok(SqliteOptions {
+            busy_timeout_ms: u64::try_from(pragma("busy_timeout")?)?,
+            cache_size_kib: u64::try_from(-pragma("cache_size")?)?,
+            mmap_size_bytes: u64::try_from(pragma("mmap_size")?)?,
+        })
+    }
+}
+
+impl<C> SqliteRepository<C> {
+    /// Tune a connection to a database file with `options`
+    fn apply_options(conn: &Connection, options: &SqliteOptions) -> Result<(), DatabaseError> {
+        Self::configure_connection(conn, options.cache_size_kib)?;
+        conn.pragma_update(None, "busy_timeout", options.busy_timeout_ms)?;
+        // mmap_size returns the resulting size as a row
+        conn.query_row(
+            &format!(
+                "PRAGMA mmap_size={}",
+                i64::try_from(options.mmap_size_bytes)?
+            ),
+            [],
+            |_| Ok(()),
+        )?;
+        Ok(())
     }
 
-    fn init_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    /// Connection tuning for write throughput and read caching
+    /// In-memory databases ignore WAL and keep their `memory` journal
+    fn configure_connection(conn: &Connection, cache_size_kib: u64) -> Result<(), DatabaseError> {
+        // journal_mode returns the resulting mode as a row
+        conn.query_row("PRAGMA journal_mode=WAL", [], |_| Ok(()))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        // Negative values are read by SQLite as KiB rather than pages
+        conn.pragma_update(None, "cache_size", -i64::try_from(cache_size_kib)?)?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        Ok(())
+    }
+
+    fn init_schema(conn: &Connection) -> Result<(), DatabaseError> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS documents (
                 id TEXT PRIMARY KEY,
@@ -47,108 +552,2310 @@ impl SqliteRepository {
                 file_name TEXT NOT NULL,
                 mime_type TEXT NOT NULL,
                 submitted_by TEXT NOT NULL,
-                created_at INTEGER NOT NULL
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER,
+                supersedes TEXT,
+                detected_mime_type TEXT,
+                ipfs_cid TEXT,
+                content_hash_keccak TEXT,
+                blake2b_hash TEXT,
+                description TEXT,
+                epoch_index INTEGER NOT NULL DEFAULT 0,
+                input_index INTEGER NOT NULL DEFAULT 0,
+                metadata TEXT,
+                wallet_signature TEXT,
+                signed_message TEXT
             )",
             [],
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_content_hash ON documents(content_hash)",
+            "CREATE TABLE IF NOT EXISTS ownership_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                previous_owner TEXT NOT NULL,
+                new_owner TEXT NOT NULL,
+                transferred_at INTEGER NOT NULL,
+                block_number INTEGER NOT NULL
+            )",
             [],
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_created_at ON documents(created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_ownership_content_hash ON ownership_history(content_hash)",
             [],
         )?;
 
-        Ok(())
-    }
+        // Original bytes, only written when content retention is enabled
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_content (
+                document_id TEXT PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                content BLOB NOT NULL
+            )",
+            [],
+        )?;
 
-    fn row_to_document(row: &rusqlite::Row) -> Result<Document, rusqlite::Error> {
-        Ok(Document {
-            id: row.get(0)?,
-            content_hash: row.get(1)?,
-            file_name: row.get(2)?,
-            mime_type: row.get(3)?,
-            submitted_by: row.get(4)?,
-            created_at: row.get(5)?,
-        })
-    }
-}
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_tags (
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (document_id, tag)
+            )",
+            [],
+        )?;
 
-impl DocumentRepository for SqliteRepository {
-    fn save_document(&self, doc: &Document) -> Result<(), Box<dyn Error>> {
-        match self.conn.execute(
-            "INSERT INTO documents (id, content_hash, file_name, mime_type, submitted_by, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                &doc.id,
-                &doc.content_hash,
-                &doc.file_name,
-                &doc.mime_type,
-                &doc.submitted_by,
-                &doc.created_at
-            ],
-        ) {
-            Ok(_) => Ok(()),
-            Err(rusqlite::Error::SqliteFailure(err, _)) => {
-                if err.code == rusqlite::ErrorCode::ConstraintViolation {
-                    Err(Box::new(DatabaseError::DuplicateHash))
-                } else {
-                    Err(Box::new(rusqlite::Error::SqliteFailure(err, None)))
-                }
-            }
-            Err(e) => Err(Box::new(e)),
-        }
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_document_tags_tag ON document_tags(tag)",
+            [],
+        )?;
 
-    fn find_by_hash(&self, hash: &str) -> Result<Document, Box<dyn Error>> {
-        let doc = self
-            .conn
-            .query_row(
-                "SELECT id, content_hash, file_name, mime_type, submitted_by, created_at
-                 FROM documents
-                 WHERE content_hash = ?1",
-                params![hash],
-                Self::row_to_document,
-            )
-            .optional()?;
+        // Further filenames the same content was notarized under
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_aliases (
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                file_name TEXT NOT NULL,
+                submitted_by TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (document_id, file_name)
+            )",
+            [],
+        )?;
 
-        doc.ok_or_else(|| Box::new(DatabaseError::NotFound) as Box<dyn Error>)
-    }
+        // Notarizations waiting for their cosigners
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pending_cosigns (
+                content_hash TEXT PRIMARY KEY,
+                submitted_by TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                content BLOB NOT NULL,
+                expires_at INTEGER,
+                expiration_blocks INTEGER,
+                tags TEXT NOT NULL,
+                supersedes TEXT,
+                description TEXT,
+                signature TEXT,
+                signed_message TEXT,
+                created_at INTEGER NOT NULL,
+                deadline INTEGER NOT NULL
+            )",
+            [],
+        )?;
 
-    fn find_by_id(&self, id: &str) -> Result<Document, Box<dyn Error>> {
-        let doc = self
-            .conn
-            .query_row(
-                "SELECT id, content_hash, file_name, mime_type, submitted_by, created_at
-                 FROM documents
-                 WHERE id = ?1",
-                params![id],
-                Self::row_to_document,
-            )
-            .optional()?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS document_cosigners (
+                content_hash TEXT NOT NULL REFERENCES pending_cosigns(content_hash) ON DELETE CASCADE,
+                cosigner TEXT NOT NULL,
+                signature TEXT,
+                signed_at INTEGER,
+                PRIMARY KEY (content_hash, cosigner)
+            )",
+            [],
+        )?;
 
-        doc.ok_or_else(|| Box::new(DatabaseError::NotFound) as Box<dyn Error>)
-    }
+        // Staging area for documents assembled from several inputs
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS upload_sessions (
+                upload_id TEXT PRIMARY KEY,
+                submitted_by TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                started_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
 
-    fn count_documents(&self) -> Result<usize, Box<dyn Error>> {
-        let count: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS upload_chunks (
+                upload_id TEXT NOT NULL REFERENCES upload_sessions(upload_id) ON DELETE CASCADE,
+                chunk_index INTEGER NOT NULL,
+                data BLOB NOT NULL,
+                PRIMARY KEY (upload_id, chunk_index)
+            )",
+            [],
+        )?;
 
-        Ok(count as usize)
-    }
-}
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS idempotency (
+                idempotency_key TEXT NOT NULL,
+                submitter TEXT NOT NULL,
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                block_number INTEGER NOT NULL,
+                PRIMARY KEY (idempotency_key, submitter)
+            )",
+            [],
+        )?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS epoch_roots (
+                epoch INTEGER PRIMARY KEY,
+                root TEXT NOT NULL,
+                leaf_count INTEGER NOT NULL
+            )",
+            [],
+        )?;
 
-    #[test]
-    fn test_create_in_memory_db() {
-        let repo = SqliteRepository::new_in_memory();
-        assert!(repo.is_ok());
+        // Leaves keep the document hashes of a sealed epoch, so proofs survive purges
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS epoch_leaves (
+                epoch INTEGER NOT NULL REFERENCES epoch_roots(epoch),
+                position INTEGER NOT NULL,
+                document_id TEXT NOT NULL UNIQUE,
+                content_hash TEXT NOT NULL,
+                PRIMARY KEY (epoch, position)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS epoch_state (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                open_epoch INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS nonces (
+                submitter TEXT PRIMARY KEY,
+                last_nonce INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // One row per advance input; never updated or deleted, see `init_audit_log`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                submitter TEXT NOT NULL,
+                block_number INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                outcome TEXT NOT NULL,
+                content_hash TEXT
+            )",
+            [],
+        )?;
+
+        // Amounts are decimal TEXT, since deposits can exceed an INTEGER column
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS balances (
+                owner TEXT NOT NULL,
+                token TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                PRIMARY KEY (owner, token)
+            )",
+            [],
+        )?;
+
+        // Rows of past epochs are never read again, so quotas reset with each epoch
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS quota (
+                submitter TEXT NOT NULL,
+                epoch_index INTEGER NOT NULL,
+                used INTEGER NOT NULL,
+                PRIMARY KEY (submitter, epoch_index)
+            )",
+            [],
+        )?;
+
+        // Hashes committed before their document is notarized, without any metadata
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS commitments (
+                content_hash TEXT PRIMARY KEY,
+                committed_by TEXT NOT NULL,
+                block_number INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Columns added after the initial schema, for databases created earlier
+        Self::ensure_column(conn, "documents", "expires_at", "INTEGER")?;
+        Self::ensure_column(conn, "documents", "supersedes", "TEXT")?;
+        Self::ensure_column(conn, "documents", "detected_mime_type", "TEXT")?;
+        Self::ensure_column(conn, "documents", "ipfs_cid", "TEXT")?;
+        Self::ensure_column(conn, "documents", "content_hash_keccak", "TEXT")?;
+        Self::ensure_column(conn, "documents", "blake2b_hash", "TEXT")?;
+        Self::ensure_column(conn, "documents", "description", "TEXT")?;
+        for column in ["epoch_index", "input_index"] {
+            Self::ensure_column(conn, "documents", column, "INTEGER NOT NULL DEFAULT 0")?;
+        }
+        Self::ensure_column(conn, "documents", "metadata", "TEXT")?;
+        for column in ["wallet_signature", "signed_message"] {
+            Self::ensure_column(conn, "documents", column, "TEXT")?;
+        }
+        for column in ["description", "signature", "signed_message"] {
+            Self::ensure_column(conn, "pending_cosigns", column, "TEXT")?;
+        }
+        Self::ensure_column(conn, "audit_log", "content_hash", "TEXT")?;
+
+        Self::init_search_index(conn)?;
+        Self::init_audit_log(conn)?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_content_hash ON documents(content_hash)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_created_at ON documents(created_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_expires_at ON documents(expires_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_supersedes ON documents(supersedes)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ipfs_cid ON documents(ipfs_cid)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_content_hash_keccak ON documents(content_hash_keccak)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_blake2b_hash ON documents(blake2b_hash)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_input ON documents(epoch_index, input_index)",
+            [],
+        )?;
+
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(())
+    }
+
+    /// Full-text index over filename, MIME type and tags, kept in sync by triggers
+    /// Rows share the rowid of their document; existing documents are indexed on creation,
+    /// and an index from before tags were searchable is rebuilt
+    fn init_search_index(conn: &Connection) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare("PRAGMA table_info(documents_fts)")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !columns.iter().any(|name| name == "tags") {
+            conn.execute_batch(
+                "DROP TABLE IF EXISTS documents_fts;
+                 CREATE VIRTUAL TABLE documents_fts
+                     USING fts5(id UNINDEXED, file_name, mime_type, tags);
+                 INSERT INTO documents_fts (rowid, id, file_name, mime_type, tags)
+                     SELECT rowid, id, file_name, mime_type,
+                            (SELECT group_concat(tag, ' ') FROM document_tags
+                                 WHERE document_id = documents.id)
+                     FROM documents;",
+            )?;
+        }
+
+        // Tags are attached after their document is inserted, so they update its row
+        conn.execute_batch(
+            "CREATE TRIGGER IF NOT EXISTS documents_fts_insert AFTER INSERT ON documents BEGIN
+                 INSERT INTO documents_fts (rowid, id, file_name, mime_type)
+                     VALUES (new.rowid, new.id, new.file_name, new.mime_type);
+             END;
+             CREATE TRIGGER IF NOT EXISTS documents_fts_update
+             AFTER UPDATE OF file_name, mime_type ON documents BEGIN
+                 UPDATE documents_fts SET file_name = new.file_name, mime_type = new.mime_type
+                     WHERE rowid = old.rowid;
+             END;
+             CREATE TRIGGER IF NOT EXISTS documents_fts_delete AFTER DELETE ON documents BEGIN
+                 DELETE FROM documents_fts WHERE rowid = old.rowid;
+             END;
+             CREATE TRIGGER IF NOT EXISTS documents_fts_tag_insert
+             AFTER INSERT ON document_tags BEGIN
+                 UPDATE documents_fts
+                     SET tags = (SELECT group_concat(tag, ' ') FROM document_tags
+                                     WHERE document_id = new.document_id)
+                     WHERE rowid = (SELECT rowid FROM documents WHERE id = new.document_id);
+             END;
+             CREATE TRIGGER IF NOT EXISTS documents_fts_tag_delete
+             AFTER DELETE ON document_tags BEGIN
+                 UPDATE documents_fts
+                     SET tags = (SELECT group_concat(tag, ' ') FROM document_tags
+                                     WHERE document_id = old.document_id)
+                     WHERE rowid = (SELECT rowid FROM documents WHERE id = old.document_id);
+             END;",
+        )?;
+
+        Ok(())
+    }
+
+    /// Indexes for the audit log filters, and triggers that make its rows immutable
+    fn init_audit_log(conn: &Connection) -> Result<(), DatabaseError> {
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_submitter ON audit_log(submitter);
+             CREATE INDEX IF NOT EXISTS idx_audit_log_block_number ON audit_log(block_number);
+             CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log BEGIN
+                 SELECT RAISE(ABORT, 'audit_log is append-only');
+             END;
+             CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log BEGIN
+                 SELECT RAISE(ABORT, 'audit_log is append-only');
+             END;",
+        )?;
+
+        Ok(())
+    }
+
+    /// Add a column to an existing table if it is not already present
+    fn ensure_column(
+        conn: &Connection,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !columns.iter().any(|name| name == column) {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Stored balance, parsed from its decimal TEXT form
+    fn read_balance(conn: &Connection, owner: &str, token: &str) -> Result<u128, DatabaseError> {
+        let amount = conn
+            .query_row(
+                "SELECT amount FROM balances WHERE owner = ?1 AND token = ?2",
+                params![owner.to_lowercase(), token.to_lowercase()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        match amount {
+            Some(amount) => amount.parse().map_err(|e| {
+                DatabaseError::SqliteError(rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                ))
+            }),
+            None => Ok(0),
+        }
+    }
+
+    fn write_balance(
+        conn: &Connection,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<(), DatabaseError> {
+        conn.execute(
+            "INSERT INTO balances (owner, token, amount) VALUES (?1, ?2, ?3)
+             ON CONFLICT(owner, token) DO UPDATE SET amount = excluded.amount",
+            params![
+                owner.to_lowercase(),
+                token.to_lowercase(),
+                amount.to_string()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Insert one document using the connection's cached statement
+    /// Only failures of the insert itself are classified as duplicates
+    fn insert_document(conn: &Connection, doc: &Document) -> Result<(), DatabaseError> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO documents (id, content_hash, file_name, mime_type, submitted_by, created_at, expires_at, supersedes, detected_mime_type, ipfs_cid, content_hash_keccak, blake2b_hash, description, epoch_index, input_index, metadata, wallet_signature, signed_message)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        )?;
+
+        match stmt.execute(params![
+            &doc.id,
+            &doc.content_hash,
+            &doc.file_name,
+            &doc.mime_type,
+            &doc.submitted_by,
+            &doc.created_at,
+            &doc.expires_at,
+            &doc.supersedes,
+            &doc.detected_mime_type,
+            &doc.ipfs_cid,
+            &doc.content_hash_keccak,
+            &doc.blake2b_hash,
+            &doc.description,
+            i64::try_from(doc.epoch_index)?,
+            i64::try_from(doc.input_index)?,
+            metadata_column(&doc.metadata),
+            &doc.wallet_signature,
+            &doc.signed_message
+        ]) {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(err, _)) => {
+                if err.code == rusqlite::ErrorCode::ConstraintViolation {
+                    Err(DatabaseError::DuplicateHash)
+                } else {
+                    Err(rusqlite::Error::SqliteFailure(err, None).into())
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set_open_epoch(conn: &Connection, epoch: u64) -> Result<(), DatabaseError> {
+        conn.execute(
+            "INSERT INTO epoch_state (id, open_epoch) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET open_epoch = excluded.open_epoch",
+            params![i64::try_from(epoch)?],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_audit_entry(row: &rusqlite::Row) -> Result<AuditEntry, rusqlite::Error> {
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            action: row.get(1)?,
+            submitter: row.get(2)?,
+            block_number: row.get::<_, i64>(3)? as u64,
+            timestamp: row.get(4)?,
+            outcome: AuditOutcome::from_status(&row.get::<_, String>(5)?),
+            content_hash: row.get(6)?,
+        })
+    }
+
+    fn row_to_document(row: &rusqlite::Row) -> Result<Document, rusqlite::Error> {
+        Ok(Document {
+            id: row.get(0)?,
+            content_hash: row.get(1)?,
+            file_name: row.get(2)?,
+            mime_type: row.get(3)?,
+            submitted_by: row.get(4)?,
+            created_at: row.get(5)?,
+            expires_at: row.get(6)?,
+            supersedes: row.get(7)?,
+            detected_mime_type: row.get(8)?,
+            ipfs_cid: row.get(9)?,
+            content_hash_keccak: row.get(10)?,
+            blake2b_hash: row.get(11)?,
+            description: row.get(12)?,
+            wallet_signature: row.get(16)?,
+            signed_message: row.get(17)?,
+            epoch_index: row.get::<_, i64>(13)? as u64,
+            input_index: row.get::<_, i64>(14)? as u64,
+            metadata: match row.get::<_, Option<String>>(15)? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(15, Type::Text, Box::new(e))
+                })?,
+                None => empty_metadata(),
+            },
+        })
+    }
+}
+
+impl<C: Deref<Target = Connection>> DocumentRepository for SqliteRepository<C> {
+    fn save_document(&self, doc: &Document) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        Self::insert_document(&self.conn, doc)
+    }
+
+    fn save_documents_atomic(&self, docs: &[&Document]) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        // Returning early drops the transaction, which rolls it back
+        for doc in docs {
+            Self::insert_document(&tx, doc)?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn save_document_with_content(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        Self::insert_document(&tx, doc)?;
+        tx.execute(
+            "INSERT INTO document_content (document_id, content) VALUES (?1, ?2)",
+            params![&doc.id, content],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_content(&self, id: &DocumentId) -> Result<Vec<u8>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let content = self
+            .conn
+            .query_row(
+                "SELECT content FROM document_content WHERE document_id = ?1",
+                params![id],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()?;
+
+        content.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_by_hash(&self, hash: &ContentHash) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE content_hash = ?1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let doc = stmt
+            .query_row(params![hash], Self::row_to_document)
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_many_by_hashes(
+        &self,
+        hashes: &[&ContentHash],
+    ) -> Result<Vec<Option<Document>>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; hashes.len()].join(", ");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM documents WHERE content_hash IN ({})",
+            DOCUMENT_COLUMNS, placeholders
+        ))?;
+
+        let found: HashMap<ContentHash, Document> = stmt
+            .query_map(rusqlite::params_from_iter(hashes), Self::row_to_document)?
+            .map(|doc| doc.map(|doc| (doc.content_hash.clone(), doc)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(hashes
+            .iter()
+            .map(|hash| found.get(*hash).cloned())
+            .collect())
+    }
+
+    fn find_by_cid(&self, cid: &str) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE ipfs_cid = ?1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let doc = stmt
+            .query_row(params![cid], Self::row_to_document)
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_by_keccak(&self, hash: &str) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE content_hash_keccak = ?1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let doc = stmt
+            .query_row(params![hash], Self::row_to_document)
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_by_blake2b_hash(&self, hash: &str) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE blake2b_hash = ?1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let doc = stmt
+            .query_row(params![hash], Self::row_to_document)
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_by_id(&self, id: &DocumentId) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let doc = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM documents WHERE id = ?1", DOCUMENT_COLUMNS),
+                params![id],
+                Self::row_to_document,
+            )
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_by_input(&self, epoch: u64, input: u64) -> Result<Document, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // Served by idx_input
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE epoch_index = ?1 AND input_index = ?2
+             ORDER BY rowid
+             LIMIT 1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let doc = stmt
+            .query_row(
+                params![i64::try_from(epoch)?, i64::try_from(input)?],
+                Self::row_to_document,
+            )
+            .optional()?;
+
+        doc.ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn schema_version(&self) -> Result<u32, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let version = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        Ok(version)
+    }
+
+    fn count_documents(&self) -> Result<usize, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+
+        Ok(count as usize)
+    }
+
+    fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let Some(expression) = fts_match_expression(query) else {
+            return Ok(Vec::new());
+        };
+
+        // Best bm25 score first; a filename hit counts more than a tag, and a tag more
+        // than the MIME type. Equal scores fall back to the newest document
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             JOIN (SELECT rowid AS match_rowid, bm25(documents_fts, 0.0, 10.0, 1.0, 5.0) AS score
+                   FROM documents_fts WHERE documents_fts MATCH ?1)
+                 ON documents.rowid = match_rowid
+             ORDER BY score, created_at DESC, id
+             LIMIT ?2",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![expression, i64::try_from(limit)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn add_tags(&self, document_id: &DocumentId, tags: &[&str]) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO document_tags (document_id, tag) VALUES (?1, ?2)",
+            )?;
+            for tag in tags {
+                stmt.execute(params![document_id, tag])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn find_tags(&self, document_id: &DocumentId) -> Result<Vec<String>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT tag FROM document_tags WHERE document_id = ?1 ORDER BY tag")?;
+
+        let tags = stmt
+            .query_map(params![document_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    fn add_alias(
+        &self,
+        document_id: &DocumentId,
+        file_name: &str,
+        submitted_by: &str,
+        created_at: i64,
+    ) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        self.conn.execute(
+            "INSERT OR IGNORE INTO document_aliases (document_id, file_name, submitted_by, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![document_id, file_name, submitted_by, created_at],
+        )?;
+        Ok(())
+    }
+
+    fn find_all_by_content_hash(&self, hash: &ContentHash) -> Result<Vec<String>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // The document's own name sorts first with position 0; alias rowids start at 1
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT file_name, 0 AS position FROM documents WHERE content_hash = ?1
+             UNION ALL
+             SELECT a.file_name, a.rowid FROM document_aliases a
+             JOIN documents d ON d.id = a.document_id
+             WHERE d.content_hash = ?1
+             ORDER BY position",
+        )?;
+
+        let file_names = stmt
+            .query_map(params![hash], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(file_names)
+    }
+
+    fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE id IN (SELECT document_id FROM document_tags WHERE tag = ?1)
+             ORDER BY created_at DESC, id
+             LIMIT ?2 OFFSET ?3",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![tag, i64::try_from(limit)?, i64::try_from(offset)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        // Served by idx_created_at
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE created_at BETWEEN ?1 AND ?2
+             ORDER BY created_at, id
+             LIMIT ?3 OFFSET ?4",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![start, end, i64::try_from(limit)?, i64::try_from(offset)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn find_expired(
+        &self,
+        as_of: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        // Served by idx_expires_at; NULL means the document never expires
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE expires_at IS NOT NULL AND expires_at <= ?1
+             ORDER BY expires_at, id
+             LIMIT ?2 OFFSET ?3",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![as_of, i64::try_from(limit)?, i64::try_from(offset)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn find_versions(&self, root_hash: &ContentHash) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        let mut root = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE content_hash = ?1",
+            DOCUMENT_COLUMNS
+        ))?;
+        let mut versions = match root
+            .query_row([root_hash], Self::row_to_document)
+            .optional()?
+        {
+            Some(document) => vec![document],
+            None => return Ok(Vec::new()),
+        };
+
+        // Served by idx_supersedes; stops at a repeated hash so a tampered chain cannot loop
+        let mut successor = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents WHERE supersedes = ?1 ORDER BY created_at, id LIMIT 1",
+            DOCUMENT_COLUMNS
+        ))?;
+        while let Some(next) = successor
+            .query_row(
+                [&versions[versions.len() - 1].content_hash],
+                Self::row_to_document,
+            )
+            .optional()?
+        {
+            if versions
+                .iter()
+                .any(|doc| doc.content_hash == next.content_hash)
+            {
+                break;
+            }
+            versions.push(next);
+        }
+
+        Ok(versions)
+    }
+
+    fn list_documents(
+        &self,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let limit = i64::try_from(limit)?;
+
+        // Keyset pagination served by idx_created_at; the row value comparison
+        // resumes exactly after the last key of the previous page
+        let documents = match after {
+            Some((created_at, id)) => {
+                let mut stmt = self.conn.prepare_cached(&format!(
+                    "SELECT {} FROM documents
+                     WHERE (created_at, id) < (?1, ?2)
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT ?3",
+                    DOCUMENT_COLUMNS
+                ))?;
+                let documents = stmt
+                    .query_map(params![created_at, id, limit], Self::row_to_document)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                documents
+            }
+            None => {
+                let mut stmt = self.conn.prepare_cached(&format!(
+                    "SELECT {} FROM documents
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT ?1",
+                    DOCUMENT_COLUMNS
+                ))?;
+                let documents = stmt
+                    .query_map(params![limit], Self::row_to_document)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                documents
+            }
+        };
+
+        Ok(documents)
+    }
+
+    fn list_all(&self, limit: usize, offset: usize) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             ORDER BY created_at, id
+             LIMIT ?1 OFFSET ?2",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![i64::try_from(limit)?, i64::try_from(offset)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let (created_at, id) = after.unzip();
+
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE submitted_by = ?1 COLLATE NOCASE
+               AND (?2 IS NULL OR (created_at, id) < (?2, ?3))
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?4",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                params![submitter, created_at, id, i64::try_from(limit)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        // Floor to midnight, also for timestamps before 1970 where `%` is negative
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT created_at - ((created_at % 86400) + 86400) % 86400 AS day, COUNT(*)
+             FROM documents
+             WHERE created_at BETWEEN ?1 AND ?2
+             GROUP BY day
+             ORDER BY day",
+        )?;
+        let days = stmt
+            .query_map(params![start, end], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(days)
+    }
+
+    fn count_by_submitter(&self, submitter: &str) -> Result<usize, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM documents WHERE submitted_by = ?1 COLLATE NOCASE",
+            params![submitter],
+            |row| row.get(0),
+        )?;
+
+        Ok(count as usize)
+    }
+
+    fn list_submitters(&self) -> Result<Vec<(String, usize)>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare(
+            "SELECT lower(submitted_by) AS submitter, COUNT(*) AS document_count
+             FROM documents
+             GROUP BY submitter
+             ORDER BY document_count DESC, submitter",
+        )?;
+
+        let submitters = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(submitters)
+    }
+
+    fn count_by_submitter_top_n(&self, n: usize) -> Result<Vec<(String, usize)>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT lower(submitted_by) AS submitter, COUNT(*) AS document_count
+             FROM documents
+             GROUP BY submitter
+             ORDER BY document_count DESC, submitter
+             LIMIT ?1",
+        )?;
+
+        let submitters = stmt
+            .query_map(params![i64::try_from(n)?], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(submitters)
+    }
+
+    fn count_documents_since(&self, ts: i64) -> Result<usize, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        // Served by idx_created_at
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM documents WHERE created_at >= ?1",
+            params![ts],
+            |row| row.get(0),
+        )?;
+
+        Ok(count as usize)
+    }
+
+    fn update_metadata(
+        &self,
+        content_hash: &ContentHash,
+        file_name: Option<&str>,
+        mime_type: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Document, DatabaseError> {
+        let updated = {
+            let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+            self.conn.execute(
+                "UPDATE documents
+                 SET file_name = COALESCE(?2, file_name),
+                     mime_type = COALESCE(?3, mime_type),
+                     metadata = COALESCE(?4, metadata)
+                 WHERE content_hash = ?1",
+                params![
+                    content_hash,
+                    file_name,
+                    mime_type,
+                    metadata.and_then(metadata_column)
+                ],
+            )?
+        };
+
+        if updated == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        self.find_by_hash(content_hash)
+    }
+
+    fn find_missing_digests(
+        &self,
+        blake2b: bool,
+        ipfs_cid: bool,
+        after: Option<&DocumentId>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // Every id sorts after the empty string
+        let after = after.map(DocumentId::as_str).unwrap_or("");
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM documents
+             WHERE id > ?1
+               AND (content_hash_keccak IS NULL
+                    OR (?2 AND blake2b_hash IS NULL)
+                    OR (?3 AND ipfs_cid IS NULL))
+             ORDER BY id
+             LIMIT ?4",
+            DOCUMENT_COLUMNS
+        ))?;
+        let documents = stmt
+            .query_map(
+                params![after, blake2b, ipfs_cid, i64::try_from(limit)?],
+                Self::row_to_document,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(documents)
+    }
+
+    fn update_digests(
+        &self,
+        id: &DocumentId,
+        keccak: Option<&str>,
+        blake2b: Option<&str>,
+        ipfs_cid: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let updated = self.conn.execute(
+            "UPDATE documents
+             SET content_hash_keccak = COALESCE(?2, content_hash_keccak),
+                 blake2b_hash = COALESCE(?3, blake2b_hash),
+                 ipfs_cid = COALESCE(?4, ipfs_cid)
+             WHERE id = ?1",
+            params![id, keccak, blake2b, ipfs_cid],
+        )?;
+
+        if updated == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn transfer_ownership(&self, transfer: &OwnershipTransfer) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        // Only move the document if it is still held by the expected owner
+        let updated = tx.execute(
+            "UPDATE documents SET submitted_by = ?2 WHERE content_hash = ?1 AND submitted_by = ?3",
+            params![
+                &transfer.content_hash,
+                &transfer.new_owner,
+                &transfer.previous_owner
+            ],
+        )?;
+
+        if updated == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        tx.execute(
+            "INSERT INTO ownership_history
+                (document_id, content_hash, previous_owner, new_owner, transferred_at, block_number)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                &transfer.document_id,
+                &transfer.content_hash,
+                &transfer.previous_owner,
+                &transfer.new_owner,
+                &transfer.transferred_at,
+                transfer.block_number as i64
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn find_ownership_history(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Vec<OwnershipTransfer>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare(
+            "SELECT document_id, content_hash, previous_owner, new_owner, transferred_at, block_number
+             FROM ownership_history
+             WHERE content_hash = ?1
+             ORDER BY id",
+        )?;
+
+        let history = stmt
+            .query_map(params![content_hash], |row| {
+                Ok(OwnershipTransfer {
+                    document_id: row.get(0)?,
+                    content_hash: row.get(1)?,
+                    previous_owner: row.get(2)?,
+                    new_owner: row.get(3)?,
+                    transferred_at: row.get(4)?,
+                    block_number: row.get::<_, i64>(5)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    fn append_audit_entry(&self, entry: &AuditEntry) -> Result<i64, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO audit_log
+                 (action, submitter, block_number, timestamp, outcome, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        stmt.execute(params![
+            &entry.action,
+            &entry.submitter,
+            i64::try_from(entry.block_number)?,
+            &entry.timestamp,
+            entry.outcome.as_str(),
+            &entry.content_hash
+        ])?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn find_audit_entries(
+        &self,
+        submitter: Option<&str>,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        after_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // Blocks past i64::MAX cannot be stored, so such a bound behaves like i64::MAX
+        let block = |bound: Option<u64>| bound.map(|b| i64::try_from(b).unwrap_or(i64::MAX));
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM audit_log
+             WHERE (?1 IS NULL OR submitter = ?1 COLLATE NOCASE)
+               AND (?2 IS NULL OR block_number >= ?2)
+               AND (?3 IS NULL OR block_number <= ?3)
+               AND (?4 IS NULL OR id > ?4)
+             ORDER BY id
+             LIMIT ?5",
+            AUDIT_COLUMNS
+        ))?;
+
+        let entries = stmt
+            .query_map(
+                params![
+                    submitter,
+                    block(from_block),
+                    block(to_block),
+                    after_id,
+                    i64::try_from(limit)?
+                ],
+                Self::row_to_audit_entry,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    fn list_audit_entries(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM audit_log ORDER BY id LIMIT ?1 OFFSET ?2",
+            AUDIT_COLUMNS
+        ))?;
+
+        let entries = stmt
+            .query_map(
+                params![i64::try_from(limit)?, i64::try_from(offset)?],
+                Self::row_to_audit_entry,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    fn delete_by_hash(&self, hash: &ContentHash) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let deleted = tx.execute(
+            "DELETE FROM documents WHERE content_hash = ?1",
+            params![hash],
+        )?;
+
+        if deleted == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        tx.execute(
+            "DELETE FROM ownership_history WHERE content_hash = ?1",
+            params![hash],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn save_pending_notarization(
+        &self,
+        pending: &PendingNotarization,
+        cosigners: &[&str],
+    ) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        // Tags are validated to letters, digits, '-' and '_', so a comma is a safe separator
+        let inserted = tx.execute(
+            "INSERT INTO pending_cosigns
+                (content_hash, submitted_by, file_name, mime_type, content, expires_at,
+                 expiration_blocks, tags, supersedes, description, created_at, deadline,
+                 signature, signed_message)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                &pending.content_hash,
+                &pending.submitted_by,
+                &pending.file_name,
+                &pending.mime_type,
+                &pending.content,
+                &pending.expires_at,
+                pending.expiration_blocks.map(i64::try_from).transpose()?,
+                pending.tags.join(","),
+                &pending.supersedes,
+                &pending.description,
+                &pending.created_at,
+                &pending.deadline,
+                &pending.signature,
+                &pending.signed_message
+            ],
+        );
+
+        match inserted {
+            Ok(_) => {}
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                return Err(DatabaseError::DuplicateHash);
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        for cosigner in cosigners {
+            tx.execute(
+                "INSERT INTO document_cosigners (content_hash, cosigner) VALUES (?1, ?2)",
+                params![&pending.content_hash, cosigner],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn find_pending_notarization(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let pending = self
+            .conn
+            .query_row(
+                "SELECT content_hash, submitted_by, file_name, mime_type, content, expires_at,
+                        expiration_blocks, tags, supersedes, description, created_at, deadline,
+                        signature, signed_message
+                 FROM pending_cosigns WHERE content_hash = ?1",
+                params![content_hash],
+                |row| {
+                    let tags: String = row.get(7)?;
+                    Ok(PendingNotarization {
+                        content_hash: row.get(0)?,
+                        submitted_by: row.get(1)?,
+                        file_name: row.get(2)?,
+                        mime_type: row.get(3)?,
+                        content: row.get(4)?,
+                        expires_at: row.get(5)?,
+                        expiration_blocks: row.get::<_, Option<i64>>(6)?.map(|b| b as u64),
+                        tags: tags
+                            .split(',')
+                            .filter(|tag| !tag.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                        supersedes: row.get(8)?,
+                        description: row.get(9)?,
+                        signature: row.get(12)?,
+                        signed_message: row.get(13)?,
+                        created_at: row.get(10)?,
+                        deadline: row.get(11)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(pending)
+    }
+
+    fn find_cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare(
+            "SELECT cosigner, signature, signed_at FROM document_cosigners
+             WHERE content_hash = ?1
+             ORDER BY cosigner",
+        )?;
+
+        let cosigners = stmt
+            .query_map(params![content_hash], |row| {
+                Ok(Cosigner {
+                    address: row.get(0)?,
+                    signature: row.get(1)?,
+                    signed_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(cosigners)
+    }
+
+    fn record_cosignature(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        signed_at: i64,
+    ) -> Result<bool, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let updated = self.conn.execute(
+            "UPDATE document_cosigners SET signature = ?3, signed_at = ?4
+             WHERE content_hash = ?1 AND cosigner = ?2 AND signed_at IS NULL",
+            params![content_hash, cosigner, signature, signed_at],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn delete_pending_notarization(&self, content_hash: &str) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // Cosigner rows cascade with the pending notarization
+        self.conn.execute(
+            "DELETE FROM pending_cosigns WHERE content_hash = ?1",
+            params![content_hash],
+        )?;
+        Ok(())
+    }
+
+    fn start_upload(&self, session: &UploadSession) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let result = self.conn.execute(
+            "INSERT INTO upload_sessions (upload_id, submitted_by, file_name, mime_type, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                &session.upload_id,
+                &session.submitted_by,
+                &session.file_name,
+                &session.mime_type,
+                &session.started_at
+            ],
+        );
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                Err(DatabaseError::DuplicateUpload)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn find_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let session = self
+            .conn
+            .query_row(
+                "SELECT s.upload_id, s.submitted_by, s.file_name, s.mime_type, s.started_at,
+                        COUNT(c.chunk_index), COALESCE(SUM(LENGTH(c.data)), 0)
+                 FROM upload_sessions s
+                 LEFT JOIN upload_chunks c ON c.upload_id = s.upload_id
+                 WHERE s.upload_id = ?1
+                 GROUP BY s.upload_id",
+                params![upload_id],
+                |row| {
+                    Ok(UploadSession {
+                        upload_id: row.get(0)?,
+                        submitted_by: row.get(1)?,
+                        file_name: row.get(2)?,
+                        mime_type: row.get(3)?,
+                        started_at: row.get(4)?,
+                        chunk_count: row.get(5)?,
+                        total_bytes: row.get::<_, i64>(6)? as u64,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(session)
+    }
+
+    fn append_chunk(&self, upload_id: &str, index: u32, data: &[u8]) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        self.conn.execute(
+            "INSERT INTO upload_chunks (upload_id, chunk_index, data) VALUES (?1, ?2, ?3)",
+            params![upload_id, index, data],
+        )?;
+        Ok(())
+    }
+
+    fn upload_content(&self, upload_id: &str) -> Result<Vec<u8>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM upload_chunks WHERE upload_id = ?1 ORDER BY chunk_index")?;
+
+        let mut content = Vec::new();
+        let mut rows = stmt.query(params![upload_id])?;
+        while let Some(row) = rows.next()? {
+            content.extend_from_slice(row.get_ref(0)?.as_blob().map_err(rusqlite::Error::from)?);
+        }
+
+        Ok(content)
+    }
+
+    fn delete_upload(&self, upload_id: &str) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        // Staged chunks cascade with the session
+        self.conn.execute(
+            "DELETE FROM upload_sessions WHERE upload_id = ?1",
+            params![upload_id],
+        )?;
+        Ok(())
+    }
+
+    fn save_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+        document_id: &DocumentId,
+        block_number: u64,
+    ) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        self.conn.execute(
+            "INSERT INTO idempotency (idempotency_key, submitter, document_id, block_number)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                key,
+                submitter.to_lowercase(),
+                document_id,
+                i64::try_from(block_number)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn find_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+    ) -> Result<Option<(DocumentId, u64)>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let found = self
+            .conn
+            .query_row(
+                "SELECT document_id, block_number FROM idempotency
+                 WHERE idempotency_key = ?1 AND submitter = ?2",
+                params![key, submitter.to_lowercase()],
+                |row| Ok((row.get::<_, DocumentId>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()?;
+
+        Ok(found.map(|(document_id, block_number)| (document_id, block_number as u64)))
+    }
+
+    fn find_open_epoch(&self) -> Result<Option<u64>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let epoch = self
+            .conn
+            .query_row(
+                "SELECT open_epoch FROM epoch_state WHERE id = 1",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+
+        Ok(epoch.map(|epoch| epoch as u64))
+    }
+
+    fn open_epoch(&self, epoch: u64) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        Self::set_open_epoch(&self.conn, epoch)
+    }
+
+    fn seal_epoch(&self, epoch: u64, next_epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        // Documents in notarization order that no earlier epoch has committed to
+        let unsealed = {
+            let mut stmt = tx.prepare(
+                "SELECT d.id, d.content_hash FROM documents d
+                 WHERE NOT EXISTS (SELECT 1 FROM epoch_leaves l WHERE l.document_id = d.id)
+                 ORDER BY d.rowid",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+
+        let hashes: Vec<String> = unsealed.iter().map(|(_, hash)| hash.clone()).collect();
+        let sealed = match merkle_root(&hashes) {
+            Some(root) => {
+                let epoch_index = i64::try_from(epoch)?;
+                tx.execute(
+                    "INSERT INTO epoch_roots (epoch, root, leaf_count) VALUES (?1, ?2, ?3)",
+                    params![epoch_index, &root, hashes.len() as i64],
+                )?;
+                for (position, (document_id, content_hash)) in unsealed.iter().enumerate() {
+                    tx.execute(
+                        "INSERT INTO epoch_leaves (epoch, position, document_id, content_hash)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![epoch_index, position as i64, document_id, content_hash],
+                    )?;
+                }
+
+                Some(EpochRoot {
+                    epoch,
+                    root,
+                    leaf_count: hashes.len(),
+                })
+            }
+            None => None,
+        };
+
+        Self::set_open_epoch(&tx, next_epoch)?;
+        tx.commit()?;
+        Ok(sealed)
+    }
+
+    fn find_epoch_root(&self, epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let root = self
+            .conn
+            .query_row(
+                "SELECT root, leaf_count FROM epoch_roots WHERE epoch = ?1",
+                params![i64::try_from(epoch)?],
+                |row| {
+                    Ok(EpochRoot {
+                        epoch,
+                        root: row.get(0)?,
+                        leaf_count: row.get::<_, i64>(1)? as usize,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(root)
+    }
+
+    fn generate_inclusion_proof(
+        &self,
+        content_hash: &ContentHash,
+        epoch: u64,
+    ) -> Result<MerkleProof, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT content_hash FROM epoch_leaves WHERE epoch = ?1 ORDER BY position",
+        )?;
+        let leaves = stmt
+            .query_map(params![i64::try_from(epoch)?], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let content_hash = content_hash.as_str().to_lowercase();
+        leaves
+            .iter()
+            .position(|leaf| *leaf == content_hash)
+            .and_then(|index| merkle_proof(&leaves, index))
+            .ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_last_nonce(&self, submitter: &str) -> Result<Option<u64>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let last = self
+            .conn
+            .query_row(
+                "SELECT last_nonce FROM nonces WHERE submitter = ?1",
+                params![submitter.to_lowercase()],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+
+        Ok(last.map(|nonce| nonce as u64))
+    }
+
+    fn advance_nonce(&self, submitter: &str, nonce: u64) -> Result<bool, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+
+        // Single statement so the comparison and the write cannot interleave
+        let updated = self.conn.execute(
+            "INSERT INTO nonces (submitter, last_nonce) VALUES (?1, ?2)
+             ON CONFLICT(submitter) DO UPDATE SET last_nonce = excluded.last_nonce
+             WHERE excluded.last_nonce > nonces.last_nonce",
+            params![submitter.to_lowercase(), i64::try_from(nonce)?],
+        )?;
+
+        Ok(updated == 1)
+    }
+
+    fn find_balance(&self, owner: &str, token: &str) -> Result<u128, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        Self::read_balance(&self.conn, owner, token)
+    }
+
+    fn credit_balance(
+        &self,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<u128, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let balance = Self::read_balance(&tx, owner, token)?
+            .checked_add(amount)
+            .ok_or(DatabaseError::BalanceOverflow)?;
+        Self::write_balance(&tx, owner, token, balance)?;
+
+        tx.commit()?;
+        Ok(balance)
+    }
+
+    fn debit_balance(&self, owner: &str, token: &str, amount: u128) -> Result<bool, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let Some(balance) = Self::read_balance(&tx, owner, token)?.checked_sub(amount) else {
+            return Ok(false);
+        };
+        Self::write_balance(&tx, owner, token, balance)?;
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn find_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let used = self
+            .conn
+            .query_row(
+                "SELECT used FROM quota WHERE submitter = ?1 AND epoch_index = ?2",
+                params![submitter.to_lowercase(), i64::try_from(epoch)?],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+
+        Ok(used.unwrap_or(0) as u64)
+    }
+
+    fn increment_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let used = self.conn.query_row(
+            "INSERT INTO quota (submitter, epoch_index, used) VALUES (?1, ?2, 1)
+             ON CONFLICT(submitter, epoch_index) DO UPDATE SET used = quota.used + 1
+             RETURNING used",
+            params![submitter.to_lowercase(), i64::try_from(epoch)?],
+            |row| row.get::<_, i64>(0),
+        )?;
+
+        Ok(used as u64)
+    }
+
+    fn save_commitment(&self, commitment: &Commitment) -> Result<(), DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let result = self.conn.execute(
+            "INSERT INTO commitments (content_hash, committed_by, block_number, timestamp)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                &commitment.content_hash,
+                &commitment.committed_by,
+                i64::try_from(commitment.block_number)?,
+                &commitment.timestamp
+            ],
+        );
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                Err(DatabaseError::DuplicateHash)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn find_commitment(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Option<Commitment>, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let commitment = self
+            .conn
+            .query_row(
+                "SELECT content_hash, committed_by, block_number, timestamp
+                 FROM commitments WHERE content_hash = ?1",
+                params![content_hash.as_str()],
+                |row| {
+                    Ok(Commitment {
+                        content_hash: row.get(0)?,
+                        committed_by: row.get(1)?,
+                        block_number: row.get::<_, i64>(2)? as u64,
+                        timestamp: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(commitment)
+    }
+}
+
+/// Repository over a pool of connections to one database file
+/// `Send + Sync`, so a single instance can serve parallel requests such as concurrent
+/// inspects; every call checks out a connection for its duration. The advance loop
+/// processes one input at a time and keeps the single-connection `SqliteRepository`
+pub struct PooledSqliteRepository {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl PooledSqliteRepository {
+    /// Open up to `max_size` connections to the database at `path`, each tuned with `options`
+    /// The schema is created or migrated through the first connection
+    pub fn open(path: &str, options: &SqliteOptions, max_size: u32) -> Result<Self, DatabaseError> {
+        let options = *options;
+        // Connection setup may only fail with a rusqlite error
+        let manager = SqliteConnectionManager::file(path).with_init(move |conn| {
+            match SqliteRepository::<Box<Connection>>::apply_options(conn, &options) {
+                Ok(()) => Ok(()),
+                Err(DatabaseError::SqliteError(e)) => Err(e),
+                Err(e) => Err(rusqlite::Error::ToSqlConversionFailure(Box::new(e))),
+            }
+        });
+        let pool = Pool::builder().max_size(max_size).build(manager)?;
+        let conn = pool.get()?;
+        SqliteRepository::<Box<Connection>>::init_schema(&conn)?;
+        Ok(Self { pool })
+    }
+
+    /// Most connections the pool keeps open
+    pub fn max_size(&self) -> u32 {
+        self.pool.max_size()
+    }
+
+    /// Repository over a connection taken from the pool, which gets it back when dropped
+    fn checkout(
+        &self,
+    ) -> Result<SqliteRepository<PooledConnection<SqliteConnectionManager>>, DatabaseError> {
+        Ok(SqliteRepository {
+            conn: self.pool.get()?,
+        })
+    }
+}
+
+impl DocumentRepository for PooledSqliteRepository {
+    fn save_document(&self, doc: &Document) -> Result<(), DatabaseError> {
+        self.checkout()?.save_document(doc)
+    }
+
+    fn save_documents_atomic(&self, docs: &[&Document]) -> Result<(), DatabaseError> {
+        self.checkout()?.save_documents_atomic(docs)
+    }
+
+    fn save_document_with_content(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(), DatabaseError> {
+        self.checkout()?.save_document_with_content(doc, content)
+    }
+
+    fn get_content(&self, id: &DocumentId) -> Result<Vec<u8>, DatabaseError> {
+        self.checkout()?.get_content(id)
+    }
+
+    fn find_by_hash(&self, hash: &ContentHash) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_hash(hash)
+    }
+
+    fn find_by_id(&self, id: &DocumentId) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_id(id)
+    }
+
+    fn find_by_input(&self, epoch: u64, input: u64) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_input(epoch, input)
+    }
+
+    fn find_by_cid(&self, cid: &str) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_cid(cid)
+    }
+
+    fn find_by_keccak(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_keccak(hash)
+    }
+
+    fn find_by_blake2b_hash(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.checkout()?.find_by_blake2b_hash(hash)
+    }
+
+    fn find_many_by_hashes(
+        &self,
+        hashes: &[&ContentHash],
+    ) -> Result<Vec<Option<Document>>, DatabaseError> {
+        self.checkout()?.find_many_by_hashes(hashes)
+    }
+
+    fn count_documents(&self) -> Result<usize, DatabaseError> {
+        self.checkout()?.count_documents()
+    }
+
+    fn schema_version(&self) -> Result<u32, DatabaseError> {
+        self.checkout()?.schema_version()
+    }
+
+    fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.full_text_search(query, limit)
+    }
+
+    fn add_tags(&self, document_id: &DocumentId, tags: &[&str]) -> Result<(), DatabaseError> {
+        self.checkout()?.add_tags(document_id, tags)
+    }
+
+    fn find_tags(&self, document_id: &DocumentId) -> Result<Vec<String>, DatabaseError> {
+        self.checkout()?.find_tags(document_id)
+    }
+
+    fn add_alias(
+        &self,
+        document_id: &DocumentId,
+        file_name: &str,
+        submitted_by: &str,
+        created_at: i64,
+    ) -> Result<(), DatabaseError> {
+        self.checkout()?
+            .add_alias(document_id, file_name, submitted_by, created_at)
+    }
+
+    fn find_all_by_content_hash(&self, hash: &ContentHash) -> Result<Vec<String>, DatabaseError> {
+        self.checkout()?.find_all_by_content_hash(hash)
+    }
+
+    fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.find_by_tag(tag, limit, offset)
+    }
+
+    fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?
+            .find_by_date_range(start, end, limit, offset)
+    }
+
+    fn find_expired(
+        &self,
+        as_of: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.find_expired(as_of, limit, offset)
+    }
+
+    fn list_documents(
+        &self,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.list_documents(after, limit)
+    }
+
+    fn list_all(&self, limit: usize, offset: usize) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.list_all(limit, offset)
+    }
+
+    fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.list_by_submitter(submitter, after, limit)
+    }
+
+    fn find_versions(&self, root_hash: &ContentHash) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?.find_versions(root_hash)
+    }
+
+    fn count_by_submitter(&self, submitter: &str) -> Result<usize, DatabaseError> {
+        self.checkout()?.count_by_submitter(submitter)
+    }
+
+    fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, DatabaseError> {
+        self.checkout()?.count_by_day(start, end)
+    }
+
+    fn list_submitters(&self) -> Result<Vec<(String, usize)>, DatabaseError> {
+        self.checkout()?.list_submitters()
+    }
+
+    fn count_by_submitter_top_n(&self, n: usize) -> Result<Vec<(String, usize)>, DatabaseError> {
+        self.checkout()?.count_by_submitter_top_n(n)
+    }
+
+    fn count_documents_since(&self, ts: i64) -> Result<usize, DatabaseError> {
+        self.checkout()?.count_documents_since(ts)
+    }
+
+    fn update_metadata(
+        &self,
+        content_hash: &ContentHash,
+        file_name: Option<&str>,
+        mime_type: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Document, DatabaseError> {
+        self.checkout()?
+            .update_metadata(content_hash, file_name, mime_type, metadata)
+    }
+
+    fn find_missing_digests(
+        &self,
+        blake2b: bool,
+        ipfs_cid: bool,
+        after: Option<&DocumentId>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.checkout()?
+            .find_missing_digests(blake2b, ipfs_cid, after, limit)
+    }
+
+    fn update_digests(
+        &self,
+        id: &DocumentId,
+        keccak: Option<&str>,
+        blake2b: Option<&str>,
+        ipfs_cid: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        self.checkout()?
+            .update_digests(id, keccak, blake2b, ipfs_cid)
+    }
+
+    fn transfer_ownership(&self, transfer: &OwnershipTransfer) -> Result<(), DatabaseError> {
+        self.checkout()?.transfer_ownership(transfer)
+    }
+
+    fn find_ownership_history(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Vec<OwnershipTransfer>, DatabaseError> {
+        self.checkout()?.find_ownership_history(content_hash)
+    }
+
+    fn append_audit_entry(&self, entry: &AuditEntry) -> Result<i64, DatabaseError> {
+        self.checkout()?.append_audit_entry(entry)
+    }
+
+    fn find_audit_entries(
+        &self,
+        submitter: Option<&str>,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        after_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        self.checkout()?
+            .find_audit_entries(submitter, from_block, to_block, after_id, limit)
+    }
+
+    fn list_audit_entries(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        self.checkout()?.list_audit_entries(limit, offset)
+    }
+
+    fn delete_by_hash(&self, hash: &ContentHash) -> Result<(), DatabaseError> {
+        self.checkout()?.delete_by_hash(hash)
+    }
+
+    fn start_upload(&self, session: &UploadSession) -> Result<(), DatabaseError> {
+        self.checkout()?.start_upload(session)
+    }
+
+    fn find_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError> {
+        self.checkout()?.find_upload(upload_id)
+    }
+
+    fn append_chunk(&self, upload_id: &str, index: u32, data: &[u8]) -> Result<(), DatabaseError> {
+        self.checkout()?.append_chunk(upload_id, index, data)
+    }
+
+    fn upload_content(&self, upload_id: &str) -> Result<Vec<u8>, DatabaseError> {
+        self.checkout()?.upload_content(upload_id)
+    }
+
+    fn delete_upload(&self, upload_id: &str) -> Result<(), DatabaseError> {
+        self.checkout()?.delete_upload(upload_id)
+    }
+
+    fn save_pending_notarization(
+        &self,
+        pending: &PendingNotarization,
+        cosigners: &[&str],
+    ) -> Result<(), DatabaseError> {
+        self.checkout()?
+            .save_pending_notarization(pending, cosigners)
+    }
+
+    fn find_pending_notarization(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, DatabaseError> {
+        self.checkout()?.find_pending_notarization(content_hash)
+    }
+
+    fn find_cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, DatabaseError> {
+        self.checkout()?.find_cosigners(content_hash)
+    }
+
+    fn record_cosignature(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        signed_at: i64,
+    ) -> Result<bool, DatabaseError> {
+        self.checkout()?
+            .record_cosignature(content_hash, cosigner, signature, signed_at)
+    }
+
+    fn delete_pending_notarization(&self, content_hash: &str) -> Result<(), DatabaseError> {
+        self.checkout()?.delete_pending_notarization(content_hash)
+    }
+
+    fn save_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+        document_id: &DocumentId,
+        block_number: u64,
+    ) -> Result<(), DatabaseError> {
+        self.checkout()?
+            .save_idempotency_key(key, submitter, document_id, block_number)
+    }
+
+    fn find_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+    ) -> Result<Option<(DocumentId, u64)>, DatabaseError> {
+        self.checkout()?.find_idempotency_key(key, submitter)
+    }
+
+    fn find_open_epoch(&self) -> Result<Option<u64>, DatabaseError> {
+        self.checkout()?.find_open_epoch()
+    }
+
+    fn open_epoch(&self, epoch: u64) -> Result<(), DatabaseError> {
+        self.checkout()?.open_epoch(epoch)
+    }
+
+    fn seal_epoch(&self, epoch: u64, next_epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        self.checkout()?.seal_epoch(epoch, next_epoch)
+    }
+
+    fn find_epoch_root(&self, epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        self.checkout()?.find_epoch_root(epoch)
+    }
+
+    fn generate_inclusion_proof(
+        &self,
+        content_hash: &ContentHash,
+        epoch: u64,
+    ) -> Result<MerkleProof, DatabaseError> {
+        self.checkout()?
+            .generate_inclusion_proof(content_hash, epoch)
+    }
+
+    fn find_last_nonce(&self, submitter: &str) -> Result<Option<u64>, DatabaseError> {
+        self.checkout()?.find_last_nonce(submitter)
+    }
+
+    fn advance_nonce(&self, submitter: &str, nonce: u64) -> Result<bool, DatabaseError> {
+        self.checkout()?.advance_nonce(submitter, nonce)
+    }
+
+    fn find_balance(&self, owner: &str, token: &str) -> Result<u128, DatabaseError> {
+        self.checkout()?.find_balance(owner, token)
+    }
+
+    fn credit_balance(
+        &self,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<u128, DatabaseError> {
+        self.checkout()?.credit_balance(owner, token, amount)
+    }
+
+    fn debit_balance(&self, owner: &str, token: &str, amount: u128) -> Result<bool, DatabaseError> {
+        self.checkout()?.debit_balance(owner, token, amount)
+    }
+
+    fn find_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        self.checkout()?.find_quota_usage(submitter, epoch)
+    }
+
+    fn increment_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        self.checkout()?.increment_quota_usage(submitter, epoch)
+    }
+
+    fn save_commitment(&self, commitment: &Commitment) -> Result<(), DatabaseError> {
+        self.checkout()?.save_commitment(commitment)
+    }
+
+    fn find_commitment(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Option<Commitment>, DatabaseError> {
+        self.checkout()?.find_commitment(content_hash)
+    }
+}
+
+/// Stored form of a document's metadata; an empty object is stored as NULL
+pub(super) fn metadata_column(metadata: &serde_json::Value) -> Option<String> {
+    match metadata.as_object() {
+        Some(map) if map.is_empty() => None,
+        _ if metadata.is_null() => None,
+        _ => Some(metadata.to_string()),
+    }
+}
+
+/// Turn free text into an FTS5 query matching every word as a prefix
+/// Each word is quoted, so punctuation such as `-` or `/` cannot break the query syntax
+fn fts_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_in_memory_db() {
+        let repo = SqliteRepository::new_in_memory();
+        assert!(repo.is_ok());
+    }
+
+    #[test]
+    fn test_fts_match_expression_quotes_words() {
+        assert_eq!(
+            fts_match_expression("acme  application/pd").as_deref(),
+            Some("\"acme\"* \"application/pd\"*")
+        );
+        assert_eq!(
+            fts_match_expression("say \"hi\"").as_deref(),
+            Some("\"say\"* \"\"\"hi\"\"\"*")
+        );
+        assert!(fts_match_expression("   ").is_none());
     }
 }
@@ -0,0 +1,73 @@
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram, register_int_counter, register_int_gauge, Encoder, Histogram, IntCounter,
+    IntGauge, TextEncoder,
+};
+use std::error::Error;
+
+lazy_static! {
+    /// Successful notarizations
+    pub static ref NOTARIZATIONS_TOTAL: IntCounter = register_int_counter!(
+        "notarizations_total",
+        "Total number of documents notarized"
+    )
+    .expect("Failed to register notarizations_total");
+
+    /// Verification lookups, regardless of whether the document was found
+    pub static ref VERIFICATIONS_TOTAL: IntCounter = register_int_counter!(
+        "verifications_total",
+        "Total number of document verifications"
+    )
+    .expect("Failed to register verifications_total");
+
+    /// Notarizations rejected because the content hash already exists
+    pub static ref DUPLICATE_REJECTIONS_TOTAL: IntCounter = register_int_counter!(
+        "duplicate_rejections_total",
+        "Total number of notarizations rejected as duplicates"
+    )
+    .expect("Failed to register duplicate_rejections_total");
+
+    /// Time spent executing repository queries
+    pub static ref DB_QUERY_DURATION_SECONDS: Histogram = register_histogram!(
+        "db_query_duration_seconds",
+        "Duration of database queries in seconds"
+    )
+    .expect("Failed to register db_query_duration_seconds");
+
+    /// Number of documents currently stored
+    pub static ref DOCUMENTS_STORED: IntGauge = register_int_gauge!(
+        "documents_stored",
+        "Number of documents stored in the notary database"
+    )
+    .expect("Failed to register documents_stored");
+}
+
+/// Render all registered metrics in the Prometheus text exposition format
+pub fn encode_text() -> Result<String, Box<dyn Error>> {
+    // Touch every metric so it appears in the output even before first use
+    lazy_static::initialize(&NOTARIZATIONS_TOTAL);
+    lazy_static::initialize(&VERIFICATIONS_TOTAL);
+    lazy_static::initialize(&DUPLICATE_REJECTIONS_TOTAL);
+    lazy_static::initialize(&DB_QUERY_DURATION_SECONDS);
+    lazy_static::initialize(&DOCUMENTS_STORED);
+
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_text_includes_all_metrics() {
+        let text = encode_text().unwrap();
+
+        assert!(text.contains("notarizations_total"));
+        assert!(text.contains("verifications_total"));
+        assert!(text.contains("duplicate_rejections_total"));
+        assert!(text.contains("db_query_duration_seconds"));
+        assert!(text.contains("documents_stored"));
+    }
+}
@@ -1,2 +1,6 @@
+pub mod abi;
 pub mod cartesi;
 pub mod database;
+pub mod metrics;
+pub mod postgres_repository;
+pub mod signing;
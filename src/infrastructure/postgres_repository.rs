@@ -0,0 +1,1884 @@
+use super::database::{
+    metadata_column, DatabaseError, DocumentRepository, AUDIT_COLUMNS, DOCUMENT_COLUMNS,
+    SCHEMA_VERSION,
+};
+use super::metrics;
+use crate::domain::{
+    empty_metadata, merkle_proof, merkle_root, AuditEntry, AuditOutcome, Commitment, ContentHash,
+    Cosigner, Document, DocumentId, EpochRoot, MerkleProof, OwnershipTransfer, PendingNotarization,
+    UploadSession,
+};
+use postgres::{Client, GenericClient, NoTls, Row};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Mutex, PoisonError};
+
+/// Repository running every query on a single PostgreSQL connection
+/// The schema mirrors `SqliteRepository`'s, with BIGINT for every INTEGER column
+/// Calls block; from inside a tokio runtime they run on a thread of their own, since the
+/// blocking client drives a runtime of its own
+pub struct PostgresRepository {
+    connection: Mutex<Connection>,
+}
+
+/// The blocking client, whose runtime is also shut down away from any tokio runtime
+struct Connection(Option<Client>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(client) = self.0.take() {
+            off_runtime(move || drop(client));
+        }
+    }
+}
+
+/// Run `f` on this thread, or on a scoped one when this thread drives a tokio runtime,
+/// which cannot block on another runtime
+fn off_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    if tokio::runtime::Handle::try_current().is_err() {
+        return f();
+    }
+
+    std::thread::scope(|scope| match scope.spawn(f).join() {
+        Ok(value) => value,
+        Err(panic) => std::panic::resume_unwind(panic),
+    })
+}
+
+/// Whether `e` violates a constraint, such as a duplicate key
+fn is_constraint_violation(e: &postgres::Error) -> bool {
+    // Class 23 covers every integrity constraint
+    e.code().is_some_and(|code| code.code().starts_with("23"))
+}
+
+impl PostgresRepository {
+    /// Connect to the database at `connection_str`, a `postgres://` URL or `key=value`
+    /// settings, and create or update the schema
+    pub fn new(connection_str: &str) -> Result<Self, Box<dyn Error>> {
+        let client = off_runtime(|| Client::connect(connection_str, NoTls))?;
+        let repository = Self {
+            connection: Mutex::new(Connection(Some(client))),
+        };
+        repository.run(Self::init_schema)?;
+        Ok(repository)
+    }
+
+    /// Run `f` on the connection, timing it as one query
+    fn run<T: Send>(
+        &self,
+        f: impl FnOnce(&mut Client) -> Result<T, DatabaseError> + Send,
+    ) -> Result<T, DatabaseError> {
+        let _timer = metrics::DB_QUERY_DURATION_SECONDS.start_timer();
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let client = connection
+            .0
+            .as_mut()
+            .expect("the client is only taken when the connection is dropped");
+        off_runtime(move || f(client))
+    }
+
+    fn init_schema(client: &mut Client) -> Result<(), DatabaseError> {
+        let mut tx = client.transaction()?;
+
+        // Text that is sorted or compared by range uses byte order, as in SQLite
+        tx.batch_execute(
+            "CREATE TABLE IF NOT EXISTS documents (
+                id TEXT COLLATE \"C\" PRIMARY KEY,
+                content_hash TEXT UNIQUE NOT NULL,
+                file_name TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                submitted_by TEXT COLLATE \"C\" NOT NULL,
+                created_at BIGINT NOT NULL,
+                expires_at BIGINT,
+                supersedes TEXT,
+                detected_mime_type TEXT,
+                ipfs_cid TEXT,
+                content_hash_keccak TEXT,
+                blake2b_hash TEXT,
+                description TEXT,
+                epoch_index BIGINT NOT NULL DEFAULT 0,
+                input_index BIGINT NOT NULL DEFAULT 0,
+                metadata TEXT,
+                wallet_signature TEXT,
+                signed_message TEXT,
+                -- Insertion order, which SQLite keeps in the implicit rowid
+                rowid BIGINT GENERATED ALWAYS AS IDENTITY
+            );
+
+            CREATE TABLE IF NOT EXISTS ownership_history (
+                id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                document_id TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                previous_owner TEXT NOT NULL,
+                new_owner TEXT NOT NULL,
+                transferred_at BIGINT NOT NULL,
+                block_number BIGINT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_ownership_content_hash ON ownership_history(content_hash);
+
+            -- Original bytes, only written when content retention is enabled
+            CREATE TABLE IF NOT EXISTS document_content (
+                document_id TEXT PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                content BYTEA NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS document_tags (
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                tag TEXT COLLATE \"C\" NOT NULL,
+                PRIMARY KEY (document_id, tag)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_document_tags_tag ON document_tags(tag);
+
+            -- Further filenames the same content was notarized under
+            CREATE TABLE IF NOT EXISTS document_aliases (
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                file_name TEXT NOT NULL,
+                submitted_by TEXT NOT NULL,
+                created_at BIGINT NOT NULL,
+                rowid BIGINT GENERATED ALWAYS AS IDENTITY,
+                PRIMARY KEY (document_id, file_name)
+            );
+
+            -- Notarizations waiting for their cosigners
+            CREATE TABLE IF NOT EXISTS pending_cosigns (
+                content_hash TEXT PRIMARY KEY,
+                submitted_by TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                content BYTEA NOT NULL,
+                expires_at BIGINT,
+                expiration_blocks BIGINT,
+                tags TEXT NOT NULL,
+                supersedes TEXT,
+                description TEXT,
+                signature TEXT,
+                signed_message TEXT,
+                created_at BIGINT NOT NULL,
+                deadline BIGINT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS document_cosigners (
+                content_hash TEXT NOT NULL REFERENCES pending_cosigns(content_hash) ON DELETE CASCADE,
+                cosigner TEXT COLLATE \"C\" NOT NULL,
+                signature TEXT,
+                signed_at BIGINT,
+                PRIMARY KEY (content_hash, cosigner)
+            );
+
+            -- Staging area for documents assembled from several inputs
+            CREATE TABLE IF NOT EXISTS upload_sessions (
+                upload_id TEXT PRIMARY KEY,
+                submitted_by TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                started_at BIGINT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS upload_chunks (
+                upload_id TEXT NOT NULL REFERENCES upload_sessions(upload_id) ON DELETE CASCADE,
+                chunk_index BIGINT NOT NULL,
+                data BYTEA NOT NULL,
+                PRIMARY KEY (upload_id, chunk_index)
+            );
+
+            CREATE TABLE IF NOT EXISTS idempotency (
+                idempotency_key TEXT NOT NULL,
+                submitter TEXT NOT NULL,
+                document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+                block_number BIGINT NOT NULL,
+                PRIMARY KEY (idempotency_key, submitter)
+            );
+
+            CREATE TABLE IF NOT EXISTS epoch_roots (
+                epoch BIGINT PRIMARY KEY,
+                root TEXT NOT NULL,
+                leaf_count BIGINT NOT NULL
+            );
+
+            -- Leaves keep the document hashes of a sealed epoch, so proofs survive purges
+            CREATE TABLE IF NOT EXISTS epoch_leaves (
+                epoch BIGINT NOT NULL REFERENCES epoch_roots(epoch),
+                position BIGINT NOT NULL,
+                document_id TEXT NOT NULL UNIQUE,
+                content_hash TEXT NOT NULL,
+                PRIMARY KEY (epoch, position)
+            );
+
+            CREATE TABLE IF NOT EXISTS epoch_state (
+                id BIGINT PRIMARY KEY CHECK (id = 1),
+                open_epoch BIGINT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS nonces (
+                submitter TEXT PRIMARY KEY,
+                last_nonce BIGINT NOT NULL
+            );
+
+            -- One row per advance input; never updated or deleted, see `init_audit_log`
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                action TEXT NOT NULL,
+                submitter TEXT NOT NULL,
+                block_number BIGINT NOT NULL,
+                timestamp BIGINT NOT NULL,
+                outcome TEXT NOT NULL,
+                content_hash TEXT
+            );
+
+            -- Amounts are decimal TEXT, since deposits can exceed a BIGINT column
+            CREATE TABLE IF NOT EXISTS balances (
+                owner TEXT NOT NULL,
+                token TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                PRIMARY KEY (owner, token)
+            );
+
+            -- Rows of past epochs are never read again, so quotas reset with each epoch
+            CREATE TABLE IF NOT EXISTS quota (
+                submitter TEXT NOT NULL,
+                epoch_index BIGINT NOT NULL,
+                used BIGINT NOT NULL,
+                PRIMARY KEY (submitter, epoch_index)
+            );
+
+            -- Hashes committed before their document is notarized, without any metadata
+            CREATE TABLE IF NOT EXISTS commitments (
+                content_hash TEXT PRIMARY KEY,
+                committed_by TEXT NOT NULL,
+                block_number BIGINT NOT NULL,
+                timestamp BIGINT NOT NULL
+            );
+
+            -- Stands in for SQLite's PRAGMA user_version
+            CREATE TABLE IF NOT EXISTS schema_version (
+                id BIGINT PRIMARY KEY CHECK (id = 1),
+                version BIGINT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_created_at ON documents(created_at);
+            CREATE INDEX IF NOT EXISTS idx_expires_at ON documents(expires_at);
+            CREATE INDEX IF NOT EXISTS idx_supersedes ON documents(supersedes);
+            CREATE INDEX IF NOT EXISTS idx_ipfs_cid ON documents(ipfs_cid);
+            CREATE INDEX IF NOT EXISTS idx_content_hash_keccak ON documents(content_hash_keccak);
+            CREATE INDEX IF NOT EXISTS idx_blake2b_hash ON documents(blake2b_hash);
+            CREATE INDEX IF NOT EXISTS idx_input ON documents(epoch_index, input_index);",
+        )?;
+
+        Self::init_search_index(&mut tx)?;
+        Self::init_audit_log(&mut tx)?;
+
+        tx.execute(
+            "INSERT INTO schema_version (id, version) VALUES (1, $1)
+             ON CONFLICT (id) DO UPDATE SET version = excluded.version",
+            &[&i64::from(SCHEMA_VERSION)],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Full-text index over filename, MIME type and tags, kept in sync by triggers
+    /// Words are split on anything but letters and digits, like SQLite's tokenizer, and
+    /// weighted so a filename hit counts more than a tag, and a tag more than the MIME type
+    fn init_search_index(tx: &mut impl GenericClient) -> Result<(), DatabaseError> {
+        tx.batch_execute(
+            "CREATE TABLE IF NOT EXISTS documents_fts (
+                document_id TEXT PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+                document TSVECTOR NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_documents_fts ON documents_fts USING GIN (document);
+
+            CREATE OR REPLACE FUNCTION documents_fts_words(value TEXT) RETURNS TEXT
+            LANGUAGE sql IMMUTABLE AS $$
+                SELECT regexp_replace(value, '[^[:alnum:]]+', ' ', 'g')
+            $$;
+
+            CREATE OR REPLACE FUNCTION documents_fts_vector(doc_id TEXT) RETURNS TSVECTOR
+            LANGUAGE sql STABLE AS $$
+                SELECT COALESCE((
+                    SELECT setweight(to_tsvector('simple', documents_fts_words(d.file_name)), 'A')
+                        || setweight(to_tsvector('simple', documents_fts_words(COALESCE(
+                               (SELECT string_agg(tag, ' ') FROM document_tags
+                                    WHERE document_id = d.id), ''))), 'B')
+                        || setweight(to_tsvector('simple', documents_fts_words(d.mime_type)), 'C')
+                    FROM documents d WHERE d.id = doc_id
+                ), ''::TSVECTOR)
+            $$;
+
+            CREATE OR REPLACE FUNCTION documents_fts_index() RETURNS TRIGGER
+            LANGUAGE plpgsql AS $$
+            BEGIN
+                INSERT INTO documents_fts (document_id, document)
+                    VALUES (NEW.id, documents_fts_vector(NEW.id))
+                    ON CONFLICT (document_id) DO UPDATE SET document = excluded.document;
+                RETURN NULL;
+            END;
+            $$;
+
+            -- Tags are attached after their document is inserted, so they update its row
+            CREATE OR REPLACE FUNCTION documents_fts_tags() RETURNS TRIGGER
+            LANGUAGE plpgsql AS $$
+            DECLARE
+                doc_id TEXT;
+            BEGIN
+                IF TG_OP = 'DELETE' THEN
+                    doc_id := OLD.document_id;
+                ELSE
+                    doc_id := NEW.document_id;
+                END IF;
+                UPDATE documents_fts SET document = documents_fts_vector(doc_id)
+                    WHERE document_id = doc_id;
+                RETURN NULL;
+            END;
+            $$;
+
+            DROP TRIGGER IF EXISTS documents_fts_insert ON documents;
+            CREATE TRIGGER documents_fts_insert
+                AFTER INSERT OR UPDATE OF file_name, mime_type ON documents
+                FOR EACH ROW EXECUTE FUNCTION documents_fts_index();
+
+            DROP TRIGGER IF EXISTS documents_fts_tag ON document_tags;
+            CREATE TRIGGER documents_fts_tag
+                AFTER INSERT OR DELETE ON document_tags
+                FOR EACH ROW EXECUTE FUNCTION documents_fts_tags();",
+        )?;
+
+        Ok(())
+    }
+
+    /// Indexes for the audit log filters, and triggers that make its rows immutable
+    fn init_audit_log(tx: &mut impl GenericClient) -> Result<(), DatabaseError> {
+        tx.batch_execute(
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_submitter ON audit_log(submitter);
+             CREATE INDEX IF NOT EXISTS idx_audit_log_block_number ON audit_log(block_number);
+
+             CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER
+             LANGUAGE plpgsql AS $$
+             BEGIN
+                 RAISE EXCEPTION 'audit_log is append-only';
+             END;
+             $$;
+
+             DROP TRIGGER IF EXISTS audit_log_no_update ON audit_log;
+             CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+                 FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+
+             DROP TRIGGER IF EXISTS audit_log_no_delete ON audit_log;
+             CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+                 FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();",
+        )?;
+
+        Ok(())
+    }
+
+    /// Stored balance, parsed from its decimal TEXT form
+    fn read_balance(
+        client: &mut impl GenericClient,
+        owner: &str,
+        token: &str,
+    ) -> Result<u128, DatabaseError> {
+        let amount = client.query_opt(
+            "SELECT amount FROM balances WHERE owner = $1 AND token = $2",
+            &[&owner.to_lowercase(), &token.to_lowercase()],
+        )?;
+
+        match amount {
+            Some(row) => {
+                let amount: String = row.try_get(0)?;
+                amount.parse().map_err(|e| {
+                    DatabaseError::CorruptDatabase(format!("balance {:?}: {}", amount, e))
+                })
+            }
+            None => Ok(0),
+        }
+    }
+
+    fn write_balance(
+        client: &mut impl GenericClient,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<(), DatabaseError> {
+        client.execute(
+            "INSERT INTO balances (owner, token, amount) VALUES ($1, $2, $3)
+             ON CONFLICT (owner, token) DO UPDATE SET amount = excluded.amount",
+            &[
+                &owner.to_lowercase(),
+                &token.to_lowercase(),
+                &amount.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Insert one document; only failures of the insert itself are classified as duplicates
+    fn insert_document(
+        client: &mut impl GenericClient,
+        doc: &Document,
+    ) -> Result<(), DatabaseError> {
+        let inserted = client.execute(
+            "INSERT INTO documents (id, content_hash, file_name, mime_type, submitted_by, created_at, expires_at, supersedes, detected_mime_type, ipfs_cid, content_hash_keccak, blake2b_hash, description, epoch_index, input_index, metadata, wallet_signature, signed_message)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)",
+            &[
+                &doc.id.as_str(),
+                &doc.content_hash.as_str(),
+                &doc.file_name,
+                &doc.mime_type,
+                &doc.submitted_by,
+                &doc.created_at,
+                &doc.expires_at,
+                &doc.supersedes,
+                &doc.detected_mime_type,
+                &doc.ipfs_cid,
+                &doc.content_hash_keccak,
+                &doc.blake2b_hash,
+                &doc.description,
+                &i64::try_from(doc.epoch_index)?,
+                &i64::try_from(doc.input_index)?,
+                &metadata_column(&doc.metadata),
+                &doc.wallet_signature,
+                &doc.signed_message,
+            ],
+        );
+
+        match inserted {
+            Ok(_) => Ok(()),
+            Err(e) if is_constraint_violation(&e) => Err(DatabaseError::DuplicateHash),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set_open_epoch(client: &mut impl GenericClient, epoch: u64) -> Result<(), DatabaseError> {
+        client.execute(
+            "INSERT INTO epoch_state (id, open_epoch) VALUES (1, $1)
+             ON CONFLICT (id) DO UPDATE SET open_epoch = excluded.open_epoch",
+            &[&i64::try_from(epoch)?],
+        )?;
+        Ok(())
+    }
+
+    /// Documents returned by a query selecting `DOCUMENT_COLUMNS`
+    fn query_documents(
+        client: &mut impl GenericClient,
+        query: &str,
+        params: &[&(dyn postgres::types::ToSql + Sync)],
+    ) -> Result<Vec<Document>, DatabaseError> {
+        client
+            .query(query, params)?
+            .iter()
+            .map(Self::row_to_document)
+            .collect()
+    }
+
+    /// The single document a query selecting `DOCUMENT_COLUMNS` returns, or `NotFound`
+    fn query_document(
+        client: &mut impl GenericClient,
+        query: &str,
+        params: &[&(dyn postgres::types::ToSql + Sync)],
+    ) -> Result<Document, DatabaseError> {
+        match client.query_opt(query, params)? {
+            Some(row) => Self::row_to_document(&row),
+            None => Err(DatabaseError::NotFound),
+        }
+    }
+
+    fn row_to_audit_entry(row: &Row) -> Result<AuditEntry, DatabaseError> {
+        Ok(AuditEntry {
+            id: row.try_get(0)?,
+            action: row.try_get(1)?,
+            submitter: row.try_get(2)?,
+            block_number: row.try_get::<_, i64>(3)? as u64,
+            timestamp: row.try_get(4)?,
+            outcome: AuditOutcome::from_status(&row.try_get::<_, String>(5)?),
+            content_hash: row.try_get(6)?,
+        })
+    }
+
+    fn row_to_document(row: &Row) -> Result<Document, DatabaseError> {
+        let id = DocumentId::from(row.try_get::<_, String>(0)?);
+        let metadata = match row.try_get::<_, Option<String>>(15)? {
+            Some(json) => serde_json::from_str(&json).map_err(|e| {
+                DatabaseError::CorruptDatabase(format!("metadata of document {}: {}", id, e))
+            })?,
+            None => empty_metadata(),
+        };
+
+        Ok(Document {
+            id,
+            content_hash: ContentHash::from(row.try_get::<_, String>(1)?),
+            file_name: row.try_get(2)?,
+            mime_type: row.try_get(3)?,
+            submitted_by: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            expires_at: row.try_get(6)?,
+            supersedes: row.try_get(7)?,
+            detected_mime_type: row.try_get(8)?,
+            ipfs_cid: row.try_get(9)?,
+            content_hash_keccak: row.try_get(10)?,
+            blake2b_hash: row.try_get(11)?,
+            description: row.try_get(12)?,
+            wallet_signature: row.try_get(16)?,
+            signed_message: row.try_get(17)?,
+            epoch_index: row.try_get::<_, i64>(13)? as u64,
+            input_index: row.try_get::<_, i64>(14)? as u64,
+            metadata,
+        })
+    }
+}
+
+impl DocumentRepository for PostgresRepository {
+    fn save_document(&self, doc: &Document) -> Result<(), DatabaseError> {
+        self.run(|client| Self::insert_document(client, doc))
+    }
+
+    fn save_documents_atomic(&self, docs: &[&Document]) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            // Returning early drops the transaction, which rolls it back
+            for doc in docs {
+                Self::insert_document(&mut tx, doc)?;
+            }
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn save_document_with_content(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            Self::insert_document(&mut tx, doc)?;
+            tx.execute(
+                "INSERT INTO document_content (document_id, content) VALUES ($1, $2)",
+                &[&doc.id.as_str(), &content],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn get_content(&self, id: &DocumentId) -> Result<Vec<u8>, DatabaseError> {
+        self.run(|client| {
+            let content = client.query_opt(
+                "SELECT content FROM document_content WHERE document_id = $1",
+                &[&id.as_str()],
+            )?;
+
+            match content {
+                Some(row) => Ok(row.try_get(0)?),
+                None => Err(DatabaseError::NotFound),
+            }
+        })
+    }
+
+    fn find_by_hash(&self, hash: &ContentHash) -> Result<Document, DatabaseError> {
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE content_hash = $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&hash.as_str()],
+            )
+        })
+    }
+
+    fn find_many_by_hashes(
+        &self,
+        hashes: &[&ContentHash],
+    ) -> Result<Vec<Option<Document>>, DatabaseError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<&str> = hashes.iter().map(|hash| hash.as_str()).collect();
+        let found: HashMap<ContentHash, Document> = self.run(|client| {
+            let documents = Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE content_hash = ANY($1)",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&keys],
+            )?;
+            Ok(documents
+                .into_iter()
+                .map(|doc| (doc.content_hash.clone(), doc))
+                .collect())
+        })?;
+
+        Ok(hashes
+            .iter()
+            .map(|hash| found.get(*hash).cloned())
+            .collect())
+    }
+
+    fn find_by_cid(&self, cid: &str) -> Result<Document, DatabaseError> {
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE ipfs_cid = $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&cid],
+            )
+        })
+    }
+
+    fn find_by_keccak(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE content_hash_keccak = $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&hash],
+            )
+        })
+    }
+
+    fn find_by_blake2b_hash(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE blake2b_hash = $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&hash],
+            )
+        })
+    }
+
+    fn find_by_id(&self, id: &DocumentId) -> Result<Document, DatabaseError> {
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!("SELECT {} FROM documents WHERE id = $1", DOCUMENT_COLUMNS),
+                &[&id.as_str()],
+            )
+        })
+    }
+
+    fn find_by_input(&self, epoch: u64, input: u64) -> Result<Document, DatabaseError> {
+        let (epoch, input) = (i64::try_from(epoch)?, i64::try_from(input)?);
+        // Served by idx_input
+        self.run(|client| {
+            Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE epoch_index = $1 AND input_index = $2
+                     ORDER BY rowid
+                     LIMIT 1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&epoch, &input],
+            )
+        })
+    }
+
+    fn schema_version(&self) -> Result<u32, DatabaseError> {
+        let version: i64 = self.run(|client| {
+            Ok(client
+                .query_one("SELECT version FROM schema_version WHERE id = 1", &[])?
+                .try_get(0)?)
+        })?;
+        Ok(u32::try_from(version)?)
+    }
+
+    fn count_documents(&self) -> Result<usize, DatabaseError> {
+        let count: i64 = self.run(|client| {
+            Ok(client
+                .query_one("SELECT COUNT(*) FROM documents", &[])?
+                .try_get(0)?)
+        })?;
+
+        Ok(count as usize)
+    }
+
+    fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Document>, DatabaseError> {
+        let Some(expression) = tsquery_expression(query) else {
+            return Ok(Vec::new());
+        };
+        let limit = i64::try_from(limit)?;
+
+        // Best rank first, with filename, tag and MIME type hits weighted 10:5:1 as in
+        // SQLite's bm25; shorter documents rank higher. Equal ranks fall back to the newest
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     JOIN (SELECT document_id,
+                                  ts_rank('{{0, 0.1, 0.5, 1.0}}', document, query, 1) AS score
+                           FROM documents_fts, to_tsquery('simple', $1) query
+                           WHERE document @@ query) matches
+                         ON documents.id = matches.document_id
+                     ORDER BY score DESC, created_at DESC, id
+                     LIMIT $2",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&expression, &limit],
+            )
+        })
+    }
+
+    fn add_tags(&self, document_id: &DocumentId, tags: &[&str]) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            let stmt = tx.prepare(
+                "INSERT INTO document_tags (document_id, tag) VALUES ($1, $2)
+                 ON CONFLICT DO NOTHING",
+            )?;
+            for tag in tags {
+                tx.execute(&stmt, &[&document_id.as_str(), tag])?;
+            }
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn find_tags(&self, document_id: &DocumentId) -> Result<Vec<String>, DatabaseError> {
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT tag FROM document_tags WHERE document_id = $1 ORDER BY tag",
+                    &[&document_id.as_str()],
+                )?
+                .iter()
+                .map(|row| Ok(row.try_get(0)?))
+                .collect()
+        })
+    }
+
+    fn add_alias(
+        &self,
+        document_id: &DocumentId,
+        file_name: &str,
+        submitted_by: &str,
+        created_at: i64,
+    ) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            client.execute(
+                "INSERT INTO document_aliases (document_id, file_name, submitted_by, created_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &document_id.as_str(),
+                    &file_name,
+                    &submitted_by,
+                    &created_at,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn find_all_by_content_hash(&self, hash: &ContentHash) -> Result<Vec<String>, DatabaseError> {
+        // The document's own name sorts first with position 0; alias rowids start at 1
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT file_name, 0::BIGINT AS position FROM documents WHERE content_hash = $1
+                     UNION ALL
+                     SELECT a.file_name, a.rowid FROM document_aliases a
+                     JOIN documents d ON d.id = a.document_id
+                     WHERE d.content_hash = $1
+                     ORDER BY position",
+                    &[&hash.as_str()],
+                )?
+                .iter()
+                .map(|row| Ok(row.try_get(0)?))
+                .collect()
+        })
+    }
+
+    fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let (limit, offset) = (i64::try_from(limit)?, i64::try_from(offset)?);
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE id IN (SELECT document_id FROM document_tags WHERE tag = $1)
+                     ORDER BY created_at DESC, id
+                     LIMIT $2 OFFSET $3",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&tag, &limit, &offset],
+            )
+        })
+    }
+
+    fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let (limit, offset) = (i64::try_from(limit)?, i64::try_from(offset)?);
+        // Served by idx_created_at
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE created_at BETWEEN $1 AND $2
+                     ORDER BY created_at, id
+                     LIMIT $3 OFFSET $4",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&start, &end, &limit, &offset],
+            )
+        })
+    }
+
+    fn find_expired(
+        &self,
+        as_of: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let (limit, offset) = (i64::try_from(limit)?, i64::try_from(offset)?);
+        // Served by idx_expires_at; NULL means the document never expires
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE expires_at IS NOT NULL AND expires_at <= $1
+                     ORDER BY expires_at, id
+                     LIMIT $2 OFFSET $3",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&as_of, &limit, &offset],
+            )
+        })
+    }
+
+    fn find_versions(&self, root_hash: &ContentHash) -> Result<Vec<Document>, DatabaseError> {
+        self.run(|client| {
+            let mut versions = match Self::query_document(
+                client,
+                &format!(
+                    "SELECT {} FROM documents WHERE content_hash = $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&root_hash.as_str()],
+            ) {
+                Ok(document) => vec![document],
+                Err(DatabaseError::NotFound) => return Ok(Vec::new()),
+                Err(e) => return Err(e),
+            };
+
+            // Served by idx_supersedes; stops at a repeated hash so a tampered chain cannot loop
+            let successor = client.prepare(&format!(
+                "SELECT {} FROM documents WHERE supersedes = $1 ORDER BY created_at, id LIMIT 1",
+                DOCUMENT_COLUMNS
+            ))?;
+            loop {
+                let last = versions[versions.len() - 1].content_hash.as_str();
+                let Some(row) = client.query_opt(&successor, &[&last])? else {
+                    break;
+                };
+                let next = Self::row_to_document(&row)?;
+                if versions
+                    .iter()
+                    .any(|doc| doc.content_hash == next.content_hash)
+                {
+                    break;
+                }
+                versions.push(next);
+            }
+
+            Ok(versions)
+        })
+    }
+
+    fn list_documents(
+        &self,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let limit = i64::try_from(limit)?;
+
+        // Keyset pagination served by idx_created_at; the row value comparison
+        // resumes exactly after the last key of the previous page
+        self.run(|client| match after {
+            Some((created_at, id)) => Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE (created_at, id) < ($1, $2)
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $3",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&created_at, &id.as_str(), &limit],
+            ),
+            None => Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $1",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&limit],
+            ),
+        })
+    }
+
+    fn list_all(&self, limit: usize, offset: usize) -> Result<Vec<Document>, DatabaseError> {
+        let (limit, offset) = (i64::try_from(limit)?, i64::try_from(offset)?);
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     ORDER BY created_at, id
+                     LIMIT $1 OFFSET $2",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&limit, &offset],
+            )
+        })
+    }
+
+    fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let (created_at, id) = after.unzip();
+        let id = id.map(DocumentId::as_str);
+        let limit = i64::try_from(limit)?;
+
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE lower(submitted_by) = lower($1)
+                       AND ($2::BIGINT IS NULL OR (created_at, id) < ($2, $3))
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $4",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&submitter, &created_at, &id, &limit],
+            )
+        })
+    }
+
+    fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, DatabaseError> {
+        // Floor to midnight, also for timestamps before 1970 where `%` is negative
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT created_at - ((created_at % 86400) + 86400) % 86400 AS day, COUNT(*)
+                     FROM documents
+                     WHERE created_at BETWEEN $1 AND $2
+                     GROUP BY day
+                     ORDER BY day",
+                    &[&start, &end],
+                )?
+                .iter()
+                .map(|row| Ok((row.try_get(0)?, row.try_get::<_, i64>(1)? as usize)))
+                .collect()
+        })
+    }
+
+    fn count_by_submitter(&self, submitter: &str) -> Result<usize, DatabaseError> {
+        let count: i64 = self.run(|client| {
+            Ok(client
+                .query_one(
+                    "SELECT COUNT(*) FROM documents WHERE lower(submitted_by) = lower($1)",
+                    &[&submitter],
+                )?
+                .try_get(0)?)
+        })?;
+
+        Ok(count as usize)
+    }
+
+    fn list_submitters(&self) -> Result<Vec<(String, usize)>, DatabaseError> {
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT lower(submitted_by) AS submitter, COUNT(*) AS document_count
+                     FROM documents
+                     GROUP BY submitter
+                     ORDER BY document_count DESC, submitter",
+                    &[],
+                )?
+                .iter()
+                .map(|row| Ok((row.try_get(0)?, row.try_get::<_, i64>(1)? as usize)))
+                .collect()
+        })
+    }
+
+    fn count_by_submitter_top_n(&self, n: usize) -> Result<Vec<(String, usize)>, DatabaseError> {
+        let n = i64::try_from(n)?;
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT lower(submitted_by) AS submitter, COUNT(*) AS document_count
+                     FROM documents
+                     GROUP BY submitter
+                     ORDER BY document_count DESC, submitter
+                     LIMIT $1",
+                    &[&n],
+                )?
+                .iter()
+                .map(|row| Ok((row.try_get(0)?, row.try_get::<_, i64>(1)? as usize)))
+                .collect()
+        })
+    }
+
+    fn count_documents_since(&self, ts: i64) -> Result<usize, DatabaseError> {
+        // Served by idx_created_at
+        let count: i64 = self.run(|client| {
+            Ok(client
+                .query_one(
+                    "SELECT COUNT(*) FROM documents WHERE created_at >= $1",
+                    &[&ts],
+                )?
+                .try_get(0)?)
+        })?;
+
+        Ok(count as usize)
+    }
+
+    fn update_metadata(
+        &self,
+        content_hash: &ContentHash,
+        file_name: Option<&str>,
+        mime_type: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Document, DatabaseError> {
+        let metadata = metadata.and_then(metadata_column);
+        let updated = self.run(|client| {
+            Ok(client.execute(
+                "UPDATE documents
+                 SET file_name = COALESCE($2, file_name),
+                     mime_type = COALESCE($3, mime_type),
+                     metadata = COALESCE($4, metadata)
+                 WHERE content_hash = $1",
+                &[&content_hash.as_str(), &file_name, &mime_type, &metadata],
+            )?)
+        })?;
+
+        if updated == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+
+        self.find_by_hash(content_hash)
+    }
+
+    fn find_missing_digests(
+        &self,
+        blake2b: bool,
+        ipfs_cid: bool,
+        after: Option<&DocumentId>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        // Every id sorts after the empty string
+        let after = after.map(DocumentId::as_str).unwrap_or("");
+        let limit = i64::try_from(limit)?;
+        self.run(|client| {
+            Self::query_documents(
+                client,
+                &format!(
+                    "SELECT {} FROM documents
+                     WHERE id > $1
+                       AND (content_hash_keccak IS NULL
+                            OR ($2 AND blake2b_hash IS NULL)
+                            OR ($3 AND ipfs_cid IS NULL))
+                     ORDER BY id
+                     LIMIT $4",
+                    DOCUMENT_COLUMNS
+                ),
+                &[&after, &blake2b, &ipfs_cid, &limit],
+            )
+        })
+    }
+
+    fn update_digests(
+        &self,
+        id: &DocumentId,
+        keccak: Option<&str>,
+        blake2b: Option<&str>,
+        ipfs_cid: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let updated = self.run(|client| {
+            Ok(client.execute(
+                "UPDATE documents
+                 SET content_hash_keccak = COALESCE($2, content_hash_keccak),
+                     blake2b_hash = COALESCE($3, blake2b_hash),
+                     ipfs_cid = COALESCE($4, ipfs_cid)
+                 WHERE id = $1",
+                &[&id.as_str(), &keccak, &blake2b, &ipfs_cid],
+            )?)
+        })?;
+
+        if updated == 0 {
+            return Err(DatabaseError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn transfer_ownership(&self, transfer: &OwnershipTransfer) -> Result<(), DatabaseError> {
+        let block_number = transfer.block_number as i64;
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            // Only move the document if it is still held by the expected owner
+            let updated = tx.execute(
+                "UPDATE documents SET submitted_by = $2 WHERE content_hash = $1 AND submitted_by = $3",
+                &[
+                    &transfer.content_hash,
+                    &transfer.new_owner,
+                    &transfer.previous_owner,
+                ],
+            )?;
+
+            if updated == 0 {
+                return Err(DatabaseError::NotFound);
+            }
+
+            tx.execute(
+                "INSERT INTO ownership_history
+                    (document_id, content_hash, previous_owner, new_owner, transferred_at, block_number)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &transfer.document_id,
+                    &transfer.content_hash,
+                    &transfer.previous_owner,
+                    &transfer.new_owner,
+                    &transfer.transferred_at,
+                    &block_number,
+                ],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn find_ownership_history(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Vec<OwnershipTransfer>, DatabaseError> {
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT document_id, content_hash, previous_owner, new_owner, transferred_at, block_number
+                     FROM ownership_history
+                     WHERE content_hash = $1
+                     ORDER BY id",
+                    &[&content_hash.as_str()],
+                )?
+                .iter()
+                .map(|row| {
+                    Ok(OwnershipTransfer {
+                        document_id: row.try_get(0)?,
+                        content_hash: row.try_get(1)?,
+                        previous_owner: row.try_get(2)?,
+                        new_owner: row.try_get(3)?,
+                        transferred_at: row.try_get(4)?,
+                        block_number: row.try_get::<_, i64>(5)? as u64,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn append_audit_entry(&self, entry: &AuditEntry) -> Result<i64, DatabaseError> {
+        let block_number = i64::try_from(entry.block_number)?;
+        self.run(|client| {
+            Ok(client
+                .query_one(
+                    "INSERT INTO audit_log
+                         (action, submitter, block_number, timestamp, outcome, content_hash)
+                     VALUES ($1, $2, $3, $4, $5, $6)
+                     RETURNING id",
+                    &[
+                        &entry.action,
+                        &entry.submitter,
+                        &block_number,
+                        &entry.timestamp,
+                        &entry.outcome.as_str(),
+                        &entry.content_hash,
+                    ],
+                )?
+                .try_get(0)?)
+        })
+    }
+
+    fn find_audit_entries(
+        &self,
+        submitter: Option<&str>,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        after_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        // Blocks past i64::MAX cannot be stored, so such a bound behaves like i64::MAX
+        let block = |bound: Option<u64>| bound.map(|b| i64::try_from(b).unwrap_or(i64::MAX));
+        let (from_block, to_block) = (block(from_block), block(to_block));
+        let limit = i64::try_from(limit)?;
+
+        self.run(|client| {
+            client
+                .query(
+                    &format!(
+                        "SELECT {} FROM audit_log
+                         WHERE ($1::TEXT IS NULL OR lower(submitter) = lower($1))
+                           AND ($2::BIGINT IS NULL OR block_number >= $2)
+                           AND ($3::BIGINT IS NULL OR block_number <= $3)
+                           AND ($4::BIGINT IS NULL OR id > $4)
+                         ORDER BY id
+                         LIMIT $5",
+                        AUDIT_COLUMNS
+                    ),
+                    &[&submitter, &from_block, &to_block, &after_id, &limit],
+                )?
+                .iter()
+                .map(Self::row_to_audit_entry)
+                .collect()
+        })
+    }
+
+    fn list_audit_entries(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let (limit, offset) = (i64::try_from(limit)?, i64::try_from(offset)?);
+        self.run(|client| {
+            client
+                .query(
+                    &format!(
+                        "SELECT {} FROM audit_log ORDER BY id LIMIT $1 OFFSET $2",
+                        AUDIT_COLUMNS
+                    ),
+                    &[&limit, &offset],
+                )?
+                .iter()
+                .map(Self::row_to_audit_entry)
+                .collect()
+        })
+    }
+
+    fn delete_by_hash(&self, hash: &ContentHash) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            let deleted = tx.execute(
+                "DELETE FROM documents WHERE content_hash = $1",
+                &[&hash.as_str()],
+            )?;
+
+            if deleted == 0 {
+                return Err(DatabaseError::NotFound);
+            }
+
+            tx.execute(
+                "DELETE FROM ownership_history WHERE content_hash = $1",
+                &[&hash.as_str()],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn save_pending_notarization(
+        &self,
+        pending: &PendingNotarization,
+        cosigners: &[&str],
+    ) -> Result<(), DatabaseError> {
+        let expiration_blocks = pending.expiration_blocks.map(i64::try_from).transpose()?;
+        // Tags are validated to letters, digits, '-' and '_', so a comma is a safe separator
+        let tags = pending.tags.join(",");
+
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            let inserted = tx.execute(
+                "INSERT INTO pending_cosigns
+                    (content_hash, submitted_by, file_name, mime_type, content, expires_at,
+                     expiration_blocks, tags, supersedes, description, created_at, deadline,
+                     signature, signed_message)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+                &[
+                    &pending.content_hash,
+                    &pending.submitted_by,
+                    &pending.file_name,
+                    &pending.mime_type,
+                    &pending.content,
+                    &pending.expires_at,
+                    &expiration_blocks,
+                    &tags,
+                    &pending.supersedes,
+                    &pending.description,
+                    &pending.created_at,
+                    &pending.deadline,
+                    &pending.signature,
+                    &pending.signed_message,
+                ],
+            );
+
+            match inserted {
+                Ok(_) => {}
+                Err(e) if is_constraint_violation(&e) => return Err(DatabaseError::DuplicateHash),
+                Err(e) => return Err(e.into()),
+            }
+
+            for cosigner in cosigners {
+                tx.execute(
+                    "INSERT INTO document_cosigners (content_hash, cosigner) VALUES ($1, $2)",
+                    &[&pending.content_hash, cosigner],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn find_pending_notarization(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, DatabaseError> {
+        self.run(|client| {
+            let Some(row) = client.query_opt(
+                "SELECT content_hash, submitted_by, file_name, mime_type, content, expires_at,
+                        expiration_blocks, tags, supersedes, description, created_at, deadline,
+                        signature, signed_message
+                 FROM pending_cosigns WHERE content_hash = $1",
+                &[&content_hash],
+            )?
+            else {
+                return Ok(None);
+            };
+
+            let tags: String = row.try_get(7)?;
+            Ok(Some(PendingNotarization {
+                content_hash: row.try_get(0)?,
+                submitted_by: row.try_get(1)?,
+                file_name: row.try_get(2)?,
+                mime_type: row.try_get(3)?,
+                content: row.try_get(4)?,
+                expires_at: row.try_get(5)?,
+                expiration_blocks: row.try_get::<_, Option<i64>>(6)?.map(|b| b as u64),
+                tags: tags
+                    .split(',')
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                supersedes: row.try_get(8)?,
+                description: row.try_get(9)?,
+                signature: row.try_get(12)?,
+                signed_message: row.try_get(13)?,
+                created_at: row.try_get(10)?,
+                deadline: row.try_get(11)?,
+            }))
+        })
+    }
+
+    fn find_cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, DatabaseError> {
+        self.run(|client| {
+            client
+                .query(
+                    "SELECT cosigner, signature, signed_at FROM document_cosigners
+                     WHERE content_hash = $1
+                     ORDER BY cosigner",
+                    &[&content_hash],
+                )?
+                .iter()
+                .map(|row| {
+                    Ok(Cosigner {
+                        address: row.try_get(0)?,
+                        signature: row.try_get(1)?,
+                        signed_at: row.try_get(2)?,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn record_cosignature(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        signed_at: i64,
+    ) -> Result<bool, DatabaseError> {
+        let updated = self.run(|client| {
+            Ok(client.execute(
+                "UPDATE document_cosigners SET signature = $3, signed_at = $4
+                 WHERE content_hash = $1 AND cosigner = $2 AND signed_at IS NULL",
+                &[&content_hash, &cosigner, &signature, &signed_at],
+            )?)
+        })?;
+        Ok(updated > 0)
+    }
+
+    fn delete_pending_notarization(&self, content_hash: &str) -> Result<(), DatabaseError> {
+        // Cosigner rows cascade with the pending notarization
+        self.run(|client| {
+            client.execute(
+                "DELETE FROM pending_cosigns WHERE content_hash = $1",
+                &[&content_hash],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn start_upload(&self, session: &UploadSession) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            let result = client.execute(
+                "INSERT INTO upload_sessions (upload_id, submitted_by, file_name, mime_type, started_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &session.upload_id,
+                    &session.submitted_by,
+                    &session.file_name,
+                    &session.mime_type,
+                    &session.started_at,
+                ],
+            );
+
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) if is_constraint_violation(&e) => Err(DatabaseError::DuplicateUpload),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn find_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError> {
+        self.run(|client| {
+            let Some(row) = client.query_opt(
+                "SELECT s.upload_id, s.submitted_by, s.file_name, s.mime_type, s.started_at,
+                        COUNT(c.chunk_index), COALESCE(SUM(octet_length(c.data)), 0)
+                 FROM upload_sessions s
+                 LEFT JOIN upload_chunks c ON c.upload_id = s.upload_id
+                 WHERE s.upload_id = $1
+                 GROUP BY s.upload_id",
+                &[&upload_id],
+            )?
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some(UploadSession {
+                upload_id: row.try_get(0)?,
+                submitted_by: row.try_get(1)?,
+                file_name: row.try_get(2)?,
+                mime_type: row.try_get(3)?,
+                started_at: row.try_get(4)?,
+                chunk_count: u32::try_from(row.try_get::<_, i64>(5)?)?,
+                total_bytes: row.try_get::<_, i64>(6)? as u64,
+            }))
+        })
+    }
+
+    fn append_chunk(&self, upload_id: &str, index: u32, data: &[u8]) -> Result<(), DatabaseError> {
+        self.run(|client| {
+            client.execute(
+                "INSERT INTO upload_chunks (upload_id, chunk_index, data) VALUES ($1, $2, $3)",
+                &[&upload_id, &i64::from(index), &data],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn upload_content(&self, upload_id: &str) -> Result<Vec<u8>, DatabaseError> {
+        self.run(|client| {
+            let mut content = Vec::new();
+            for row in client.query(
+                "SELECT data FROM upload_chunks WHERE upload_id = $1 ORDER BY chunk_index",
+                &[&upload_id],
+            )? {
+                content.extend_from_slice(row.try_get::<_, &[u8]>(0)?);
+            }
+
+            Ok(content)
+        })
+    }
+
+    fn delete_upload(&self, upload_id: &str) -> Result<(), DatabaseError> {
+        // Staged chunks cascade with the session
+        self.run(|client| {
+            client.execute(
+                "DELETE FROM upload_sessions WHERE upload_id = $1",
+                &[&upload_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn save_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+        document_id: &DocumentId,
+        block_number: u64,
+    ) -> Result<(), DatabaseError> {
+        let block_number = i64::try_from(block_number)?;
+        self.run(|client| {
+            client.execute(
+                "INSERT INTO idempotency (idempotency_key, submitter, document_id, block_number)
+                 VALUES ($1, $2, $3, $4)",
+                &[
+                    &key,
+                    &submitter.to_lowercase(),
+                    &document_id.as_str(),
+                    &block_number,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn find_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+    ) -> Result<Option<(DocumentId, u64)>, DatabaseError> {
+        self.run(|client| {
+            let Some(row) = client.query_opt(
+                "SELECT document_id, block_number FROM idempotency
+                 WHERE idempotency_key = $1 AND submitter = $2",
+                &[&key, &submitter.to_lowercase()],
+            )?
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some((
+                DocumentId::from(row.try_get::<_, String>(0)?),
+                row.try_get::<_, i64>(1)? as u64,
+            )))
+        })
+    }
+
+    fn find_open_epoch(&self) -> Result<Option<u64>, DatabaseError> {
+        self.run(|client| {
+            let epoch = client.query_opt("SELECT open_epoch FROM epoch_state WHERE id = 1", &[])?;
+            Ok(epoch
+                .map(|row| row.try_get::<_, i64>(0))
+                .transpose()?
+                .map(|epoch| epoch as u64))
+        })
+    }
+
+    fn open_epoch(&self, epoch: u64) -> Result<(), DatabaseError> {
+        self.run(|client| Self::set_open_epoch(client, epoch))
+    }
+
+    fn seal_epoch(&self, epoch: u64, next_epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            // Documents in notarization order that no earlier epoch has committed to
+            let unsealed = tx
+                .query(
+                    "SELECT d.id, d.content_hash FROM documents d
+                     WHERE NOT EXISTS (SELECT 1 FROM epoch_leaves l WHERE l.document_id = d.id)
+                     ORDER BY d.rowid",
+                    &[],
+                )?
+                .iter()
+                .map(|row| Ok((row.try_get::<_, String>(0)?, row.try_get::<_, String>(1)?)))
+                .collect::<Result<Vec<_>, DatabaseError>>()?;
+
+            let hashes: Vec<String> = unsealed.iter().map(|(_, hash)| hash.clone()).collect();
+            let sealed = match merkle_root(&hashes) {
+                Some(root) => {
+                    let epoch_index = i64::try_from(epoch)?;
+                    tx.execute(
+                        "INSERT INTO epoch_roots (epoch, root, leaf_count) VALUES ($1, $2, $3)",
+                        &[&epoch_index, &root, &(hashes.len() as i64)],
+                    )?;
+                    let leaf = tx.prepare(
+                        "INSERT INTO epoch_leaves (epoch, position, document_id, content_hash)
+                         VALUES ($1, $2, $3, $4)",
+                    )?;
+                    for (position, (document_id, content_hash)) in unsealed.iter().enumerate() {
+                        tx.execute(
+                            &leaf,
+                            &[&epoch_index, &(position as i64), document_id, content_hash],
+                        )?;
+                    }
+
+                    Some(EpochRoot {
+                        epoch,
+                        root,
+                        leaf_count: hashes.len(),
+                    })
+                }
+                None => None,
+            };
+
+            Self::set_open_epoch(&mut tx, next_epoch)?;
+            tx.commit()?;
+            Ok(sealed)
+        })
+    }
+
+    fn find_epoch_root(&self, epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        let epoch_index = i64::try_from(epoch)?;
+        self.run(|client| {
+            let Some(row) = client.query_opt(
+                "SELECT root, leaf_count FROM epoch_roots WHERE epoch = $1",
+                &[&epoch_index],
+            )?
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some(EpochRoot {
+                epoch,
+                root: row.try_get(0)?,
+                leaf_count: row.try_get::<_, i64>(1)? as usize,
+            }))
+        })
+    }
+
+    fn generate_inclusion_proof(
+        &self,
+        content_hash: &ContentHash,
+        epoch: u64,
+    ) -> Result<MerkleProof, DatabaseError> {
+        let epoch = i64::try_from(epoch)?;
+        let leaves = self.run(|client| {
+            client
+                .query(
+                    "SELECT content_hash FROM epoch_leaves WHERE epoch = $1 ORDER BY position",
+                    &[&epoch],
+                )?
+                .iter()
+                .map(|row| Ok(row.try_get::<_, String>(0)?))
+                .collect::<Result<Vec<_>, DatabaseError>>()
+        })?;
+
+        let content_hash = content_hash.as_str().to_lowercase();
+        leaves
+            .iter()
+            .position(|leaf| *leaf == content_hash)
+            .and_then(|index| merkle_proof(&leaves, index))
+            .ok_or_else(|| DatabaseError::NotFound)
+    }
+
+    fn find_last_nonce(&self, submitter: &str) -> Result<Option<u64>, DatabaseError> {
+        self.run(|client| {
+            let last = client.query_opt(
+                "SELECT last_nonce FROM nonces WHERE submitter = $1",
+                &[&submitter.to_lowercase()],
+            )?;
+            Ok(last
+                .map(|row| row.try_get::<_, i64>(0))
+                .transpose()?
+                .map(|nonce| nonce as u64))
+        })
+    }
+
+    fn advance_nonce(&self, submitter: &str, nonce: u64) -> Result<bool, DatabaseError> {
+        let nonce = i64::try_from(nonce)?;
+
+        // Single statement so the comparison and the write cannot interleave
+        let updated = self.run(|client| {
+            Ok(client.execute(
+                "INSERT INTO nonces (submitter, last_nonce) VALUES ($1, $2)
+                 ON CONFLICT (submitter) DO UPDATE SET last_nonce = excluded.last_nonce
+                 WHERE excluded.last_nonce > nonces.last_nonce",
+                &[&submitter.to_lowercase(), &nonce],
+            )?)
+        })?;
+
+        Ok(updated == 1)
+    }
+
+    fn find_balance(&self, owner: &str, token: &str) -> Result<u128, DatabaseError> {
+        self.run(|client| Self::read_balance(client, owner, token))
+    }
+
+    fn credit_balance(
+        &self,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<u128, DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            let balance = Self::read_balance(&mut tx, owner, token)?
+                .checked_add(amount)
+                .ok_or(DatabaseError::BalanceOverflow)?;
+            Self::write_balance(&mut tx, owner, token, balance)?;
+
+            tx.commit()?;
+            Ok(balance)
+        })
+    }
+
+    fn debit_balance(&self, owner: &str, token: &str, amount: u128) -> Result<bool, DatabaseError> {
+        self.run(|client| {
+            let mut tx = client.transaction()?;
+
+            let Some(balance) = Self::read_balance(&mut tx, owner, token)?.checked_sub(amount)
+            else {
+                return Ok(false);
+            };
+            Self::write_balance(&mut tx, owner, token, balance)?;
+
+            tx.commit()?;
+            Ok(true)
+        })
+    }
+
+    fn find_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        let epoch = i64::try_from(epoch)?;
+        self.run(|client| {
+            let used = client.query_opt(
+                "SELECT used FROM quota WHERE submitter = $1 AND epoch_index = $2",
+                &[&submitter.to_lowercase(), &epoch],
+            )?;
+            Ok(used
+                .map(|row| row.try_get::<_, i64>(0))
+                .transpose()?
+                .unwrap_or(0) as u64)
+        })
+    }
+
+    fn increment_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        let epoch = i64::try_from(epoch)?;
+        let used: i64 = self.run(|client| {
+            Ok(client
+                .query_one(
+                    "INSERT INTO quota (submitter, epoch_index, used) VALUES ($1, $2, 1)
+                     ON CONFLICT (submitter, epoch_index) DO UPDATE SET used = quota.used + 1
+                     RETURNING used",
+                    &[&submitter.to_lowercase(), &epoch],
+                )?
+                .try_get(0)?)
+        })?;
+
+        Ok(used as u64)
+    }
+
+    fn save_commitment(&self, commitment: &Commitment) -> Result<(), DatabaseError> {
+        let block_number = i64::try_from(commitment.block_number)?;
+        self.run(|client| {
+            let result = client.execute(
+                "INSERT INTO commitments (content_hash, committed_by, block_number, timestamp)
+                 VALUES ($1, $2, $3, $4)",
+                &[
+                    &commitment.content_hash,
+                    &commitment.committed_by,
+                    &block_number,
+                    &commitment.timestamp,
+                ],
+            );
+
+            match result {
+                Ok(_) => Ok(()),
+                Err(e) if is_constraint_violation(&e) => Err(DatabaseError::DuplicateHash),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn find_commitment(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Option<Commitment>, DatabaseError> {
+        self.run(|client| {
+            let Some(row) = client.query_opt(
+                "SELECT content_hash, committed_by, block_number, timestamp
+                 FROM commitments WHERE content_hash = $1",
+                &[&content_hash.as_str()],
+            )?
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some(Commitment {
+                content_hash: row.try_get(0)?,
+                committed_by: row.try_get(1)?,
+                block_number: row.try_get::<_, i64>(2)? as u64,
+                timestamp: row.try_get(3)?,
+            }))
+        })
+    }
+}
+
+/// Turn free text into a tsquery matching every word as a prefix
+/// Words are split into letters and digits like the indexed text, so `acme-contract`
+/// matches `acme` directly followed by a word starting with `contract`
+fn tsquery_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter_map(|word| {
+            let tokens: Vec<String> = word
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|token| !token.is_empty())
+                .map(|token| format!("'{}'", token.to_lowercase()))
+                .collect();
+            (!tokens.is_empty()).then(|| format!("({}:*)", tokens.join(" <-> ")))
+        })
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" & "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsquery_expression_splits_punctuation() {
+        assert_eq!(
+            tsquery_expression("acme  application/pd").as_deref(),
+            Some("('acme':*) & ('application' <-> 'pd':*)")
+        );
+        assert_eq!(
+            tsquery_expression("Say \"hi\" -").as_deref(),
+            Some("('say':*) & ('hi':*)")
+        );
+        assert!(tsquery_expression(" - ").is_none());
+    }
+}
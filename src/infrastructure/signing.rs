@@ -0,0 +1,168 @@
+use crate::domain::NotarizationReceipt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Secret used to sign outgoing notices (NOTARY_NOTICE_SIGNING_KEY)
+/// The key bytes are never printed, so configs can be logged safely
+#[derive(Clone, PartialEq, Eq)]
+pub struct NoticeSigningKey(Vec<u8>);
+
+impl NoticeSigningKey {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self(key.into())
+    }
+
+    /// Hex-encoded HMAC-SHA256 of the receipt's canonical JSON
+    pub fn sign_receipt(&self, receipt: &NotarizationReceipt) -> String {
+        hex::encode(hmac_sha256(
+            &self.0,
+            canonical_receipt_json(receipt).as_bytes(),
+        ))
+    }
+
+    /// Hex-encoded HMAC-SHA256 of `value` in canonical JSON form
+    pub fn sign_json(&self, value: &serde_json::Value) -> String {
+        hex::encode(hmac_sha256(&self.0, canonical_json(value).as_bytes()))
+    }
+}
+
+impl fmt::Debug for NoticeSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NoticeSigningKey(<redacted>)")
+    }
+}
+
+/// Check a notice signature against the receipt it was sent with
+/// Clients holding the signing key use this to confirm a notice came from this DApp
+pub fn verify_receipt_signature(
+    receipt: &NotarizationReceipt,
+    key: &[u8],
+    signature: &str,
+) -> bool {
+    signature_matches(key, canonical_receipt_json(receipt).as_bytes(), signature)
+}
+
+/// Check a signature made by `NoticeSigningKey::sign_json` against the value it covers
+pub fn verify_json_signature(value: &serde_json::Value, key: &[u8], signature: &str) -> bool {
+    signature_matches(key, canonical_json(value).as_bytes(), signature)
+}
+
+/// Whether the hex `signature` is the HMAC-SHA256 of `message` under `key`
+/// `verify_slice` compares in constant time, so the check does not leak the matching prefix
+fn signature_matches(key: &[u8], message: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Receipt serialized with keys in lexicographic order and no whitespace
+/// This form is what gets signed, independent of how the notice itself is laid out
+/// `committed_at` is only included when set, so receipts without it sign as before
+pub fn canonical_receipt_json(receipt: &NotarizationReceipt) -> String {
+    let committed_at = receipt
+        .committed_at
+        .map(|block| format!(r#""committed_at":{},"#, block))
+        .unwrap_or_default();
+    format!(
+        r#"{{"block_number":{},{}"content_hash":{},"document_id":{},"notarized_at":{},"proof":{}}}"#,
+        receipt.block_number,
+        committed_at,
+        json_string(&receipt.content_hash),
+        json_string(&receipt.document_id),
+        receipt.notarized_at,
+        json_string(&receipt.proof),
+    )
+}
+
+/// JSON with object keys in lexicographic order and no whitespace
+/// Keys are sorted explicitly, so the form does not depend on how `serde_json` orders maps
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let fields: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", json_string(key), canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        scalar => scalar.to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// HMAC-SHA256 as defined in RFC 2104
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from RFC 4231
+    #[test]
+    fn test_hmac_sha256_rfc4231_case_2() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_long_key() {
+        let key = [0xaa; 131];
+        let mac = hmac_sha256(
+            &key,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        assert_eq!(
+            hex::encode(mac),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_signature_matches_only_the_exact_mac() {
+        let key = b"notice-key";
+        let signature = hex::encode(hmac_sha256(key, b"message"));
+        assert!(signature_matches(key, b"message", &signature));
+        assert!(!signature_matches(key, b"other message", &signature));
+        assert!(!signature_matches(b"other key", b"message", &signature));
+        // A truncated MAC or one that is not hex never matches
+        assert!(!signature_matches(key, b"message", &signature[..62]));
+        assert!(!signature_matches(key, b"message", "not hex"));
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_nested_keys() {
+        let value = serde_json::json!({"b": [{"z": 1, "a": "x"}], "a": null});
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":null,"b":[{"a":"x","z":1}]}"#
+        );
+    }
+
+    #[test]
+    fn test_debug_hides_key() {
+        let key = NoticeSigningKey::new("super-secret");
+        assert!(!format!("{:?}", key).contains("super-secret"));
+    }
+}
@@ -1,9 +1,13 @@
 pub mod application;
 pub mod domain;
+pub mod error;
 pub mod handlers;
 pub mod infrastructure;
+pub mod notary;
 
 // Re-export commonly used types
 pub use application::{NotarizeUseCase, VerificationResult, VerifyUseCase};
 pub use domain::{Document, NotarizationReceipt};
-pub use infrastructure::database::{DocumentRepository, SqliteRepository};
+pub use error::NotaryError;
+pub use infrastructure::database::{DocumentRepository, PooledSqliteRepository, SqliteRepository};
+pub use notary::Notary;
@@ -1,38 +1,72 @@
-use dapp::handlers::{get_repository, handle_advance, handle_inspect};
+use dapp::application::Config;
+use dapp::handlers::{handle_advance_or_throw, handle_inspect};
+use dapp::infrastructure::cartesi::Endpoints;
+use dapp::Notary;
 use json::object;
 use std::env;
+use tracing::{debug, error, info, warn};
+
+/// Initialize the global tracing subscriber
+/// Set NOTARY_LOG_FORMAT=json for machine-readable output, defaults to pretty text
+fn init_tracing() {
+    let json_format = env::var("NOTARY_LOG_FORMAT")
+        .map(|format| format.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    if json_format {
+        tracing_subscriber::fmt().json().init();
+    } else {
+        tracing_subscriber::fmt().pretty().init();
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Starting Cartesi Notary DApp");
+    init_tracing();
+
+    info!("Starting Cartesi Notary DApp");
 
-    // Test database connection
-    let test_repo = get_repository();
-    println!(
-        "Database initialized with {} documents",
-        test_repo.count_documents().unwrap_or(0)
+    let config = Config::from_env().map_err(|e| {
+        error!(error = %e, "Invalid configuration");
+        e
+    })?;
+
+    // Open the database once and share it across all requests
+    let notary = Notary::new(config).map_err(|e| {
+        error!(error = %e, "Failed to initialize database");
+        e
+    })?;
+    let (repository, config) = (notary.repository(), notary.config());
+    info!(
+        documents = repository.count_documents().unwrap_or(0),
+        "Database initialized"
     );
-    drop(test_repo); // Close test connection
 
     let client = hyper::Client::new();
-    let server_addr = env::var("ROLLUP_HTTP_SERVER_URL")?;
+    let server_addr = &config.rollup_server_url;
+
+    // Validate the endpoint URLs once before entering the loop
+    let endpoints = Endpoints::new(server_addr, &config.rollup_path_prefix).map_err(|e| {
+        error!(error = %e, "Invalid rollup server endpoints");
+        e
+    })?;
 
-    println!("Connected to rollup server at: {}", server_addr);
+    info!(server_addr = %server_addr, "Connected to rollup server");
 
     let mut status = "accept";
     loop {
-        println!("Sending finish with status: {}", status);
+        debug!(status, "Sending finish");
         let response = object! {"status" => status};
         let request = hyper::Request::builder()
             .method(hyper::Method::POST)
             .header(hyper::header::CONTENT_TYPE, "application/json")
-            .uri(format!("{}/finish", &server_addr))
+            .uri(endpoints.finish_url())
             .body(hyper::Body::from(response.dump()))?;
         let response = client.request(request).await?;
-        println!("Received finish status {}", response.status());
+        debug!(status = %response.status(), "Received finish status");
 
         if response.status() == hyper::StatusCode::ACCEPTED {
-            println!("No pending rollup request, trying again");
+            debug!("No pending rollup request, trying again");
         } else {
             let body = hyper::body::to_bytes(response).await?;
             let utf = std::str::from_utf8(&body)?;
@@ -42,13 +76,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .as_str()
                 .ok_or("request_type is not a string")?;
 
-            println!("Processing request type: {}", request_type);
+            info!(request_type, "Processing request");
 
             status = match request_type {
-                "advance_state" => handle_advance(&client, &server_addr[..], req).await?,
-                "inspect_state" => handle_inspect(&client, &server_addr[..], req).await?,
+                // Invalid inputs are rejected; system failures raise an exception and stop the DApp
+                "advance_state" => {
+                    handle_advance_or_throw(&client, server_addr, repository, config, req).await?
+                }
+                "inspect_state" => {
+                    handle_inspect(&client, server_addr, repository, config, req).await?
+                }
                 &_ => {
-                    eprintln!("Unknown request type: {}", request_type);
+                    warn!(request_type, "Unknown request type");
                     "reject"
                 }
             };
@@ -0,0 +1,113 @@
+use crate::application::{
+    Config, DocumentPage, NotarizeConfig, NotarizeOptions, NotarizeUseCase, SearchUseCase,
+    VerificationResult, VerifyUseCase,
+};
+use crate::domain::NotarizationReceipt;
+use crate::error::NotaryError;
+use crate::handlers::get_repository;
+use crate::infrastructure::database::DocumentRepository;
+use std::error::Error;
+
+/// Recommended entry point for using the notary as a library
+///
+/// Owns the repository and the configuration, and builds the use cases for
+/// each call; the use cases stay public for anything the facade does not cover.
+///
+/// ```
+/// use dapp::application::Config;
+/// use dapp::{Notary, SqliteRepository};
+///
+/// let repository = SqliteRepository::new_in_memory().unwrap();
+/// let notary = Notary::with_repository(Box::new(repository), Config::default());
+///
+/// let submitter = "0x0000000000000000000000000000000000000123";
+/// let receipt = notary
+///     .notarize(b"contract", "contract.txt", "text/plain", submitter, 1)
+///     .unwrap();
+/// assert!(notary.verify(&receipt.content_hash).unwrap().exists);
+/// ```
+pub struct Notary {
+    repository: Box<dyn DocumentRepository>,
+    config: Config,
+}
+
+impl Notary {
+    /// Open the repository described by `config`, as the DApp does at startup
+    pub fn new(config: Config) -> Result<Self, Box<dyn Error>> {
+        let repository = get_repository(&config)?;
+        Ok(Self::with_repository(repository, config))
+    }
+
+    /// Use an already opened repository
+    pub fn with_repository(repository: Box<dyn DocumentRepository>, config: Config) -> Self {
+        Self { repository, config }
+    }
+
+    pub fn repository(&self) -> &dyn DocumentRepository {
+        self.repository.as_ref()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Notarize `content` on behalf of `submitter`, applying the configured policy
+    pub fn notarize(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitter: &str,
+        block_number: u64,
+    ) -> Result<NotarizationReceipt, NotaryError> {
+        self.notarize_with_options(
+            content,
+            file_name,
+            mime_type,
+            submitter,
+            block_number,
+            &NotarizeOptions::default(),
+        )
+    }
+
+    /// Notarize with an expiry, tags, description or other options
+    pub fn notarize_with_options(
+        &self,
+        content: &[u8],
+        file_name: &str,
+        mime_type: &str,
+        submitter: &str,
+        block_number: u64,
+        options: &NotarizeOptions,
+    ) -> Result<NotarizationReceipt, NotaryError> {
+        NotarizeUseCase::new(self.repository(), NotarizeConfig::from(&self.config))
+            .execute_with_options(
+                content,
+                file_name,
+                mime_type,
+                submitter,
+                block_number,
+                options,
+            )
+    }
+
+    /// Look up a document by content hash
+    pub fn verify(&self, content_hash: &str) -> Result<VerificationResult, NotaryError> {
+        VerifyUseCase::new(self.repository()).execute(content_hash)
+    }
+
+    /// Look up a document by the id from its receipt
+    pub fn verify_by_id(&self, id: &str) -> Result<VerificationResult, NotaryError> {
+        VerifyUseCase::new(self.repository()).execute_by_id(id)
+    }
+
+    /// All documents newest first, one page at a time
+    /// `after` is the `next_cursor` of the previous page, encoded
+    pub fn list(
+        &self,
+        after: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<DocumentPage, Box<dyn Error>> {
+        SearchUseCase::new(self.repository()).list_documents(after, limit)
+    }
+}
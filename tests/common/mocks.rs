@@ -0,0 +1,1028 @@
+use dapp::domain::{
+    merkle_proof, merkle_root, AuditEntry, Commitment, ContentHash, Cosigner, Document, DocumentId,
+    EpochRoot, MerkleProof, OwnershipTransfer, PendingNotarization, UploadSession,
+};
+use dapp::infrastructure::database::{DatabaseError, DocumentRepository, SCHEMA_VERSION};
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// In-memory `DocumentRepository` for use case tests that do not need SQLite
+///
+/// Results follow the same order and case rules as `SqliteRepository`, and uniqueness
+/// and references are checked like its constraints. Full-text search is approximated
+/// by prefix matching on the words of the filename and MIME type.
+#[derive(Default)]
+pub struct MockDocumentRepository {
+    /// The next `save_document*` call fails with a database error
+    pub fail_next_save: Cell<bool>,
+    /// The next `find_by*` or `find_many_by_hashes` call fails with a database error
+    pub fail_next_find: Cell<bool>,
+    state: RefCell<State>,
+}
+
+#[derive(Default)]
+struct State {
+    documents: HashMap<ContentHash, Document>,
+    /// Content hashes in the order they were saved, like SQLite rowids
+    saved_order: Vec<ContentHash>,
+    content: HashMap<DocumentId, Vec<u8>>,
+    tags: HashMap<DocumentId, BTreeSet<String>>,
+    aliases: HashMap<DocumentId, Vec<String>>,
+    ownership_history: Vec<OwnershipTransfer>,
+    audit_log: Vec<AuditEntry>,
+    uploads: HashMap<String, UploadSession>,
+    chunks: HashMap<String, BTreeMap<u32, Vec<u8>>>,
+    pending: HashMap<String, PendingNotarization>,
+    cosigners: HashMap<String, BTreeMap<String, Cosigner>>,
+    idempotency: HashMap<(String, String), (DocumentId, u64)>,
+    open_epoch: Option<u64>,
+    epoch_roots: HashMap<u64, EpochRoot>,
+    /// Document ids and content hashes committed to each epoch, in leaf order
+    epoch_leaves: HashMap<u64, Vec<(DocumentId, String)>>,
+    nonces: HashMap<String, u64>,
+    balances: HashMap<(String, String), u128>,
+    quota: HashMap<(String, u64), u64>,
+    commitments: HashMap<String, Commitment>,
+}
+
+impl MockDocumentRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn check_save(&self) -> Result<(), DatabaseError> {
+        if self.fail_next_save.replace(false) {
+            return Err(failure(
+                rusqlite::ffi::SQLITE_IOERR,
+                "injected save failure",
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_find(&self) -> Result<(), DatabaseError> {
+        if self.fail_next_find.replace(false) {
+            return Err(failure(
+                rusqlite::ffi::SQLITE_IOERR,
+                "injected find failure",
+            ));
+        }
+        Ok(())
+    }
+
+    fn find_one(&self, predicate: impl Fn(&Document) -> bool) -> Result<Document, DatabaseError> {
+        self.check_find()?;
+        self.state
+            .borrow()
+            .documents
+            .values()
+            .find(|doc| predicate(doc))
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    /// Documents matching `predicate`, sorted by `key`
+    fn find_sorted<K: Ord>(
+        &self,
+        predicate: impl Fn(&Document) -> bool,
+        key: impl Fn(&Document) -> K,
+    ) -> Vec<Document> {
+        let mut documents: Vec<Document> = self
+            .state
+            .borrow()
+            .documents
+            .values()
+            .filter(|doc| predicate(doc))
+            .cloned()
+            .collect();
+        documents.sort_by_key(|doc| key(doc));
+        documents
+    }
+}
+
+impl State {
+    fn insert_document(&mut self, doc: &Document) -> Result<(), DatabaseError> {
+        let id_taken = self.documents.values().any(|stored| stored.id == doc.id);
+        if id_taken || self.documents.contains_key(&doc.content_hash) {
+            return Err(DatabaseError::DuplicateHash);
+        }
+
+        self.documents.insert(doc.content_hash.clone(), doc.clone());
+        self.saved_order.push(doc.content_hash.clone());
+        Ok(())
+    }
+
+    fn check_document(&self, id: &DocumentId) -> Result<(), DatabaseError> {
+        if self.documents.values().any(|doc| doc.id == *id) {
+            Ok(())
+        } else {
+            Err(constraint_violation("unknown document id"))
+        }
+    }
+
+    fn submitter_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for doc in self.documents.values() {
+            *counts.entry(doc.submitted_by.to_lowercase()).or_default() += 1;
+        }
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+}
+
+fn failure(code: std::os::raw::c_int, message: &str) -> DatabaseError {
+    DatabaseError::SqliteError(rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(code),
+        Some(message.to_string()),
+    ))
+}
+
+fn constraint_violation(message: &str) -> DatabaseError {
+    failure(rusqlite::ffi::SQLITE_CONSTRAINT, message)
+}
+
+/// Whether `(created_at, id)` sorts before the cursor, as SQLite compares row values
+fn is_before(doc: &Document, after: Option<(i64, &DocumentId)>) -> bool {
+    after.is_none_or(|(created_at, id)| (doc.created_at, &doc.id) < (created_at, id))
+}
+
+/// Lowercase words of `text`, split like the default FTS5 tokenizer
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn paginate(documents: Vec<Document>, limit: usize, offset: usize) -> Vec<Document> {
+    documents.into_iter().skip(offset).take(limit).collect()
+}
+
+impl DocumentRepository for MockDocumentRepository {
+    fn save_document(&self, doc: &Document) -> Result<(), DatabaseError> {
+        self.check_save()?;
+        self.state.borrow_mut().insert_document(doc)
+    }
+
+    fn save_documents_atomic(&self, docs: &[&Document]) -> Result<(), DatabaseError> {
+        self.check_save()?;
+        let mut state = self.state.borrow_mut();
+
+        // Check the whole batch first, so a failure leaves nothing saved
+        let mut hashes = HashSet::new();
+        let mut ids = HashSet::new();
+        for doc in docs {
+            let stored = state.documents.contains_key(&doc.content_hash)
+                || state.documents.values().any(|stored| stored.id == doc.id);
+            if stored || !hashes.insert(&doc.content_hash) || !ids.insert(&doc.id) {
+                return Err(DatabaseError::DuplicateHash);
+            }
+        }
+
+        for doc in docs {
+            state.insert_document(doc)?;
+        }
+        Ok(())
+    }
+
+    fn save_document_with_content(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(), DatabaseError> {
+        self.check_save()?;
+        let mut state = self.state.borrow_mut();
+        state.insert_document(doc)?;
+        state.content.insert(doc.id.clone(), content.to_vec());
+        Ok(())
+    }
+
+    fn get_content(&self, id: &DocumentId) -> Result<Vec<u8>, DatabaseError> {
+        self.state
+            .borrow()
+            .content
+            .get(id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    fn find_by_hash(&self, hash: &ContentHash) -> Result<Document, DatabaseError> {
+        self.check_find()?;
+        self.state
+            .borrow()
+            .documents
+            .get(hash)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    fn find_by_id(&self, id: &DocumentId) -> Result<Document, DatabaseError> {
+        self.find_one(|doc| doc.id == *id)
+    }
+
+    fn find_by_input(&self, epoch: u64, input: u64) -> Result<Document, DatabaseError> {
+        self.check_find()?;
+        let state = self.state.borrow();
+        state
+            .saved_order
+            .iter()
+            .map(|hash| &state.documents[hash])
+            .find(|doc| doc.epoch_index == epoch && doc.input_index == input)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    fn find_by_cid(&self, cid: &str) -> Result<Document, DatabaseError> {
+        self.find_one(|doc| doc.ipfs_cid.as_deref() == Some(cid))
+    }
+
+    fn find_by_keccak(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.find_one(|doc| doc.content_hash_keccak.as_deref() == Some(hash))
+    }
+
+    fn find_by_blake2b_hash(&self, hash: &str) -> Result<Document, DatabaseError> {
+        self.find_one(|doc| doc.blake2b_hash.as_deref() == Some(hash))
+    }
+
+    fn find_many_by_hashes(
+        &self,
+        hashes: &[&ContentHash],
+    ) -> Result<Vec<Option<Document>>, DatabaseError> {
+        self.check_find()?;
+        let state = self.state.borrow();
+        Ok(hashes
+            .iter()
+            .map(|hash| state.documents.get(*hash).cloned())
+            .collect())
+    }
+
+    fn count_documents(&self) -> Result<usize, DatabaseError> {
+        Ok(self.state.borrow().documents.len())
+    }
+
+    fn schema_version(&self) -> Result<u32, DatabaseError> {
+        Ok(SCHEMA_VERSION)
+    }
+
+    fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<Document>, DatabaseError> {
+        let terms = words(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Unranked, unlike SQLite: matches come newest first
+        let tags = self.state.borrow().tags.clone();
+        let documents = self.find_sorted(
+            |doc| {
+                let doc_tags = tags.get(&doc.id).into_iter().flatten();
+                let indexed = words(&format!(
+                    "{} {} {}",
+                    doc.file_name,
+                    doc.mime_type,
+                    doc_tags.cloned().collect::<Vec<_>>().join(" ")
+                ));
+                terms
+                    .iter()
+                    .all(|term| indexed.iter().any(|word| word.starts_with(term.as_str())))
+            },
+            |doc| (Reverse(doc.created_at), doc.id.clone()),
+        );
+        Ok(paginate(documents, limit, 0))
+    }
+
+    fn add_tags(&self, document_id: &DocumentId, tags: &[&str]) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        state.check_document(document_id)?;
+        state
+            .tags
+            .entry(document_id.clone())
+            .or_default()
+            .extend(tags.iter().map(|tag| tag.to_string()));
+        Ok(())
+    }
+
+    fn find_tags(&self, document_id: &DocumentId) -> Result<Vec<String>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .tags
+            .get(document_id)
+            .map(|tags| tags.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn add_alias(
+        &self,
+        document_id: &DocumentId,
+        file_name: &str,
+        _submitted_by: &str,
+        _created_at: i64,
+    ) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        state.check_document(document_id)?;
+        let aliases = state.aliases.entry(document_id.clone()).or_default();
+        if !aliases.iter().any(|alias| alias == file_name) {
+            aliases.push(file_name.to_string());
+        }
+        Ok(())
+    }
+
+    fn find_all_by_content_hash(&self, hash: &ContentHash) -> Result<Vec<String>, DatabaseError> {
+        let state = self.state.borrow();
+        let Some(doc) = state.documents.get(hash) else {
+            return Ok(Vec::new());
+        };
+
+        let mut file_names = vec![doc.file_name.clone()];
+        file_names.extend(state.aliases.get(&doc.id).into_iter().flatten().cloned());
+        Ok(file_names)
+    }
+
+    fn find_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let tagged: HashSet<DocumentId> = self
+            .state
+            .borrow()
+            .tags
+            .iter()
+            .filter(|(_, tags)| tags.contains(tag))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let documents = self.find_sorted(
+            |doc| tagged.contains(&doc.id),
+            |doc| (Reverse(doc.created_at), doc.id.clone()),
+        );
+        Ok(paginate(documents, limit, offset))
+    }
+
+    fn find_by_date_range(
+        &self,
+        start: i64,
+        end: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let documents = self.find_sorted(
+            |doc| (start..=end).contains(&doc.created_at),
+            |doc| (doc.created_at, doc.id.clone()),
+        );
+        Ok(paginate(documents, limit, offset))
+    }
+
+    fn find_expired(
+        &self,
+        as_of: i64,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let documents = self.find_sorted(
+            |doc| doc.expires_at.is_some_and(|expires_at| expires_at <= as_of),
+            |doc| (doc.expires_at, doc.id.clone()),
+        );
+        Ok(paginate(documents, limit, offset))
+    }
+
+    fn list_documents(
+        &self,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let documents = self.find_sorted(
+            |doc| is_before(doc, after),
+            |doc| Reverse((doc.created_at, doc.id.clone())),
+        );
+        Ok(paginate(documents, limit, 0))
+    }
+
+    fn list_all(&self, limit: usize, offset: usize) -> Result<Vec<Document>, DatabaseError> {
+        let documents = self.find_sorted(|_| true, |doc| (doc.created_at, doc.id.clone()));
+        Ok(paginate(documents, limit, offset))
+    }
+
+    fn list_by_submitter(
+        &self,
+        submitter: &str,
+        after: Option<(i64, &DocumentId)>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let documents = self.find_sorted(
+            |doc| doc.submitted_by.eq_ignore_ascii_case(submitter) && is_before(doc, after),
+            |doc| Reverse((doc.created_at, doc.id.clone())),
+        );
+        Ok(paginate(documents, limit, 0))
+    }
+
+    fn find_versions(&self, root_hash: &ContentHash) -> Result<Vec<Document>, DatabaseError> {
+        let state = self.state.borrow();
+        let Some(root) = state.documents.get(root_hash) else {
+            return Ok(Vec::new());
+        };
+
+        // Stops at a repeated hash, as the SQLite walk does
+        let mut versions = vec![root.clone()];
+        loop {
+            let last = versions[versions.len() - 1].content_hash.as_str();
+            let Some(next) = state
+                .documents
+                .values()
+                .filter(|doc| doc.supersedes.as_deref() == Some(last))
+                .min_by_key(|doc| (doc.created_at, doc.id.clone()))
+            else {
+                break;
+            };
+
+            if versions
+                .iter()
+                .any(|doc| doc.content_hash == next.content_hash)
+            {
+                break;
+            }
+            versions.push(next.clone());
+        }
+
+        Ok(versions)
+    }
+
+    fn count_by_submitter(&self, submitter: &str) -> Result<usize, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .documents
+            .values()
+            .filter(|doc| doc.submitted_by.eq_ignore_ascii_case(submitter))
+            .count())
+    }
+
+    fn count_by_day(&self, start: i64, end: i64) -> Result<Vec<(i64, usize)>, DatabaseError> {
+        let mut days: BTreeMap<i64, usize> = BTreeMap::new();
+        for doc in self.state.borrow().documents.values() {
+            if (start..=end).contains(&doc.created_at) {
+                *days
+                    .entry(doc.created_at - doc.created_at.rem_euclid(86400))
+                    .or_default() += 1;
+            }
+        }
+        Ok(days.into_iter().collect())
+    }
+
+    fn list_submitters(&self) -> Result<Vec<(String, usize)>, DatabaseError> {
+        Ok(self.state.borrow().submitter_counts())
+    }
+
+    fn count_by_submitter_top_n(&self, n: usize) -> Result<Vec<(String, usize)>, DatabaseError> {
+        let mut counts = self.state.borrow().submitter_counts();
+        counts.truncate(n);
+        Ok(counts)
+    }
+
+    fn count_documents_since(&self, ts: i64) -> Result<usize, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .documents
+            .values()
+            .filter(|doc| doc.created_at >= ts)
+            .count())
+    }
+
+    fn update_metadata(
+        &self,
+        content_hash: &ContentHash,
+        file_name: Option<&str>,
+        mime_type: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Document, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let doc = state
+            .documents
+            .get_mut(content_hash)
+            .ok_or(DatabaseError::NotFound)?;
+
+        if let Some(file_name) = file_name {
+            doc.file_name = file_name.to_string();
+        }
+        if let Some(mime_type) = mime_type {
+            doc.mime_type = mime_type.to_string();
+        }
+        if let Some(metadata) = metadata {
+            doc.metadata = metadata.clone();
+        }
+        Ok(doc.clone())
+    }
+
+    fn find_missing_digests(
+        &self,
+        blake2b: bool,
+        ipfs_cid: bool,
+        after: Option<&DocumentId>,
+        limit: usize,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        self.check_find()?;
+        let mut documents: Vec<Document> = self
+            .state
+            .borrow()
+            .documents
+            .values()
+            .filter(|doc| after.is_none_or(|after| doc.id > *after))
+            .filter(|doc| {
+                doc.content_hash_keccak.is_none()
+                    || (blake2b && doc.blake2b_hash.is_none())
+                    || (ipfs_cid && doc.ipfs_cid.is_none())
+            })
+            .cloned()
+            .collect();
+        documents.sort_by(|a, b| a.id.cmp(&b.id));
+        documents.truncate(limit);
+        Ok(documents)
+    }
+
+    fn update_digests(
+        &self,
+        id: &DocumentId,
+        keccak: Option<&str>,
+        blake2b: Option<&str>,
+        ipfs_cid: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let doc = state
+            .documents
+            .values_mut()
+            .find(|doc| doc.id == *id)
+            .ok_or(DatabaseError::NotFound)?;
+
+        if let Some(keccak) = keccak {
+            doc.content_hash_keccak = Some(keccak.to_string());
+        }
+        if let Some(blake2b) = blake2b {
+            doc.blake2b_hash = Some(blake2b.to_string());
+        }
+        if let Some(ipfs_cid) = ipfs_cid {
+            doc.ipfs_cid = Some(ipfs_cid.to_string());
+        }
+        Ok(())
+    }
+
+    fn transfer_ownership(&self, transfer: &OwnershipTransfer) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+
+        // Only move the document if it is still held by the expected owner
+        let doc = state
+            .documents
+            .get_mut(&ContentHash::from(transfer.content_hash.as_str()))
+            .filter(|doc| doc.submitted_by == transfer.previous_owner)
+            .ok_or(DatabaseError::NotFound)?;
+        doc.submitted_by = transfer.new_owner.clone();
+
+        state.ownership_history.push(transfer.clone());
+        Ok(())
+    }
+
+    fn find_ownership_history(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Vec<OwnershipTransfer>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .ownership_history
+            .iter()
+            .filter(|transfer| *content_hash == transfer.content_hash)
+            .cloned()
+            .collect())
+    }
+
+    fn append_audit_entry(&self, entry: &AuditEntry) -> Result<i64, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let id = state.audit_log.len() as i64 + 1;
+        state.audit_log.push(AuditEntry {
+            id,
+            ..entry.clone()
+        });
+        Ok(id)
+    }
+
+    fn find_audit_entries(
+        &self,
+        submitter: Option<&str>,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        after_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .audit_log
+            .iter()
+            .filter(|entry| {
+                submitter.is_none_or(|submitter| entry.submitter.eq_ignore_ascii_case(submitter))
+                    && from_block.is_none_or(|from| entry.block_number >= from)
+                    && to_block.is_none_or(|to| entry.block_number <= to)
+                    && after_id.is_none_or(|after| entry.id > after)
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn list_audit_entries(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEntry>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .audit_log
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_by_hash(&self, hash: &ContentHash) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let doc = state
+            .documents
+            .remove(hash)
+            .ok_or(DatabaseError::NotFound)?;
+
+        // Rows that reference the document cascade with it; epoch leaves are kept
+        state.saved_order.retain(|saved| saved != hash);
+        state.content.remove(&doc.id);
+        state.tags.remove(&doc.id);
+        state.aliases.remove(&doc.id);
+        state
+            .idempotency
+            .retain(|_, (document_id, _)| *document_id != doc.id);
+        state
+            .ownership_history
+            .retain(|transfer| *hash != transfer.content_hash);
+        Ok(())
+    }
+
+    fn start_upload(&self, session: &UploadSession) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        if state.uploads.contains_key(&session.upload_id) {
+            return Err(DatabaseError::DuplicateUpload);
+        }
+
+        state
+            .uploads
+            .insert(session.upload_id.clone(), session.clone());
+        state
+            .chunks
+            .insert(session.upload_id.clone(), BTreeMap::new());
+        Ok(())
+    }
+
+    fn find_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError> {
+        let state = self.state.borrow();
+        let Some(session) = state.uploads.get(upload_id) else {
+            return Ok(None);
+        };
+
+        let chunks = &state.chunks[upload_id];
+        Ok(Some(UploadSession {
+            chunk_count: chunks.len() as u32,
+            total_bytes: chunks.values().map(|data| data.len() as u64).sum(),
+            ..session.clone()
+        }))
+    }
+
+    fn append_chunk(&self, upload_id: &str, index: u32, data: &[u8]) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let chunks = state
+            .chunks
+            .get_mut(upload_id)
+            .ok_or_else(|| constraint_violation("unknown upload id"))?;
+        if chunks.contains_key(&index) {
+            return Err(constraint_violation("duplicate chunk index"));
+        }
+
+        chunks.insert(index, data.to_vec());
+        Ok(())
+    }
+
+    fn upload_content(&self, upload_id: &str) -> Result<Vec<u8>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .chunks
+            .get(upload_id)
+            .map(|chunks| chunks.values().flatten().copied().collect())
+            .unwrap_or_default())
+    }
+
+    fn delete_upload(&self, upload_id: &str) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        state.uploads.remove(upload_id);
+        state.chunks.remove(upload_id);
+        Ok(())
+    }
+
+    fn save_pending_notarization(
+        &self,
+        pending: &PendingNotarization,
+        cosigners: &[&str],
+    ) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        if state.pending.contains_key(&pending.content_hash) {
+            return Err(DatabaseError::DuplicateHash);
+        }
+
+        let mut signers = BTreeMap::new();
+        for cosigner in cosigners {
+            let signer = Cosigner {
+                address: cosigner.to_string(),
+                signature: None,
+                signed_at: None,
+            };
+            if signers.insert(cosigner.to_string(), signer).is_some() {
+                return Err(constraint_violation("duplicate cosigner"));
+            }
+        }
+
+        state
+            .pending
+            .insert(pending.content_hash.clone(), pending.clone());
+        state
+            .cosigners
+            .insert(pending.content_hash.clone(), signers);
+        Ok(())
+    }
+
+    fn find_pending_notarization(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<PendingNotarization>, DatabaseError> {
+        Ok(self.state.borrow().pending.get(content_hash).cloned())
+    }
+
+    fn find_cosigners(&self, content_hash: &str) -> Result<Vec<Cosigner>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .cosigners
+            .get(content_hash)
+            .map(|signers| signers.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn record_cosignature(
+        &self,
+        content_hash: &str,
+        cosigner: &str,
+        signature: &str,
+        signed_at: i64,
+    ) -> Result<bool, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let Some(signer) = state
+            .cosigners
+            .get_mut(content_hash)
+            .and_then(|signers| signers.get_mut(cosigner))
+            .filter(|signer| !signer.has_signed())
+        else {
+            return Ok(false);
+        };
+
+        signer.signature = Some(signature.to_string());
+        signer.signed_at = Some(signed_at);
+        Ok(true)
+    }
+
+    fn delete_pending_notarization(&self, content_hash: &str) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        state.pending.remove(content_hash);
+        state.cosigners.remove(content_hash);
+        Ok(())
+    }
+
+    fn save_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+        document_id: &DocumentId,
+        block_number: u64,
+    ) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        state.check_document(document_id)?;
+
+        let entry_key = (key.to_string(), submitter.to_lowercase());
+        if state.idempotency.contains_key(&entry_key) {
+            return Err(constraint_violation("duplicate idempotency key"));
+        }
+        state
+            .idempotency
+            .insert(entry_key, (document_id.clone(), block_number));
+        Ok(())
+    }
+
+    fn find_idempotency_key(
+        &self,
+        key: &str,
+        submitter: &str,
+    ) -> Result<Option<(DocumentId, u64)>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .idempotency
+            .get(&(key.to_string(), submitter.to_lowercase()))
+            .cloned())
+    }
+
+    fn find_open_epoch(&self) -> Result<Option<u64>, DatabaseError> {
+        Ok(self.state.borrow().open_epoch)
+    }
+
+    fn open_epoch(&self, epoch: u64) -> Result<(), DatabaseError> {
+        self.state.borrow_mut().open_epoch = Some(epoch);
+        Ok(())
+    }
+
+    fn seal_epoch(&self, epoch: u64, next_epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+
+        // Documents in notarization order that no earlier epoch has committed to
+        let sealed: HashSet<&DocumentId> = state
+            .epoch_leaves
+            .values()
+            .flatten()
+            .map(|(document_id, _)| document_id)
+            .collect();
+        let unsealed: Vec<(DocumentId, String)> = state
+            .saved_order
+            .iter()
+            .map(|hash| &state.documents[hash])
+            .filter(|doc| !sealed.contains(&doc.id))
+            .map(|doc| (doc.id.clone(), doc.content_hash.to_string()))
+            .collect();
+
+        let hashes: Vec<String> = unsealed.iter().map(|(_, hash)| hash.clone()).collect();
+        let root = match merkle_root(&hashes) {
+            Some(root) => {
+                if state.epoch_roots.contains_key(&epoch) {
+                    return Err(constraint_violation("epoch already sealed"));
+                }
+
+                let root = EpochRoot {
+                    epoch,
+                    root,
+                    leaf_count: hashes.len(),
+                };
+                state.epoch_roots.insert(epoch, root.clone());
+                state.epoch_leaves.insert(epoch, unsealed);
+                Some(root)
+            }
+            None => None,
+        };
+
+        state.open_epoch = Some(next_epoch);
+        Ok(root)
+    }
+
+    fn find_epoch_root(&self, epoch: u64) -> Result<Option<EpochRoot>, DatabaseError> {
+        Ok(self.state.borrow().epoch_roots.get(&epoch).cloned())
+    }
+
+    fn generate_inclusion_proof(
+        &self,
+        content_hash: &ContentHash,
+        epoch: u64,
+    ) -> Result<MerkleProof, DatabaseError> {
+        let leaves: Vec<String> = self
+            .state
+            .borrow()
+            .epoch_leaves
+            .get(&epoch)
+            .map(|leaves| leaves.iter().map(|(_, hash)| hash.clone()).collect())
+            .unwrap_or_default();
+
+        let content_hash = content_hash.as_str().to_lowercase();
+        leaves
+            .iter()
+            .position(|leaf| *leaf == content_hash)
+            .and_then(|index| merkle_proof(&leaves, index))
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    fn find_last_nonce(&self, submitter: &str) -> Result<Option<u64>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .nonces
+            .get(&submitter.to_lowercase())
+            .copied())
+    }
+
+    fn advance_nonce(&self, submitter: &str, nonce: u64) -> Result<bool, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let submitter = submitter.to_lowercase();
+        if state
+            .nonces
+            .get(&submitter)
+            .is_some_and(|&last| nonce <= last)
+        {
+            return Ok(false);
+        }
+
+        state.nonces.insert(submitter, nonce);
+        Ok(true)
+    }
+
+    fn find_balance(&self, owner: &str, token: &str) -> Result<u128, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .balances
+            .get(&(owner.to_lowercase(), token.to_lowercase()))
+            .copied()
+            .unwrap_or(0))
+    }
+
+    fn credit_balance(
+        &self,
+        owner: &str,
+        token: &str,
+        amount: u128,
+    ) -> Result<u128, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let balance = state
+            .balances
+            .entry((owner.to_lowercase(), token.to_lowercase()))
+            .or_insert(0);
+        *balance = balance
+            .checked_add(amount)
+            .ok_or(DatabaseError::BalanceOverflow)?;
+        Ok(*balance)
+    }
+
+    fn debit_balance(&self, owner: &str, token: &str, amount: u128) -> Result<bool, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let key = (owner.to_lowercase(), token.to_lowercase());
+        let Some(balance) = state
+            .balances
+            .get(&key)
+            .copied()
+            .unwrap_or(0)
+            .checked_sub(amount)
+        else {
+            return Ok(false);
+        };
+
+        state.balances.insert(key, balance);
+        Ok(true)
+    }
+
+    fn find_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .quota
+            .get(&(submitter.to_lowercase(), epoch))
+            .copied()
+            .unwrap_or(0))
+    }
+
+    fn increment_quota_usage(&self, submitter: &str, epoch: u64) -> Result<u64, DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        let used = state
+            .quota
+            .entry((submitter.to_lowercase(), epoch))
+            .or_insert(0);
+        *used += 1;
+        Ok(*used)
+    }
+
+    fn save_commitment(&self, commitment: &Commitment) -> Result<(), DatabaseError> {
+        let mut state = self.state.borrow_mut();
+        if state.commitments.contains_key(&commitment.content_hash) {
+            return Err(DatabaseError::DuplicateHash);
+        }
+        state
+            .commitments
+            .insert(commitment.content_hash.clone(), commitment.clone());
+        Ok(())
+    }
+
+    fn find_commitment(
+        &self,
+        content_hash: &ContentHash,
+    ) -> Result<Option<Commitment>, DatabaseError> {
+        Ok(self
+            .state
+            .borrow()
+            .commitments
+            .get(content_hash.as_str())
+            .cloned())
+    }
+}
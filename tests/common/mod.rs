@@ -0,0 +1,3 @@
+mod mocks;
+
+pub use mocks::MockDocumentRepository;
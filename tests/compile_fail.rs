@@ -0,0 +1,8 @@
+//! Repository lookups that must not compile, checked with trybuild
+//! Each case in `tests/compile_fail/` has the expected compiler output beside it
+
+#[test]
+fn repository_lookups_reject_the_wrong_key_type() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile_fail/*.rs");
+}
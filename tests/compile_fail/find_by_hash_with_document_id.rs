@@ -0,0 +1,7 @@
+use dapp::domain::DocumentId;
+use dapp::{DocumentRepository, SqliteRepository};
+
+fn main() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let _ = repo.find_by_hash(&DocumentId::from("550e8400"));
+}
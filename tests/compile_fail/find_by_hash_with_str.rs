@@ -0,0 +1,6 @@
+use dapp::{DocumentRepository, SqliteRepository};
+
+fn main() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let _ = repo.find_by_hash("a591a6d4");
+}
@@ -0,0 +1,7 @@
+use dapp::domain::ContentHash;
+use dapp::{DocumentRepository, SqliteRepository};
+
+fn main() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let _ = repo.find_by_id(&ContentHash::from("a591a6d4"));
+}
@@ -0,0 +1,282 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const ALICE: &str = "0x00000000000000000000000000000000000000aa";
+const ALICE_UPPERCASE: &str = "0x00000000000000000000000000000000000000AA";
+const BOB: &str = "0x00000000000000000000000000000000000000bb";
+
+async fn advance(
+    repo: &SqliteRepository,
+    server_url: &str,
+    config: &Config,
+    payload: &str,
+    sender: &str,
+    block_number: u64,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let request = create_advance_request(payload, sender, block_number);
+    handle_advance(&client, server_url, repo, config, request)
+        .await
+        .unwrap()
+}
+
+/// Send an audit_log inspect and return its report
+async fn audit_log(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    payload: &str,
+) -> serde_json::Value {
+    server.clear();
+    let client = hyper::Client::new();
+    let request = create_inspect_request(payload);
+    let result = handle_inspect(&client, server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_rejected_notarization_is_not_audited() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_notarize_payload(b"Audited contract", "contract.txt", "text/plain");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, ALICE, 100).await,
+        "accept"
+    );
+    // The same content again is a duplicate
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, ALICE, 101).await,
+        "reject"
+    );
+
+    let report = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_audit_log_payload(None, (None, None), None, 10),
+    )
+    .await;
+
+    // The node reverts a rejected input's state, so only the accepted one is logged
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["has_more"], false);
+    let entry = &report["entries"][0];
+    assert_eq!(entry["action"], "notarize");
+    assert_eq!(entry["submitter"], ALICE);
+    assert_eq!(entry["block_number"], 100);
+    assert_eq!(entry["timestamp"], 1234567890);
+    assert_eq!(entry["outcome"], "accept");
+}
+
+#[tokio::test]
+async fn test_unparseable_input_is_not_audited() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert_eq!(
+        advance(&repo, &server_url, &config, "not json", BOB, 7).await,
+        "reject"
+    );
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let rejection: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(rejection["error_code"], "INVALID_PAYLOAD");
+
+    let report = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_audit_log_payload(Some(BOB), (None, None), None, 10),
+    )
+    .await;
+
+    assert_eq!(report["count"], 0);
+}
+
+#[tokio::test]
+async fn test_audit_log_filters_and_pages() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for block_number in 1..=5 {
+        for sender in [ALICE, BOB] {
+            let content = format!("{} at {}", sender, block_number);
+            let payload = create_notarize_payload(content.as_bytes(), "doc.txt", "text/plain");
+            advance(&repo, &server_url, &config, &payload, sender, block_number).await;
+        }
+    }
+
+    // Alice's inputs in blocks 2..=4, two at a time; the filter ignores address case
+    let first = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_audit_log_payload(Some(ALICE_UPPERCASE), (Some(2), Some(4)), None, 2),
+    )
+    .await;
+    assert_eq!(first["count"], 2);
+    assert_eq!(first["has_more"], true);
+    let blocks: Vec<u64> = first["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["block_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(blocks, vec![2, 3]);
+
+    let cursor = first["next_cursor"].as_i64().unwrap();
+    let second = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_audit_log_payload(Some(ALICE), (Some(2), Some(4)), Some(cursor), 2),
+    )
+    .await;
+    assert_eq!(second["count"], 1);
+    assert_eq!(second["entries"][0]["block_number"], 4);
+    assert_eq!(second["entries"][0]["submitter"], ALICE);
+    assert_eq!(second["has_more"], false);
+    assert!(second["next_cursor"].is_null());
+}
+
+#[tokio::test]
+async fn test_audit_log_rejects_inverted_block_range() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_audit_log_payload(None, (Some(9), Some(3)), None, 10),
+    )
+    .await;
+
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("from_block 9 is after to_block 3"));
+}
+
+#[tokio::test]
+async fn test_rejections_are_reported_instead_of_audited() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content = b"Audited lease";
+    let payload = create_notarize_payload(content, "lease.txt", "text/plain");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, ALICE, 100).await,
+        "accept"
+    );
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, BOB, 101).await,
+        "reject"
+    );
+    let empty = create_notarize_payload(b"", "empty.txt", "text/plain");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &empty, BOB, 102).await,
+        "reject"
+    );
+    let transfer =
+        create_transfer_payload(Document::hash_content(b"Never notarized").as_str(), ALICE);
+    assert_eq!(
+        advance(&repo, &server_url, &config, &transfer, BOB, 103).await,
+        "reject"
+    );
+
+    // Each rejection's reason is in its report
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let codes: Vec<String> = server
+        .get_reports()
+        .iter()
+        .map(|report| {
+            let report: serde_json::Value = serde_json::from_str(report).unwrap();
+            assert!(!report["error_detail"].as_str().unwrap().is_empty());
+            report["error_code"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(codes, ["DUPLICATE_DOCUMENT", "EMPTY_CONTENT", "NOT_FOUND"]);
+
+    let report = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_list_audit_log_payload(10, 0),
+    )
+    .await;
+    assert_eq!(report["count"], 1);
+    assert_eq!(report["offset"], 0);
+    assert_eq!(report["has_more"], false);
+
+    let entry = &report["entries"][0];
+    assert_eq!(entry["outcome"], "accept");
+    assert_eq!(
+        entry["content_hash"],
+        Document::hash_content(content).as_str()
+    );
+}
+
+#[tokio::test]
+async fn test_list_audit_log_pages_by_offset() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for block_number in 1..=5 {
+        let content = format!("Entry {}", block_number);
+        let payload = create_notarize_payload(content.as_bytes(), "doc.txt", "text/plain");
+        advance(&repo, &server_url, &config, &payload, ALICE, block_number).await;
+    }
+
+    let report = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_list_audit_log_payload(2, 2),
+    )
+    .await;
+    assert_eq!(report["count"], 2);
+    assert_eq!(report["offset"], 2);
+    assert_eq!(report["has_more"], true);
+    assert_eq!(report["entries"][0]["block_number"], 3);
+    assert_eq!(report["entries"][1]["block_number"], 4);
+
+    let last = audit_log(
+        &repo,
+        &server,
+        &server_url,
+        &create_list_audit_log_payload(2, 4),
+    )
+    .await;
+    assert_eq!(last["count"], 1);
+    assert_eq!(last["has_more"], false);
+}
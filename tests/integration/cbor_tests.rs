@@ -0,0 +1,81 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, NotarizeRequestBuilder, ResponseEncoding, CBOR_PAYLOAD_PREFIX};
+use dapp::domain::NotarizationReceipt;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::SqliteRepository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+async fn notarize(
+    repo: &SqliteRepository,
+    server_url: &str,
+    content: &[u8],
+    encoding: ResponseEncoding,
+) {
+    let client = hyper::Client::new();
+    let payload = notarize_action(
+        NotarizeRequestBuilder::new()
+            .content(content)
+            .file_name("deed.pdf")
+            .mime_type("application/pdf")
+            .response_encoding(encoding)
+            .build()
+            .unwrap(),
+    );
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_cbor_receipt_notice_round_trips() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    notarize(
+        &repo,
+        &server_url,
+        b"binary receipt",
+        ResponseEncoding::Cbor,
+    )
+    .await;
+
+    let payloads = server.get_notice_payloads();
+    assert_eq!(payloads.len(), 1);
+    let body = payloads[0].strip_prefix(CBOR_PAYLOAD_PREFIX).unwrap();
+    let notice: serde_json::Value = ciborium::from_reader(body).unwrap();
+    assert_eq!(notice["type"], "notarization_receipt");
+
+    let receipt: NotarizationReceipt = serde_json::from_value(notice["receipt"].clone()).unwrap();
+    assert_eq!(
+        NotarizationReceipt::from_cbor(&receipt.to_cbor()).unwrap(),
+        receipt
+    );
+    assert!(receipt.proof.starts_with("sha256:"));
+}
+
+#[tokio::test]
+async fn test_cbor_notice_is_smaller_than_json() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    notarize(&repo, &server_url, b"json receipt", ResponseEncoding::Json).await;
+    notarize(&repo, &server_url, b"cbor receipt", ResponseEncoding::Cbor).await;
+
+    let payloads = server.get_notice_payloads();
+    assert_eq!(payloads.len(), 2);
+    assert!(payloads[0].starts_with(b"{"));
+    assert!(payloads[1].starts_with(CBOR_PAYLOAD_PREFIX));
+    assert!(
+        payloads[1].len() < payloads[0].len(),
+        "CBOR notice of {} bytes is not smaller than JSON notice of {} bytes",
+        payloads[1].len(),
+        payloads[0].len()
+    );
+}
@@ -0,0 +1,140 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{CertificateResponse, Config};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+use dapp::infrastructure::signing::NoticeSigningKey;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+const KEY: &[u8] = b"notary-key";
+
+fn signing_config() -> Config {
+    Config {
+        notice_signing_key: Some(NoticeSigningKey::new(KEY)),
+        ..Config::default()
+    }
+}
+
+async fn request_certificate(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    config: &Config,
+    content_hash: &str,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let payload = create_certificate_payload(content_hash);
+    let result = handle_inspect(
+        &client,
+        server_url,
+        repo,
+        config,
+        create_inspect_request(&payload),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    serde_json::from_str(server.get_reports().last().unwrap()).unwrap()
+}
+
+/// Notarize a deed and return its content hash
+async fn notarize_deed(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    config: &Config,
+) -> String {
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"deed of sale", "deed.pdf", "application/pdf");
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    handle_advance(&client, server_url, repo, config, request)
+        .await
+        .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notice: serde_json::Value =
+        serde_json::from_str(server.get_notices().last().unwrap()).unwrap();
+    notice["receipt"]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_certificate_round_trips_through_verifier() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = signing_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content_hash = notarize_deed(&repo, &server, &server_url, &config).await;
+    let report = request_certificate(&repo, &server, &server_url, &config, &content_hash).await;
+
+    assert_eq!(report["type"], "notarization_certificate");
+    assert_eq!(report["document"]["file_name"], "deed.pdf");
+    assert_eq!(report["receipt"]["content_hash"], content_hash.as_str());
+    assert_eq!(report["notarized_at"], report["document"]["created_at"]);
+
+    let certificate: CertificateResponse = serde_json::from_value(report).unwrap();
+    assert!(certificate.verify(KEY));
+    assert!(!certificate.verify(b"other-key"));
+}
+
+#[tokio::test]
+async fn test_altered_certificate_fails_verification() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = signing_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content_hash = notarize_deed(&repo, &server, &server_url, &config).await;
+    let report = request_certificate(&repo, &server, &server_url, &config, &content_hash).await;
+
+    let alterations: [(&str, &str, serde_json::Value); 5] = [
+        ("document", "file_name", "forged.pdf".into()),
+        (
+            "document",
+            "submitted_by",
+            "0x0000000000000000000000000000000000000456".into(),
+        ),
+        ("document", "created_at", 0.into()),
+        ("receipt", "block_number", 7.into()),
+        (
+            "receipt",
+            "document_id",
+            "00000000-0000-0000-0000-000000000000".into(),
+        ),
+    ];
+    for (section, field, value) in alterations {
+        let mut altered = report.clone();
+        altered[section][field] = value;
+        let certificate: CertificateResponse = serde_json::from_value(altered).unwrap();
+        assert!(!certificate.verify(KEY), "{}.{}", section, field);
+    }
+
+    let mut altered = report.clone();
+    altered["notarized_at"] = 0.into();
+    let certificate: CertificateResponse = serde_json::from_value(altered).unwrap();
+    assert!(!certificate.verify(KEY));
+}
+
+#[tokio::test]
+async fn test_certificate_for_unknown_hash_reports_not_found() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let missing = "0".repeat(64);
+    let report =
+        request_certificate(&repo, &server, &server_url, &signing_config(), &missing).await;
+    assert_eq!(report["error_code"], "NOT_FOUND");
+
+    // Without a signing key no certificate can be issued
+    let report =
+        request_certificate(&repo, &server, &server_url, &Config::default(), &missing).await;
+    assert_eq!(report["error_code"], "UNAVAILABLE");
+}
@@ -0,0 +1,111 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::ContentHash;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+/// Send each payload as an advance input and return the handler statuses
+async fn advance_all(
+    repo: &SqliteRepository,
+    server_url: &str,
+    payloads: &[String],
+) -> Vec<&'static str> {
+    let client = hyper::Client::new();
+    let config = Config::default();
+    let mut statuses = Vec::new();
+    for (i, payload) in payloads.iter().enumerate() {
+        let request = create_advance_request(payload, SUBMITTER, 100 + i as u64);
+        let status = handle_advance(&client, server_url, repo, &config, request)
+            .await
+            .unwrap();
+        statuses.push(status);
+    }
+    statuses
+}
+
+#[tokio::test]
+async fn test_three_chunk_document_matches_single_shot_hash() {
+    let content: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+
+    // Single-shot notarization in its own database, for the reference hash
+    let single_repo = SqliteRepository::new_in_memory().unwrap();
+    let single_server = MockRollupServer::new();
+    let single_url = single_server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let payload = create_notarize_payload(&content, "scan.pdf", "application/pdf");
+    assert_eq!(
+        advance_all(&single_repo, &single_url, &[payload]).await,
+        vec!["accept"]
+    );
+
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut payloads = vec![create_chunk_start_payload(
+        "scan-42",
+        "scan.pdf",
+        "application/pdf",
+    )];
+    for (index, chunk) in content.chunks(1000).enumerate() {
+        payloads.push(create_chunk_append_payload("scan-42", index as u32, chunk));
+    }
+    payloads.push(create_chunk_finish_payload("scan-42"));
+
+    let statuses = advance_all(&repo, &server_url, &payloads).await;
+    assert!(statuses.iter().all(|status| *status == "accept"));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Start and each append report progress; finish emits the receipt notice
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 4);
+    let progress: serde_json::Value = serde_json::from_str(&reports[3]).unwrap();
+    assert_eq!(progress["chunks"], 3);
+    assert_eq!(progress["bytes"], 3000);
+
+    let chunked: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let single: serde_json::Value = serde_json::from_str(&single_server.get_notices()[0]).unwrap();
+    assert_eq!(chunked["type"], "notarization_receipt");
+    assert_eq!(
+        chunked["receipt"]["content_hash"],
+        single["receipt"]["content_hash"]
+    );
+
+    let hash = chunked["receipt"]["content_hash"].as_str().unwrap();
+    let doc = repo.find_by_hash(&ContentHash::from(hash)).unwrap();
+    assert_eq!(doc.file_name, "scan.pdf");
+    assert_eq!(doc.submitted_by, SUBMITTER);
+    assert!(repo.find_upload("scan-42").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_out_of_order_chunk_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payloads = vec![
+        create_chunk_start_payload("scan-1", "scan.pdf", "application/pdf"),
+        create_chunk_append_payload("scan-1", 0, b"first"),
+        create_chunk_append_payload("scan-1", 2, b"third"),
+        create_chunk_append_payload("scan-1", 0, b"first"),
+    ];
+
+    let statuses = advance_all(&repo, &server_url, &payloads).await;
+    assert_eq!(statuses, vec!["accept", "accept", "reject", "reject"]);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    let gap: serde_json::Value = serde_json::from_str(&reports[2]).unwrap();
+    assert_eq!(gap["error_detail"], "Expected chunk 1, got 2");
+    let duplicate: serde_json::Value = serde_json::from_str(&reports[3]).unwrap();
+    assert_eq!(duplicate["error_detail"], "Chunk 0 was already received");
+    assert!(server.get_notices().is_empty());
+}
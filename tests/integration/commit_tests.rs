@@ -0,0 +1,162 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::{ContentHash, Document};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x00000000000000000000000000000000000000a1";
+
+async fn advance(
+    repo: &SqliteRepository,
+    server_url: &str,
+    payload: &str,
+    block_number: u64,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let request = create_advance_request(payload, SUBMITTER, block_number);
+    handle_advance(&client, server_url, repo, &Config::default(), request)
+        .await
+        .unwrap()
+}
+
+async fn verify(repo: &SqliteRepository, server_url: &str, content_hash: &str) {
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_verify_payload(content_hash));
+    let result = handle_inspect(&client, server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+}
+
+#[tokio::test]
+async fn test_commit_then_notarize_records_committed_at() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content = b"Contract signed before the dispute";
+    let content_hash = Document::hash_content(content).to_string();
+
+    let payload = create_commit_payload(&content_hash);
+    assert_eq!(advance(&repo, &server_url, &payload, 100).await, "accept");
+
+    // Only the hash is stored until the content is revealed
+    let commitment = repo
+        .find_commitment(&ContentHash::from(content_hash.as_str()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(commitment.block_number, 100);
+    assert_eq!(commitment.committed_by, SUBMITTER);
+    assert_eq!(repo.count_documents().unwrap(), 0);
+
+    let payload = create_notarize_payload(content, "contract.pdf", "application/pdf");
+    assert_eq!(advance(&repo, &server_url, &payload, 250).await, "accept");
+
+    verify(&repo, &server_url, &content_hash).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 2);
+    let commit_notice: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(commit_notice["type"], "commitment");
+    assert_eq!(commit_notice["commitment"]["content_hash"], content_hash);
+    assert_eq!(commit_notice["commitment"]["block_number"], 100);
+
+    let receipt_notice: serde_json::Value = serde_json::from_str(&notices[1]).unwrap();
+    assert_eq!(receipt_notice["receipt"]["block_number"], 250);
+    assert_eq!(receipt_notice["receipt"]["committed_at"], 100);
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let result: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(result["exists"], true);
+    assert_eq!(result["committed_at"], 100);
+    assert_eq!(result["receipt"]["committed_at"], 100);
+}
+
+#[tokio::test]
+async fn test_uncommitted_notarization_has_no_committed_at() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content = b"Plain notarization";
+    let payload = create_notarize_payload(content, "plain.pdf", "application/pdf");
+    assert_eq!(advance(&repo, &server_url, &payload, 100).await, "accept");
+
+    verify(&repo, &server_url, Document::hash_content(content).as_str()).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    assert!(notice["receipt"].get("committed_at").is_none());
+
+    let result: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert!(result.get("committed_at").is_none());
+}
+
+#[tokio::test]
+async fn test_committed_hash_verifies_before_reveal() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let content_hash = Document::hash_content(b"Sealed bid").to_string();
+    let payload = create_commit_payload(&content_hash.to_uppercase());
+    assert_eq!(advance(&repo, &server_url, &payload, 42).await, "accept");
+
+    verify(&repo, &server_url, &content_hash).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let result: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(result["exists"], false);
+    assert_eq!(result["committed_at"], 42);
+}
+
+#[tokio::test]
+async fn test_commit_rejects_repeated_and_notarized_hashes() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let committed = Document::hash_content(b"Committed once").to_string();
+    let payload = create_commit_payload(&committed);
+    assert_eq!(advance(&repo, &server_url, &payload, 100).await, "accept");
+    assert_eq!(advance(&repo, &server_url, &payload, 101).await, "reject");
+
+    let payload = create_notarize_payload(b"Already public", "public.pdf", "application/pdf");
+    assert_eq!(advance(&repo, &server_url, &payload, 102).await, "accept");
+    let notarized = Document::hash_content(b"Already public").to_string();
+    let payload = create_commit_payload(&notarized);
+    assert_eq!(advance(&repo, &server_url, &payload, 103).await, "reject");
+
+    let payload = create_commit_payload("not-a-hash");
+    assert_eq!(advance(&repo, &server_url, &payload, 104).await, "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 3);
+    let repeated: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(repeated["error_code"], "DUPLICATE_DOCUMENT");
+    assert!(repeated["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("already committed at block 100"));
+
+    let revealed: serde_json::Value = serde_json::from_str(&reports[1]).unwrap();
+    assert_eq!(revealed["error_code"], "DUPLICATE_DOCUMENT");
+
+    let malformed: serde_json::Value = serde_json::from_str(&reports[2]).unwrap();
+    assert_eq!(malformed["errors"][0]["field"], "content_hash");
+    assert_eq!(malformed["errors"][0]["code"], "INVALID_HASH");
+
+    // The first commitment is kept
+    let commitment = repo
+        .find_commitment(&ContentHash::from(committed.as_str()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(commitment.block_number, 100);
+}
@@ -0,0 +1,163 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const OWNER: &str = "0x0000000000000000000000000000000000000123";
+const OTHER: &str = "0x0000000000000000000000000000000000000456";
+
+fn retention_config() -> Config {
+    Config {
+        store_content: true,
+        ..Config::default()
+    }
+}
+
+/// Notarize a document as `OWNER` and return its id from the receipt notice
+async fn notarize(
+    client: &hyper::Client<hyper::client::HttpConnector>,
+    server: &MockRollupServer,
+    server_url: &str,
+    repo: &SqliteRepository,
+    config: &Config,
+) -> String {
+    let payload = create_notarize_payload(b"Archived contract", "contract.txt", "text/plain");
+    let request = create_advance_request(&payload, OWNER, 100);
+    let result = handle_advance(client, server_url, repo, config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    let notice_json: serde_json::Value = serde_json::from_str(notices.last().unwrap()).unwrap();
+    server.clear();
+    notice_json["receipt"]["document_id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_owner_fetches_retained_content() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = retention_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let document_id = notarize(&client, &server, &server_url, &repo, &config).await;
+
+    let payload = create_fetch_content_payload(&document_id);
+    let request = create_advance_request(&payload, OWNER, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["document_id"], document_id);
+    assert_eq!(report_json["file_name"], "contract.txt");
+
+    use base64::Engine;
+    let content = base64::engine::general_purpose::STANDARD
+        .decode(report_json["content"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(content, b"Archived contract");
+}
+
+#[tokio::test]
+async fn test_non_owner_fetch_denied() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = retention_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let document_id = notarize(&client, &server, &server_url, &repo, &config).await;
+
+    // A requester declared in the payload is ignored in favour of msg_sender
+    let payload = serde_json::json!({
+        "action": "fetch_content",
+        "data": { "document_id": document_id, "requester": OWNER }
+    })
+    .to_string();
+    let request = create_advance_request(&payload, OTHER, 101);
+    handle_advance(&client, &server_url, &repo, &config, request)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert!(report_json["content"].is_null());
+    assert_eq!(report_json["error_code"], "UNAUTHORIZED");
+    assert!(report_json["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Only the document owner"));
+}
+
+#[tokio::test]
+async fn test_inspect_fetch_never_serves_content() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = retention_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let document_id = notarize(&client, &server, &server_url, &repo, &config).await;
+
+    // Even naming the real owner does not help: inspect has no authenticated sender
+    let payload = serde_json::json!({
+        "action": "fetch_content",
+        "data": { "document_id": document_id, "requester": OWNER }
+    })
+    .to_string();
+    let request = create_inspect_request(&payload);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert!(report_json["content"].is_null());
+    assert_eq!(report_json["error_code"], "UNAUTHORIZED");
+    assert!(report_json["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("advance_state"));
+}
+
+#[tokio::test]
+async fn test_content_not_retained_by_default() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let document_id = notarize(&client, &server, &server_url, &repo, &config).await;
+
+    let payload = create_fetch_content_payload(&document_id);
+    let request = create_advance_request(&payload, OWNER, 101);
+    handle_advance(&client, &server_url, &repo, &config, request)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Content was not retained"));
+}
@@ -0,0 +1,130 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::ContentHash;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+const BOB: &str = "0x00000000000000000000000000000000000000b2";
+
+async fn advance(
+    repo: &SqliteRepository,
+    server_url: &str,
+    config: &Config,
+    payload: &str,
+    sender: &str,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let request = create_advance_request(payload, sender, 100);
+    handle_advance(&client, server_url, repo, config, request)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_two_of_two_co_notarization() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_cosigned_notarize_payload(b"partnership deed", "deed.pdf", &[ALICE, BOB]);
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, SUBMITTER).await,
+        "accept"
+    );
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let pending: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(pending["status"], "pending_cosignatures");
+    assert_eq!(pending["required"], 2);
+    assert_eq!(pending["signed"], 0);
+    let hash = pending["content_hash"].as_str().unwrap().to_string();
+    assert!(server.get_notices().is_empty());
+
+    let payload = create_cosign_payload(&hash, "alice-signature");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, ALICE).await,
+        "accept"
+    );
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let progress: serde_json::Value = serde_json::from_str(&server.get_reports()[1]).unwrap();
+    assert_eq!(progress["signed"], 1);
+    assert!(server.get_notices().is_empty());
+    assert!(repo
+        .find_by_hash(&ContentHash::from(hash.as_str()))
+        .is_err());
+
+    let payload = create_cosign_payload(&hash, "bob-signature");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, BOB).await,
+        "accept"
+    );
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    let notice: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(notice["type"], "notarization_receipt");
+    assert_eq!(notice["receipt"]["content_hash"], hash.as_str());
+    let cosigners: Vec<&str> = notice["cosigners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["address"].as_str().unwrap())
+        .collect();
+    assert_eq!(cosigners, vec![ALICE, BOB]);
+
+    assert_eq!(
+        repo.find_by_hash(&ContentHash::from(hash.as_str()))
+            .unwrap()
+            .submitted_by,
+        SUBMITTER
+    );
+}
+
+#[tokio::test]
+async fn test_partial_co_notarization_times_out() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        cosign_window_secs: 2,
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_cosigned_notarize_payload(b"lapsed deed", "deed.pdf", &[ALICE, BOB]);
+    advance(&repo, &server_url, &config, &payload, SUBMITTER).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let pending: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    let hash = pending["content_hash"].as_str().unwrap().to_string();
+
+    let payload = create_cosign_payload(&hash, "alice-signature");
+    assert_eq!(
+        advance(&repo, &server_url, &config, &payload, ALICE).await,
+        "accept"
+    );
+
+    // Bob's input lands in a block after the window closed
+    let payload = create_cosign_payload(&hash, "bob-signature");
+    let request = create_advance_request_at(&payload, BOB, 101, ADVANCE_TIMESTAMP + 3);
+    let result = handle_advance(&hyper::Client::new(), &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    let report: serde_json::Value = serde_json::from_str(reports.last().unwrap()).unwrap();
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Cosigning window closed"));
+    assert!(server.get_notices().is_empty());
+    assert!(repo
+        .find_by_hash(&ContentHash::from(hash.as_str()))
+        .is_err());
+    assert!(repo.find_pending_notarization(&hash).unwrap().is_none());
+}
@@ -0,0 +1,168 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, MAX_SEARCH_LIMIT};
+use dapp::domain::{parse_rfc3339, Document};
+use dapp::handlers::handle_inspect;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+/// Store documents notarized at the given Unix timestamps
+fn seed_documents(repo: &SqliteRepository, times: impl IntoIterator<Item = i64>) {
+    for (i, created_at) in times.into_iter().enumerate() {
+        let mut doc = Document::new(
+            format!("document {} notarized at {}", i, created_at).as_bytes(),
+            "dated.pdf",
+            "application/pdf",
+            "0x0000000000000000000000000000000000000123",
+        );
+        doc.created_at = created_at;
+        repo.save_document(&doc).unwrap();
+    }
+}
+
+/// Send an inspect request and return the parsed report
+async fn inspect(repo: &SqliteRepository, payload: &str) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(payload);
+    let result = handle_inspect(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_date_range_includes_both_ends() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(
+        &repo,
+        [
+            1_699_999_999,
+            1_700_000_000,
+            1_700_050_000,
+            1_700_086_400,
+            1_700_086_401,
+        ],
+    );
+
+    let report = inspect(
+        &repo,
+        &create_date_range_payload(1_700_000_000, 1_700_086_400, None),
+    )
+    .await;
+
+    assert_eq!(report["count"], 3);
+    let times: Vec<i64> = report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| parse_rfc3339(doc["created_at"].as_str().unwrap()).unwrap())
+        .collect();
+    assert_eq!(times, vec![1_700_000_000, 1_700_050_000, 1_700_086_400]);
+}
+
+#[tokio::test]
+async fn test_date_range_capped_at_max_limit() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo, 0..(MAX_SEARCH_LIMIT as i64 + 5));
+
+    let report = inspect(&repo, &create_date_range_payload(0, 1_000, Some(500))).await;
+
+    assert_eq!(report["count"], MAX_SEARCH_LIMIT);
+}
+
+#[tokio::test]
+async fn test_inverted_date_range_reports_error() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let report = inspect(&repo, &create_date_range_payload(2_000, 1_000, None)).await;
+
+    assert_eq!(
+        report["error_detail"],
+        "Invalid date range: start 2000 is after end 1000"
+    );
+}
+
+#[tokio::test]
+async fn test_timeseries_omits_empty_days() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let day = 1_700_006_400; // 2023-11-15T00:00:00Z
+    seed_documents(&repo, [day + 10, day + 20, day + 2 * 86_400 + 5]);
+
+    let report = inspect(&repo, &create_timeseries_payload(day, day + 3 * 86_400)).await;
+
+    assert_eq!(report["start"], day);
+    assert_eq!(
+        report["days"],
+        serde_json::json!([
+            { "day": day, "count": 2 },
+            { "day": day + 2 * 86_400, "count": 1 }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_timeseries_rejects_inverted_range() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let report = inspect(&repo, &create_timeseries_payload(10, 5)).await;
+
+    assert_eq!(
+        report["error_detail"],
+        "Invalid date range: start 10 is after end 5"
+    );
+}
+
+fn created_ats(report: &serde_json::Value) -> Vec<i64> {
+    report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| parse_rfc3339(doc["created_at"].as_str().unwrap()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_sync_since_advances_checkpoint() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo, [300, 100, 200]);
+
+    let first = inspect(&repo, &create_sync_since_payload(0, 10)).await;
+    assert_eq!(created_ats(&first), vec![100, 200, 300]);
+    assert_eq!(first["next_since"], 300);
+    assert_eq!(first["has_more"], false);
+
+    seed_documents(&repo, [400, 500]);
+
+    let second = inspect(&repo, &create_sync_since_payload(300, 10)).await;
+    assert_eq!(created_ats(&second), vec![400, 500]);
+    assert_eq!(second["next_since"], 500);
+
+    let idle = inspect(&repo, &create_sync_since_payload(500, 10)).await;
+    assert_eq!(idle["count"], 0);
+    assert_eq!(idle["next_since"], 500);
+}
+
+#[tokio::test]
+async fn test_sync_since_does_not_split_a_timestamp() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo, [100, 200, 200, 300]);
+
+    let first = inspect(&repo, &create_sync_since_payload(0, 2)).await;
+    assert_eq!(created_ats(&first), vec![100]);
+    assert_eq!(first["has_more"], true);
+
+    let second = inspect(&repo, &create_sync_since_payload(100, 1)).await;
+    assert_eq!(created_ats(&second), vec![200, 200]);
+    assert_eq!(second["next_since"], 200);
+
+    let third = inspect(&repo, &create_sync_since_payload(200, 2)).await;
+    assert_eq!(created_ats(&third), vec![300]);
+    assert_eq!(third["has_more"], false);
+}
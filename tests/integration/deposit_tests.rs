@@ -0,0 +1,154 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const PORTAL: &str = "0x9c21aeb2093c32ddbc53eef24b873bdcd1ada1db";
+const TOKEN: &str = "0x00000000000000000000000000000000000000f0";
+const DEPOSITOR: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+const FEE: u128 = 100;
+
+fn paid_config() -> Config {
+    Config {
+        erc20_portal_address: Some(PORTAL.to_string()),
+        notarization_fee: FEE,
+        fee_token: Some(TOKEN.to_string()),
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn test_deposit_then_paid_notarization() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = paid_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let request = create_erc20_deposit_request(PORTAL, TOKEN, DEPOSITOR, 250, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    let notice: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(notice["type"], "deposit");
+    assert_eq!(notice["depositor"], DEPOSITOR);
+    assert_eq!(notice["token"], TOKEN);
+    assert_eq!(notice["amount"], "250");
+    assert_eq!(notice["balance"], "250");
+    assert_eq!(repo.count_documents().unwrap(), 0);
+
+    let payload = create_notarize_payload(b"Paid document", "paid.txt", "text/plain");
+    let request = create_advance_request(&payload, DEPOSITOR, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    assert_eq!(repo.count_documents().unwrap(), 1);
+    assert_eq!(repo.find_balance(DEPOSITOR, TOKEN).unwrap(), 150);
+}
+
+#[tokio::test]
+async fn test_unfunded_notarization_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = paid_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload(b"Unpaid document", "unpaid.txt", "text/plain");
+    let request = create_advance_request(&payload, DEPOSITOR, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Insufficient balance"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_balance_runs_out_after_paid_notarizations() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = paid_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let request = create_erc20_deposit_request(PORTAL, TOKEN, DEPOSITOR, FEE, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_notarize_payload(b"First paid document", "one.txt", "text/plain");
+    let request = create_advance_request(&payload, DEPOSITOR, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_notarize_payload(b"Second paid document", "two.txt", "text/plain");
+    let request = create_advance_request(&payload, DEPOSITOR, 102);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    assert_eq!(repo.count_documents().unwrap(), 1);
+    assert_eq!(repo.find_balance(DEPOSITOR, TOKEN).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_failed_notarization_is_not_charged() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = paid_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let request = create_erc20_deposit_request(PORTAL, TOKEN, DEPOSITOR, 2 * FEE, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_notarize_payload(b"Same document", "doc.txt", "text/plain");
+    for expected in ["accept", "reject"] {
+        let request = create_advance_request(&payload, DEPOSITOR, 101);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    // The duplicate was rejected after the balance check, so only one fee was taken
+    assert_eq!(repo.find_balance(DEPOSITOR, TOKEN).unwrap(), FEE);
+}
+
+#[tokio::test]
+async fn test_malformed_deposit_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = paid_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // A JSON action sent by the portal is still read as a deposit
+    let payload = create_notarize_payload(b"Portal document", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload[..40], PORTAL, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Invalid deposit"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
@@ -0,0 +1,152 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::verify_merkle_proof;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+async fn notarize_in_epoch(
+    repo: &SqliteRepository,
+    server_url: &str,
+    content: &[u8],
+    epoch: u64,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(content, "ledger.pdf", "application/pdf");
+    let request = create_advance_request_in_epoch(&payload, SUBMITTER, 100 + epoch, epoch);
+    handle_advance(&client, server_url, repo, &Config::default(), request)
+        .await
+        .unwrap()
+}
+
+async fn inspect_proof(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    content_hash: &str,
+    epoch: u64,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let payload = create_inclusion_proof_payload(content_hash, epoch);
+    handle_inspect(
+        &client,
+        server_url,
+        repo,
+        &Config::default(),
+        create_inspect_request(&payload),
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    serde_json::from_str(server.get_reports().last().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_new_epoch_seals_previous_one_with_provable_root() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for content in [&b"ledger page 1"[..], b"ledger page 2", b"ledger page 3"] {
+        assert_eq!(
+            notarize_in_epoch(&repo, &server_url, content, 4).await,
+            "accept"
+        );
+    }
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let hashes: Vec<String> = server
+        .get_notices()
+        .iter()
+        .map(|notice| {
+            let notice: serde_json::Value = serde_json::from_str(notice).unwrap();
+            notice["receipt"]["content_hash"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(hashes.len(), 3);
+
+    // The first input of epoch 5 publishes the root of epoch 4 before its own receipt
+    assert_eq!(
+        notarize_in_epoch(&repo, &server_url, b"ledger page 4", 5).await,
+        "accept"
+    );
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 5);
+    let sealed: serde_json::Value = serde_json::from_str(&notices[3]).unwrap();
+    assert_eq!(sealed["type"], "epoch_root");
+    assert_eq!(sealed["epoch"], 4);
+    assert_eq!(sealed["leaf_count"], 3);
+    let root = sealed["root"].as_str().unwrap();
+
+    for hash in &hashes {
+        let report = inspect_proof(&repo, &server, &server_url, hash, 4).await;
+        assert_eq!(report["epoch"], 4);
+        assert_eq!(report["root"], root);
+        assert_eq!(report["leaf"], hash.as_str());
+
+        let path: Vec<String> = serde_json::from_value(report["path"].clone()).unwrap();
+        assert!(verify_merkle_proof(hash, &path, root));
+    }
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_errors() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    notarize_in_epoch(&repo, &server_url, b"early", 0).await;
+    notarize_in_epoch(&repo, &server_url, b"late", 1).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notices = server.get_notices();
+    let late: serde_json::Value = serde_json::from_str(notices.last().unwrap()).unwrap();
+    let late_hash = late["receipt"]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Notarized in epoch 1, so absent from epoch 0
+    let report = inspect_proof(&repo, &server, &server_url, &late_hash, 0).await;
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("not committed to the root of epoch 0"));
+
+    // Epoch 1 is still open
+    let report = inspect_proof(&repo, &server, &server_url, &late_hash, 1).await;
+    assert_eq!(report["error_detail"], "Epoch 1 has no Merkle root");
+
+    let report = inspect_proof(&repo, &server, &server_url, "abc", 0).await;
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid hash format"));
+}
+
+#[tokio::test]
+async fn test_document_records_notarizing_input() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"minutes", "minutes.pdf", "application/pdf");
+    let mut request = create_advance_request_in_epoch(&payload, SUBMITTER, 120, 5);
+    request["data"]["metadata"]["input_index"] = 9.into();
+    handle_advance(&client, &server_url, &repo, &Config::default(), request)
+        .await
+        .unwrap();
+
+    let doc = repo.find_by_input(5, 9).unwrap();
+    assert_eq!(doc.file_name, "minutes.pdf");
+    assert_eq!((doc.epoch_index, doc.input_index), (5, 9));
+}
@@ -0,0 +1,193 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, RateLimiter};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+use json::JsonValue;
+
+const SENDER: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+/// Notarize payload whose `content` field is replaced verbatim
+fn notarize_with_raw_content(content: &str) -> String {
+    let mut payload: serde_json::Value =
+        serde_json::from_str(&create_notarize_payload(b"x", "doc.txt", "text/plain")).unwrap();
+    payload["data"]["content"] = content.into();
+    payload.to_string()
+}
+
+/// Send one request and return the `error_code` of the report it produced
+/// A rejected advance must produce exactly that one report
+async fn error_code_of(
+    server: &MockRollupServer,
+    server_url: &str,
+    repo: &SqliteRepository,
+    config: &Config,
+    request: JsonValue,
+) -> serde_json::Value {
+    server.clear();
+    let client = hyper::Client::new();
+
+    let is_advance = request["request_type"] != "inspect_state";
+    let result = if is_advance {
+        handle_advance(&client, server_url, repo, config, request).await
+    } else {
+        handle_inspect(&client, server_url, repo, config, request).await
+    };
+    let status = result.unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    if is_advance {
+        assert_eq!(status, "reject");
+        assert_eq!(reports.len(), 1, "reports: {:?}", reports);
+        assert!(server.get_notices().is_empty());
+    }
+    let report: serde_json::Value = serde_json::from_str(reports.last().unwrap()).unwrap();
+    assert!(
+        report["error_detail"].is_string(),
+        "report without error_detail: {}",
+        report
+    );
+    report["error_code"].clone()
+}
+
+#[tokio::test]
+async fn test_every_error_path_reports_an_error_code() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let default = Config::default();
+    let small_payloads = Config {
+        max_payload_kb: 1,
+        ..Config::default()
+    };
+    let limited = Config {
+        rate_limiter: Some(RateLimiter::new(1)),
+        ..Config::default()
+    };
+    let denied = Config {
+        denied_submitters: Some(vec![SENDER.to_string()]),
+        ..Config::default()
+    };
+    let nonced = Config {
+        require_nonce: true,
+        ..Config::default()
+    };
+    let paid = Config {
+        erc20_portal_address: Some("0x00000000000000000000000000000000000000e2".to_string()),
+        notarization_fee: 1,
+        fee_token: Some("0x00000000000000000000000000000000000000f0".to_string()),
+        ..Config::default()
+    };
+
+    let duplicate = create_notarize_payload(b"Error code doc", "doc.txt", "text/plain");
+    let client = hyper::Client::new();
+    let request = create_advance_request(&duplicate, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &default, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let limited_payload = create_notarize_payload(b"Limited doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&limited_payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &limited, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let cases: Vec<(&str, &Config, JsonValue)> = vec![
+        (
+            "INVALID_PAYLOAD",
+            &default,
+            create_advance_request("not json", SENDER, 100),
+        ),
+        (
+            "UNKNOWN_ACTION",
+            &default,
+            create_advance_request(r#"{"action":"frobnicate"}"#, SENDER, 100),
+        ),
+        (
+            "PAYLOAD_TOO_LARGE",
+            &small_payloads,
+            create_advance_request(&"x".repeat(2048), SENDER, 100),
+        ),
+        (
+            "INVALID_BASE64",
+            &default,
+            create_advance_request(&notarize_with_raw_content("!!!"), SENDER, 100),
+        ),
+        (
+            "EMPTY_CONTENT",
+            &default,
+            create_advance_request(&notarize_with_raw_content(""), SENDER, 100),
+        ),
+        (
+            "DUPLICATE_DOCUMENT",
+            &default,
+            create_advance_request(&duplicate, SENDER, 101),
+        ),
+        (
+            "INVALID_PAYLOAD",
+            &default,
+            create_advance_request(&duplicate, "", 101),
+        ),
+        (
+            "INVALID_HASH",
+            &default,
+            create_inspect_request(&create_verify_payload("xyz")),
+        ),
+        (
+            "INVALID_HASH",
+            &default,
+            create_advance_request(&create_commit_payload("xyz"), SENDER, 101),
+        ),
+        (
+            "UNSUPPORTED_VERSION",
+            &default,
+            create_advance_request(r#"{"action":"health","version":99}"#, SENDER, 101),
+        ),
+        (
+            "NOT_FOUND",
+            &default,
+            create_advance_request(
+                &create_update_metadata_payload(&"a".repeat(64), Some("renamed.txt"), None),
+                SENDER,
+                101,
+            ),
+        ),
+        (
+            "NOT_FOUND",
+            &default,
+            create_inspect_request(&create_verifiable_credential_payload(&"a".repeat(64))),
+        ),
+        (
+            "UNAUTHORIZED",
+            &default,
+            create_advance_request(&create_purge_payload(&"a".repeat(64)), SENDER, 101),
+        ),
+        (
+            "UNAUTHORIZED",
+            &denied,
+            create_advance_request(&duplicate, SENDER, 101),
+        ),
+        (
+            "RATE_LIMITED",
+            &limited,
+            create_advance_request(&duplicate, SENDER, 100),
+        ),
+        (
+            "INVALID_NONCE",
+            &nonced,
+            create_advance_request(&duplicate, SENDER, 101),
+        ),
+        (
+            "INSUFFICIENT_BALANCE",
+            &paid,
+            create_advance_request(&duplicate, SENDER, 101),
+        ),
+    ];
+
+    for (expected, config, request) in cases {
+        let code = error_code_of(&server, &server_url, &repo, config, request).await;
+        assert_eq!(code, expected);
+    }
+}
@@ -0,0 +1,121 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance_or_throw, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const SENDER: &str = "0x0000000000000000000000000000000000000123";
+
+#[tokio::test]
+async fn test_fatal_error_sends_exception() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // A request the rollup server should never produce: the payload is not hex
+    let mut request = create_advance_request("{}", SENDER, 100);
+    request["data"]["payload"] = "not hex".into();
+
+    let result = handle_advance_or_throw(&client, &server_url, &repo, &config, request).await;
+    assert!(result.is_err());
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let exceptions = server.get_exceptions();
+    assert_eq!(exceptions.len(), 1);
+    let exception_json: serde_json::Value = serde_json::from_str(&exceptions[0]).unwrap();
+    assert_eq!(
+        exception_json["error"],
+        result.unwrap_err().to_string().as_str()
+    );
+    // A hex decoding failure has no more specific code
+    assert_eq!(exception_json["code"], "INTERNAL_ERROR");
+    assert!(server.get_reports().is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_input_rejected_without_exception() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_advance_request(r#"{"action":"unknown"}"#, SENDER, 100);
+
+    let result = handle_advance_or_throw(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert!(server.get_exceptions().is_empty());
+    assert_eq!(server.get_reports().len(), 1);
+}
+
+#[tokio::test]
+async fn test_non_utf8_payload_rejected_and_next_input_processed() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // Valid hex, but the bytes are not UTF-8
+    let mut request = create_advance_request("{}", SENDER, 100);
+    request["data"]["payload"] = hex::encode([0xff, 0xfe, 0xfd]).into();
+    let result = handle_advance_or_throw(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    let payload = create_notarize_payload(b"After the bad input", "next.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 101);
+    let result = handle_advance_or_throw(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert!(server.get_exceptions().is_empty());
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Payload is not valid UTF-8"));
+    assert_eq!(server.get_notices().len(), 1);
+}
+
+#[tokio::test]
+async fn test_non_utf8_inspect_payload_reported_and_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let mut request = create_inspect_request("{}");
+    request["data"]["payload"] = hex::encode([0xff, 0xfe, 0xfd]).into();
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert!(server.get_exceptions().is_empty());
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Payload is not valid UTF-8"));
+}
@@ -0,0 +1,198 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, SECONDS_PER_BLOCK};
+use dapp::domain::{format_rfc3339, parse_rfc3339, ContentHash, Document};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+/// Store one document per expiry timestamp
+fn seed_expiring(repo: &SqliteRepository, expiries: impl IntoIterator<Item = Option<i64>>) {
+    for (i, expires_at) in expiries.into_iter().enumerate() {
+        let doc = Document::new(
+            format!("expiring document {}", i).as_bytes(),
+            "expiring.pdf",
+            "application/pdf",
+            SUBMITTER,
+        )
+        .with_expiry(expires_at);
+        repo.save_document(&doc).unwrap();
+    }
+}
+
+/// Send an inspect request and return the parsed report
+async fn inspect(repo: &SqliteRepository, payload: &str) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(payload);
+    let result = handle_inspect(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_list_expired_includes_documents_expiring_at_as_of() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_expiring(
+        &repo,
+        [
+            Some(1_699_999_999),
+            Some(1_700_000_000),
+            Some(1_700_000_001),
+            None,
+        ],
+    );
+
+    let report = inspect(&repo, &create_list_expired_payload(Some(1_700_000_000))).await;
+
+    assert_eq!(report["as_of"], 1_700_000_000);
+    assert_eq!(report["count"], 2);
+    let expiries: Vec<i64> = report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| parse_rfc3339(doc["expires_at"].as_str().unwrap()).unwrap())
+        .collect();
+    assert_eq!(expiries, vec![1_699_999_999, 1_700_000_000]);
+}
+
+#[tokio::test]
+async fn test_list_expired_defaults_to_now() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let now = chrono::Utc::now().timestamp();
+    seed_expiring(&repo, [Some(now - 60), Some(now + 3600)]);
+
+    let report = inspect(&repo, r#"{"action":"list_expired"}"#).await;
+
+    assert!(report["as_of"].as_i64().unwrap() >= now);
+    assert_eq!(report["count"], 1);
+    assert_eq!(
+        report["documents"][0]["expires_at"],
+        format_rfc3339(now - 60)
+    );
+}
+
+/// Send an advance request in a block with Unix timestamp `timestamp` and return its report
+async fn advance_at(repo: &SqliteRepository, payload: &str, timestamp: i64) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_advance_request_at(payload, SUBMITTER, 100, timestamp);
+    let result = handle_advance(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_advance_list_expired_defaults_to_block_timestamp() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_expiring(&repo, [Some(1_699_999_999), Some(1_700_000_060)]);
+
+    let report = advance_at(&repo, r#"{"action":"list_expired"}"#, 1_700_000_000).await;
+
+    // Both have expired by the host clock, only one by the block's
+    assert_eq!(report["as_of"], 1_700_000_000);
+    assert_eq!(report["count"], 1);
+    assert_eq!(
+        report["documents"][0]["expires_at"],
+        format_rfc3339(1_699_999_999)
+    );
+}
+
+#[tokio::test]
+async fn test_advance_verify_judges_expiry_at_block_timestamp() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_expiring(&repo, [Some(1_700_000_060)]);
+    let hash = Document::hash_content(b"expiring document 0");
+
+    let verify = serde_json::json!({
+        "action": "verify",
+        "data": { "content_hash": hash.as_str() }
+    })
+    .to_string();
+    let batch = serde_json::json!({
+        "action": "batch_verify",
+        "data": { "hashes": [hash.as_str()] }
+    })
+    .to_string();
+
+    for (timestamp, expired) in [(1_700_000_000, false), (1_700_000_060, true)] {
+        let report = advance_at(&repo, &verify, timestamp).await;
+        assert_eq!(report["expired"], expired, "verify at {}", timestamp);
+
+        let report = advance_at(&repo, &batch, timestamp).await;
+        assert_eq!(
+            report["results"][0]["expired"], expired,
+            "batch_verify at {}",
+            timestamp
+        );
+    }
+
+    // Inspect has no block, so it judges by the host clock
+    let report = inspect(&repo, &create_verify_payload(hash.as_str())).await;
+    assert_eq!(report["expired"], true);
+}
+
+#[tokio::test]
+async fn test_notarize_with_expiration_blocks_over_advance() {
+    use base64::Engine;
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = serde_json::json!({
+        "action": "notarize",
+        "data": {
+            "content": base64::engine::general_purpose::STANDARD.encode(b"short-lived"),
+            "file_name": "quote.pdf",
+            "mime_type": "application/pdf",
+            "expiration_blocks": 300
+        }
+    })
+    .to_string();
+
+    let client = hyper::Client::new();
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    let hash = notice_json["receipt"]["content_hash"].as_str().unwrap();
+    let doc = repo.find_by_hash(&ContentHash::from(hash)).unwrap();
+    assert_eq!(
+        doc.expires_at,
+        Some(doc.created_at + 300 * SECONDS_PER_BLOCK)
+    );
+
+    // Not yet expired, and listed once its estimated expiry is reached
+    assert!(repo
+        .find_expired(doc.created_at, 100, 0)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        repo.find_expired(doc.expires_at.unwrap(), 100, 0)
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,144 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::parse_rfc3339;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use std::collections::HashSet;
+
+const SUBMITTER: &str = "0x00000000000000000000000000000000000000e1";
+
+/// Notarize `count` documents through advance inputs, two per block timestamp
+/// so that ordering has to fall back to the document id
+async fn notarize_documents(repo: &SqliteRepository, count: u64) {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    for i in 0..count {
+        let content = format!("Registry entry {}", i);
+        let payload = create_notarize_payload(
+            content.as_bytes(),
+            &format!("entry-{}.txt", i),
+            "text/plain",
+        );
+        let timestamp = ADVANCE_TIMESTAMP + (i / 2) as i64;
+        let request = create_advance_request_at(&payload, SUBMITTER, 100 + i, timestamp);
+        let result = handle_advance(&client, &server_url, repo, &Config::default(), request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+}
+
+/// Send an export_registry inspect request and return the parsed report
+async fn export(repo: &SqliteRepository, page: usize, page_size: usize) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_export_registry_payload(page, page_size));
+    let result = handle_inspect(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_export_registry_pages_through_every_document() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    notarize_documents(&repo, 50).await;
+
+    let mut exported = Vec::new();
+    for page in 0..5 {
+        let report = export(&repo, page, 10).await;
+        assert_eq!(report["total_count"], 50);
+        assert_eq!(report["page"], page);
+        assert_eq!(report["page_size"], 10);
+        assert_eq!(report["count"], 10);
+        assert_eq!(report["has_more"], page < 4);
+
+        for doc in report["documents"].as_array().unwrap() {
+            exported.push((
+                parse_rfc3339(doc["created_at"].as_str().unwrap()).unwrap(),
+                doc["id"].as_str().unwrap().to_string(),
+            ));
+        }
+    }
+
+    assert_eq!(exported.len(), 50);
+    let ids: HashSet<&str> = exported.iter().map(|(_, id)| id.as_str()).collect();
+    assert_eq!(ids.len(), 50);
+    assert!(exported.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Past the last page is empty rather than an error
+    let beyond = export(&repo, 5, 10).await;
+    assert_eq!(beyond["count"], 0);
+    assert_eq!(beyond["has_more"], false);
+    assert_eq!(beyond["total_count"], 50);
+}
+
+#[tokio::test]
+async fn test_export_registry_rejects_oversized_page() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    notarize_documents(&repo, 1).await;
+
+    let report = export(&repo, 0, 101).await;
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"].as_str().unwrap().contains("100"));
+
+    let report = export(&repo, 0, 0).await;
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_export_registry_defaults_to_first_page() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    notarize_documents(&repo, 3).await;
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(r#"{"action":"export_registry"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(report["page"], 0);
+    assert_eq!(report["page_size"], 20);
+    assert_eq!(report["count"], 3);
+    assert_eq!(report["has_more"], false);
+}
+
+#[tokio::test]
+async fn test_export_registry_same_over_advance_and_inspect() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    notarize_documents(&repo, 3).await;
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_advance_request(&create_export_registry_payload(0, 2), SUBMITTER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let advanced: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert!(server.get_notices().is_empty());
+
+    assert_eq!(advanced, export(&repo, 0, 2).await);
+    assert_eq!(advanced["count"], 2);
+    assert_eq!(advanced["has_more"], true);
+}
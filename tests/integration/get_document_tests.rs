@@ -0,0 +1,126 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const OWNER: &str = "0x0000000000000000000000000000000000000123";
+
+/// Notarize a document as `OWNER` and return its id from the receipt notice
+async fn notarize(server: &MockRollupServer, server_url: &str, repo: &SqliteRepository) -> String {
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"Lookup by id", "lookup.txt", "text/plain");
+    let request = create_advance_request(&payload, OWNER, 100);
+    let result = handle_advance(&client, server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    let notice_json: serde_json::Value = serde_json::from_str(notices.last().unwrap()).unwrap();
+    server.clear();
+    notice_json["receipt"]["document_id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Send a get_document_by_id inspect request and return the parsed report
+async fn get_document(
+    server: &MockRollupServer,
+    server_url: &str,
+    repo: &SqliteRepository,
+    id: &str,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_get_document_payload(id));
+    let result = handle_inspect(&client, server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    server.clear();
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_document_by_id_found() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let id = notarize(&server, &server_url, &repo).await;
+
+    let report = get_document(&server, &server_url, &repo, &id).await;
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["document"]["id"], id);
+    assert_eq!(report["document"]["file_name"], "lookup.txt");
+    assert_eq!(report["receipt"]["document_id"], id);
+
+    // Uppercase ids name the same document
+    let report = get_document(&server, &server_url, &repo, &id.to_uppercase()).await;
+    assert_eq!(report["document"]["id"], id);
+}
+
+#[tokio::test]
+async fn test_get_document_by_id_via_advance() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let id = notarize(&server, &server_url, &repo).await;
+
+    let client = hyper::Client::new();
+    let request = create_advance_request(&create_get_document_payload(&id), OWNER, 101);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert!(server.get_notices().is_empty());
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["document"]["id"], id);
+}
+
+#[tokio::test]
+async fn test_get_document_by_id_not_found() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report = get_document(
+        &server,
+        &server_url,
+        &repo,
+        "6f1c2d3e-4b5a-4c6d-8e7f-9a0b1c2d3e4f",
+    )
+    .await;
+
+    assert_eq!(report["exists"], false);
+    assert!(report.get("document").is_none());
+}
+
+#[tokio::test]
+async fn test_get_document_by_id_rejects_malformed_id() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for id in ["", "not-a-uuid", "6f1c2d3e-4b5a-4c6d-8e7f"] {
+        let report = get_document(&server, &server_url, &repo, id).await;
+        assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+        assert_eq!(
+            report["error_detail"],
+            "Invalid document id: must be a UUID"
+        );
+    }
+}
@@ -1,31 +1,76 @@
+use dapp::application::{Config, NotarizeRequest, NotarizeRequestBuilder, RepositoryKind};
 use json::JsonValue;
 
+/// Base fixture for notarize payloads submitted as PDFs
+#[allow(dead_code)]
+const PDF: NotarizeRequestBuilder = NotarizeRequestBuilder::new().mime_type("application/pdf");
+
 /// Database helper for integration tests
-/// Sets up a temporary database and cleans up on drop
+/// Provides a unique temporary database path and cleans up on drop
 pub struct TestDatabase {
     path: String,
 }
 
 impl TestDatabase {
     pub fn new() -> Self {
-        let path = format!("/tmp/notary_test_{}.db", uuid::Uuid::new_v4());
-        std::env::set_var("NOTARY_DB_PATH", &path);
-        Self { path }
+        Self {
+            path: format!("/tmp/notary_test_{}.db", uuid::Uuid::new_v4()),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Default configuration pointing at this database
+    pub fn config(&self) -> Config {
+        Config {
+            repository: RepositoryKind::Sqlite(self.path.clone()),
+            ..Config::default()
+        }
     }
 }
 
 impl Drop for TestDatabase {
     fn drop(&mut self) {
-        std::env::remove_var("NOTARY_DB_PATH");
         let _ = std::fs::remove_file(&self.path);
+        // WAL mode side files
+        let _ = std::fs::remove_file(format!("{}-wal", self.path));
+        let _ = std::fs::remove_file(format!("{}-shm", self.path));
     }
 }
 
+/// Block timestamp carried by advance requests unless one is given
+pub const ADVANCE_TIMESTAMP: i64 = 1234567890;
+
 /// Create a test advance_state request
 pub fn create_advance_request(
     payload_json: &str,
     msg_sender: &str,
     block_number: u64,
+) -> JsonValue {
+    create_advance_request_in_epoch(payload_json, msg_sender, block_number, 0)
+}
+
+/// Create a test advance_state request whose block has Unix timestamp `timestamp`
+#[allow(dead_code)]
+pub fn create_advance_request_at(
+    payload_json: &str,
+    msg_sender: &str,
+    block_number: u64,
+    timestamp: i64,
+) -> JsonValue {
+    let mut request = create_advance_request(payload_json, msg_sender, block_number);
+    request["data"]["metadata"]["timestamp"] = timestamp.into();
+    request
+}
+
+/// Create a test advance_state request processed during rollup epoch `epoch_index`
+pub fn create_advance_request_in_epoch(
+    payload_json: &str,
+    msg_sender: &str,
+    block_number: u64,
+    epoch_index: u64,
 ) -> JsonValue {
     let payload_hex = hex::encode(payload_json);
 
@@ -36,8 +81,8 @@ pub fn create_advance_request(
             "metadata" => json::object! {
                 "msg_sender" => msg_sender,
                 "block_number" => block_number,
-                "timestamp" => 1234567890,
-                "epoch_index" => 0,
+                "timestamp" => ADVANCE_TIMESTAMP,
+                "epoch_index" => epoch_index,
                 "input_index" => 0
             }
         }
@@ -56,22 +101,554 @@ pub fn create_inspect_request(payload_json: &str) -> JsonValue {
     }
 }
 
+/// Wrap a notarize request in its action envelope
+#[allow(dead_code)]
+pub fn notarize_action(request: NotarizeRequest) -> String {
+    serde_json::json!({ "action": "notarize", "data": request }).to_string()
+}
+
 /// Create a notarize action payload
 pub fn create_notarize_payload(content: &[u8], file_name: &str, mime_type: &str) -> String {
-    use base64::Engine;
-    let content_base64 = base64::engine::general_purpose::STANDARD.encode(content);
+    notarize_action(
+        NotarizeRequestBuilder::new()
+            .content(content)
+            .file_name(file_name)
+            .mime_type(mime_type)
+            .build()
+            .unwrap(),
+    )
+}
 
-    format!(
-        r#"{{"action":"notarize","data":{{"content":"{}","file_name":"{}","mime_type":"{}"}}}}"#,
-        content_base64, file_name, mime_type
+/// Create a notarize action payload carrying a wallet signature of `signed_message`
+#[allow(dead_code)]
+pub fn create_signed_notarize_payload(
+    content: &[u8],
+    signature: &str,
+    signed_message: &str,
+) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name("signed.pdf")
+            .signature(signature, signed_message)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a preview action payload for `content`
+#[allow(dead_code)]
+pub fn create_preview_payload(content: &[u8], file_name: &str) -> String {
+    let request = PDF.content(content).file_name(file_name).build().unwrap();
+    serde_json::json!({ "action": "preview", "data": request }).to_string()
+}
+
+/// Create a notarize action payload carrying a replay protection nonce
+pub fn create_notarize_payload_with_nonce(
+    content: &[u8],
+    file_name: &str,
+    mime_type: &str,
+    nonce: u64,
+) -> String {
+    notarize_action(
+        NotarizeRequestBuilder::new()
+            .content(content)
+            .file_name(file_name)
+            .mime_type(mime_type)
+            .nonce(nonce)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a notarize action payload with category tags
+pub fn create_notarize_payload_with_tags(content: &[u8], file_name: &str, tags: &[&str]) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .tags(tags)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a notarize action payload that records an alias when the content is already notarized
+#[allow(dead_code)]
+pub fn create_notarize_payload_with_alias(content: &[u8], file_name: &str) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .allow_alias(true)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a notarize action payload with a human-readable description
+#[allow(dead_code)]
+pub fn create_notarize_payload_with_description(
+    content: &[u8],
+    file_name: &str,
+    description: &str,
+) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .description(description)
+            .build()
+            .unwrap(),
     )
 }
 
+/// Create a find_by_tag action payload
+pub fn create_find_by_tag_payload(tag: &str) -> String {
+    format!(r#"{{"action":"find_by_tag","data":{{"tag":"{}"}}}}"#, tag)
+}
+
 /// Create a verify payload for inspect requests (VerifyRequest format)
 pub fn create_verify_payload(content_hash: &str) -> String {
     format!(r#"{{"content_hash":"{}"}}"#, content_hash)
 }
 
+/// Create a verify_by_blake2b action payload
+#[allow(dead_code)]
+pub fn create_verify_blake2b_payload(hash: &str) -> String {
+    serde_json::json!({
+        "action": "verify_by_blake2b",
+        "data": { "hash": hash }
+    })
+    .to_string()
+}
+
+/// Create an update_metadata action payload
+pub fn create_update_metadata_payload(
+    content_hash: &str,
+    new_file_name: Option<&str>,
+    new_mime_type: Option<&str>,
+) -> String {
+    serde_json::json!({
+        "action": "update_metadata",
+        "data": {
+            "content_hash": content_hash,
+            "new_file_name": new_file_name,
+            "new_mime_type": new_mime_type
+        }
+    })
+    .to_string()
+}
+
+pub fn create_metadata_bag_payload(content_hash: &str, metadata: serde_json::Value) -> String {
+    serde_json::json!({
+        "action": "update_metadata",
+        "data": {
+            "content_hash": content_hash,
+            "metadata": metadata
+        }
+    })
+    .to_string()
+}
+
+/// Create a transfer_ownership action payload
+pub fn create_transfer_payload(content_hash: &str, new_owner: &str) -> String {
+    format!(
+        r#"{{"action":"transfer_ownership","data":{{"content_hash":"{}","new_owner":"{}"}}}}"#,
+        content_hash, new_owner
+    )
+}
+
+/// Create a purge action payload
+pub fn create_purge_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"purge","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Create a commit action payload for a content hash
+pub fn create_commit_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"commit","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Create a rehash action payload resuming after `after`
+pub fn create_rehash_payload(after: Option<&str>, limit: Option<usize>) -> String {
+    serde_json::json!({
+        "action": "rehash",
+        "data": { "after": after, "limit": limit }
+    })
+    .to_string()
+}
+
+/// Create a fetch_content action payload
+pub fn create_fetch_content_payload(document_id: &str) -> String {
+    serde_json::json!({
+        "action": "fetch_content",
+        "data": {
+            "document_id": document_id
+        }
+    })
+    .to_string()
+}
+
+/// Create a search_documents action payload
+pub fn create_search_payload(query: &str, limit: Option<usize>) -> String {
+    serde_json::json!({
+        "action": "search_documents",
+        "data": {
+            "query": query,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create a list_by_date_range action payload
+pub fn create_date_range_payload(start: i64, end: i64, limit: Option<usize>) -> String {
+    serde_json::json!({
+        "action": "list_by_date_range",
+        "data": {
+            "start": start,
+            "end": end,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create a get_inclusion_proof payload
+#[allow(dead_code)]
+pub fn create_inclusion_proof_payload(content_hash: &str, epoch: u64) -> String {
+    serde_json::json!({
+        "action": "get_inclusion_proof",
+        "data": {
+            "content_hash": content_hash,
+            "epoch": epoch
+        }
+    })
+    .to_string()
+}
+
+/// Create a get_proof payload
+#[allow(dead_code)]
+pub fn create_get_proof_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"get_proof","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Create a get_verifiable_credential payload
+#[allow(dead_code)]
+pub fn create_verifiable_credential_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"get_verifiable_credential","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Create a certificate payload
+#[allow(dead_code)]
+pub fn create_certificate_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"certificate","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Create a notarize payload requesting an on-chain callback to `callback_address`
+#[allow(dead_code)]
+pub fn create_notarize_payload_with_callback(
+    content: &[u8],
+    callback_address: &str,
+    idempotency_key: Option<&str>,
+) -> String {
+    let builder = PDF
+        .content(content)
+        .file_name("order.pdf")
+        .callback_address(callback_address);
+    let builder = match idempotency_key {
+        Some(key) => builder.idempotency_key(key),
+        None => builder,
+    };
+    notarize_action(builder.build().unwrap())
+}
+
+/// Create a notarize payload carrying a client retry key
+#[allow(dead_code)]
+pub fn create_notarize_payload_with_idempotency_key(
+    content: &[u8],
+    file_name: &str,
+    idempotency_key: &str,
+) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .idempotency_key(idempotency_key)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a notarize payload amending the document with hash `supersedes`
+#[allow(dead_code)]
+pub fn create_notarize_payload_superseding(
+    content: &[u8],
+    file_name: &str,
+    supersedes: &str,
+) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .supersedes(supersedes)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a notarize_chunk_start payload
+#[allow(dead_code)]
+pub fn create_chunk_start_payload(upload_id: &str, file_name: &str, mime_type: &str) -> String {
+    serde_json::json!({
+        "action": "notarize_chunk_start",
+        "data": {
+            "upload_id": upload_id,
+            "file_name": file_name,
+            "mime_type": mime_type
+        }
+    })
+    .to_string()
+}
+
+/// Create a notarize_chunk_append payload
+#[allow(dead_code)]
+pub fn create_chunk_append_payload(upload_id: &str, index: u32, chunk: &[u8]) -> String {
+    use base64::Engine;
+    serde_json::json!({
+        "action": "notarize_chunk_append",
+        "data": {
+            "upload_id": upload_id,
+            "index": index,
+            "content": base64::engine::general_purpose::STANDARD.encode(chunk)
+        }
+    })
+    .to_string()
+}
+
+/// Create a notarize_chunk_finish payload
+#[allow(dead_code)]
+pub fn create_chunk_finish_payload(upload_id: &str) -> String {
+    serde_json::json!({
+        "action": "notarize_chunk_finish",
+        "data": {
+            "upload_id": upload_id
+        }
+    })
+    .to_string()
+}
+
+/// Create a notarize payload that waits for `cosigners` to approve it
+#[allow(dead_code)]
+pub fn create_cosigned_notarize_payload(
+    content: &[u8],
+    file_name: &str,
+    cosigners: &[&str],
+) -> String {
+    notarize_action(
+        PDF.content(content)
+            .file_name(file_name)
+            .required_cosigners(cosigners)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Create a cosign payload approving the pending document `content_hash`
+#[allow(dead_code)]
+pub fn create_cosign_payload(content_hash: &str, signature: &str) -> String {
+    serde_json::json!({
+        "action": "cosign",
+        "data": {
+            "content_hash": content_hash,
+            "signature": signature
+        }
+    })
+    .to_string()
+}
+
+/// Create a timeseries payload
+#[allow(dead_code)]
+pub fn create_timeseries_payload(start: i64, end: i64) -> String {
+    serde_json::json!({
+        "action": "timeseries",
+        "data": { "start": start, "end": end }
+    })
+    .to_string()
+}
+
+/// Create a list_expired payload; `None` leaves `as_of` to the current time
+#[allow(dead_code)]
+pub fn create_list_expired_payload(as_of: Option<i64>) -> String {
+    serde_json::json!({
+        "action": "list_expired",
+        "data": {
+            "as_of": as_of
+        }
+    })
+    .to_string()
+}
+
+/// Create a get_document_by_id action payload
+#[allow(dead_code)]
+pub fn create_get_document_payload(id: &str) -> String {
+    serde_json::json!({
+        "action": "get_document_by_id",
+        "data": { "id": id }
+    })
+    .to_string()
+}
+
+/// Create a list_documents payload; `None` fetches the first page
+#[allow(dead_code)]
+pub fn create_list_documents_payload(after: Option<&str>, limit: usize) -> String {
+    serde_json::json!({
+        "action": "list_documents",
+        "data": {
+            "after": after,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create an export_registry payload for zero-based page `page`
+#[allow(dead_code)]
+pub fn create_export_registry_payload(page: usize, page_size: usize) -> String {
+    serde_json::json!({
+        "action": "export_registry",
+        "data": {
+            "page": page,
+            "page_size": page_size
+        }
+    })
+    .to_string()
+}
+
+/// Create a list_by_submitter payload; `None` fetches the first page
+#[allow(dead_code)]
+pub fn create_list_by_submitter_payload(
+    submitter: &str,
+    cursor: Option<&str>,
+    limit: usize,
+) -> String {
+    serde_json::json!({
+        "action": "list_by_submitter",
+        "data": {
+            "submitter": submitter,
+            "cursor": cursor,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create a sync_since payload starting after the `since` checkpoint
+#[allow(dead_code)]
+pub fn create_sync_since_payload(since: i64, limit: usize) -> String {
+    serde_json::json!({
+        "action": "sync_since",
+        "data": {
+            "since_created_at": since,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create an audit_log payload; `None` leaves a filter or the cursor out
+#[allow(dead_code)]
+pub fn create_audit_log_payload(
+    submitter: Option<&str>,
+    blocks: (Option<u64>, Option<u64>),
+    cursor: Option<i64>,
+    limit: usize,
+) -> String {
+    serde_json::json!({
+        "action": "audit_log",
+        "data": {
+            "submitter": submitter,
+            "from_block": blocks.0,
+            "to_block": blocks.1,
+            "cursor": cursor,
+            "limit": limit
+        }
+    })
+    .to_string()
+}
+
+/// Create a list_audit_log payload skipping the first `offset` entries
+#[allow(dead_code)]
+pub fn create_list_audit_log_payload(limit: usize, offset: usize) -> String {
+    serde_json::json!({
+        "action": "list_audit_log",
+        "data": {
+            "limit": limit,
+            "offset": offset
+        }
+    })
+    .to_string()
+}
+
+/// Create a get_statistics payload
+#[allow(dead_code)]
+pub fn create_statistics_payload() -> String {
+    serde_json::json!({ "action": "get_statistics" }).to_string()
+}
+
+/// Create a verify_content payload carrying `content` and the hash it should match
+#[allow(dead_code)]
+pub fn create_verify_content_payload(content: &[u8], content_hash: &str) -> String {
+    use base64::Engine;
+    serde_json::json!({
+        "action": "verify_content",
+        "data": {
+            "content": base64::engine::general_purpose::STANDARD.encode(content),
+            "content_hash": content_hash
+        }
+    })
+    .to_string()
+}
+
+/// Create a verify_by_content payload carrying `content` for the DApp to hash
+#[allow(dead_code)]
+pub fn create_verify_by_content_payload(content: &[u8]) -> String {
+    use base64::Engine;
+    serde_json::json!({
+        "action": "verify_by_content",
+        "data": { "content": base64::engine::general_purpose::STANDARD.encode(content) }
+    })
+    .to_string()
+}
+
+/// Create an ERC20Portal deposit input: packed token, depositor and uint256 amount
+#[allow(dead_code)]
+pub fn create_erc20_deposit_request(
+    portal: &str,
+    token: &str,
+    depositor: &str,
+    amount: u128,
+    block_number: u64,
+) -> JsonValue {
+    let mut payload = hex::decode(&token[2..]).unwrap();
+    payload.extend(hex::decode(&depositor[2..]).unwrap());
+    payload.extend([0u8; 16]);
+    payload.extend(amount.to_be_bytes());
+
+    let mut request = create_advance_request("", portal, block_number);
+    request["data"]["payload"] = hex::encode(payload).into();
+    request
+}
+
 /// Decode a hex-encoded payload
 #[allow(dead_code)]
 pub fn decode_hex_payload(hex_str: &str) -> Result<String, Box<dyn std::error::Error>> {
@@ -86,12 +663,13 @@ mod tests {
     #[test]
     fn test_create_advance_request() {
         let payload = r#"{"test":"data"}"#;
-        let req = create_advance_request(payload, "0x123", 100);
+        let req =
+            create_advance_request(payload, "0x0000000000000000000000000000000000000123", 100);
 
         assert_eq!(req["request_type"].as_str().unwrap(), "advance_state");
         assert_eq!(
             req["data"]["metadata"]["msg_sender"].as_str().unwrap(),
-            "0x123"
+            "0x0000000000000000000000000000000000000123"
         );
         assert_eq!(
             req["data"]["metadata"]["block_number"].as_u64().unwrap(),
@@ -0,0 +1,77 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+const BOB: &str = "0x00000000000000000000000000000000000000b2";
+
+async fn advance(
+    repo: &SqliteRepository,
+    server_url: &str,
+    payload: &str,
+    sender: &str,
+    block_number: u64,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let request = create_advance_request(payload, sender, block_number);
+    handle_advance(&client, server_url, repo, &Config::default(), request)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_retry_with_same_key_returns_original_receipt() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_notarize_payload_with_idempotency_key(b"lease", "lease.pdf", "req-42");
+    assert_eq!(
+        advance(&repo, &server_url, &payload, ALICE, 100).await,
+        "accept"
+    );
+    assert_eq!(
+        advance(&repo, &server_url, &payload, ALICE, 101).await,
+        "accept"
+    );
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 2);
+    assert_eq!(notices[0], notices[1]);
+    let notice: serde_json::Value = serde_json::from_str(&notices[1]).unwrap();
+    assert_eq!(notice["receipt"]["block_number"], 100);
+    assert!(server.get_reports().is_empty());
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_different_user_duplicate_still_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_notarize_payload_with_idempotency_key(b"lease", "lease.pdf", "req-42");
+    assert_eq!(
+        advance(&repo, &server_url, &payload, ALICE, 100).await,
+        "accept"
+    );
+    assert_eq!(
+        advance(&repo, &server_url, &payload, BOB, 101).await,
+        "reject"
+    );
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert_eq!(server.get_notices().len(), 1);
+    let reports = server.get_reports();
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "DUPLICATE_DOCUMENT");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("already exists"));
+}
@@ -0,0 +1,102 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// In-memory writer so the test can inspect formatted log output
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_advance_logs_include_span_fields() {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"Logged content", "log.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x00000000000000000000000000000000feedface", 4242);
+
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let output = logs.contents();
+    let line = output
+        .lines()
+        .find(|line| line.contains("Document notarized successfully"))
+        .expect("Expected a notarization log line");
+
+    let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(entry["span"]["name"], "advance");
+    assert_eq!(
+        entry["span"]["msg_sender"],
+        "0x00000000000000000000000000000000feedface"
+    );
+    assert_eq!(entry["span"]["block_number"], 4242);
+    assert_eq!(entry["span"]["action"], "notarize");
+    assert!(entry["fields"]["document_id"].is_string());
+}
+
+#[tokio::test]
+async fn test_inspect_logs_record_query_action() {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_export_registry_payload(0, 10));
+
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let output = logs.contents();
+    let line = output
+        .lines()
+        .find(|line| line.contains("Exporting registry"))
+        .expect("Expected a registry export log line");
+
+    let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(entry["span"]["name"], "inspect");
+    assert_eq!(entry["span"]["action"], "export_registry");
+}
@@ -0,0 +1,188 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, MAX_METADATA_BYTES};
+use dapp::domain::{ContentHash, Document};
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const OWNER: &str = "0x0000000000000000000000000000000000000aaa";
+const INTRUDER: &str = "0x0000000000000000000000000000000000000bbb";
+
+/// Store a document owned by `owner` and return its content hash
+fn seed_document(repo: &SqliteRepository, owner: &str) -> String {
+    let doc = Document::new(b"Metadata content", "wrong-name.txt", "text/plain", owner);
+    repo.save_document(&doc).unwrap();
+    doc.content_hash.into()
+}
+
+#[tokio::test]
+async fn test_update_metadata_by_owner_succeeds() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo, OWNER);
+
+    let payload =
+        create_update_metadata_payload(&content_hash, Some("contract.txt"), Some("text/markdown"));
+    let request = create_advance_request(&payload, OWNER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Notice records both the old and new values
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(notice_json["type"], "metadata_update");
+    assert_eq!(
+        notice_json["change"]["previous_file_name"],
+        "wrong-name.txt"
+    );
+    assert_eq!(notice_json["change"]["file_name"], "contract.txt");
+    assert_eq!(notice_json["change"]["mime_type"], "text/markdown");
+
+    // Content hash is untouched
+    let stored = repo
+        .find_by_hash(&ContentHash::from(content_hash.as_str()))
+        .unwrap();
+    assert_eq!(stored.file_name, "contract.txt");
+    assert_eq!(stored.content_hash, content_hash);
+}
+
+#[tokio::test]
+async fn test_update_metadata_by_other_caller_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo, OWNER);
+
+    let payload = create_update_metadata_payload(&content_hash, Some("stolen.txt"), None);
+    let request = create_advance_request(&payload, INTRUDER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("original submitter"));
+    assert!(server.get_notices().is_empty());
+    assert_eq!(
+        repo.find_by_hash(&ContentHash::from(content_hash.as_str()))
+            .unwrap()
+            .file_name,
+        "wrong-name.txt"
+    );
+}
+
+#[tokio::test]
+async fn test_update_metadata_nonexistent_hash_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_update_metadata_payload(&"c".repeat(64), Some("renamed.txt"), None);
+    let request = create_advance_request(&payload, OWNER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("not found"));
+}
+
+#[tokio::test]
+async fn test_update_metadata_merges_keys() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo, OWNER);
+
+    let payload = create_metadata_bag_payload(
+        &content_hash,
+        serde_json::json!({"department": "legal", "case": "A-1"}),
+    );
+    let request = create_advance_request(&payload, OWNER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    // New keys are added, existing ones overwritten and null ones removed
+    let payload = create_metadata_bag_payload(
+        &content_hash,
+        serde_json::json!({"case": "A-2", "department": null, "reviewer": "bob"}),
+    );
+    let request = create_advance_request(&payload, OWNER, 201);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let expected = serde_json::json!({"case": "A-2", "reviewer": "bob"});
+    let stored = repo
+        .find_by_hash(&ContentHash::from(content_hash.as_str()))
+        .unwrap();
+    assert_eq!(stored.metadata, expected);
+    assert_eq!(stored.file_name, "wrong-name.txt");
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 2);
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[1]).unwrap();
+    assert_eq!(notice_json["change"]["metadata"], expected);
+}
+
+#[tokio::test]
+async fn test_update_metadata_rejects_oversized_or_non_object_metadata() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo, OWNER);
+
+    let payload = create_metadata_bag_payload(
+        &content_hash,
+        serde_json::json!({"notes": "x".repeat(MAX_METADATA_BYTES)}),
+    );
+    let request = create_advance_request(&payload, OWNER, 200);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    let payload = create_metadata_bag_payload(&content_hash, serde_json::json!(["legal"]));
+    let request = create_advance_request(&payload, OWNER, 201);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    assert!(reports[0].contains("exceeds limit of 4096 bytes"));
+    assert!(reports[1].contains("must be a JSON object"));
+    assert!(server.get_notices().is_empty());
+
+    let stored = repo
+        .find_by_hash(&ContentHash::from(content_hash.as_str()))
+        .unwrap();
+    assert_eq!(stored.metadata, serde_json::json!({}));
+}
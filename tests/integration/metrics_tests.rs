@@ -0,0 +1,94 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+use dapp::infrastructure::metrics;
+
+#[tokio::test]
+async fn test_notarize_increments_counters() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"Metrics content", "metrics.txt", "text/plain");
+
+    let notarizations_before = metrics::NOTARIZATIONS_TOTAL.get();
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    assert_eq!(
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap(),
+        "accept"
+    );
+    assert!(metrics::NOTARIZATIONS_TOTAL.get() > notarizations_before);
+
+    // Resubmitting the same content counts as a duplicate rejection
+    let duplicates_before = metrics::DUPLICATE_REJECTIONS_TOTAL.get();
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000456", 101);
+    assert_eq!(
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap(),
+        "reject"
+    );
+    assert!(metrics::DUPLICATE_REJECTIONS_TOTAL.get() > duplicates_before);
+}
+
+#[tokio::test]
+async fn test_verify_increments_counter() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let verifications_before = metrics::VERIFICATIONS_TOTAL.get();
+    let verify_req = create_inspect_request(&create_verify_payload(&"b".repeat(64)));
+    assert_eq!(
+        handle_inspect(&client, &server_url, &repo, &config, verify_req)
+            .await
+            .unwrap(),
+        "accept"
+    );
+    assert!(metrics::VERIFICATIONS_TOTAL.get() > verifications_before);
+}
+
+#[tokio::test]
+async fn test_metrics_inspect_reports_prometheus_text() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // Store one document so the gauge has something to report
+    let payload = create_notarize_payload(b"Gauge content", "gauge.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    handle_advance(&client, &server_url, &repo, &config, request)
+        .await
+        .unwrap();
+    server.clear();
+
+    let request = create_inspect_request(r#"{"action":"metrics"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("# TYPE notarizations_total counter"));
+    assert!(reports[0].contains("# TYPE db_query_duration_seconds histogram"));
+    assert!(reports[0].contains("documents_stored 1"));
+}
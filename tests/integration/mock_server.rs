@@ -4,31 +4,69 @@ use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 
+/// Canned GIO response and the request bodies received on `/gio`
+#[derive(Default)]
+struct GioStub {
+    response: Option<(u16, String)>,
+    requests: Vec<String>,
+}
+
 #[derive(Clone)]
 pub struct MockRollupServer {
-    notices: Arc<Mutex<Vec<String>>>,
+    /// Decoded notice payloads, which may be JSON text or binary
+    notices: Arc<Mutex<Vec<Vec<u8>>>>,
+    /// Raw JSON bodies received on `/voucher`
+    vouchers: Arc<Mutex<Vec<String>>>,
     reports: Arc<Mutex<Vec<String>>>,
+    exceptions: Arc<Mutex<Vec<String>>>,
+    gio: Arc<Mutex<GioStub>>,
+    prefix: String,
 }
 
 impl MockRollupServer {
     pub fn new() -> Self {
+        Self::with_prefix("")
+    }
+
+    /// Mount the endpoints under a path prefix, e.g. `/rollup/notice`
+    pub fn with_prefix(prefix: &str) -> Self {
         Self {
             notices: Arc::new(Mutex::new(Vec::new())),
+            vouchers: Arc::new(Mutex::new(Vec::new())),
             reports: Arc::new(Mutex::new(Vec::new())),
+            exceptions: Arc::new(Mutex::new(Vec::new())),
+            gio: Arc::new(Mutex::new(GioStub::default())),
+            prefix: prefix.to_string(),
         }
     }
 
     pub async fn start(&self) -> String {
         let notices = self.notices.clone();
+        let vouchers = self.vouchers.clone();
         let reports = self.reports.clone();
+        let exceptions = self.exceptions.clone();
+        let gio = self.gio.clone();
+        let prefix = self.prefix.clone();
 
         let make_svc = make_service_fn(move |_conn| {
             let notices = notices.clone();
+            let vouchers = vouchers.clone();
             let reports = reports.clone();
+            let exceptions = exceptions.clone();
+            let gio = gio.clone();
+            let prefix = prefix.clone();
 
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
-                    handle_request(req, notices.clone(), reports.clone())
+                    handle_request(
+                        req,
+                        notices.clone(),
+                        vouchers.clone(),
+                        reports.clone(),
+                        exceptions.clone(),
+                        gio.clone(),
+                        prefix.clone(),
+                    )
                 }))
             }
         });
@@ -48,28 +86,65 @@ impl MockRollupServer {
         format!("http://{}", actual_addr)
     }
 
+    /// Notice payloads that are UTF-8 text, i.e. JSON notices
     pub fn get_notices(&self) -> Vec<String> {
+        self.notices
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|payload| String::from_utf8(payload.clone()).ok())
+            .collect()
+    }
+
+    /// Every notice payload as raw bytes, including binary ones
+    #[allow(dead_code)]
+    pub fn get_notice_payloads(&self) -> Vec<Vec<u8>> {
         self.notices.lock().unwrap().clone()
     }
 
+    /// Voucher request bodies, with `destination`, `value` and hex `payload`
+    pub fn get_vouchers(&self) -> Vec<String> {
+        self.vouchers.lock().unwrap().clone()
+    }
+
     pub fn get_reports(&self) -> Vec<String> {
         self.reports.lock().unwrap().clone()
     }
 
+    /// Decoded payloads received on `/exception`
+    pub fn get_exceptions(&self) -> Vec<String> {
+        self.exceptions.lock().unwrap().clone()
+    }
+
+    /// Answer every `/gio` request with this code and hex data
+    pub fn set_gio_response(&self, code: u16, data: &str) {
+        self.gio.lock().unwrap().response = Some((code, data.to_string()));
+    }
+
+    /// Raw JSON bodies of the GIO requests received so far
+    pub fn get_gio_requests(&self) -> Vec<String> {
+        self.gio.lock().unwrap().requests.clone()
+    }
+
     pub fn clear(&self) {
         self.notices.lock().unwrap().clear();
+        self.vouchers.lock().unwrap().clear();
         self.reports.lock().unwrap().clear();
     }
 }
 
 async fn handle_request(
     req: Request<Body>,
-    notices: Arc<Mutex<Vec<String>>>,
+    notices: Arc<Mutex<Vec<Vec<u8>>>>,
+    vouchers: Arc<Mutex<Vec<String>>>,
     reports: Arc<Mutex<Vec<String>>>,
+    exceptions: Arc<Mutex<Vec<String>>>,
+    gio: Arc<Mutex<GioStub>>,
+    prefix: String,
 ) -> Result<Response<Body>, Infallible> {
-    let path = req.uri().path();
+    let path = req.uri().path().to_string();
 
-    match path {
+    match path.strip_prefix(prefix.as_str()).unwrap_or_default() {
         "/notice" => {
             // Read body
             let body_bytes = hyper::body::to_bytes(req.into_body())
@@ -80,11 +155,9 @@ async fn handle_request(
                 // Parse JSON to extract payload
                 if let Ok(json) = json::parse(body_str) {
                     if let Some(payload_hex) = json["payload"].as_str() {
-                        // Decode hex to get actual JSON
+                        // Decode hex to get the actual payload
                         if let Ok(payload_bytes) = hex::decode(payload_hex) {
-                            if let Ok(payload_json) = std::str::from_utf8(&payload_bytes) {
-                                notices.lock().unwrap().push(payload_json.to_string());
-                            }
+                            notices.lock().unwrap().push(payload_bytes);
                         }
                     }
                 }
@@ -92,6 +165,18 @@ async fn handle_request(
 
             Ok(Response::new(Body::from("{\"status\":\"ok\"}")))
         }
+        "/voucher" => {
+            let body_bytes = hyper::body::to_bytes(req.into_body())
+                .await
+                .unwrap_or_default();
+
+            // Vouchers carry hex calldata directly, so the body is kept as sent
+            let mut vouchers = vouchers.lock().unwrap();
+            vouchers.push(String::from_utf8_lossy(&body_bytes).to_string());
+
+            let body = json::object! { "index" => vouchers.len() - 1 };
+            Ok(Response::new(Body::from(body.dump())))
+        }
         "/report" => {
             // Read body
             let body_bytes = hyper::body::to_bytes(req.into_body())
@@ -114,6 +199,46 @@ async fn handle_request(
 
             Ok(Response::new(Body::from("{\"status\":\"ok\"}")))
         }
+        "/exception" => {
+            let body_bytes = hyper::body::to_bytes(req.into_body())
+                .await
+                .unwrap_or_default();
+
+            // Same hex-encoded payload envelope as notices and reports
+            let payload = std::str::from_utf8(&body_bytes)
+                .ok()
+                .and_then(|body| json::parse(body).ok())
+                .and_then(|json| json["payload"].as_str().map(str::to_string))
+                .and_then(|payload_hex| hex::decode(payload_hex).ok())
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+
+            if let Some(payload) = payload {
+                exceptions.lock().unwrap().push(payload);
+            }
+
+            Ok(Response::new(Body::from("{\"status\":\"ok\"}")))
+        }
+        "/gio" => {
+            let body_bytes = hyper::body::to_bytes(req.into_body())
+                .await
+                .unwrap_or_default();
+
+            let mut gio = gio.lock().unwrap();
+            gio.requests
+                .push(String::from_utf8_lossy(&body_bytes).to_string());
+
+            match &gio.response {
+                Some((code, data)) => {
+                    let body = json::object! { "code" => *code, "data" => data.as_str() };
+                    Ok(Response::new(Body::from(body.dump())))
+                }
+                None => {
+                    let mut response = Response::new(Body::from("No GIO handler"));
+                    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    Ok(response)
+                }
+            }
+        }
         _ => {
             let mut response = Response::new(Body::from("Not Found"));
             *response.status_mut() = StatusCode::NOT_FOUND;
@@ -1,3 +1,43 @@
+mod audit_tests;
+mod cbor_tests;
+mod certificate_tests;
+mod chunk_tests;
+mod commit_tests;
+mod content_tests;
+mod cosign_tests;
+mod date_range_tests;
+mod deposit_tests;
+mod epoch_tests;
+mod error_code_tests;
+mod exception_tests;
+mod expiry_tests;
+mod export_registry_tests;
+mod get_document_tests;
 mod helpers;
+mod idempotency_tests;
+mod logging_tests;
+mod metadata_tests;
+mod metrics_tests;
 mod mock_server;
+mod nonce_tests;
+mod ownership_tests;
+mod postgres_tests;
+mod preview_tests;
+mod proof_tests;
+mod protocol_version_tests;
+mod provenance_tests;
+mod purge_tests;
+mod quota_tests;
+mod rate_limit_tests;
+mod rehash_tests;
 mod rollup_tests;
+mod search_tests;
+mod sqlite_tests;
+mod statistics_tests;
+mod tag_tests;
+mod validation_tests;
+mod vc_tests;
+mod verify_content_tests;
+mod version_tests;
+mod voucher_tests;
+mod wallet_signature_tests;
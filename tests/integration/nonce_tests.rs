@@ -0,0 +1,171 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SENDER: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+fn nonce_config() -> Config {
+    Config {
+        require_nonce: true,
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn test_increasing_nonces_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = nonce_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    for (nonce, content) in [(1, b"First nonce doc"), (2, b"Other nonce doc")] {
+        let payload = create_notarize_payload_with_nonce(content, "doc.txt", "text/plain", nonce);
+        let request = create_advance_request(&payload, SENDER, 100 + nonce);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    assert_eq!(repo.count_documents().unwrap(), 2);
+    assert_eq!(repo.find_last_nonce(SENDER).unwrap(), Some(2));
+}
+
+#[tokio::test]
+async fn test_replayed_nonce_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = nonce_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload_with_nonce(b"Replay me", "doc.txt", "text/plain", 3);
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    // Same nonce with different content must not get past the nonce check
+    let payload = create_notarize_payload_with_nonce(b"Fresh content", "doc.txt", "text/plain", 3);
+    let request = create_advance_request(&payload, SENDER, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    // Lower nonce is rejected too
+    let payload = create_notarize_payload_with_nonce(b"Older content", "doc.txt", "text/plain", 2);
+    let request = create_advance_request(&payload, SENDER, 102);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    assert!(reports[0].contains("must be greater than last used nonce 3"));
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_resubmitted_payload_rejected_before_duplicate_check() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = nonce_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // The exact accepted payload is sent again in a later block
+    let payload =
+        create_notarize_payload_with_nonce(b"Captured payload", "doc.txt", "text/plain", 7);
+    for block_number in [100, 250] {
+        let request = create_advance_request(&payload, SENDER, block_number);
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap();
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Caught by the nonce, not by hashing the content again
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Nonce 7 must be greater than last used nonce 7"));
+    assert!(!reports[0].contains("already exists"));
+}
+
+#[tokio::test]
+async fn test_missing_nonce_rejected_when_required() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = nonce_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload(b"No nonce", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Nonce is required"));
+}
+
+#[tokio::test]
+async fn test_payload_without_nonce_accepted_when_not_required() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload(b"Legacy payload", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    assert_eq!(repo.find_last_nonce(SENDER).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_present_nonce_checked_with_default_config() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // The same payload and nonce resubmitted in a later block
+    let payload = create_notarize_payload_with_nonce(b"Optional nonce", "doc.txt", "text/plain", 5);
+    let mut statuses = Vec::new();
+    for block_number in [100, 250] {
+        let request = create_advance_request(&payload, SENDER, block_number);
+        statuses.push(
+            handle_advance(&client, &server_url, &repo, &config, request)
+                .await
+                .unwrap(),
+        );
+    }
+    assert_eq!(statuses, ["accept", "reject"]);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Nonce 5 must be greater than last used nonce 5"));
+    assert_eq!(repo.find_last_nonce(SENDER).unwrap(), Some(5));
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
@@ -0,0 +1,114 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::{ContentHash, Document};
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const OWNER: &str = "0x1111111111111111111111111111111111111111";
+const BUYER: &str = "0x2222222222222222222222222222222222222222";
+
+/// Store a document owned by OWNER and return its content hash
+fn seed_document(repo: &SqliteRepository) -> String {
+    let doc = Document::new(b"Deed of sale", "deed.pdf", "application/pdf", OWNER);
+    repo.save_document(&doc).unwrap();
+    doc.content_hash.into()
+}
+
+#[tokio::test]
+async fn test_transfer_by_owner_succeeds() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let payload = create_transfer_payload(&content_hash, BUYER);
+    let request = create_advance_request(&payload, OWNER, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Notice records both owners
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(notice_json["type"], "ownership_transfer");
+    assert_eq!(notice_json["transfer"]["previous_owner"], OWNER);
+    assert_eq!(notice_json["transfer"]["new_owner"], BUYER);
+    assert_eq!(notice_json["transfer"]["block_number"], 300);
+    assert_eq!(notice_json["transfer"]["transferred_at"], ADVANCE_TIMESTAMP);
+
+    // Document now belongs to the buyer, with the change kept in history
+    assert_eq!(
+        repo.find_by_hash(&ContentHash::from(content_hash.as_str()))
+            .unwrap()
+            .submitted_by,
+        BUYER
+    );
+    let history = repo
+        .find_ownership_history(&ContentHash::from(content_hash.as_str()))
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].previous_owner, OWNER);
+}
+
+#[tokio::test]
+async fn test_transfer_to_invalid_address_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let payload = create_transfer_payload(&content_hash, "0xnot-an-address");
+    let request = create_advance_request(&payload, OWNER, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Invalid new owner address"));
+    assert_eq!(
+        repo.find_by_hash(&ContentHash::from(content_hash.as_str()))
+            .unwrap()
+            .submitted_by,
+        OWNER
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_by_non_owner_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let payload = create_transfer_payload(&content_hash, BUYER);
+    let request = create_advance_request(&payload, BUYER, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("current owner"));
+    assert!(repo
+        .find_ownership_history(&ContentHash::from(content_hash.as_str()))
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,55 @@
+use dapp::application::{Config, RepositoryKind};
+use dapp::domain::Document;
+use dapp::handlers::get_repository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+#[test]
+fn test_get_repository_unreachable_postgres_returns_error() {
+    let config = Config {
+        repository: RepositoryKind::Postgres("postgres://notary@127.0.0.1:1/notary".to_string()),
+        ..Config::default()
+    };
+
+    assert!(get_repository(&config).is_err());
+}
+
+#[test]
+fn test_postgres_repository_serves_the_async_runtime() {
+    // Needs a server; see NOTARY_TEST_POSTGRES_URL in the README
+    let Ok(url) = std::env::var("NOTARY_TEST_POSTGRES_URL") else {
+        return;
+    };
+    let schema = format!("notary_test_{}", uuid::Uuid::new_v4().simple());
+    let mut admin = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+    admin
+        .batch_execute(&format!("CREATE SCHEMA {}", schema))
+        .unwrap();
+
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let config = Config {
+        repository: RepositoryKind::Postgres(format!(
+            "{}{}options=-csearch_path%3D{}",
+            url, separator, schema
+        )),
+        ..Config::default()
+    };
+
+    // main opens and drops its repository on a tokio worker, like this test
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let found = runtime.block_on(async {
+        let writer = get_repository(&config).unwrap();
+        let doc = Document::new(b"postgres", "file.txt", "text/plain", SUBMITTER);
+        writer.save_document(&doc).unwrap();
+        drop(writer);
+
+        let reader = get_repository(&config).unwrap();
+        reader.find_by_hash(&doc.content_hash).unwrap().id == doc.id
+    });
+    drop(runtime);
+
+    admin
+        .batch_execute(&format!("DROP SCHEMA {} CASCADE", schema))
+        .unwrap();
+    assert!(found);
+}
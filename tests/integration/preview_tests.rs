@@ -0,0 +1,94 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+async fn inspect_preview(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    payload: &str,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let result = handle_inspect(
+        &client,
+        server_url,
+        repo,
+        &Config::default(),
+        create_inspect_request(payload),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    serde_json::from_str(server.get_reports().last().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_preview_of_new_content_writes_nothing() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_preview_payload(b"draft contract", "draft.pdf");
+    let report = inspect_preview(&repo, &server, &server_url, &payload).await;
+
+    assert_eq!(
+        report["content_hash"],
+        Document::hash_content(b"draft contract").as_str()
+    );
+    assert_eq!(report["would_duplicate"], false);
+    assert_eq!(repo.count_documents().unwrap(), 0);
+    assert!(server.get_notices().is_empty());
+}
+
+#[tokio::test]
+async fn test_preview_flags_notarized_content() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let notarize = create_notarize_payload(b"signed contract", "signed.pdf", "application/pdf");
+    handle_advance(
+        &client,
+        &server_url,
+        &repo,
+        &Config::default(),
+        create_advance_request(&notarize, SUBMITTER, 100),
+    )
+    .await
+    .unwrap();
+
+    // A different filename does not change the content hash
+    let payload = create_preview_payload(b"signed contract", "copy.pdf");
+    let report = inspect_preview(&repo, &server, &server_url, &payload).await;
+
+    assert_eq!(report["would_duplicate"], true);
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_preview_reports_invalid_content() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = r#"{"action":"preview","data":{"content":"!!!","file_name":"a.pdf","mime_type":"application/pdf"}}"#;
+    let report = inspect_preview(&repo, &server, &server_url, payload).await;
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid base64 content"));
+
+    let payload = create_preview_payload(b"", "empty.pdf");
+    let report = inspect_preview(&repo, &server, &server_url, &payload).await;
+    assert_eq!(report["error_detail"], "Content cannot be empty");
+}
@@ -0,0 +1,84 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+async fn request_proof(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    content_hash: &str,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let payload = create_get_proof_payload(content_hash);
+    handle_inspect(
+        &client,
+        server_url,
+        repo,
+        &Config::default(),
+        create_inspect_request(&payload),
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    serde_json::from_str(server.get_reports().last().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_proof_matches_notarization_receipt() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"signed lease", "lease.pdf", "application/pdf");
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    handle_advance(&client, &server_url, &repo, &Config::default(), request)
+        .await
+        .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notice: serde_json::Value =
+        serde_json::from_str(server.get_notices().last().unwrap()).unwrap();
+    let receipt = &notice["receipt"];
+    let content_hash = receipt["content_hash"].as_str().unwrap();
+
+    let report = request_proof(&repo, &server, &server_url, content_hash).await;
+
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["proof"], receipt["proof"]);
+    // Nothing beyond the proof is reported
+    assert_eq!(report.as_object().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_proof_of_missing_hash_is_null() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report = request_proof(&repo, &server, &server_url, &"b".repeat(64)).await;
+
+    assert_eq!(report["exists"], false);
+    assert!(report["proof"].is_null());
+}
+
+#[tokio::test]
+async fn test_proof_rejects_malformed_hash() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report = request_proof(&repo, &server, &server_url, "xyz").await;
+
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid hash format"));
+}
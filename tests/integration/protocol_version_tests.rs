@@ -0,0 +1,101 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, InputAction, ProtocolVersionError};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use serde_json::json;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+/// Notarize payload for `content`, with a `version` field when one is given
+fn versioned_notarize_payload(content: &[u8], version: Option<u8>) -> String {
+    let mut payload: serde_json::Value =
+        serde_json::from_str(&create_notarize_payload(content, "doc.txt", "text/plain")).unwrap();
+    if let Some(version) = version {
+        payload["version"] = json!(version);
+    }
+    payload.to_string()
+}
+
+#[test]
+fn test_payload_without_version_is_version_one() {
+    let payload = versioned_notarize_payload(b"Unversioned", None);
+
+    assert!(serde_json::from_str::<InputAction>(&payload).is_ok());
+    assert_eq!(InputAction::protocol_version(&payload).unwrap(), 1);
+
+    let payload = versioned_notarize_payload(b"Versioned", Some(1));
+    assert!(serde_json::from_str::<InputAction>(&payload).is_ok());
+    assert_eq!(InputAction::protocol_version(&payload).unwrap(), 1);
+
+    let payload = versioned_notarize_payload(b"Future", Some(99));
+    assert!(matches!(
+        InputAction::protocol_version(&payload),
+        Err(ProtocolVersionError::Unsupported(99))
+    ));
+}
+
+#[tokio::test]
+async fn test_version_one_payloads_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    for (content, version) in [
+        (&b"Implicit version"[..], None),
+        (b"Explicit version", Some(1)),
+    ] {
+        let payload = versioned_notarize_payload(content, version);
+        let request = create_advance_request(&payload, SUBMITTER, 100);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    assert_eq!(repo.count_documents().unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_unsupported_version_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = versioned_notarize_payload(b"Future payload", Some(99));
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    // Inspects report the same error, though they always accept
+    let payload = json!({"action": "health", "version": 99}).to_string();
+    let result = handle_inspect(
+        &client,
+        &server_url,
+        &repo,
+        &config,
+        create_inspect_request(&payload),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    for report in &reports {
+        let report: serde_json::Value = serde_json::from_str(report).unwrap();
+        assert_eq!(report["error_code"], "UNSUPPORTED_VERSION");
+        assert!(report["error_detail"]
+            .as_str()
+            .unwrap()
+            .contains("Unsupported protocol version 99"));
+    }
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
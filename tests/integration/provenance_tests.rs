@@ -0,0 +1,117 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::handle_inspect;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const ORACLE_DOMAIN: u16 = 45;
+
+fn oracle_config() -> Config {
+    Config {
+        gio_domain: Some(ORACLE_DOMAIN),
+        ..Config::default()
+    }
+}
+
+fn provenance_payload(content_hash: &str) -> String {
+    format!(
+        r#"{{"action":"verify_provenance","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    )
+}
+
+/// Store a document and return its content hash
+fn seed_document(repo: &SqliteRepository) -> String {
+    let doc = Document::new(
+        b"Provenance content",
+        "deed.pdf",
+        "application/pdf",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+    doc.content_hash.into()
+}
+
+#[tokio::test]
+async fn test_gio_response_included_in_report() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = oracle_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    server.set_gio_response(200, "0x6f6b");
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let request = create_inspect_request(&provenance_payload(&content_hash));
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // The oracle was asked about this hash on the configured domain
+    let gio_requests = server.get_gio_requests();
+    assert_eq!(gio_requests.len(), 1);
+    let gio_request: serde_json::Value = serde_json::from_str(&gio_requests[0]).unwrap();
+    assert_eq!(gio_request["domain"], ORACLE_DOMAIN);
+    assert_eq!(gio_request["id"], format!("0x{}", content_hash));
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["exists"], true);
+    assert_eq!(report_json["oracle"]["code"], 200);
+    assert_eq!(report_json["oracle"]["data"], "0x6f6b");
+    assert!(report_json.get("error").is_none());
+}
+
+#[tokio::test]
+async fn test_gio_failure_code_surfaces_in_report_error() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = oracle_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    server.set_gio_response(404, "0x");
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let request = create_inspect_request(&provenance_payload(&content_hash));
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["exists"], true);
+    assert_eq!(report_json["oracle"]["code"], 404);
+    assert_eq!(report_json["error"], "Oracle returned code 404");
+}
+
+#[tokio::test]
+async fn test_provenance_requires_configured_domain() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    let request = create_inspect_request(&provenance_payload(&content_hash));
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert!(server.get_gio_requests().is_empty());
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Provenance oracle is not configured"));
+}
@@ -0,0 +1,112 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::{ContentHash, Document};
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const ADMIN: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const OWNER: &str = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359";
+
+fn admin_config() -> Config {
+    Config {
+        admin_address: Some(ADMIN.to_string()),
+        ..Config::default()
+    }
+}
+
+/// Store a document owned by `OWNER` and return its content hash
+fn seed_document(repo: &SqliteRepository) -> String {
+    let doc = Document::new(b"Personal data", "personal.txt", "text/plain", OWNER);
+    repo.save_document(&doc).unwrap();
+    doc.content_hash.into()
+}
+
+#[tokio::test]
+async fn test_admin_purge_deletes_document() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = admin_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    // Admin address matches regardless of case
+    let payload = create_purge_payload(&content_hash);
+    let request = create_advance_request(&payload, &ADMIN.to_lowercase(), 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Notice records the erasure
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    assert_eq!(notice_json["type"], "purge");
+    assert_eq!(notice_json["purge"]["content_hash"], content_hash);
+    assert_eq!(notice_json["purge"]["block_number"], 300);
+    assert_eq!(notice_json["purge"]["purged_at"], ADVANCE_TIMESTAMP);
+
+    assert!(repo
+        .find_by_hash(&ContentHash::from(content_hash.as_str()))
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_non_admin_purge_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = admin_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content_hash = seed_document(&repo);
+
+    // Not even the document owner may purge
+    let payload = create_purge_payload(&content_hash);
+    let request = create_advance_request(&payload, OWNER, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    // Purging is disabled entirely without an admin address
+    let request = create_advance_request(&payload, ADMIN, 301);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    assert!(reports[0].contains("Only the admin"));
+    assert!(reports[1].contains("no admin address configured"));
+    assert!(server.get_notices().is_empty());
+    assert!(repo
+        .find_by_hash(&ContentHash::from(content_hash.as_str()))
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_purge_missing_hash_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = admin_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_purge_payload(&"0".repeat(64));
+    let request = create_advance_request(&payload, ADMIN, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Document not found"));
+}
@@ -0,0 +1,105 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SENDER: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+const OTHER_SENDER: &str = "0x0000000000000000000000000000000000000456";
+
+fn quota_config(max_per_epoch: u64) -> Config {
+    Config {
+        max_per_submitter_per_epoch: Some(max_per_epoch),
+        ..Config::default()
+    }
+}
+
+async fn notarize_in_epoch(
+    repo: &SqliteRepository,
+    config: &Config,
+    server_url: &str,
+    content: &[u8],
+    sender: &str,
+    epoch: u64,
+) -> &'static str {
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(content, "doc.txt", "text/plain");
+    let request = create_advance_request_in_epoch(&payload, sender, 100 + epoch, epoch);
+    handle_advance(&client, server_url, repo, config, request)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_notarizations_within_quota_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = quota_config(2);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for content in [&b"Quota doc one"[..], b"Quota doc two"] {
+        let result = notarize_in_epoch(&repo, &config, &server_url, content, SENDER, 3).await;
+        assert_eq!(result, "accept");
+    }
+
+    assert_eq!(repo.count_documents().unwrap(), 2);
+    assert_eq!(repo.find_quota_usage(SENDER, 3).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_notarization_over_quota_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = quota_config(2);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    for content in [&b"Quota doc one"[..], b"Quota doc two"] {
+        let result = notarize_in_epoch(&repo, &config, &server_url, content, SENDER, 3).await;
+        assert_eq!(result, "accept");
+    }
+    let result =
+        notarize_in_epoch(&repo, &config, &server_url, b"Quota doc three", SENDER, 3).await;
+    assert_eq!(result, "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value =
+        serde_json::from_str(server.get_reports().last().unwrap()).unwrap();
+    assert_eq!(report["error_code"], "QUOTA_EXCEEDED");
+    let detail = report["error_detail"].as_str().unwrap();
+    assert!(detail.contains("2 of 2"), "{}", detail);
+    assert!(detail.contains("epoch 3"), "{}", detail);
+    assert_eq!(repo.count_documents().unwrap(), 2);
+
+    // The quota is per submitter
+    let result = notarize_in_epoch(
+        &repo,
+        &config,
+        &server_url,
+        b"Quota doc three",
+        OTHER_SENDER,
+        3,
+    )
+    .await;
+    assert_eq!(result, "accept");
+}
+
+#[tokio::test]
+async fn test_quota_resets_in_next_epoch() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = quota_config(1);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let result = notarize_in_epoch(&repo, &config, &server_url, b"Epoch 3 doc", SENDER, 3).await;
+    assert_eq!(result, "accept");
+    let result = notarize_in_epoch(&repo, &config, &server_url, b"Epoch 3 extra", SENDER, 3).await;
+    assert_eq!(result, "reject");
+
+    let result = notarize_in_epoch(&repo, &config, &server_url, b"Epoch 4 doc", SENDER, 4).await;
+    assert_eq!(result, "accept");
+    assert_eq!(repo.find_quota_usage(SENDER, 4).unwrap(), 1);
+}
@@ -0,0 +1,100 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::{Config, RateLimiter};
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SENDER: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+const OTHER_SENDER: &str = "0x0000000000000000000000000000000000000456";
+
+fn limited_config(max_per_block: u32) -> Config {
+    Config {
+        rate_limiter: Some(RateLimiter::new(max_per_block)),
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn test_inputs_up_to_limit_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = limited_config(2);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    for content in [b"Limit doc one", b"Limit doc two"] {
+        let payload = create_notarize_payload(content, "doc.txt", "text/plain");
+        let request = create_advance_request(&payload, SENDER, 100);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    assert_eq!(repo.count_documents().unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_input_over_limit_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = limited_config(1);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload(b"Allowed doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_notarize_payload(b"Throttled doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("RATE_LIMITED"));
+    assert_eq!(repo.count_documents().unwrap(), 1);
+
+    // Another sender in the same block and the same sender in the next block are unaffected
+    let payload = create_notarize_payload(b"Other sender doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, OTHER_SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_notarize_payload(b"Throttled doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+}
+
+#[tokio::test]
+async fn test_queries_are_not_limited() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = limited_config(1);
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = create_notarize_payload(b"Queried doc", "doc.txt", "text/plain");
+    let request = create_advance_request(&payload, SENDER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    for _ in 0..2 {
+        let request = create_advance_request(r#"{"action":"health"}"#, SENDER, 100);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    let request = create_inspect_request(r#"{"action":"health"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+}
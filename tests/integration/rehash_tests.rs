@@ -0,0 +1,129 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const ADMIN: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const SUBMITTER: &str = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359";
+
+fn rehash_config() -> Config {
+    Config {
+        admin_address: Some(ADMIN.to_string()),
+        store_content: true,
+        ..Config::default()
+    }
+}
+
+/// Store a document as one notarized before Keccak-256 digests were recorded
+fn seed_legacy_document(repo: &SqliteRepository, content: &[u8], retain: bool) -> Document {
+    let mut doc = Document::new(content, "legacy.txt", "text/plain", SUBMITTER);
+    doc.content_hash_keccak = None;
+    if retain {
+        repo.save_document_with_content(&doc, content).unwrap();
+    } else {
+        repo.save_document(&doc).unwrap();
+    }
+    doc
+}
+
+#[tokio::test]
+async fn test_rehash_repopulates_missing_digest() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = rehash_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let retained = seed_legacy_document(&repo, b"Legacy contract", true);
+    let hash_only = seed_legacy_document(&repo, b"Legacy receipt", false);
+
+    let payload = create_rehash_payload(None, None);
+    let request = create_advance_request(&payload, &ADMIN.to_lowercase(), 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["rehashed"], 1);
+    assert_eq!(report["skipped"][0], hash_only.content_hash.to_string());
+    assert!(report.get("next_after").is_none());
+
+    let stored = repo.find_by_id(&retained.id).unwrap();
+    assert_eq!(
+        stored.content_hash_keccak.as_deref(),
+        Some(Document::hash_content_keccak(b"Legacy contract").as_str())
+    );
+    assert!(repo
+        .find_by_id(&hash_only.id)
+        .unwrap()
+        .content_hash_keccak
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_rehash_pages_through_documents() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = rehash_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    for i in 0..3 {
+        seed_legacy_document(&repo, format!("Legacy {}", i).as_bytes(), true);
+    }
+
+    let payload = create_rehash_payload(None, Some(2));
+    let request = create_advance_request(&payload, ADMIN, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(report["rehashed"], 2);
+    let next_after = report["next_after"].as_str().unwrap().to_string();
+
+    let payload = create_rehash_payload(Some(&next_after), Some(2));
+    let request = create_advance_request(&payload, ADMIN, 301);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[1]).unwrap();
+    assert_eq!(report["rehashed"], 1);
+    assert!(report.get("next_after").is_none());
+}
+
+#[tokio::test]
+async fn test_non_admin_rehash_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = rehash_config();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let doc = seed_legacy_document(&repo, b"Legacy contract", true);
+
+    let payload = create_rehash_payload(None, None);
+    let request = create_advance_request(&payload, SUBMITTER, 300);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Only the admin"));
+    assert!(repo
+        .find_by_id(&doc.id)
+        .unwrap()
+        .content_hash_keccak
+        .is_none());
+}
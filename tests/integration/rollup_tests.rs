@@ -1,9 +1,15 @@
 use super::helpers::*;
 use super::mock_server::MockRollupServer;
-use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::application::{Config, RepositoryKind, MAX_DESCRIPTION_LENGTH};
+use dapp::domain::{compute_blake2b, Document};
+use dapp::handlers::{get_repository, handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository, SCHEMA_VERSION};
 
 #[tokio::test]
 async fn test_notarize_document_workflow() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+
     // Start mock server
     let server = MockRollupServer::new();
     let server_url = server.start().await;
@@ -14,13 +20,14 @@ async fn test_notarize_document_workflow() {
     // Create notarize request
     let content = b"Hello, Cartesi Notary!";
     let payload = create_notarize_payload(content, "test.txt", "text/plain");
-    let request = create_advance_request(&payload, "0x1234567890abcdef", 100);
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000001234567890abcdef", 100);
 
     // Create HTTP client
     let client = hyper::Client::new();
 
     // Call handler
-    let result = handle_advance(&client, &server_url, request).await;
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
 
     // Should succeed
     assert!(result.is_ok());
@@ -46,7 +53,8 @@ async fn test_notarize_document_workflow() {
 
 #[tokio::test]
 async fn test_notarize_duplicate_rejected() {
-    let _db = TestDatabase::new(); // Set up persistent database for this test
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
     let server = MockRollupServer::new();
     let server_url = server.start().await;
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -56,16 +64,18 @@ async fn test_notarize_duplicate_rejected() {
     let payload = create_notarize_payload(content, "file1.txt", "text/plain");
 
     // First notarization
-    let request1 = create_advance_request(&payload, "0x111", 100);
-    let result1 = handle_advance(&client, &server_url, request1).await;
+    let request1 =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000111", 100);
+    let result1 = handle_advance(&client, &server_url, &repo, &config, request1).await;
     assert_eq!(result1.unwrap(), "accept");
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     server.clear(); // Clear first notice
 
     // Second notarization with same content
-    let request2 = create_advance_request(&payload, "0x222", 101);
-    let result2 = handle_advance(&client, &server_url, request2).await;
+    let request2 =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000222", 101);
+    let result2 = handle_advance(&client, &server_url, &repo, &config, request2).await;
 
     // Should be rejected due to duplicate
     assert_eq!(result2.unwrap(), "reject");
@@ -78,9 +88,55 @@ async fn test_notarize_duplicate_rejected() {
     assert!(reports[0].contains("error") || reports[0].contains("Duplicate"));
 }
 
+#[tokio::test]
+async fn test_duplicate_report_names_original_notarization() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let original_submitter = "0x0000000000000000000000000000000000000111";
+    let content = b"Deed of sale, signed copy";
+    let payload =
+        create_notarize_payload_with_description(content, "deed.pdf", "Private notes on the sale");
+    let request = create_advance_request_at(&payload, original_submitter, 100, 1_700_000_000);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+    let original = repo.find_by_hash(&Document::hash_content(content)).unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    server.clear();
+
+    let payload = create_notarize_payload(content, "copy.pdf", "application/pdf");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000222", 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "DUPLICATE_DOCUMENT");
+    assert_eq!(
+        report["original"],
+        serde_json::json!({
+            "document_id": original.id.to_string(),
+            "submitted_by": original_submitter,
+            "created_at": "2023-11-14T22:13:20Z",
+        })
+    );
+    // Only metadata of the original is disclosed
+    assert!(!reports[0].contains("Private notes"));
+}
+
 #[tokio::test]
 async fn test_verify_existing_document() {
-    let _db = TestDatabase::new(); // Set up persistent database for this test
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
     let server = MockRollupServer::new();
     let server_url = server.start().await;
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -90,8 +146,12 @@ async fn test_verify_existing_document() {
 
     // First, notarize a document
     let notarize_payload = create_notarize_payload(content, "doc.txt", "text/plain");
-    let notarize_req = create_advance_request(&notarize_payload, "0x123", 100);
-    handle_advance(&client, &server_url, notarize_req)
+    let notarize_req = create_advance_request(
+        &notarize_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    handle_advance(&client, &server_url, &repo, &config, notarize_req)
         .await
         .unwrap();
 
@@ -110,7 +170,7 @@ async fn test_verify_existing_document() {
     // Now verify it via inspect
     let verify_payload = create_verify_payload(&content_hash);
     let verify_req = create_inspect_request(&verify_payload);
-    let result = handle_inspect(&client, &server_url, verify_req).await;
+    let result = handle_inspect(&client, &server_url, &repo, &config, verify_req).await;
 
     assert_eq!(result.unwrap(), "accept");
 
@@ -126,9 +186,228 @@ async fn test_verify_existing_document() {
     assert!(report_json["receipt"].is_object());
 }
 
+#[tokio::test]
+async fn test_description_round_trips_through_verify() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content = b"Quarterly audit";
+    let description = "Q3 2024 financial audit report";
+
+    let notarize_payload =
+        create_notarize_payload_with_description(content, "audit.pdf", description);
+    let notarize_req = create_advance_request(
+        &notarize_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    let result = handle_advance(&client, &server_url, &repo, &config, notarize_req).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    server.clear();
+
+    let content_hash = Document::hash_content(content);
+    let verify_req = create_inspect_request(&create_verify_payload(content_hash.as_str()));
+    let result = handle_inspect(&client, &server_url, &repo, &config, verify_req).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["exists"], true);
+    assert_eq!(report_json["description"], description);
+    assert_eq!(report_json["document"]["description"], description);
+}
+
+#[tokio::test]
+async fn test_alias_is_opt_in_and_listed_by_verify() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let sender = "0x0000000000000000000000000000000000000123";
+    let content = b"Signed lease";
+
+    let payload = create_notarize_payload(content, "lease.pdf", "application/pdf");
+    let request = create_advance_request(&payload, sender, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    // Without allow_alias the same bytes are still a duplicate
+    let payload = create_notarize_payload(content, "lease-copy.pdf", "application/pdf");
+    let request = create_advance_request(&payload, sender, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    for (file_name, block_number) in [("lease-copy.pdf", 102), ("lease-signed.pdf", 103)] {
+        let payload = create_notarize_payload_with_alias(content, file_name);
+        let request = create_advance_request(&payload, sender, block_number);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Each alias is answered with the original receipt
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 3);
+    let document_ids: Vec<serde_json::Value> = notices
+        .iter()
+        .map(|notice| {
+            let notice_json: serde_json::Value = serde_json::from_str(notice).unwrap();
+            notice_json["receipt"]["document_id"].clone()
+        })
+        .collect();
+    assert!(document_ids.iter().all(|id| *id == document_ids[0]));
+    assert_eq!(repo.count_documents().unwrap(), 1);
+    server.clear();
+
+    let content_hash = Document::hash_content(content);
+    let verify_req = create_inspect_request(&create_verify_payload(content_hash.as_str()));
+    let result = handle_inspect(&client, &server_url, &repo, &config, verify_req).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["document"]["file_name"], "lease.pdf");
+    assert_eq!(
+        report_json["aliases"],
+        serde_json::json!(["lease-copy.pdf", "lease-signed.pdf"])
+    );
+}
+
+#[tokio::test]
+async fn test_too_long_description_rejects_notarization() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload_with_description(
+        b"Overly described",
+        "long.pdf",
+        &"x".repeat(MAX_DESCRIPTION_LENGTH + 1),
+    );
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["error_code"], "INVALID_PAYLOAD");
+    assert!(report_json["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Description of 1025 characters"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_verify_by_keccak_reports_both_digests() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content = b"Content for EVM";
+    let notarize_payload = create_notarize_payload(content, "doc.txt", "text/plain");
+    let notarize_req = create_advance_request(
+        &notarize_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    handle_advance(&client, &server_url, &repo, &config, notarize_req)
+        .await
+        .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let sha256 = Document::hash_content(content);
+    let keccak = Document::hash_content_keccak(content);
+
+    for (hash, matched) in [(sha256.as_str(), "sha256"), (keccak.as_str(), "keccak256")] {
+        server.clear();
+        let verify_req = create_inspect_request(&create_verify_payload(hash));
+        handle_inspect(&client, &server_url, &repo, &config, verify_req)
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let reports = server.get_reports();
+        let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+        assert_eq!(report_json["exists"], true);
+        assert_eq!(report_json["matched_hash"], matched);
+        assert_eq!(report_json["document"]["content_hash"], sha256.as_str());
+        assert_eq!(report_json["document"]["content_hash_keccak"], keccak);
+    }
+}
+
+#[tokio::test]
+async fn test_verify_by_blake2b_inspect() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        enable_blake2b: true,
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let content = b"Content for Blake2b";
+    let notarize_payload = create_notarize_payload(content, "doc.txt", "text/plain");
+    let notarize_req = create_advance_request(
+        &notarize_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    handle_advance(&client, &server_url, &repo, &config, notarize_req)
+        .await
+        .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    server.clear();
+
+    let blake2b = compute_blake2b(content);
+    let request = create_inspect_request(&create_verify_blake2b_payload(&blake2b));
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["exists"], true);
+    assert_eq!(report_json["matched_hash"], "blake2b256");
+    assert_eq!(report_json["document"]["blake2b_hash"], blake2b);
+    assert_eq!(
+        report_json["document"]["content_hash"],
+        Document::hash_content(content).as_str()
+    );
+}
+
 #[tokio::test]
 async fn test_verify_nonexistent_document() {
-    let _db = TestDatabase::new(); // Set up persistent database for this test
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
     let server = MockRollupServer::new();
     let server_url = server.start().await;
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -139,7 +418,7 @@ async fn test_verify_nonexistent_document() {
     let fake_hash = "a".repeat(64);
     let verify_payload = create_verify_payload(&fake_hash);
     let verify_req = create_inspect_request(&verify_payload);
-    let result = handle_inspect(&client, &server_url, verify_req).await;
+    let result = handle_inspect(&client, &server_url, &repo, &config, verify_req).await;
 
     assert_eq!(result.unwrap(), "accept"); // Inspect always accepts
 
@@ -157,6 +436,8 @@ async fn test_verify_nonexistent_document() {
 
 #[tokio::test]
 async fn test_invalid_json_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
     let server = MockRollupServer::new();
     let server_url = server.start().await;
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -165,8 +446,12 @@ async fn test_invalid_json_rejected() {
 
     // Send invalid JSON
     let invalid_payload = "not valid json {{{";
-    let request = create_advance_request(invalid_payload, "0x123", 100);
-    let result = handle_advance(&client, &server_url, request).await;
+    let request = create_advance_request(
+        invalid_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
 
     // Should be rejected
     assert_eq!(result.unwrap(), "reject");
@@ -179,8 +464,62 @@ async fn test_invalid_json_rejected() {
     assert!(reports[0].contains("error"));
 }
 
+#[tokio::test]
+async fn test_unknown_action_rejected_with_supported_actions() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let payload = r#"{"action":"frobnicate","data":{}}"#;
+    let request =
+        create_advance_request(payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "UNKNOWN_ACTION");
+    assert_eq!(report["error_detail"], "Unknown action: frobnicate");
+    let supported = report["supported_actions"].as_array().unwrap();
+    assert!(supported.iter().any(|action| action == "notarize"));
+    assert!(supported.iter().any(|action| action == "verify"));
+}
+
+#[tokio::test]
+async fn test_unknown_action_reported_on_inspect() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    let request = create_inspect_request(r#"{"action":"frobnicate"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Unknown action: frobnicate"));
+    assert!(reports[0].contains("supported_actions"));
+}
+
 #[tokio::test]
 async fn test_invalid_base64_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
     let server = MockRollupServer::new();
     let server_url = server.start().await;
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -189,8 +528,12 @@ async fn test_invalid_base64_rejected() {
 
     // Create payload with invalid base64
     let invalid_payload = r#"{"action":"notarize","data":{"content":"!!!invalid-base64!!!","file_name":"test.txt","mime_type":"text/plain"}}"#;
-    let request = create_advance_request(invalid_payload, "0x123", 100);
-    let result = handle_advance(&client, &server_url, request).await;
+    let request = create_advance_request(
+        invalid_payload,
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
 
     // Should be rejected
     assert_eq!(result.unwrap(), "reject");
@@ -202,3 +545,592 @@ async fn test_invalid_base64_rejected() {
     assert!(!reports.is_empty());
     assert!(reports[0].contains("error"));
 }
+
+#[tokio::test]
+async fn test_sequential_advances_share_repository() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // First advance notarizes a document
+    let payload = create_notarize_payload(b"Shared state", "shared.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    assert_eq!(
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap(),
+        "accept"
+    );
+
+    let notices = server.get_notices();
+    let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+    let content_hash = notice_json["receipt"]["content_hash"].as_str().unwrap();
+    server.clear();
+
+    // Second advance verifies it against the same in-memory repository
+    let verify_payload = format!(
+        r#"{{"action":"verify","data":{{"content_hash":"{}"}}}}"#,
+        content_hash
+    );
+    let request = create_advance_request(
+        &verify_payload,
+        "0x0000000000000000000000000000000000000456",
+        101,
+    );
+    assert_eq!(
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap(),
+        "accept"
+    );
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["exists"], true);
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_notarization_time_comes_from_block_timestamp() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let timestamp = 1_700_000_000;
+
+    // Every node replays the same input with the same metadata, so both must record it exactly
+    for (content, block_number) in [(&b"First"[..], 100), (&b"Second"[..], 101)] {
+        let payload = create_notarize_payload(content, "doc.txt", "text/plain");
+        let request = create_advance_request_at(
+            &payload,
+            "0x0000000000000000000000000000000000000123",
+            block_number,
+            timestamp,
+        );
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+
+        let doc = repo.find_by_hash(&Document::hash_content(content)).unwrap();
+        assert_eq!(doc.created_at, timestamp);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 2);
+    for notice in &notices {
+        let notice_json: serde_json::Value = serde_json::from_str(notice).unwrap();
+        assert_eq!(
+            notice_json["receipt"]["notarized_at"],
+            "2023-11-14T22:13:20Z"
+        );
+    }
+}
+
+#[test]
+fn test_get_repository_uses_configured_path() {
+    let db = TestDatabase::new();
+
+    let repository = get_repository(&db.config()).expect("Configured path should open");
+    let doc = Document::new(
+        b"persisted",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repository.save_document(&doc).unwrap();
+
+    assert!(std::path::Path::new(db.path()).exists());
+}
+
+#[test]
+fn test_db_path_env_reaches_every_repository() {
+    let db = TestDatabase::new();
+    std::env::set_var("NOTARY_DB_PATH", db.path());
+    let config = Config::from_env();
+    std::env::remove_var("NOTARY_DB_PATH");
+
+    // main and the tests open their repository through the same configuration
+    let config = config.unwrap();
+    assert_eq!(
+        config.repository,
+        RepositoryKind::Sqlite(db.path().to_string())
+    );
+    let writer = get_repository(&config).unwrap();
+    let reader = get_repository(&config).unwrap();
+
+    let doc = Document::new(
+        b"shared",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    writer.save_document(&doc).unwrap();
+
+    assert_eq!(reader.find_by_hash(&doc.content_hash).unwrap().id, doc.id);
+}
+
+#[test]
+fn test_get_repository_unwritable_path_returns_error() {
+    let config = Config {
+        repository: RepositoryKind::Sqlite("/nonexistent/notary/notary.db".to_string()),
+        ..Config::default()
+    };
+
+    // Should surface an error instead of panicking
+    assert!(get_repository(&config).is_err());
+}
+
+#[tokio::test]
+async fn test_oversized_payload_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        max_payload_kb: 1,
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(&[b'a'; 2048], "big.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("maximum size"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_oversized_content_rejected_before_decoding() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        max_content_bytes: 64,
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(&[b'a'; 65], "big.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Content size 65 bytes exceeds limit of 64 bytes"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_oversized_base64_rejected_by_length_alone() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        max_content_bytes: 64,
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Not valid base64 at all: had it been decoded, the report would name the bad encoding
+    let content = "!".repeat(4000);
+    let client = hyper::Client::new();
+
+    let notarize = serde_json::json!({
+        "action": "notarize",
+        "data": {"content": content, "file_name": "big.txt", "mime_type": "text/plain"}
+    })
+    .to_string();
+    let request =
+        create_advance_request(&notarize, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    let preview = serde_json::json!({
+        "action": "preview",
+        "data": {"content": content, "file_name": "big.txt", "mime_type": "text/plain"}
+    })
+    .to_string();
+    let result = handle_inspect(
+        &client,
+        &server_url,
+        &repo,
+        &config,
+        create_inspect_request(&preview),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    for report in &reports {
+        let report: serde_json::Value = serde_json::from_str(report).unwrap();
+        assert_eq!(report["error_code"], "PAYLOAD_TOO_LARGE");
+        assert_eq!(
+            report["error_detail"],
+            "Content size 3000 bytes exceeds limit of 64 bytes"
+        );
+    }
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_health_check_reports_status() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+
+    // Store a document so the count is non-zero
+    let payload = create_notarize_payload(b"Health content", "health.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    handle_advance(&client, &server_url, &repo, &config, request)
+        .await
+        .unwrap();
+    server.clear();
+
+    let request = create_inspect_request(r#"{"action":"health"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["status"], "ok");
+    assert_eq!(report_json["db_document_count"], 1);
+    assert_eq!(report_json["schema_version"], SCHEMA_VERSION);
+    assert!(report_json.get("error").is_none());
+    assert_eq!(report_json["version"], env!("CARGO_PKG_VERSION"));
+    assert!(report_json["db_latency_ms"].as_f64().unwrap() >= 0.0);
+}
+
+#[tokio::test]
+async fn test_submitter_stats_inspect() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let sender = "0x0000000000000000000000000000000000000123";
+
+    for content in [b"Stats one".as_slice(), b"Stats two".as_slice()] {
+        let payload = create_notarize_payload(content, "stats.txt", "text/plain");
+        let request = create_advance_request(&payload, sender, 100);
+        handle_advance(&client, &server_url, &repo, &config, request)
+            .await
+            .unwrap();
+    }
+    server.clear();
+
+    let request = create_inspect_request(r#"{"action":"get_submitter_stats"}"#);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = format!(
+        r#"{{"action":"get_submitter_stats","data":{{"submitter":"{}"}}}}"#,
+        "0x0000000000000000000000000000000000000456"
+    );
+    let request = create_inspect_request(&payload);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+
+    let all: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(all["submitters"][0]["submitter"], sender);
+    assert_eq!(all["submitters"][0]["document_count"], 2);
+
+    let single: serde_json::Value = serde_json::from_str(&reports[1]).unwrap();
+    assert_eq!(single["submitters"][0]["document_count"], 0);
+}
+
+#[tokio::test]
+async fn test_notarize_accepts_all_base64_variants() {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+    use base64::Engine;
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let config = Config::default();
+
+    // Bytes chosen so the encodings differ in alphabet and padding
+    let content = [0xfb, 0xff, 0xbf, 0x3e, 0x01];
+    let expected_hash = Document::new(&content, "x", "text/plain", "0x0").content_hash;
+
+    for encoded in [
+        STANDARD.encode(content),
+        STANDARD_NO_PAD.encode(content),
+        URL_SAFE.encode(content),
+        URL_SAFE_NO_PAD.encode(content),
+    ] {
+        // Fresh repository per encoding, otherwise the later ones are duplicates
+        let repo = SqliteRepository::new_in_memory().unwrap();
+        server.clear();
+
+        let payload = serde_json::json!({
+            "action": "notarize",
+            "data": {"content": encoded, "file_name": "bytes.bin", "mime_type": "text/plain"}
+        })
+        .to_string();
+        let request =
+            create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept", "encoding {} rejected", encoded);
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let notices = server.get_notices();
+        let notice_json: serde_json::Value = serde_json::from_str(&notices[0]).unwrap();
+        assert_eq!(
+            notice_json["receipt"]["content_hash"],
+            expected_hash.as_str()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_notice_sent_to_prefixed_endpoint() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        rollup_path_prefix: "/rollup".to_string(),
+        ..Config::default()
+    };
+    let server = MockRollupServer::with_prefix("/rollup");
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"Prefixed content", "prefix.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 1);
+    assert!(notices[0].contains("notarization_receipt"));
+
+    // Without the prefix the server answers 404 and sending fails
+    let payload = create_notarize_payload(b"Unprefixed content", "plain.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 101);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_batch_verify_single_report() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let doc = Document::new(
+        b"Batch content",
+        "batch.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    let missing = "0".repeat(64);
+    let payload = serde_json::json!({
+        "action": "batch_verify",
+        "data": {"hashes": [doc.content_hash, missing, "not-a-hash"]}
+    })
+    .to_string();
+    let request = create_inspect_request(&payload);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    let results = report_json["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["content_hash"], doc.content_hash.as_str());
+    assert_eq!(results[0]["exists"], true);
+    assert_eq!(results[1]["exists"], false);
+    assert!(results[1].get("error").is_none());
+    assert_eq!(results[2]["exists"], false);
+    assert!(results[2]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid hash format"));
+}
+
+#[tokio::test]
+async fn test_checksummed_sender_stored_lowercase() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let sender = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    let payload = create_notarize_payload(b"Checksummed sender", "sender.txt", "text/plain");
+    let request = create_advance_request(&payload, sender, 100);
+
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let hash =
+        Document::new(b"Checksummed sender", "sender.txt", "text/plain", sender).content_hash;
+    let stored = repo.find_by_hash(&hash).unwrap();
+    assert_eq!(stored.submitted_by, sender.to_lowercase());
+}
+
+#[tokio::test]
+async fn test_malformed_sender_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"Malformed sender", "sender.txt", "text/plain");
+    let request = create_advance_request(&payload, "0x12345", 100);
+
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Invalid msg_sender"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_missing_sender_rejected_for_advance() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"No sender", "sender.txt", "text/plain");
+    let mut request = create_advance_request(&payload, "", 100);
+    request["data"]["metadata"].remove("msg_sender");
+
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].contains("Missing msg_sender"));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_missing_timestamp_rejected_for_advance() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"No timestamp", "clock.txt", "text/plain");
+    let mut request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    request["data"]["metadata"].remove("timestamp");
+
+    // The host clock differs between nodes, so it is never used in its place
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert_eq!(
+        report["error_detail"],
+        "Missing timestamp in input metadata"
+    );
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_notice_signed_when_key_configured() {
+    use dapp::domain::NotarizationReceipt;
+    use dapp::infrastructure::signing::{verify_receipt_signature, NoticeSigningKey};
+
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config {
+        notice_signing_key: Some(NoticeSigningKey::new("notary-key")),
+        ..Config::default()
+    };
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"signed receipt", "signed.txt", "text/plain");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000001234567890abcdef", 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let signature = notice["signature"].as_str().unwrap();
+    let receipt: NotarizationReceipt = serde_json::from_value(notice["receipt"].clone()).unwrap();
+    assert!(verify_receipt_signature(&receipt, b"notary-key", signature));
+}
@@ -0,0 +1,249 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::{parse_rfc3339, Document};
+use dapp::handlers::handle_inspect;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+fn seed_documents(repo: &SqliteRepository) {
+    for (content, file_name, mime_type) in [
+        (
+            b"one".as_slice(),
+            "Acme Corp - Service Contract 2024.pdf",
+            "application/pdf",
+        ),
+        (
+            b"two".as_slice(),
+            "acme_invoice_march.pdf",
+            "application/pdf",
+        ),
+        (
+            b"three".as_slice(),
+            "Globex contract.docx",
+            "application/msword",
+        ),
+        (b"four".as_slice(), "notes.txt", "text/plain"),
+    ] {
+        let doc = Document::new(content, file_name, mime_type, SUBMITTER);
+        repo.save_document(&doc).unwrap();
+    }
+}
+
+/// Send a search inspect request and return the parsed report
+async fn search(repo: &SqliteRepository, payload: &str) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(payload);
+    let result = handle_inspect(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+fn file_names(report: &serde_json::Value) -> Vec<String> {
+    let mut names: Vec<String> = report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| doc["file_name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_multi_word_search_matches_all_words() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let report = search(&repo, &create_search_payload("acme contract", None)).await;
+
+    assert_eq!(report["query"], "acme contract");
+    assert_eq!(report["count"], 1);
+    assert_eq!(
+        file_names(&report),
+        vec!["Acme Corp - Service Contract 2024.pdf"]
+    );
+}
+
+#[tokio::test]
+async fn test_search_matches_word_prefixes_and_mime_types() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    // Partial words match as prefixes
+    let report = search(&repo, &create_search_payload("contr", None)).await;
+    assert_eq!(
+        file_names(&report),
+        vec![
+            "Acme Corp - Service Contract 2024.pdf",
+            "Globex contract.docx"
+        ]
+    );
+
+    // MIME type prefix combined with a filename word
+    let report = search(&repo, &create_search_payload("application/pd acme", None)).await;
+    assert_eq!(
+        file_names(&report),
+        vec![
+            "Acme Corp - Service Contract 2024.pdf",
+            "acme_invoice_march.pdf"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_search_matches_tags_and_ranks_filenames_first() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let tagged = Document::new(b"five", "scan-0042.pdf", "application/pdf", SUBMITTER);
+    repo.save_document(&tagged).unwrap();
+    repo.add_tags(&tagged.id, &["contract"]).unwrap();
+
+    let report = search(&repo, &create_search_payload("contract", None)).await;
+    assert_eq!(report["count"], 3);
+    let ranked: Vec<&str> = report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| doc["file_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(ranked[2], "scan-0042.pdf");
+}
+
+#[tokio::test]
+async fn test_search_respects_limit() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let report = search(&repo, &create_search_payload("application", Some(2))).await;
+    assert_eq!(report["count"], 2);
+}
+
+#[tokio::test]
+async fn test_empty_search_reports_error() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let report = search(&repo, &create_search_payload("  ", None)).await;
+    assert_eq!(report["error_detail"], "Search query cannot be empty");
+}
+
+#[tokio::test]
+async fn test_list_documents_pages_with_cursor() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let first = search(&repo, &create_list_documents_payload(None, 3)).await;
+    assert_eq!(first["count"], 3);
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+
+    let second = search(&repo, &create_list_documents_payload(Some(&cursor), 3)).await;
+    assert_eq!(second["count"], 1);
+    assert!(second["next_cursor"].is_null());
+
+    let mut names = file_names(&first);
+    names.extend(file_names(&second));
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 4);
+}
+
+#[tokio::test]
+async fn test_list_documents_invalid_cursor_reports_error() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo);
+
+    let report = search(&repo, &create_list_documents_payload(Some("!!!"), 3)).await;
+    assert_eq!(report["error_detail"], "Invalid cursor");
+}
+
+/// Store a document from `submitter` notarized at `created_at`
+fn save_dated(repo: &SqliteRepository, submitter: &str, created_at: i64) {
+    let mut doc = Document::new(
+        format!("{} at {}", submitter, created_at).as_bytes(),
+        &format!("{}.pdf", created_at),
+        "application/pdf",
+        submitter,
+    );
+    doc.created_at = created_at;
+    repo.save_document(&doc).unwrap();
+}
+
+fn created_ats(report: &serde_json::Value) -> Vec<i64> {
+    report["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| parse_rfc3339(doc["created_at"].as_str().unwrap()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_by_submitter_stable_across_inserts() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let other = "0x0000000000000000000000000000000000000456";
+    for created_at in [100, 200, 300, 400, 500] {
+        save_dated(&repo, SUBMITTER, created_at);
+    }
+    save_dated(&repo, other, 350);
+
+    let first = search(&repo, &create_list_by_submitter_payload(SUBMITTER, None, 2)).await;
+    assert_eq!(created_ats(&first), vec![500, 400]);
+    assert_eq!(first["has_more"], true);
+
+    // Newly notarized documents sort before the cursor and do not shift later pages
+    save_dated(&repo, SUBMITTER, 600);
+    save_dated(&repo, SUBMITTER, 700);
+
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+    let second = search(
+        &repo,
+        &create_list_by_submitter_payload(SUBMITTER, Some(&cursor), 2),
+    )
+    .await;
+    assert_eq!(created_ats(&second), vec![300, 200]);
+    assert_eq!(second["has_more"], true);
+
+    let cursor = second["next_cursor"].as_str().unwrap().to_string();
+    let third = search(
+        &repo,
+        &create_list_by_submitter_payload(SUBMITTER, Some(&cursor), 2),
+    )
+    .await;
+    assert_eq!(created_ats(&third), vec![100]);
+    assert_eq!(third["has_more"], false);
+    assert!(third["next_cursor"].is_null());
+}
+
+#[tokio::test]
+async fn test_list_by_submitter_matches_address_case_insensitively() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    save_dated(&repo, "0x00000000000000000000000000000000000000AA", 100);
+
+    let report = search(
+        &repo,
+        &create_list_by_submitter_payload("0x00000000000000000000000000000000000000aa", None, 10),
+    )
+    .await;
+    assert_eq!(report["count"], 1);
+}
+
+#[tokio::test]
+async fn test_list_by_submitter_rejects_invalid_address() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let report = search(&repo, &create_list_by_submitter_payload("alice", None, 10)).await;
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert_eq!(report["error_detail"], "Invalid submitter address: alice");
+}
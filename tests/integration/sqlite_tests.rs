@@ -0,0 +1,245 @@
+use super::helpers::TestDatabase;
+use dapp::domain::Document;
+use dapp::handlers::get_repository;
+use dapp::infrastructure::database::{
+    DatabaseError, DocumentRepository, PooledSqliteRepository, SqliteOptions, SqliteRepository,
+    DEFAULT_BUSY_TIMEOUT_MS,
+};
+use std::io::{Seek, SeekFrom, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const INSERTS: usize = 500;
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+fn document(i: usize) -> Document {
+    Document::new(
+        format!("document {}", i).as_bytes(),
+        "perf.txt",
+        "text/plain",
+        SUBMITTER,
+    )
+}
+
+/// Insert documents through a plain connection using SQLite's default pragmas
+fn insert_with_default_pragmas(path: &str) -> Duration {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute(
+        "CREATE TABLE documents (
+            id TEXT PRIMARY KEY,
+            content_hash TEXT UNIQUE NOT NULL,
+            file_name TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            submitted_by TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER
+        )",
+        [],
+    )
+    .unwrap();
+
+    let started = Instant::now();
+    for i in 0..INSERTS {
+        let doc = document(i);
+        conn.execute(
+            "INSERT INTO documents (id, content_hash, file_name, mime_type, submitted_by, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                doc.id,
+                doc.content_hash,
+                doc.file_name,
+                doc.mime_type,
+                doc.submitted_by,
+                doc.created_at,
+                doc.expires_at
+            ],
+        )
+        .unwrap();
+    }
+    started.elapsed()
+}
+
+#[test]
+fn test_file_database_uses_wal_mode() {
+    let db = TestDatabase::new();
+    let _repo = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+
+    // journal_mode=WAL is persisted in the database file
+    let conn = rusqlite::Connection::open(db.path()).unwrap();
+    let mode: String = conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(mode, "wal");
+}
+
+#[test]
+fn test_wal_sequential_inserts_at_least_as_fast_as_default() {
+    let baseline_db = TestDatabase::new();
+    let default_elapsed = insert_with_default_pragmas(baseline_db.path());
+
+    let wal_db = TestDatabase::new();
+    let repo = SqliteRepository::new(wal_db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let started = Instant::now();
+    for i in 0..INSERTS {
+        repo.save_document(&document(i)).unwrap();
+    }
+    let wal_elapsed = started.elapsed();
+
+    assert_eq!(repo.count_documents().unwrap(), INSERTS);
+
+    // Small allowance for timer noise on fast storage such as tmpfs
+    assert!(
+        wal_elapsed <= default_elapsed.mul_f64(1.25),
+        "WAL inserts took {:?}, default journal took {:?}",
+        wal_elapsed,
+        default_elapsed
+    );
+}
+
+#[test]
+fn test_concurrent_writers_wait_for_lock() {
+    let db = TestDatabase::new();
+    SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+
+    // Each thread owns its own connection to the same file
+    let writers: Vec<_> = (0..4)
+        .map(|writer| {
+            let path = db.path().to_string();
+            std::thread::spawn(move || {
+                let repo = SqliteRepository::new(&path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+                for i in 0..50 {
+                    repo.save_document(&document(writer * 1000 + i)).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for writer in writers {
+        writer.join().expect("Writer should not hit a busy error");
+    }
+
+    let repo = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    assert_eq!(repo.count_documents().unwrap(), 200);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pooled_repository_serves_concurrent_lookups() {
+    let db = TestDatabase::new();
+    let repo =
+        Arc::new(PooledSqliteRepository::open(db.path(), &SqliteOptions::default(), 4).unwrap());
+    let docs: Vec<Document> = (0..8).map(document).collect();
+    for doc in &docs {
+        repo.save_document(doc).unwrap();
+    }
+
+    // More tasks than connections, so some wait for one to be returned to the pool
+    let lookups: Vec<_> = docs
+        .iter()
+        .map(|doc| {
+            let repo = Arc::clone(&repo);
+            let hash = doc.content_hash.clone();
+            tokio::task::spawn_blocking(move || {
+                (0..25)
+                    .map(|_| repo.find_by_hash(&hash).map(|found| found.id))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+        })
+        .collect();
+
+    for (doc, lookup) in docs.iter().zip(lookups) {
+        let ids = lookup.await.unwrap().unwrap();
+        assert!(ids.iter().all(|id| *id == doc.id));
+    }
+    assert_eq!(repo.max_size(), 4);
+}
+
+/// Fill a file database with documents and return its size once every connection is closed
+fn seeded_database(db: &TestDatabase) -> u64 {
+    let repo = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    for i in 0..200 {
+        repo.save_document(&document(i)).unwrap();
+    }
+    assert!(repo.integrity_check().is_ok());
+    drop(repo);
+
+    // Closing the last connection checkpoints the WAL, so every page is in the file
+    std::fs::metadata(db.path()).unwrap().len()
+}
+
+#[test]
+fn test_integrity_check_detects_overwritten_pages() {
+    let db = TestDatabase::new();
+    let len = seeded_database(&db);
+
+    // Document pages follow the schema, so the repository still opens
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(db.path())
+        .unwrap();
+    file.seek(SeekFrom::Start(len / 2)).unwrap();
+    file.write_all(&[0xff; 8192]).unwrap();
+    drop(file);
+
+    let repo = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let err = repo.integrity_check().unwrap_err();
+    assert!(
+        matches!(err, DatabaseError::CorruptDatabase(_)),
+        "{:?}",
+        err
+    );
+    assert!(get_repository(&db.config()).is_err());
+}
+
+#[test]
+fn test_truncated_database_is_reported_corrupt() {
+    let db = TestDatabase::new();
+    let len = seeded_database(&db);
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(db.path())
+        .unwrap();
+    file.set_len(len / 2).unwrap();
+    drop(file);
+
+    // The header still counts the lost pages, which SQLite notices before any check runs
+    let err = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS)
+        .err()
+        .unwrap();
+    assert!(
+        matches!(err, DatabaseError::CorruptDatabase(_)),
+        "{:?}",
+        err
+    );
+    let err = get_repository(&db.config()).err().unwrap();
+    assert!(err.to_string().starts_with("Database is corrupt"));
+}
+
+#[test]
+fn test_open_applies_cache_and_mmap_options() {
+    let db = TestDatabase::new();
+    let options = SqliteOptions {
+        busy_timeout_ms: 1234,
+        cache_size_kib: 2048,
+        mmap_size_bytes: 1024 * 1024,
+    };
+    let repo = SqliteRepository::open(db.path(), &options).unwrap();
+
+    assert_eq!(repo.applied_options().unwrap(), options);
+
+    // Defaults go through the same path as the configured values
+    let repo = SqliteRepository::new(db.path(), DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    assert_eq!(repo.applied_options().unwrap(), SqliteOptions::default());
+}
+
+#[test]
+fn test_in_memory_database_skips_mmap() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let applied = repo.applied_options().unwrap();
+
+    assert_eq!(applied.mmap_size_bytes, 0);
+    assert_eq!(
+        applied.cache_size_kib,
+        SqliteOptions::default().cache_size_kib
+    );
+}
@@ -0,0 +1,125 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const ALICE: &str = "0x00000000000000000000000000000000000000aa";
+const BOB: &str = "0x00000000000000000000000000000000000000bb";
+const CAROL: &str = "0x00000000000000000000000000000000000000cc";
+const HOUR: i64 = 3_600;
+const DAY: i64 = 86_400;
+
+/// Block timestamp of advance statistics queries
+const BLOCK_TIMESTAMP: i64 = 1_700_000_000;
+
+/// Store 20 documents from three submitters spread over the two months before `now`
+/// 4 are an hour old, 6 are three days old, 5 are twenty days old and 5 are sixty days old
+fn seed_documents(repo: &SqliteRepository, now: i64) {
+    let ages = [HOUR; 4]
+        .into_iter()
+        .chain([3 * DAY; 6])
+        .chain([20 * DAY; 5])
+        .chain([60 * DAY; 5]);
+
+    for (i, age) in ages.enumerate() {
+        let submitter = match i % 4 {
+            0 | 1 => ALICE,
+            2 => BOB,
+            _ => CAROL,
+        };
+        let mut doc = Document::new(
+            format!("document {}", i).as_bytes(),
+            "stats.pdf",
+            "application/pdf",
+            submitter,
+        );
+        doc.created_at = now - age;
+        repo.save_document(&doc).unwrap();
+    }
+}
+
+fn assert_statistics(report: &serde_json::Value) {
+    assert_eq!(report["total_documents"], 20);
+    assert_eq!(
+        report["top_submitters"],
+        serde_json::json!([
+            { "submitter": ALICE, "document_count": 10 },
+            { "submitter": BOB, "document_count": 5 },
+            { "submitter": CAROL, "document_count": 5 }
+        ])
+    );
+    assert_eq!(report["documents_last_24h"], 4);
+    assert_eq!(report["documents_last_7d"], 10);
+    assert_eq!(report["documents_last_30d"], 15);
+    assert!(report["generated_at"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_get_statistics_inspect() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_documents(&repo, chrono::Utc::now().timestamp());
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_statistics_payload());
+    let result = handle_inspect(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert_statistics(&serde_json::from_str(&reports[0]).unwrap());
+}
+
+#[tokio::test]
+async fn test_get_statistics_advance_query() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    // Windows end at the block's time, which is long past on the host clock
+    seed_documents(&repo, BLOCK_TIMESTAMP);
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request =
+        create_advance_request_at(&create_statistics_payload(), ALICE, 100, BLOCK_TIMESTAMP);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_statistics(&report);
+    assert_eq!(report["generated_at"], BLOCK_TIMESTAMP);
+    assert!(server.get_notices().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_statistics_empty_database() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(&create_statistics_payload());
+    let result = handle_inspect(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(report["total_documents"], 0);
+    assert_eq!(report["top_submitters"], serde_json::json!([]));
+    assert_eq!(report["documents_last_30d"], 0);
+}
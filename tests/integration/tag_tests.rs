@@ -0,0 +1,143 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+#[tokio::test]
+async fn test_tagged_documents_found_by_tag() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    for (content, file_name, tags) in [
+        (
+            b"contract".as_slice(),
+            "contract.pdf",
+            vec!["contract", "acme"],
+        ),
+        (
+            b"invoice".as_slice(),
+            "invoice.pdf",
+            vec!["invoice", "acme"],
+        ),
+        (b"untagged".as_slice(), "notes.pdf", vec![]),
+    ] {
+        let payload = create_notarize_payload_with_tags(content, file_name, &tags);
+        let request = create_advance_request(&payload, SUBMITTER, 100);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), "accept");
+    }
+
+    let payload = create_find_by_tag_payload("ACME");
+    let request = create_inspect_request(&payload);
+    let result = handle_inspect(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(report_json["tag"], "acme");
+    assert_eq!(report_json["count"], 2);
+
+    let mut file_names: Vec<&str> = report_json["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|doc| doc["file_name"].as_str().unwrap())
+        .collect();
+    file_names.sort();
+    assert_eq!(file_names, vec!["contract.pdf", "invoice.pdf"]);
+}
+
+#[tokio::test]
+async fn test_invalid_tag_rejects_notarization() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload_with_tags(b"bad", "bad.pdf", &["not/valid"]);
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report_json: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert!(report_json["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid tag 'not/valid'"));
+}
+
+#[tokio::test]
+async fn test_normalized_tags_appear_in_verification_report() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload_with_tags(
+        b"lease",
+        "lease.pdf",
+        &["Contract ", "contract", "Legal"],
+    );
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let content_hash = notice["receipt"]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    server.clear();
+
+    let payload = r#"{"action":"list_by_tag","data":{"tag":"contract"}}"#;
+    let result = handle_inspect(
+        &client,
+        &server_url,
+        &repo,
+        &config,
+        create_inspect_request(payload),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let payload = create_verify_payload(&content_hash);
+    let result = handle_inspect(
+        &client,
+        &server_url,
+        &repo,
+        &config,
+        create_inspect_request(&payload),
+    )
+    .await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 2);
+    let listed: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert_eq!(listed["count"], 1);
+    assert_eq!(listed["documents"][0]["file_name"], "lease.pdf");
+
+    let verified: serde_json::Value = serde_json::from_str(&reports[1]).unwrap();
+    assert_eq!(verified["tags"], serde_json::json!(["contract", "legal"]));
+}
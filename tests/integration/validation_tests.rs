@@ -0,0 +1,93 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use json::JsonValue;
+
+const SENDER: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+/// Send one request and return its status with the single report it produced
+async fn send(repo: &SqliteRepository, request: JsonValue) -> (&'static str, serde_json::Value) {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let config = Config::default();
+    let status = if request["request_type"] == "inspect_state" {
+        handle_inspect(&client, &server_url, repo, &config, request).await
+    } else {
+        handle_advance(&client, &server_url, repo, &config, request).await
+    }
+    .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    (status, serde_json::from_str(&reports[0]).unwrap())
+}
+
+#[tokio::test]
+async fn test_notarize_reports_all_field_errors_at_once() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let payload = r#"{"action":"notarize","data":{"content":"not base64!","file_name":"","mime_type":"pdf"}}"#;
+
+    let (status, report) = send(&repo, create_advance_request(payload, SENDER, 100)).await;
+
+    assert_eq!(status, "reject");
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("3 validation errors: "));
+
+    let errors = report["errors"].as_array().unwrap();
+    let fields: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            (
+                error["field"].as_str().unwrap(),
+                error["code"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("content", "INVALID_BASE64"),
+            ("file_name", "INVALID_PAYLOAD"),
+            ("mime_type", "INVALID_PAYLOAD"),
+        ]
+    );
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_single_field_error_keeps_its_code() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let payload = r#"{"action":"notarize","data":{"content":"","file_name":"a.txt","mime_type":"text/plain"}}"#;
+
+    let (status, report) = send(&repo, create_advance_request(payload, SENDER, 100)).await;
+
+    assert_eq!(status, "reject");
+    assert_eq!(report["error_code"], "EMPTY_CONTENT");
+    assert_eq!(report["error_detail"], "Content cannot be empty");
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_verify_hash_is_validated_before_lookup() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let (status, report) = send(&repo, create_inspect_request(&create_verify_payload("xyz"))).await;
+    assert_eq!(status, "accept");
+    assert_eq!(report["error_code"], "INVALID_HASH");
+    assert_eq!(report["errors"][0]["field"], "content_hash");
+
+    let payload = r#"{"action":"verify","data":{"content_hash":"abc"}}"#;
+    let (status, report) = send(&repo, create_advance_request(payload, SENDER, 100)).await;
+    assert_eq!(status, "reject");
+    assert_eq!(report["error_code"], "INVALID_HASH");
+}
@@ -0,0 +1,90 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::W3C_CREDENTIALS_CONTEXT;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::SqliteRepository;
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+async fn request_credential(
+    repo: &SqliteRepository,
+    server: &MockRollupServer,
+    server_url: &str,
+    config: &Config,
+    content_hash: &str,
+) -> serde_json::Value {
+    let client = hyper::Client::new();
+    let payload = create_verifiable_credential_payload(content_hash);
+    handle_inspect(
+        &client,
+        server_url,
+        repo,
+        config,
+        create_inspect_request(&payload),
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    serde_json::from_str(server.get_reports().last().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_notarized_document_exported_as_credential() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"deed of sale", "deed.pdf", "application/pdf");
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    handle_advance(&client, &server_url, &repo, &Config::default(), request)
+        .await
+        .unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notice: serde_json::Value =
+        serde_json::from_str(server.get_notices().last().unwrap()).unwrap();
+    let receipt = &notice["receipt"];
+    let content_hash = receipt["content_hash"].as_str().unwrap();
+
+    let config = Config {
+        issuer_did: "did:web:notary.example".to_string(),
+        ..Config::default()
+    };
+    let vc = request_credential(&repo, &server, &server_url, &config, content_hash).await;
+
+    assert_eq!(vc["@context"][0], W3C_CREDENTIALS_CONTEXT);
+    assert_eq!(vc["type"][1], "NotaryCredential");
+    assert_eq!(vc["issuer"], "did:web:notary.example");
+    assert_eq!(vc["credentialSubject"]["contentHash"], content_hash);
+    assert_eq!(vc["credentialSubject"]["fileName"], "deed.pdf");
+    assert_eq!(
+        vc["credentialSubject"]["id"],
+        format!("urn:uuid:{}", receipt["document_id"].as_str().unwrap())
+    );
+
+    // Both dates are RFC 3339 text of the same timestamp
+    assert_eq!(vc["issuanceDate"], receipt["notarized_at"]);
+}
+
+#[tokio::test]
+async fn test_credential_errors_are_reported() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let config = Config::default();
+
+    let vc = request_credential(&repo, &server, &server_url, &config, &"a".repeat(64)).await;
+    assert_eq!(vc["error_code"], "NOT_FOUND");
+    assert_eq!(vc["error_detail"], "Document not found");
+
+    let vc = request_credential(&repo, &server, &server_url, &config, "not-a-hash").await;
+    assert_eq!(vc["error_code"], "INVALID_HASH");
+    assert!(vc["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid hash format"));
+}
@@ -0,0 +1,177 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::Document;
+use dapp::handlers::{handle_advance, handle_inspect};
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const CONTENT: &[u8] = b"Original signed contract";
+
+/// Store a document notarizing `CONTENT` and return its hash
+fn seed_document(repo: &SqliteRepository) -> String {
+    let doc = Document::new(
+        CONTENT,
+        "contract.pdf",
+        "application/pdf",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+    doc.content_hash.into_inner()
+}
+
+/// Send a verify_content inspect request and return the parsed report
+async fn verify_content(repo: &SqliteRepository, payload: &str) -> serde_json::Value {
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_inspect_request(payload);
+    let result = handle_inspect(&client, &server_url, repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    serde_json::from_str(&reports[0]).unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_content_matching() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let hash = seed_document(&repo);
+
+    let report = verify_content(&repo, &create_verify_content_payload(CONTENT, &hash)).await;
+
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["content_matches"], true);
+    assert_eq!(report["document"]["content_hash"], hash);
+}
+
+#[tokio::test]
+async fn test_verify_content_tampered() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let hash = seed_document(&repo);
+
+    let payload = create_verify_content_payload(b"Original signed contract, amended", &hash);
+    let report = verify_content(&repo, &payload).await;
+
+    // The hash is notarized, but the submitted content is not what was notarized
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["content_matches"], false);
+}
+
+#[tokio::test]
+async fn test_verify_content_unknown_hash() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_document(&repo);
+
+    let unknown = Document::hash_content(b"never notarized");
+    let payload = create_verify_content_payload(b"never notarized", unknown.as_str());
+    let report = verify_content(&repo, &payload).await;
+
+    assert_eq!(report["exists"], false);
+    assert_eq!(report["content_matches"], false);
+    assert!(report.get("document").is_none());
+}
+
+#[tokio::test]
+async fn test_verify_content_rejects_malformed_input() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let hash = seed_document(&repo);
+
+    let report = verify_content(&repo, &create_verify_content_payload(CONTENT, "abc")).await;
+    assert_eq!(report["error_code"], "INVALID_HASH");
+
+    let payload = serde_json::json!({
+        "action": "verify_content",
+        "data": { "content": "not base64!", "content_hash": hash }
+    })
+    .to_string();
+    let report = verify_content(&repo, &payload).await;
+    assert_eq!(report["error_code"], "INVALID_BASE64");
+}
+
+#[tokio::test]
+async fn test_verify_content_as_advance_query() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let hash = seed_document(&repo);
+
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let request = create_advance_request(
+        &create_verify_content_payload(CONTENT, &hash),
+        "0x0000000000000000000000000000000000000123",
+        100,
+    );
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert_eq!(report["content_matches"], true);
+    assert!(server.get_notices().is_empty());
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_verify_by_content_hashes_with_sha256() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+
+    let report = verify_content(&repo, &create_verify_by_content_payload(b"hello world")).await;
+
+    // SHA-256 of "hello world"
+    assert_eq!(
+        report["computed_hash"],
+        "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+    );
+    assert_eq!(report["exists"], false);
+    assert!(report.get("document").is_none());
+}
+
+#[tokio::test]
+async fn test_verify_by_content_finds_notarized_document() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(CONTENT, "contract.pdf", "application/pdf");
+    let request =
+        create_advance_request(&payload, "0x0000000000000000000000000000000000000123", 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    let report = verify_content(&repo, &create_verify_by_content_payload(CONTENT)).await;
+
+    let hash = Document::hash_content(CONTENT).into_inner();
+    assert_eq!(report["computed_hash"], hash);
+    assert_eq!(report["exists"], true);
+    assert_eq!(report["matched_hash"], "sha256");
+    assert_eq!(report["document"]["content_hash"], hash);
+    assert_eq!(report["document"]["file_name"], "contract.pdf");
+    assert_eq!(report["receipt"]["content_hash"], hash);
+}
+
+#[tokio::test]
+async fn test_verify_by_content_rejects_empty_and_undecodable_content() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    seed_document(&repo);
+
+    let report = verify_content(&repo, &create_verify_by_content_payload(b"")).await;
+    assert_eq!(report["error_code"], "EMPTY_CONTENT");
+
+    let payload = serde_json::json!({
+        "action": "verify_by_content",
+        "data": { "content": "not base64!" }
+    })
+    .to_string();
+    let report = verify_content(&repo, &payload).await;
+    assert_eq!(report["error_code"], "INVALID_BASE64");
+}
@@ -0,0 +1,94 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::ContentHash;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+
+const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+#[tokio::test]
+async fn test_new_version_emits_version_notice() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"lease v1", "lease.pdf", "application/pdf");
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let original = notice["receipt"]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let payload = create_notarize_payload_superseding(b"lease v2", "lease.pdf", &original);
+    let request = create_advance_request(&payload, SUBMITTER, 101);
+    let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Receipt first, then the version link
+    let notices = server.get_notices();
+    assert_eq!(notices.len(), 3);
+    let receipt: serde_json::Value = serde_json::from_str(&notices[1]).unwrap();
+    let version: serde_json::Value = serde_json::from_str(&notices[2]).unwrap();
+    assert_eq!(version["type"], "document_version");
+    assert_eq!(version["previous_hash"], original.as_str());
+    assert_eq!(version["content_hash"], receipt["receipt"]["content_hash"]);
+    assert_eq!(version["document_id"], receipt["receipt"]["document_id"]);
+
+    assert_eq!(
+        repo.find_versions(&ContentHash::from(original.as_str()))
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_second_amendment_of_same_version_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let config = Config::default();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let payload = create_notarize_payload(b"policy v1", "policy.pdf", "application/pdf");
+    let request = create_advance_request(&payload, SUBMITTER, 100);
+    handle_advance(&client, &server_url, &repo, &config, request)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let original = notice["receipt"]["content_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    for (content, expected) in [(b"policy v2a", "accept"), (b"policy v2b", "reject")] {
+        let payload = create_notarize_payload_superseding(content, "policy.pdf", &original);
+        let request = create_advance_request(&payload, SUBMITTER, 101);
+        let result = handle_advance(&client, &server_url, &repo, &config, request).await;
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let reports = server.get_reports();
+    assert_eq!(reports.len(), 1);
+    let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("already superseded"));
+}
@@ -0,0 +1,93 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::parse_rfc3339;
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use sha3::{Digest, Keccak256};
+
+const SUBMITTER: &str = "0x00000000000000000000000000000000000000a1";
+const CALLBACK: &str = "0x00000000000000000000000000000000000000c5";
+
+async fn advance(repo: &SqliteRepository, server_url: &str, payload: &str) -> &'static str {
+    let client = hyper::Client::new();
+    let request = create_advance_request(payload, SUBMITTER, 100);
+    handle_advance(&client, server_url, repo, &Config::default(), request)
+        .await
+        .unwrap()
+}
+
+/// 32-byte big-endian word holding `value`, hex-encoded
+fn uint_word(value: u64) -> String {
+    format!("{:064x}", value)
+}
+
+#[tokio::test]
+async fn test_callback_voucher_carries_abi_encoded_receipt() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_notarize_payload_with_callback(b"purchase order", CALLBACK, None);
+    assert_eq!(advance(&repo, &server_url, &payload).await, "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let notice: serde_json::Value = serde_json::from_str(&server.get_notices()[0]).unwrap();
+    let receipt = &notice["receipt"];
+
+    let vouchers = server.get_vouchers();
+    assert_eq!(vouchers.len(), 1);
+    let voucher: serde_json::Value = serde_json::from_str(&vouchers[0]).unwrap();
+    assert_eq!(voucher["destination"], CALLBACK);
+    assert_eq!(voucher["value"], format!("0x{}", uint_word(0)));
+
+    // onNotarized(bytes32 contentHash, address submitter, uint256 notarizedAt, uint256 blockNumber)
+    let selector = Keccak256::digest(b"onNotarized(bytes32,address,uint256,uint256)");
+    let expected = format!(
+        "0x{}{}{:0>64}{}{}",
+        hex::encode(&selector[..4]),
+        receipt["content_hash"].as_str().unwrap(),
+        &SUBMITTER[2..],
+        uint_word(parse_rfc3339(receipt["notarized_at"].as_str().unwrap()).unwrap() as u64),
+        uint_word(100),
+    );
+    assert_eq!(voucher["payload"], expected);
+}
+
+#[tokio::test]
+async fn test_invalid_callback_rejected_before_notarizing() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload = create_notarize_payload_with_callback(b"purchase order", "0x123", None);
+    assert_eq!(advance(&repo, &server_url, &payload).await, "reject");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value = serde_json::from_str(&server.get_reports()[0]).unwrap();
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid callback_address"));
+    assert!(server.get_vouchers().is_empty());
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_retry_does_not_repeat_callback() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let payload =
+        create_notarize_payload_with_callback(b"purchase order", CALLBACK, Some("order-1"));
+    assert_eq!(advance(&repo, &server_url, &payload).await, "accept");
+    assert_eq!(advance(&repo, &server_url, &payload).await, "accept");
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    assert_eq!(server.get_notices().len(), 2);
+    assert_eq!(server.get_vouchers().len(), 1);
+}
@@ -0,0 +1,157 @@
+use super::helpers::*;
+use super::mock_server::MockRollupServer;
+use dapp::application::Config;
+use dapp::domain::{address_of, personal_message_hash, Document};
+use dapp::handlers::handle_advance;
+use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use k256::ecdsa::SigningKey;
+
+const CONTENT: &[u8] = b"Wallet-signed contract";
+
+fn wallet(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32].into()).unwrap()
+}
+
+/// `personal_sign` of `message` by `key`, as the hex `r || s || v` a wallet returns
+fn personal_sign(key: &SigningKey, message: &str) -> String {
+    let (signature, recovery_id) = key
+        .sign_prehash_recoverable(&personal_message_hash(message.as_bytes()))
+        .unwrap();
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    format!("0x{}", hex::encode(bytes))
+}
+
+#[tokio::test]
+async fn test_signature_by_submitter_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let key = wallet(7);
+    let submitter = address_of(key.verifying_key());
+    let message = Document::hash_content(CONTENT).to_string();
+
+    let payload = create_signed_notarize_payload(CONTENT, &personal_sign(&key, &message), &message);
+    let request = create_advance_request(&payload, submitter.as_str(), 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+
+    // The signature is kept so verifiers can check it later
+    let document = repo.find_by_hash(&Document::hash_content(CONTENT)).unwrap();
+    assert_eq!(
+        document.wallet_signature.as_deref(),
+        Some(personal_sign(&key, &message).as_str())
+    );
+    assert_eq!(document.signed_message.as_deref(), Some(message.as_str()));
+}
+
+#[tokio::test]
+async fn test_message_naming_content_hash_accepted() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let key = wallet(7);
+    let submitter = address_of(key.verifying_key());
+    let message = format!(
+        "I notarize document {}",
+        Document::hash_content(CONTENT).to_string().to_uppercase()
+    );
+
+    let payload = create_signed_notarize_payload(CONTENT, &personal_sign(&key, &message), &message);
+    let request = create_advance_request(&payload, submitter.as_str(), 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "accept");
+    assert_eq!(repo.count_documents().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_signature_of_other_message_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let key = wallet(7);
+    let submitter = address_of(key.verifying_key());
+
+    // A genuine signature by the submitter, but of a different document's hash
+    let message = Document::hash_content(b"Some other contract").to_string();
+    let payload = create_signed_notarize_payload(CONTENT, &personal_sign(&key, &message), &message);
+    let request = create_advance_request(&payload, submitter.as_str(), 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value =
+        serde_json::from_str(server.get_reports().last().unwrap()).unwrap();
+    assert_eq!(report["error_code"], "SIGNED_MESSAGE_MISMATCH");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains(Document::hash_content(CONTENT).as_str()));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_signature_by_other_wallet_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let signer = wallet(7);
+    let submitter = address_of(wallet(8).verifying_key());
+    let message = Document::hash_content(CONTENT).to_string();
+
+    let payload =
+        create_signed_notarize_payload(CONTENT, &personal_sign(&signer, &message), &message);
+    let request = create_advance_request(&payload, submitter.as_str(), 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value =
+        serde_json::from_str(server.get_reports().last().unwrap()).unwrap();
+    assert_eq!(report["error_code"], "UNAUTHORIZED");
+    let detail = report["error_detail"].as_str().unwrap();
+    assert!(detail.contains(address_of(signer.verifying_key()).as_str()));
+    assert!(detail.contains(submitter.as_str()));
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_malformed_signature_rejected() {
+    let repo = SqliteRepository::new_in_memory().unwrap();
+    let server = MockRollupServer::new();
+    let server_url = server.start().await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let client = hyper::Client::new();
+    let submitter = address_of(wallet(7).verifying_key());
+
+    let message = Document::hash_content(CONTENT).to_string();
+    let payload = create_signed_notarize_payload(CONTENT, "0x1234", &message);
+    let request = create_advance_request(&payload, submitter.as_str(), 100);
+    let result = handle_advance(&client, &server_url, &repo, &Config::default(), request).await;
+    assert_eq!(result.unwrap(), "reject");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let report: serde_json::Value =
+        serde_json::from_str(server.get_reports().last().unwrap()).unwrap();
+    assert_eq!(report["error_code"], "INVALID_PAYLOAD");
+    assert!(report["error_detail"]
+        .as_str()
+        .unwrap()
+        .contains("Signature must be 65 bytes"));
+}
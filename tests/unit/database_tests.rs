@@ -1,20 +1,95 @@
-use dapp::domain::Document;
-use dapp::infrastructure::database::{DocumentRepository, SqliteRepository};
+use dapp::domain::{
+    compute_blake2b, compute_cid, AuditEntry, AuditOutcome, ContentHash, Document, UploadSession,
+};
+use dapp::infrastructure::database::{
+    DatabaseError, DocumentRepository, SqliteRepository, DEFAULT_BUSY_TIMEOUT_MS, SCHEMA_VERSION,
+};
+use dapp::infrastructure::postgres_repository::PostgresRepository;
 
-#[test]
-fn test_init_database_creates_schema() {
-    // Create in-memory database
-    let repo = SqliteRepository::new_in_memory().expect("Failed to create repository");
+/// Server the PostgreSQL runs of the repository tests use; they are skipped when unset
+const POSTGRES_URL_VAR: &str = "NOTARY_TEST_POSTGRES_URL";
+
+/// Repository in a schema of its own on the test server, dropped with it
+struct PostgresSchema {
+    repository: PostgresRepository,
+    admin: ::postgres::Client,
+    name: String,
+}
+
+impl PostgresSchema {
+    /// `None` when no test server is configured
+    fn create() -> Option<Self> {
+        let url = std::env::var(POSTGRES_URL_VAR).ok()?;
+        let name = format!("notary_test_{}", uuid::Uuid::new_v4().simple());
+
+        let mut admin = ::postgres::Client::connect(&url, ::postgres::NoTls).unwrap();
+        admin
+            .batch_execute(&format!("CREATE SCHEMA {}", name))
+            .unwrap();
+
+        // Unqualified table names resolve to the new schema
+        let connection_str = if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{}{}options=-csearch_path%3D{}", url, separator, name)
+        } else {
+            format!("{} options='-csearch_path={}'", url, name)
+        };
+
+        Some(Self {
+            repository: PostgresRepository::new(&connection_str).unwrap(),
+            admin,
+            name,
+        })
+    }
+}
+
+impl Drop for PostgresSchema {
+    fn drop(&mut self) {
+        let _ = self
+            .admin
+            .batch_execute(&format!("DROP SCHEMA {} CASCADE", self.name));
+    }
+}
 
+/// Run each test against an in-memory SQLite repository, and against PostgreSQL
+/// when `NOTARY_TEST_POSTGRES_URL` names a server
+macro_rules! repository_tests {
+    ($($name:ident),* $(,)?) => {
+        mod sqlite {
+            $(
+                #[test]
+                fn $name() {
+                    super::$name(&super::SqliteRepository::new_in_memory().unwrap());
+                }
+            )*
+        }
+
+        mod postgres {
+            $(
+                #[test]
+                fn $name() {
+                    if let Some(schema) = super::PostgresSchema::create() {
+                        super::$name(&schema.repository);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+fn test_init_database_creates_schema(repo: &dyn DocumentRepository) {
     // Verify we can interact with tables (they exist)
-    let result = repo.find_by_hash("nonexistent_hash");
+    let result = repo.find_by_hash(&ContentHash::from("nonexistent_hash"));
     assert!(result.is_err()); // Should fail to find, but not crash
 }
 
-#[test]
-fn test_save_document_persists() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-    let doc = Document::new(b"test content", "test.txt", "text/plain", "0x123");
+fn test_save_document_persists(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"test content",
+        "test.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
 
     // Save document
     repo.save_document(&doc).expect("Failed to save document");
@@ -30,24 +105,28 @@ fn test_save_document_persists() {
     assert_eq!(found.submitted_by, doc.submitted_by);
 }
 
-#[test]
-fn test_find_by_hash_not_found() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-
-    let result = repo.find_by_hash("nonexistent_hash_12345");
+fn test_find_by_hash_not_found(repo: &dyn DocumentRepository) {
+    let result = repo.find_by_hash(&ContentHash::from("nonexistent_hash_12345"));
 
     assert!(result.is_err());
     let err_msg = result.unwrap_err().to_string();
     assert!(err_msg.contains("not found") || err_msg.contains("no rows"));
 }
 
-#[test]
-fn test_duplicate_hash_constraint() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-
+fn test_duplicate_hash_constraint(repo: &dyn DocumentRepository) {
     // Create two documents with same content (same hash)
-    let doc1 = Document::new(b"same content", "file1.txt", "text/plain", "0x123");
-    let doc2 = Document::new(b"same content", "file2.txt", "text/plain", "0x456");
+    let doc1 = Document::new(
+        b"same content",
+        "file1.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    let doc2 = Document::new(
+        b"same content",
+        "file2.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000456",
+    );
 
     // First save should succeed
     repo.save_document(&doc1)
@@ -65,10 +144,13 @@ fn test_duplicate_hash_constraint() {
     );
 }
 
-#[test]
-fn test_find_by_id() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-    let doc = Document::new(b"content", "file.txt", "text/plain", "0x123");
+fn test_find_by_id(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"content",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
 
     repo.save_document(&doc).unwrap();
 
@@ -77,13 +159,25 @@ fn test_find_by_id() {
     assert_eq!(found.content_hash, doc.content_hash);
 }
 
-#[test]
-fn test_multiple_documents() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-
-    let doc1 = Document::new(b"content 1", "file1.txt", "text/plain", "0x123");
-    let doc2 = Document::new(b"content 2", "file2.txt", "text/plain", "0x456");
-    let doc3 = Document::new(b"content 3", "file3.txt", "text/plain", "0x789");
+fn test_multiple_documents(repo: &dyn DocumentRepository) {
+    let doc1 = Document::new(
+        b"content 1",
+        "file1.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    let doc2 = Document::new(
+        b"content 2",
+        "file2.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000456",
+    );
+    let doc3 = Document::new(
+        b"content 3",
+        "file3.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000789",
+    );
 
     repo.save_document(&doc1).unwrap();
     repo.save_document(&doc2).unwrap();
@@ -95,16 +189,1190 @@ fn test_multiple_documents() {
     assert!(repo.find_by_hash(&doc3.content_hash).is_ok());
 }
 
-#[test]
-fn test_document_count() {
-    let repo = SqliteRepository::new_in_memory().unwrap();
-
+fn test_document_count(repo: &dyn DocumentRepository) {
     let initial_count = repo.count_documents().unwrap();
     assert_eq!(initial_count, 0);
 
-    let doc = Document::new(b"test", "file.txt", "text/plain", "0x123");
+    let doc = Document::new(
+        b"test",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
     repo.save_document(&doc).unwrap();
 
     let count = repo.count_documents().unwrap();
     assert_eq!(count, 1);
 }
+
+fn test_expires_at_persists(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"expiring",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_expiry(Some(4102444800));
+
+    repo.save_document(&doc).unwrap();
+
+    let found = repo.find_by_hash(&doc.content_hash).unwrap();
+    assert_eq!(found.expires_at, Some(4102444800));
+}
+
+fn test_detected_mime_type_persists_beside_declared_type(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"%PDF-1.7 mislabelled",
+        "contract.pdf",
+        "application/octet-stream",
+        "0x0000000000000000000000000000000000000123",
+    );
+
+    repo.save_document(&doc).unwrap();
+
+    let found = repo.find_by_hash(&doc.content_hash).unwrap();
+    assert_eq!(found.mime_type, "application/octet-stream");
+    assert_eq!(found.detected_mime_type.as_deref(), Some("application/pdf"));
+}
+
+fn test_find_by_cid(repo: &dyn DocumentRepository) {
+    let with_cid = Document::new(
+        b"pinned",
+        "pinned.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_ipfs_cid(Some(compute_cid(b"pinned")));
+    let without_cid = Document::new(
+        b"unpinned",
+        "unpinned.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&with_cid).unwrap();
+    repo.save_document(&without_cid).unwrap();
+
+    let found = repo.find_by_cid(&compute_cid(b"pinned")).unwrap();
+    assert_eq!(found.id, with_cid.id);
+    assert_eq!(found.ipfs_cid, with_cid.ipfs_cid);
+
+    let err = repo.find_by_cid(&compute_cid(b"unpinned")).unwrap_err();
+    assert!(matches!(err, DatabaseError::NotFound));
+}
+
+fn test_find_by_blake2b_hash(repo: &dyn DocumentRepository) {
+    let blake2b = compute_blake2b(b"blake payload");
+    let doc = Document::new(
+        b"blake payload",
+        "payload.bin",
+        "application/octet-stream",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_blake2b_hash(Some(blake2b.clone()));
+    repo.save_document(&doc).unwrap();
+
+    let found = repo.find_by_blake2b_hash(&blake2b).unwrap();
+    assert_eq!(found.id, doc.id);
+    assert_eq!(found.blake2b_hash.as_deref(), Some(blake2b.as_str()));
+
+    // Documents saved without the digest are not found by it
+    let plain = Document::new(
+        b"plain payload",
+        "plain.bin",
+        "application/octet-stream",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&plain).unwrap();
+    let err = repo
+        .find_by_blake2b_hash(&compute_blake2b(b"plain payload"))
+        .unwrap_err();
+    assert!(matches!(err, DatabaseError::NotFound));
+}
+
+fn test_find_by_input(repo: &dyn DocumentRepository) {
+    for (input, content) in [(3, &b"third input"[..]), (4, b"fourth input")] {
+        let doc = Document::new(
+            content,
+            "input.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        )
+        .with_input(2, input);
+        repo.save_document(&doc).unwrap();
+    }
+
+    let found = repo.find_by_input(2, 4).unwrap();
+    assert_eq!(found.content_hash, Document::hash_content(b"fourth input"));
+    assert_eq!((found.epoch_index, found.input_index), (2, 4));
+
+    // Both coordinates must match
+    assert!(matches!(
+        repo.find_by_input(2, 5).unwrap_err(),
+        DatabaseError::NotFound
+    ));
+    assert!(matches!(
+        repo.find_by_input(3, 4).unwrap_err(),
+        DatabaseError::NotFound
+    ));
+}
+
+fn test_find_by_keccak(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"evm payload",
+        "payload.bin",
+        "application/octet-stream",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    let keccak = Document::hash_content_keccak(b"evm payload");
+    let found = repo.find_by_keccak(&keccak).unwrap();
+    assert_eq!(found.id, doc.id);
+    assert_eq!(found.content_hash_keccak.as_deref(), Some(keccak.as_str()));
+
+    // The SHA-256 hash is not a Keccak-256 key
+    let err = repo.find_by_keccak(doc.content_hash.as_str()).unwrap_err();
+    assert!(matches!(err, DatabaseError::NotFound));
+}
+
+fn test_description_round_trips(repo: &dyn DocumentRepository) {
+    let described = Document::new(
+        b"audit",
+        "audit.pdf",
+        "application/pdf",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_description(Some("Q3 2024 financial audit report".to_string()));
+    repo.save_document(&described).unwrap();
+
+    let found = repo.find_by_hash(&described.content_hash).unwrap();
+    assert_eq!(
+        found.description.as_deref(),
+        Some("Q3 2024 financial audit report")
+    );
+}
+
+#[test]
+fn test_audit_log_is_append_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.db");
+    let path = path.to_str().unwrap();
+
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let entry = AuditEntry {
+        id: 0,
+        action: "notarize".to_string(),
+        submitter: "0x0000000000000000000000000000000000000123".to_string(),
+        block_number: 42,
+        timestamp: 1_700_000_000,
+        outcome: AuditOutcome::Reject,
+        content_hash: Some("a".repeat(64)),
+    };
+    let first = repo.append_audit_entry(&entry).unwrap();
+    let second = repo.append_audit_entry(&entry).unwrap();
+    assert!(second > first);
+
+    // Even a direct connection cannot rewrite or remove entries
+    let conn = rusqlite::Connection::open(path).unwrap();
+    let update = conn.execute("UPDATE audit_log SET outcome = 'accept'", []);
+    assert!(update.unwrap_err().to_string().contains("append-only"));
+    let delete = conn.execute("DELETE FROM audit_log", []);
+    assert!(delete.unwrap_err().to_string().contains("append-only"));
+
+    let entries = repo.find_audit_entries(None, None, None, None, 10).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0], AuditEntry { id: first, ..entry });
+}
+
+#[test]
+fn test_postgres_audit_log_is_append_only() {
+    let Some(mut schema) = PostgresSchema::create() else {
+        return;
+    };
+
+    let entry = AuditEntry {
+        id: 0,
+        action: "notarize".to_string(),
+        submitter: "0x0000000000000000000000000000000000000123".to_string(),
+        block_number: 42,
+        timestamp: 1_700_000_000,
+        outcome: AuditOutcome::Reject,
+        content_hash: None,
+    };
+    let id = schema.repository.append_audit_entry(&entry).unwrap();
+
+    // Even a direct connection cannot rewrite or remove entries
+    let table = format!("{}.audit_log", schema.name);
+    let update = schema
+        .admin
+        .batch_execute(&format!("UPDATE {} SET outcome = 'accept'", table));
+    assert!(format!("{:?}", update.unwrap_err()).contains("append-only"));
+    let delete = schema
+        .admin
+        .batch_execute(&format!("DELETE FROM {}", table));
+    assert!(format!("{:?}", delete.unwrap_err()).contains("append-only"));
+
+    let entries = schema
+        .repository
+        .find_audit_entries(None, None, None, None, 10)
+        .unwrap();
+    assert_eq!(entries, vec![AuditEntry { id, ..entry }]);
+}
+
+fn test_list_audit_entries_pages_by_offset(repo: &dyn DocumentRepository) {
+    for block_number in 1..=5 {
+        let outcome = if block_number % 2 == 0 {
+            AuditOutcome::Reject
+        } else {
+            AuditOutcome::Accept
+        };
+        repo.append_audit_entry(&AuditEntry {
+            id: 0,
+            action: "notarize".to_string(),
+            submitter: "0x0000000000000000000000000000000000000123".to_string(),
+            block_number,
+            timestamp: 1_700_000_000,
+            outcome,
+            content_hash: None,
+        })
+        .unwrap();
+    }
+
+    let blocks = |entries: Vec<AuditEntry>| -> Vec<u64> {
+        entries.iter().map(|entry| entry.block_number).collect()
+    };
+    assert_eq!(blocks(repo.list_audit_entries(2, 0).unwrap()), vec![1, 2]);
+    assert_eq!(blocks(repo.list_audit_entries(2, 3).unwrap()), vec![4, 5]);
+    assert!(repo.list_audit_entries(2, 5).unwrap().is_empty());
+}
+
+#[test]
+fn test_legacy_schema_is_migrated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("legacy.db");
+
+    // Database created before the expires_at column existed
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute(
+        "CREATE TABLE documents (
+            id TEXT PRIMARY KEY,
+            content_hash TEXT UNIQUE NOT NULL,
+            file_name TEXT NOT NULL,
+            mime_type TEXT NOT NULL,
+            submitted_by TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    let repo = SqliteRepository::new(path.to_str().unwrap(), DEFAULT_BUSY_TIMEOUT_MS)
+        .expect("Migration should succeed");
+    let doc = Document::new(
+        b"legacy",
+        "file.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    let found = repo.find_by_hash(&doc.content_hash).unwrap();
+    assert!(found.expires_at.is_none());
+    assert_eq!(found.detected_mime_type.as_deref(), Some("text/plain"));
+    assert!(found.ipfs_cid.is_none());
+    assert!(found.description.is_none());
+}
+
+#[test]
+fn test_legacy_audit_log_is_migrated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("legacy_audit.db");
+
+    // Audit log created before content hashes were recorded
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute(
+        "CREATE TABLE audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            submitter TEXT NOT NULL,
+            block_number INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL,
+            outcome TEXT NOT NULL
+        )",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO audit_log (action, submitter, block_number, timestamp, outcome)
+         VALUES ('verify', '', 7, 1700000000, 'accept')",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    let repo = SqliteRepository::new(path.to_str().unwrap(), DEFAULT_BUSY_TIMEOUT_MS)
+        .expect("Migration should succeed");
+    let entries = repo.list_audit_entries(10, 0).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].block_number, 7);
+    assert!(entries[0].content_hash.is_none());
+}
+
+fn test_update_metadata_keeps_hash_and_id(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"content",
+        "old.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    let updated = repo
+        .update_metadata(&doc.content_hash, Some("new.txt"), None, None)
+        .unwrap();
+
+    assert_eq!(updated.id, doc.id);
+    assert_eq!(updated.content_hash, doc.content_hash);
+    assert_eq!(updated.file_name, "new.txt");
+    assert_eq!(updated.mime_type, "text/plain");
+
+    assert!(repo
+        .update_metadata(&ContentHash::from("missing"), Some("x.txt"), None, None)
+        .is_err());
+}
+
+fn test_document_metadata_round_trip(repo: &dyn DocumentRepository) {
+    let metadata = serde_json::json!({"department": "legal", "pages": 12, "signed": true});
+    let doc = Document::new(
+        b"annotated",
+        "annotated.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_metadata(metadata.clone());
+    repo.save_document(&doc).unwrap();
+
+    let stored = repo.find_by_hash(&doc.content_hash).unwrap();
+    assert_eq!(stored.metadata, metadata);
+
+    // Replacing the object leaves the other columns alone
+    let replacement = serde_json::json!({"department": "finance"});
+    let updated = repo
+        .update_metadata(&doc.content_hash, None, None, Some(&replacement))
+        .unwrap();
+    assert_eq!(updated.metadata, replacement);
+    assert_eq!(updated.file_name, "annotated.txt");
+
+    // Documents without metadata read back as an empty object
+    let plain = Document::new(
+        b"plain",
+        "plain.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&plain).unwrap();
+    let stored = repo.find_by_hash(&plain.content_hash).unwrap();
+    assert_eq!(stored.metadata, serde_json::json!({}));
+}
+
+fn test_delete_by_hash_removes_document(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"erase me",
+        "personal.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    repo.delete_by_hash(&doc.content_hash).unwrap();
+
+    assert!(repo.find_by_hash(&doc.content_hash).is_err());
+    assert_eq!(repo.count_documents().unwrap(), 0);
+
+    // Nothing left to delete
+    let err = repo.delete_by_hash(&doc.content_hash).unwrap_err();
+    assert!(err.to_string().contains("not found"));
+}
+
+fn test_count_by_submitter_with_no_documents(repo: &dyn DocumentRepository) {
+    assert_eq!(
+        repo.count_by_submitter("0x0000000000000000000000000000000000000123")
+            .unwrap(),
+        0
+    );
+    assert!(repo.list_submitters().unwrap().is_empty());
+}
+
+fn test_submitter_counts_with_multiple_submitters(repo: &dyn DocumentRepository) {
+    let alice = "0x00000000000000000000000000000000000000aa";
+    let bob = "0x00000000000000000000000000000000000000bb";
+
+    for (content, submitter) in [
+        (b"one".as_slice(), alice),
+        (b"two".as_slice(), bob),
+        (b"three".as_slice(), alice),
+        (
+            b"four".as_slice(),
+            "0x00000000000000000000000000000000000000AA",
+        ),
+    ] {
+        let doc = Document::new(content, "file.txt", "text/plain", submitter);
+        repo.save_document(&doc).unwrap();
+    }
+
+    // Address case does not split a submitter's documents
+    assert_eq!(repo.count_by_submitter(alice).unwrap(), 3);
+    assert_eq!(repo.count_by_submitter(bob).unwrap(), 1);
+
+    let submitters = repo.list_submitters().unwrap();
+    assert_eq!(
+        submitters,
+        vec![(alice.to_string(), 3), (bob.to_string(), 1)]
+    );
+}
+
+fn test_top_submitters_and_recent_counts(repo: &dyn DocumentRepository) {
+    let alice = "0x00000000000000000000000000000000000000aa";
+    let bob = "0x00000000000000000000000000000000000000bb";
+    let carol = "0x00000000000000000000000000000000000000cc";
+
+    for (content, submitter, created_at) in [
+        (b"one".as_slice(), alice, 100),
+        (b"two".as_slice(), bob, 200),
+        (b"three".as_slice(), alice, 300),
+        (b"four".as_slice(), carol, 400),
+        (b"five".as_slice(), bob, 500),
+        (b"six".as_slice(), alice, 600),
+    ] {
+        let mut doc = Document::new(content, "file.txt", "text/plain", submitter);
+        doc.created_at = created_at;
+        repo.save_document(&doc).unwrap();
+    }
+
+    assert_eq!(
+        repo.count_by_submitter_top_n(2).unwrap(),
+        vec![(alice.to_string(), 3), (bob.to_string(), 2)]
+    );
+    assert_eq!(repo.count_by_submitter_top_n(10).unwrap().len(), 3);
+
+    // The lower bound is inclusive
+    assert_eq!(repo.count_documents_since(400).unwrap(), 3);
+    assert_eq!(repo.count_documents_since(601).unwrap(), 0);
+}
+
+fn save_docs(repo: &dyn DocumentRepository, contents: &[&[u8]]) -> Vec<ContentHash> {
+    contents
+        .iter()
+        .map(|content| {
+            let doc = Document::new(
+                content,
+                "batch.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+            );
+            repo.save_document(&doc).unwrap();
+            doc.content_hash
+        })
+        .collect()
+}
+
+fn test_find_many_by_hashes_all_found(repo: &dyn DocumentRepository) {
+    let hashes = save_docs(repo, &[b"one", b"two", b"three"]);
+
+    // Order differs from insertion order and must be preserved
+    let lookup = [&hashes[2], &hashes[0], &hashes[1]];
+    let found = repo.find_many_by_hashes(&lookup).unwrap();
+
+    assert_eq!(found.len(), 3);
+    for (hash, doc) in lookup.iter().zip(&found) {
+        assert_eq!(doc.as_ref().unwrap().content_hash, **hash);
+    }
+}
+
+fn test_find_many_by_hashes_partial_found(repo: &dyn DocumentRepository) {
+    let hashes = save_docs(repo, &[b"present"]);
+    let missing = ContentHash::from("0".repeat(64));
+
+    let found = repo
+        .find_many_by_hashes(&[&missing, &hashes[0], &missing, &hashes[0]])
+        .unwrap();
+
+    assert_eq!(found.len(), 4);
+    assert!(found[0].is_none());
+    assert_eq!(found[1].as_ref().unwrap().content_hash, hashes[0]);
+    assert!(found[2].is_none());
+    assert_eq!(found[3].as_ref().unwrap().content_hash, hashes[0]);
+}
+
+fn test_find_many_by_hashes_all_missing(repo: &dyn DocumentRepository) {
+    save_docs(repo, &[b"unrelated"]);
+
+    let first = ContentHash::from("a".repeat(64));
+    let second = ContentHash::from("b".repeat(64));
+    let found = repo.find_many_by_hashes(&[&first, &second]).unwrap();
+
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().all(Option::is_none));
+    assert!(repo.find_many_by_hashes(&[]).unwrap().is_empty());
+}
+
+fn test_content_is_stored_and_purged_with_document(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"archived bytes",
+        "archive.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document_with_content(&doc, b"archived bytes")
+        .unwrap();
+
+    assert_eq!(repo.get_content(&doc.id).unwrap(), b"archived bytes");
+
+    // Content rows go away with their document
+    repo.delete_by_hash(&doc.content_hash).unwrap();
+    assert!(repo.get_content(&doc.id).is_err());
+}
+
+fn test_hash_only_document_has_no_content(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"hash only",
+        "hash.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    let err = repo.get_content(&doc.id).unwrap_err();
+    assert!(err.to_string().contains("not found"));
+
+    // A duplicate hash stores neither the document nor its content
+    let duplicate = Document::new(
+        b"hash only",
+        "b.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000456",
+    );
+    assert!(repo
+        .save_document_with_content(&duplicate, b"hash only")
+        .is_err());
+    assert!(repo.get_content(&duplicate.id).is_err());
+}
+
+fn test_save_documents_atomic_saves_all(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let first = Document::new(b"first", "first.txt", "text/plain", submitter);
+    let second = Document::new(b"second", "second.txt", "text/plain", submitter);
+
+    repo.save_documents_atomic(&[&first, &second]).unwrap();
+
+    assert_eq!(repo.count_documents().unwrap(), 2);
+    assert!(repo.find_by_hash(&second.content_hash).is_ok());
+}
+
+fn test_save_documents_atomic_rolls_back_on_duplicate(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let existing = Document::new(b"existing", "existing.txt", "text/plain", submitter);
+    repo.save_document(&existing).unwrap();
+
+    let fresh = Document::new(b"fresh", "fresh.txt", "text/plain", submitter);
+    let duplicate = Document::new(b"existing", "b.txt", "text/plain", submitter);
+    let later = Document::new(b"later", "later.txt", "text/plain", submitter);
+
+    let err = repo
+        .save_documents_atomic(&[&fresh, &duplicate, &later])
+        .unwrap_err();
+    assert!(err.to_string().contains("Duplicate"));
+
+    // Nothing from the failed batch was kept
+    assert_eq!(repo.count_documents().unwrap(), 1);
+    assert!(repo.find_by_hash(&fresh.content_hash).is_err());
+    assert!(repo.find_by_hash(&later.content_hash).is_err());
+}
+
+fn test_save_documents_atomic_rejects_duplicate_within_batch(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let doc = Document::new(b"twice", "a.txt", "text/plain", submitter);
+    let same_content = Document::new(b"twice", "b.txt", "text/plain", submitter);
+
+    assert!(repo.save_documents_atomic(&[&doc, &same_content]).is_err());
+    assert_eq!(repo.count_documents().unwrap(), 0);
+}
+
+fn test_repeated_lookups_with_cached_statements(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let docs: Vec<Document> = (0..10)
+        .map(|i| {
+            Document::new(
+                format!("cached {}", i).as_bytes(),
+                "cached.txt",
+                "text/plain",
+                submitter,
+            )
+        })
+        .collect();
+    for doc in &docs {
+        repo.save_document(doc).unwrap();
+    }
+
+    // Hits and misses interleave on the same cached statement
+    for i in 0..1000 {
+        let doc = &docs[i % docs.len()];
+        assert_eq!(repo.find_by_hash(&doc.content_hash).unwrap().id, doc.id);
+        assert!(repo
+            .find_by_hash(&ContentHash::from(format!("{:064x}", i)))
+            .is_err());
+    }
+}
+
+fn test_duplicate_detected_with_cached_insert(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let doc = Document::new(b"cached insert", "a.txt", "text/plain", submitter);
+    repo.save_document(&doc).unwrap();
+
+    // The second insert reuses the cached statement and still maps the constraint error
+    for _ in 0..3 {
+        let duplicate = Document::new(b"cached insert", "b.txt", "text/plain", submitter);
+        let err = repo.save_document(&duplicate).unwrap_err();
+        assert!(err.to_string().contains("Duplicate document hash"));
+    }
+
+    let other = Document::new(b"other content", "c.txt", "text/plain", submitter);
+    repo.save_document(&other).unwrap();
+    assert_eq!(repo.count_documents().unwrap(), 2);
+}
+
+fn test_full_text_search_follows_metadata_changes(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let doc = Document::new(b"indexed", "draft-notes.txt", "text/plain", submitter);
+    repo.save_document(&doc).unwrap();
+
+    assert_eq!(repo.full_text_search("draft", 10).unwrap().len(), 1);
+
+    repo.update_metadata(&doc.content_hash, Some("final-report.txt"), None, None)
+        .unwrap();
+    assert!(repo.full_text_search("draft", 10).unwrap().is_empty());
+    assert_eq!(repo.full_text_search("final report", 10).unwrap().len(), 1);
+
+    repo.delete_by_hash(&doc.content_hash).unwrap();
+    assert!(repo.full_text_search("final", 10).unwrap().is_empty());
+}
+
+fn test_full_text_search_tolerates_query_syntax(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let doc = Document::new(b"syntax", "acme-contract.pdf", "application/pdf", submitter);
+    repo.save_document(&doc).unwrap();
+
+    // FTS5 operators and punctuation are treated as plain text
+    for query in [
+        "acme-contract",
+        "\"acme",
+        "acme OR",
+        "NOT acme",
+        "-",
+        "application/pd",
+    ] {
+        assert!(repo.full_text_search(query, 10).is_ok(), "query {}", query);
+    }
+    assert_eq!(repo.full_text_search("acme-contract", 10).unwrap().len(), 1);
+    assert!(repo.full_text_search("   ", 10).unwrap().is_empty());
+}
+
+fn test_full_text_search_matches_tags(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let tagged = Document::new(b"scan", "scan-0042.pdf", "application/pdf", submitter);
+    repo.save_document(&tagged).unwrap();
+    repo.add_tags(&tagged.id, &["invoice", "acme"]).unwrap();
+    let untagged = Document::new(b"other", "scan-0043.pdf", "application/pdf", submitter);
+    repo.save_document(&untagged).unwrap();
+
+    let found = repo.full_text_search("invoi", 10).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, tagged.id);
+
+    // Words may match across the filename and the tags
+    assert_eq!(repo.full_text_search("scan acme", 10).unwrap().len(), 1);
+    assert_eq!(repo.full_text_search("scan", 10).unwrap().len(), 2);
+
+    repo.delete_by_hash(&tagged.content_hash).unwrap();
+    assert!(repo.full_text_search("invoice", 10).unwrap().is_empty());
+}
+
+fn test_full_text_search_ranks_by_relevance(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let save = |content: &[u8], file_name: &str, created_at: i64| {
+        let doc = Document::new_at(content, file_name, "text/plain", submitter, created_at);
+        repo.save_document(&doc).unwrap();
+        doc
+    };
+
+    let short = save(b"1", "contract.txt", 1_700_000_000);
+    let long = save(
+        b"2",
+        "acme corp signed service contract final version.txt",
+        1_700_000_100,
+    );
+    let tagged = save(b"3", "scan-0042.txt", 1_700_000_200);
+    repo.add_tags(&tagged.id, &["contract"]).unwrap();
+    save(b"4", "notes.txt", 1_700_000_300);
+
+    // Filename hits rank above tag hits, and shorter filenames above longer ones,
+    // regardless of age
+    let found: Vec<_> = repo
+        .full_text_search("contr", 10)
+        .unwrap()
+        .into_iter()
+        .map(|doc| doc.id)
+        .collect();
+    assert_eq!(found, vec![short.id, long.id, tagged.id]);
+}
+
+#[test]
+fn test_existing_documents_are_indexed_on_upgrade() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("before_search.db");
+    let path = path.to_str().unwrap();
+
+    // Database written before the search index existed
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let doc = Document::new(
+        b"old",
+        "acme-invoice.pdf",
+        "application/pdf",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+    drop(repo);
+
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "DROP TRIGGER documents_fts_insert;
+         DROP TRIGGER documents_fts_update;
+         DROP TRIGGER documents_fts_delete;
+         DROP TABLE documents_fts;",
+    )
+    .unwrap();
+    drop(conn);
+
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let found = repo.full_text_search("acme invoice", 10).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, doc.id);
+}
+
+#[test]
+fn test_search_index_without_tags_is_rebuilt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("before_tag_search.db");
+    let path = path.to_str().unwrap();
+
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let doc = Document::new(
+        b"old",
+        "scan-0042.pdf",
+        "application/pdf",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+    repo.add_tags(&doc.id, &["invoice"]).unwrap();
+    drop(repo);
+
+    // Index as it was before tags were searchable
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "DROP TABLE documents_fts;
+         CREATE VIRTUAL TABLE documents_fts USING fts5(id UNINDEXED, file_name, mime_type);
+         INSERT INTO documents_fts (rowid, id, file_name, mime_type)
+             SELECT rowid, id, file_name, mime_type FROM documents;",
+    )
+    .unwrap();
+    drop(conn);
+
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    let found = repo.full_text_search("invoice", 10).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, doc.id);
+    assert_eq!(repo.full_text_search("scan", 10).unwrap().len(), 1);
+}
+
+#[test]
+fn test_schema_version_is_recorded_on_upgrade() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("unversioned.db");
+    let path = path.to_str().unwrap();
+
+    // Database written before the schema version was recorded
+    drop(SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap());
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.pragma_update(None, "user_version", 0).unwrap();
+    drop(conn);
+
+    let repo = SqliteRepository::new(path, DEFAULT_BUSY_TIMEOUT_MS).unwrap();
+    assert_eq!(repo.schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+fn test_tags_are_removed_with_document(repo: &dyn DocumentRepository) {
+    let doc = Document::new(
+        b"tagged",
+        "tagged.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    repo.save_document(&doc).unwrap();
+
+    // Re-adding an existing tag is a no-op
+    repo.add_tags(&doc.id, &["contract", "acme"]).unwrap();
+    repo.add_tags(&doc.id, &["contract"]).unwrap();
+    assert_eq!(repo.find_by_tag("contract", 10, 0).unwrap().len(), 1);
+
+    repo.delete_by_hash(&doc.content_hash).unwrap();
+    assert!(repo.find_by_tag("acme", 10, 0).unwrap().is_empty());
+}
+
+fn test_find_all_by_content_hash_lists_aliases_in_order(repo: &dyn DocumentRepository) {
+    let submitter = "0x0000000000000000000000000000000000000123";
+    let doc = Document::new(b"aliased", "a.txt", "text/plain", submitter);
+    repo.save_document(&doc).unwrap();
+    assert_eq!(
+        repo.find_all_by_content_hash(&doc.content_hash).unwrap(),
+        vec!["a.txt"]
+    );
+
+    // Re-adding an existing alias is a no-op
+    repo.add_alias(&doc.id, "b.txt", submitter, 200).unwrap();
+    repo.add_alias(&doc.id, "c.txt", submitter, 300).unwrap();
+    repo.add_alias(&doc.id, "b.txt", submitter, 400).unwrap();
+    assert_eq!(
+        repo.find_all_by_content_hash(&doc.content_hash).unwrap(),
+        vec!["a.txt", "b.txt", "c.txt"]
+    );
+
+    repo.delete_by_hash(&doc.content_hash).unwrap();
+    assert!(repo
+        .find_all_by_content_hash(&doc.content_hash)
+        .unwrap()
+        .is_empty());
+}
+
+/// Store a document with a fixed creation time
+fn save_created_at(repo: &dyn DocumentRepository, content: &str, created_at: i64) -> Document {
+    let mut doc = Document::new(
+        content.as_bytes(),
+        "dated.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    );
+    doc.created_at = created_at;
+    repo.save_document(&doc).unwrap();
+    doc
+}
+
+fn test_find_by_date_range_is_inclusive(repo: &dyn DocumentRepository) {
+    for created_at in [999, 1000, 1500, 2000, 2001] {
+        save_created_at(repo, &format!("doc {}", created_at), created_at);
+    }
+
+    let found = repo.find_by_date_range(1000, 2000, 100, 0).unwrap();
+    let times: Vec<i64> = found.iter().map(|doc| doc.created_at).collect();
+    assert_eq!(times, vec![1000, 1500, 2000]);
+
+    // A single-instant window still matches
+    assert_eq!(
+        repo.find_by_date_range(1500, 1500, 100, 0).unwrap().len(),
+        1
+    );
+}
+
+fn test_find_by_date_range_paginates(repo: &dyn DocumentRepository) {
+    for i in 0..5 {
+        save_created_at(repo, &format!("page {}", i), 100 + i);
+    }
+
+    let first = repo.find_by_date_range(0, 1000, 2, 0).unwrap();
+    let third = repo.find_by_date_range(0, 1000, 2, 4).unwrap();
+    assert_eq!(
+        first.iter().map(|doc| doc.created_at).collect::<Vec<_>>(),
+        vec![100, 101]
+    );
+    assert_eq!(
+        third.iter().map(|doc| doc.created_at).collect::<Vec<_>>(),
+        vec![104]
+    );
+}
+
+fn test_list_documents_orders_by_key_and_resumes_after_cursor(repo: &dyn DocumentRepository) {
+    // Two documents share a timestamp, so the id breaks the tie
+    let docs: Vec<Document> = [100, 200, 200, 300]
+        .iter()
+        .enumerate()
+        .map(|(i, created_at)| save_created_at(repo, &format!("keyset {}", i), *created_at))
+        .collect();
+
+    let all = repo.list_documents(None, 10).unwrap();
+    let keys: Vec<(i64, String)> = all
+        .iter()
+        .map(|doc| (doc.created_at, doc.id.to_string()))
+        .collect();
+    let mut expected: Vec<(i64, String)> = docs
+        .iter()
+        .map(|doc| (doc.created_at, doc.id.to_string()))
+        .collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    assert_eq!(keys, expected);
+
+    // Resuming after the first of the tied pair returns only the second, then older rows
+    let after = repo
+        .list_documents(Some((all[1].created_at, &all[1].id)), 10)
+        .unwrap();
+    assert_eq!(
+        after.iter().map(|doc| &doc.id).collect::<Vec<_>>(),
+        vec![&all[2].id, &all[3].id]
+    );
+    assert!(repo
+        .list_documents(Some((all[3].created_at, &all[3].id)), 10)
+        .unwrap()
+        .is_empty());
+}
+
+fn test_list_all_orders_oldest_first_and_skips_offset(repo: &dyn DocumentRepository) {
+    let docs: Vec<Document> = [300, 100, 200, 200]
+        .iter()
+        .enumerate()
+        .map(|(i, created_at)| save_created_at(repo, &format!("export {}", i), *created_at))
+        .collect();
+
+    let mut expected: Vec<(i64, String)> = docs
+        .iter()
+        .map(|doc| (doc.created_at, doc.id.to_string()))
+        .collect();
+    expected.sort();
+
+    let keys = |page: Vec<Document>| -> Vec<(i64, String)> {
+        page.iter()
+            .map(|doc| (doc.created_at, doc.id.to_string()))
+            .collect()
+    };
+    assert_eq!(keys(repo.list_all(10, 0).unwrap()), expected);
+    assert_eq!(keys(repo.list_all(2, 1).unwrap()), expected[1..3].to_vec());
+    assert!(repo.list_all(10, 4).unwrap().is_empty());
+}
+
+fn test_count_by_day_buckets_utc_days(repo: &dyn DocumentRepository) {
+    const DAY: i64 = 86_400;
+    let monday = 1_699_833_600; // 2023-11-13T00:00:00Z
+
+    // Two on the first day, including its first second; one just before the third midnight
+    for (i, created_at) in [
+        monday,
+        monday + 3_600,
+        monday + 2 * DAY - 1,
+        monday + 2 * DAY,
+        monday + 2 * DAY + 10,
+        monday + 2 * DAY + 20,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        save_created_at(repo, &format!("daily {}", i), created_at);
+    }
+
+    let days = repo.count_by_day(monday, monday + 3 * DAY).unwrap();
+    assert_eq!(
+        days,
+        vec![(monday, 2), (monday + DAY, 1), (monday + 2 * DAY, 3)]
+    );
+
+    // The window bounds filter documents, not whole days
+    let days = repo.count_by_day(monday + 1, monday + 2 * DAY).unwrap();
+    assert_eq!(
+        days,
+        vec![(monday, 1), (monday + DAY, 1), (monday + 2 * DAY, 1)]
+    );
+}
+
+fn test_count_by_day_floors_timestamps_before_epoch(repo: &dyn DocumentRepository) {
+    save_created_at(repo, "before 1970", -1);
+
+    assert_eq!(repo.count_by_day(-100, 100).unwrap(), vec![(-86_400, 1)]);
+}
+
+fn save_expiring(repo: &dyn DocumentRepository, content: &str, expires_at: Option<i64>) {
+    let doc = Document::new(
+        content.as_bytes(),
+        "expiring.txt",
+        "text/plain",
+        "0x0000000000000000000000000000000000000123",
+    )
+    .with_expiry(expires_at);
+    repo.save_document(&doc).unwrap();
+}
+
+fn test_find_expired_includes_boundary(repo: &dyn DocumentRepository) {
+    save_expiring(repo, "later", Some(2_000));
+    save_expiring(repo, "at boundary", Some(1_000));
+    save_expiring(repo, "earlier", Some(999));
+    save_expiring(repo, "permanent", None);
+
+    let expired = repo.find_expired(1_000, 100, 0).unwrap();
+    let times: Vec<Option<i64>> = expired.iter().map(|d| d.expires_at).collect();
+    assert_eq!(times, vec![Some(999), Some(1_000)]);
+
+    // One second earlier the boundary document is still valid
+    let expired = repo.find_expired(999, 100, 0).unwrap();
+    assert_eq!(expired.len(), 1);
+    assert!(repo.find_expired(998, 100, 0).unwrap().is_empty());
+}
+
+fn test_find_expired_paginates(repo: &dyn DocumentRepository) {
+    for i in 0..5 {
+        save_expiring(repo, &format!("doc {}", i), Some(100 + i));
+    }
+
+    let page = repo.find_expired(1_000, 2, 2).unwrap();
+    let times: Vec<Option<i64>> = page.iter().map(|d| d.expires_at).collect();
+    assert_eq!(times, vec![Some(102), Some(103)]);
+}
+
+fn test_find_versions_unknown_root_is_empty(repo: &dyn DocumentRepository) {
+    assert!(repo
+        .find_versions(&ContentHash::from("0".repeat(64).as_str()))
+        .unwrap()
+        .is_empty());
+}
+
+fn test_find_versions_stops_on_circular_chain(repo: &dyn DocumentRepository) {
+    let docs: Vec<Document> = ["a", "b", "c"]
+        .iter()
+        .map(|content| {
+            Document::new(
+                content.as_bytes(),
+                "version.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+            )
+        })
+        .collect();
+
+    // a <- b <- c <- a, which the use case never produces
+    for (i, doc) in docs.iter().enumerate() {
+        let previous = &docs[(i + docs.len() - 1) % docs.len()];
+        repo.save_document(
+            &doc.clone()
+                .with_supersedes(Some(previous.content_hash.clone().into())),
+        )
+        .unwrap();
+    }
+
+    let versions = repo.find_versions(&docs[0].content_hash).unwrap();
+    assert_eq!(versions.len(), 3);
+}
+
+fn test_upload_chunks_are_concatenated_in_index_order(repo: &dyn DocumentRepository) {
+    let session = UploadSession {
+        upload_id: "upload-1".to_string(),
+        submitted_by: "0x0000000000000000000000000000000000000123".to_string(),
+        file_name: "scan.pdf".to_string(),
+        mime_type: "application/pdf".to_string(),
+        started_at: 1_700_000_000,
+        chunk_count: 0,
+        total_bytes: 0,
+    };
+    repo.start_upload(&session).unwrap();
+
+    // Stored out of order; the index decides the position
+    repo.append_chunk("upload-1", 1, b"world").unwrap();
+    repo.append_chunk("upload-1", 0, b"hello ").unwrap();
+
+    let found = repo.find_upload("upload-1").unwrap().unwrap();
+    assert_eq!(found.chunk_count, 2);
+    assert_eq!(found.total_bytes, 11);
+    assert_eq!(repo.upload_content("upload-1").unwrap(), b"hello world");
+
+    assert!(repo.start_upload(&session).is_err());
+    assert!(repo.append_chunk("upload-1", 0, b"again").is_err());
+
+    repo.delete_upload("upload-1").unwrap();
+    assert!(repo.find_upload("upload-1").unwrap().is_none());
+    assert!(repo.upload_content("upload-1").unwrap().is_empty());
+}
+
+fn test_balances_credit_and_debit(repo: &dyn DocumentRepository) {
+    let owner = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    let token = "0x00000000000000000000000000000000000000f0";
+
+    assert_eq!(repo.find_balance(owner, token).unwrap(), 0);
+
+    // Amounts beyond i64 survive the round trip
+    let large = u128::from(u64::MAX) * 4;
+    assert_eq!(repo.credit_balance(owner, token, large).unwrap(), large);
+    assert_eq!(repo.credit_balance(owner, token, 1).unwrap(), large + 1);
+    assert_eq!(
+        repo.find_balance(&owner.to_lowercase(), token).unwrap(),
+        large + 1
+    );
+
+    assert!(repo.debit_balance(owner, token, large).unwrap());
+    assert!(!repo.debit_balance(owner, token, 2).unwrap());
+    assert_eq!(repo.find_balance(owner, token).unwrap(), 1);
+
+    // Balances are kept per token
+    assert_eq!(repo.find_balance(owner, &"0".repeat(40)).unwrap(), 0);
+
+    assert!(matches!(
+        repo.credit_balance(owner, token, u128::MAX),
+        Err(DatabaseError::BalanceOverflow)
+    ));
+    assert_eq!(repo.find_balance(owner, token).unwrap(), 1);
+}
+
+repository_tests! {
+    test_init_database_creates_schema,
+    test_save_document_persists,
+    test_find_by_hash_not_found,
+    test_duplicate_hash_constraint,
+    test_find_by_id,
+    test_multiple_documents,
+    test_document_count,
+    test_expires_at_persists,
+    test_detected_mime_type_persists_beside_declared_type,
+    test_find_by_cid,
+    test_find_by_blake2b_hash,
+    test_find_by_input,
+    test_find_by_keccak,
+    test_description_round_trips,
+    test_list_audit_entries_pages_by_offset,
+    test_update_metadata_keeps_hash_and_id,
+    test_document_metadata_round_trip,
+    test_delete_by_hash_removes_document,
+    test_count_by_submitter_with_no_documents,
+    test_submitter_counts_with_multiple_submitters,
+    test_top_submitters_and_recent_counts,
+    test_find_many_by_hashes_all_found,
+    test_find_many_by_hashes_partial_found,
+    test_find_many_by_hashes_all_missing,
+    test_content_is_stored_and_purged_with_document,
+    test_hash_only_document_has_no_content,
+    test_save_documents_atomic_saves_all,
+    test_save_documents_atomic_rolls_back_on_duplicate,
+    test_save_documents_atomic_rejects_duplicate_within_batch,
+    test_repeated_lookups_with_cached_statements,
+    test_duplicate_detected_with_cached_insert,
+    test_full_text_search_follows_metadata_changes,
+    test_full_text_search_tolerates_query_syntax,
+    test_full_text_search_matches_tags,
+    test_full_text_search_ranks_by_relevance,
+    test_tags_are_removed_with_document,
+    test_find_all_by_content_hash_lists_aliases_in_order,
+    test_find_by_date_range_is_inclusive,
+    test_find_by_date_range_paginates,
+    test_list_documents_orders_by_key_and_resumes_after_cursor,
+    test_list_all_orders_oldest_first_and_skips_offset,
+    test_count_by_day_buckets_utc_days,
+    test_count_by_day_floors_timestamps_before_epoch,
+    test_find_expired_includes_boundary,
+    test_find_expired_paginates,
+    test_find_versions_unknown_root_is_empty,
+    test_find_versions_stops_on_circular_chain,
+    test_upload_chunks_are_concatenated_in_index_order,
+    test_balances_credit_and_debit,
+}
@@ -1,4 +1,9 @@
-use dapp::domain::{Document, NotarizationReceipt};
+use dapp::domain::{
+    compute_blake2b, compute_cid, detect_mime, verify_ots_proof, AddressError, Attestation, Clock,
+    Deposit, DepositError, Document, EthAddress, FixedClock, HashAlgorithm, NotarizationReceipt,
+    OpenTimestampsProof, OtsError, VerifiableCredential, CARTESI_ATTESTATION_TAG, ETHER_TOKEN,
+    OTS_HEADER_MAGIC, W3C_CREDENTIALS_CONTEXT,
+};
 
 #[cfg(test)]
 mod document_tests {
@@ -7,18 +12,37 @@ mod document_tests {
     #[test]
     fn test_document_generates_sha256_hash() {
         let content = b"test content";
-        let doc = Document::new(content, "test.txt", "text/plain", "0x123");
+        let doc = Document::new(
+            content,
+            "test.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
 
         // SHA-256 produces 64 hex characters
-        assert_eq!(doc.content_hash.len(), 64);
-        assert!(doc.content_hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(doc.content_hash.as_str().len(), 64);
+        assert!(doc
+            .content_hash
+            .as_str()
+            .chars()
+            .all(|c| c.is_ascii_hexdigit()));
     }
 
     #[test]
     fn test_document_hash_is_deterministic() {
         let content = b"same content";
-        let doc1 = Document::new(content, "file1.txt", "text/plain", "0x123");
-        let doc2 = Document::new(content, "file2.txt", "text/plain", "0x456");
+        let doc1 = Document::new(
+            content,
+            "file1.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
+        let doc2 = Document::new(
+            content,
+            "file2.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000456",
+        );
 
         // Same content should produce same hash regardless of other fields
         assert_eq!(doc1.content_hash, doc2.content_hash);
@@ -26,8 +50,18 @@ mod document_tests {
 
     #[test]
     fn test_document_different_content_different_hash() {
-        let doc1 = Document::new(b"content one", "file.txt", "text/plain", "0x123");
-        let doc2 = Document::new(b"content two", "file.txt", "text/plain", "0x123");
+        let doc1 = Document::new(
+            b"content one",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
+        let doc2 = Document::new(
+            b"content two",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
 
         // Different content should produce different hashes
         assert_ne!(doc1.content_hash, doc2.content_hash);
@@ -36,8 +70,18 @@ mod document_tests {
     #[test]
     fn test_document_generates_unique_id() {
         let content = b"test";
-        let doc1 = Document::new(content, "file.txt", "text/plain", "0x123");
-        let doc2 = Document::new(content, "file.txt", "text/plain", "0x123");
+        let doc1 = Document::new(
+            content,
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
+        let doc2 = Document::new(
+            content,
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
 
         // Each document should get a unique UUID
         assert_ne!(doc1.id, doc2.id);
@@ -45,13 +89,26 @@ mod document_tests {
 
     #[test]
     fn test_document_timestamp_is_set() {
-        let doc = Document::new(b"test", "file.txt", "text/plain", "0x123");
+        let doc = Document::new_with_clock(
+            b"test",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            &FixedClock(1_700_000_000),
+        );
 
-        // Timestamp should be set to current time (reasonable range)
-        let now = chrono::Utc::now().timestamp();
-        assert!(doc.created_at > 0);
-        assert!(doc.created_at <= now);
-        assert!((now - doc.created_at) < 2); // Within 2 seconds
+        assert_eq!(doc.created_at, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_fixed_clock_does_not_advance() {
+        let clock = FixedClock(1_234_567_890);
+        let first = Document::new_with_clock(b"first", "a.txt", "text/plain", "0xA", &clock);
+        let second = Document::new_with_clock(b"second", "b.txt", "text/plain", "0xB", &clock);
+
+        assert_eq!(first.created_at, 1_234_567_890);
+        assert_eq!(second.created_at, first.created_at);
+        assert_eq!(clock.now(), 1_234_567_890);
     }
 
     #[test]
@@ -64,6 +121,274 @@ mod document_tests {
     }
 }
 
+#[cfg(test)]
+mod ipfs_cid_tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_cid_matches_known_values() {
+        // CIDs reported by `ipfs add --cid-version 1 --raw-leaves` for the same bytes
+        assert_eq!(
+            compute_cid(b"hello world"),
+            "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+        );
+        assert_eq!(
+            compute_cid(b""),
+            "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku"
+        );
+        // `echo "hello world" | ipfs add --cid-version 1 --raw-leaves`
+        assert_eq!(
+            compute_cid(b"hello world\n"),
+            "bafkreifjjcie6lypi6ny7amxnfftagclbuxndqonfipmb64f2km2devei4"
+        );
+    }
+
+    #[test]
+    fn test_cid_matches_reference_encoding() {
+        // Rebuild each CID with an independent base32 encoder: CIDv1, raw codec,
+        // sha2-256 multihash, then RFC 4648 base32 in lowercase without padding
+        for length in 0..=200 {
+            let content: Vec<u8> = (0..length).map(|i| (i * 7 + length) as u8).collect();
+
+            let mut cid = vec![0x01, 0x55, 0x12, 0x20];
+            cid.extend_from_slice(&Sha256::digest(&content));
+            let expected = format!(
+                "b{}",
+                data_encoding::BASE32_NOPAD.encode(&cid).to_lowercase()
+            );
+
+            assert_eq!(
+                compute_cid(&content),
+                expected,
+                "content of {} bytes",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn test_cid_is_off_by_default() {
+        let doc = Document::new(b"hello world", "hello.txt", "text/plain", "0xABCD");
+        assert!(doc.ipfs_cid.is_none());
+    }
+}
+
+#[cfg(test)]
+mod keccak_hash_tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak_matches_known_values() {
+        assert_eq!(
+            Document::hash_content_keccak(b"hello world"),
+            "47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+        );
+        assert_eq!(
+            Document::hash_content_keccak(b""),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_document_records_both_digests() {
+        let doc = Document::new(b"hello world", "hello.txt", "text/plain", "0xABCD");
+
+        assert_eq!(
+            doc.content_hash,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_eq!(
+            doc.content_hash_keccak.as_deref(),
+            Some("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad")
+        );
+    }
+}
+
+#[cfg(test)]
+mod blake2b_hash_tests {
+    use super::*;
+
+    #[test]
+    fn test_blake2b_matches_known_values() {
+        assert_eq!(
+            compute_blake2b(b""),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        assert_eq!(
+            compute_blake2b(b"abc"),
+            "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319"
+        );
+    }
+
+    #[test]
+    fn test_blake2b_across_block_boundary() {
+        // Exactly one 128-byte block, then one byte into a second block
+        assert_eq!(
+            compute_blake2b(&[b'a'; 128]),
+            "ae2aa48507885c4c950fb809b2076f959cde9f8ea6da260d9a3587df33dac450"
+        );
+        assert_eq!(
+            compute_blake2b(&[b'a'; 129]),
+            "2f64744a6de0d2c0b56e64cf6e29a5aaa255010d415d51c75ccc82f73dccd865"
+        );
+    }
+
+    #[test]
+    fn test_document_has_no_blake2b_hash_by_default() {
+        let doc = Document::new(b"hello world", "hello.txt", "text/plain", "0xABCD");
+        assert!(doc.blake2b_hash.is_none());
+
+        let doc = doc.with_blake2b_hash(Some(compute_blake2b(b"hello world")));
+        assert_eq!(
+            doc.blake2b_hash.as_deref(),
+            Some("256c83b297114d201b30179f3f0ef0cace9783622da5974326b436178aeef610")
+        );
+    }
+
+    #[test]
+    fn test_verify_content_matches_original() {
+        let doc = Document::new(b"signed contract", "c.pdf", "application/pdf", "0xABCD");
+
+        assert!(doc.verify_content(b"signed contract", HashAlgorithm::Sha256));
+        assert!(doc.verify_content(b"signed contract", HashAlgorithm::Keccak256));
+    }
+
+    #[test]
+    fn test_verify_content_rejects_tampered_content() {
+        let doc = Document::new(b"signed contract", "c.pdf", "application/pdf", "0xABCD");
+
+        assert!(!doc.verify_content(b"signed contracT", HashAlgorithm::Sha256));
+        assert!(!doc.verify_content(b"", HashAlgorithm::Keccak256));
+    }
+
+    #[test]
+    fn test_verify_content_without_recorded_digest() {
+        let mut doc = Document::new(b"legacy", "old.txt", "text/plain", "0xABCD");
+        doc.content_hash_keccak = None;
+
+        assert!(doc.verify_content(b"legacy", HashAlgorithm::Sha256));
+        assert!(!doc.verify_content(b"legacy", HashAlgorithm::Keccak256));
+    }
+}
+
+#[cfg(test)]
+mod document_equality_tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, RandomState};
+
+    fn doc(content: &[u8], file_name: &str) -> Document {
+        Document::new(content, file_name, "text/plain", "0xABCD")
+    }
+
+    #[test]
+    fn test_equality_follows_content_hash() {
+        let (a, b, c) = (
+            doc(b"same", "a.txt"),
+            doc(b"same", "b.txt"),
+            doc(b"same", "c.txt"),
+        );
+        let other = doc(b"other", "a.txt");
+
+        // Reflexive, symmetric and transitive, despite distinct ids and names
+        assert_ne!(a.id, b.id);
+        assert_eq!(a, a.clone());
+        assert_eq!(a, b);
+        assert_eq!(b, a);
+        assert_eq!(b, c);
+        assert_eq!(a, c);
+        assert_ne!(a, other);
+    }
+
+    #[test]
+    fn test_equal_documents_hash_equally() {
+        let state = RandomState::new();
+        let (a, b) = (doc(b"same", "a.txt"), doc(b"same", "b.txt"));
+
+        assert_eq!(state.hash_one(&a), state.hash_one(&b));
+
+        let set: HashSet<Document> = [a.clone(), b, doc(b"other", "c.txt")].into();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.get(&a.content_hash).unwrap().file_name, "a.txt");
+    }
+
+    #[test]
+    fn test_cmp_by_creation_orders_by_time_then_id() {
+        let mut late = doc(b"late", "late.txt");
+        late.created_at = 200;
+        let mut early = doc(b"early", "early.txt");
+        early.created_at = 100;
+        let mut tie = doc(b"tie", "tie.txt");
+        tie.created_at = 100;
+
+        let mut docs = [late, early, tie];
+        docs.sort_by(Document::cmp_by_creation);
+
+        assert_eq!(docs[2].file_name, "late.txt");
+        assert!(docs[0].id < docs[1].id);
+    }
+}
+
+#[cfg(test)]
+mod hash_property_tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn hash_of(content: &[u8]) -> String {
+        Document::new(content, "file.bin", "application/octet-stream", SUBMITTER)
+            .content_hash
+            .into_inner()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(10_000))]
+
+        #[test]
+        fn prop_hash_is_deterministic(content in vec(any::<u8>(), 0..1024)) {
+            prop_assert_eq!(hash_of(&content), hash_of(&content));
+        }
+
+        #[test]
+        fn prop_hash_is_64_hex_characters(content in vec(any::<u8>(), 0..1024)) {
+            let hash = hash_of(&content);
+            prop_assert_eq!(hash.len(), 64);
+            prop_assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        }
+
+        #[test]
+        fn prop_distinct_content_has_distinct_hashes(
+            a in vec(any::<u8>(), 1..256),
+            b in vec(any::<u8>(), 1..256),
+        ) {
+            prop_assume!(a != b);
+            prop_assert_ne!(hash_of(&a), hash_of(&b));
+        }
+
+        #[test]
+        fn prop_new_receipts_verify_their_proof(
+            content in vec(any::<u8>(), 0..256),
+            document_id in "[0-9a-f-]{36}",
+            notarized_at in any::<i64>(),
+            block_number in any::<u64>(),
+        ) {
+            let content_hash = hash_of(&content);
+            let receipt =
+                NotarizationReceipt::new(document_id, content_hash, notarized_at, block_number);
+            prop_assert!(receipt.verify_proof());
+
+            let tampered = NotarizationReceipt {
+                notarized_at: notarized_at.wrapping_add(1),
+                ..receipt
+            };
+            prop_assert!(!tampered.verify_proof());
+        }
+    }
+}
+
 #[cfg(test)]
 mod receipt_tests {
     use super::*;
@@ -76,6 +401,7 @@ mod receipt_tests {
             notarized_at: 1234567890,
             block_number: 12345,
             proof: format!("sha256:{}@{}", "abcd1234", 1234567890),
+            committed_at: None,
         };
 
         assert_eq!(receipt.proof, "sha256:abcd1234@1234567890");
@@ -89,6 +415,7 @@ mod receipt_tests {
             notarized_at: 9999,
             block_number: 100,
             proof: "proof".to_string(),
+            committed_at: None,
         };
 
         assert_eq!(receipt.document_id, "doc-123");
@@ -97,4 +424,625 @@ mod receipt_tests {
         assert_eq!(receipt.block_number, 100);
         assert!(!receipt.proof.is_empty());
     }
+
+    fn typical_receipt() -> NotarizationReceipt {
+        NotarizationReceipt::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            Document::hash_content(b"typical document").into_inner(),
+            1_735_862_400,
+            12_345,
+        )
+    }
+
+    #[test]
+    fn test_receipt_cbor_round_trip() {
+        let receipt = typical_receipt();
+
+        let decoded = NotarizationReceipt::from_cbor(&receipt.to_cbor()).unwrap();
+        assert_eq!(decoded, receipt);
+        assert_eq!(decoded.to_cbor(), receipt.to_cbor());
+    }
+
+    #[test]
+    fn test_receipt_cbor_is_smaller_than_json() {
+        let receipt = typical_receipt();
+        let json = serde_json::to_vec(&receipt).unwrap();
+        let cbor = receipt.to_cbor();
+
+        assert!(cbor.len() < json.len(), "{} >= {}", cbor.len(), json.len());
+    }
+
+    #[test]
+    fn test_receipt_from_invalid_cbor_fails() {
+        let err = NotarizationReceipt::from_cbor(b"{\"document_id\":1}").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid CBOR receipt"));
+
+        let cbor = typical_receipt().to_cbor();
+        assert!(NotarizationReceipt::from_cbor(&cbor[..cbor.len() - 1]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn test_document_timestamps_serialize_as_rfc3339() {
+        let doc = Document::new_at(b"dated", "dated.txt", "text/plain", "0x123", 1_705_322_096)
+            .with_expiry(Some(1_736_944_496));
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["created_at"], "2024-01-15T12:34:56Z");
+        assert_eq!(json["expires_at"], "2025-01-15T12:34:56Z");
+
+        let created_at = json["created_at"].as_str().unwrap();
+        let parsed = chrono::DateTime::parse_from_rfc3339(created_at).unwrap();
+        assert_eq!(parsed.timestamp(), doc.created_at_unix());
+
+        let decoded: Document = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.created_at, doc.created_at);
+        assert_eq!(decoded.expires_at, doc.expires_at);
+    }
+
+    #[test]
+    fn test_receipt_timestamp_serializes_as_rfc3339() {
+        let receipt = NotarizationReceipt::new("id".into(), "hash".into(), 1_705_322_096, 1);
+
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["notarized_at"], "2024-01-15T12:34:56Z");
+
+        let decoded: NotarizationReceipt = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, receipt);
+    }
+
+    #[test]
+    fn test_unix_timestamps_still_deserialize() {
+        let json = serde_json::json!({
+            "document_id": "id",
+            "content_hash": "hash",
+            "notarized_at": 1_705_322_096,
+            "block_number": 1,
+            "proof": "sha256:hash@1705322096"
+        });
+        let receipt: NotarizationReceipt = serde_json::from_value(json).unwrap();
+        assert_eq!(receipt.notarized_at, 1_705_322_096);
+        assert!(receipt.verify_proof());
+
+        let json = serde_json::json!({
+            "document_id": "id",
+            "content_hash": "hash",
+            "notarized_at": "yesterday",
+            "block_number": 1,
+            "proof": "proof"
+        });
+        assert!(serde_json::from_value::<NotarizationReceipt>(json).is_err());
+    }
+}
+
+#[cfg(test)]
+mod ots_tests {
+    use super::*;
+
+    fn receipt(block_number: u64) -> NotarizationReceipt {
+        let hash = Document::hash_content(b"hello world");
+        NotarizationReceipt::new(
+            "doc-id".to_string(),
+            hash.into_inner(),
+            1_700_000_000,
+            block_number,
+        )
+    }
+
+    #[test]
+    fn test_receipt_ots_proof_carries_hash_and_block() {
+        let proof = receipt(300).to_ots_proof().unwrap();
+
+        assert_eq!(proof.hash_algorithm, HashAlgorithm::Sha256);
+        assert_eq!(
+            hex::encode(&proof.digest),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_eq!(
+            proof.attestation,
+            Attestation::Cartesi { block_number: 300 }
+        );
+    }
+
+    #[test]
+    fn test_serialized_layout() {
+        let bytes = receipt(300).to_ots_proof().unwrap().serialize();
+
+        let (header, rest) = bytes.split_at(OTS_HEADER_MAGIC.len());
+        assert_eq!(header, OTS_HEADER_MAGIC);
+        // Version 1, then the sha256 op and its digest
+        assert_eq!(rest[0], 0x01);
+        assert_eq!(rest[1], 0x08);
+        let attestation = &rest[2 + 32..];
+        assert_eq!(attestation[0], 0x00);
+        assert_eq!(attestation[1..9], CARTESI_ATTESTATION_TAG);
+        // Block 300 as a two-byte varuint, length-prefixed
+        assert_eq!(attestation[9..], [0x02, 0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_round_trip_is_idempotent() {
+        for block_number in [0, 1, 127, 128, u64::MAX] {
+            let proof = receipt(block_number).to_ots_proof().unwrap();
+            let bytes = proof.serialize();
+
+            let decoded = OpenTimestampsProof::deserialize(&bytes).unwrap();
+            assert_eq!(decoded, proof);
+            assert_eq!(decoded.serialize(), bytes);
+            assert!(verify_ots_proof(&bytes));
+        }
+    }
+
+    #[test]
+    fn test_malformed_proofs_fail_verification() {
+        let bytes = receipt(300).to_ots_proof().unwrap().serialize();
+
+        assert!(!verify_ots_proof(&bytes[..bytes.len() - 1]));
+        assert!(!verify_ots_proof(&[bytes.as_slice(), &[0x00]].concat()));
+        assert!(!verify_ots_proof(b"not a proof"));
+        assert!(!verify_ots_proof(&[]));
+
+        let mut wrong_op = bytes.clone();
+        wrong_op[OTS_HEADER_MAGIC.len() + 1] = 0x02;
+        assert_eq!(
+            OpenTimestampsProof::deserialize(&wrong_op),
+            Err(OtsError::UnsupportedHashAlgorithm(0x02))
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_encoding_fails_verification() {
+        let proof = receipt(1).to_ots_proof().unwrap();
+        let mut bytes = proof.serialize();
+        // Re-encode block 1 as the padded varuint 0x81 0x00
+        let len = bytes.len();
+        bytes.truncate(len - 2);
+        bytes.extend_from_slice(&[0x02, 0x81, 0x00]);
+
+        assert_eq!(OpenTimestampsProof::deserialize(&bytes).unwrap(), proof);
+        assert!(!verify_ots_proof(&bytes));
+    }
+
+    #[test]
+    fn test_non_hex_receipt_hash_is_rejected() {
+        let receipt = NotarizationReceipt::new("id".to_string(), "xyz".to_string(), 0, 1);
+        assert_eq!(receipt.to_ots_proof(), Err(OtsError::NonHexDigest));
+
+        let receipt = NotarizationReceipt::new("id".to_string(), "abcd".to_string(), 0, 1);
+        assert_eq!(
+            receipt.to_ots_proof(),
+            Err(OtsError::InvalidDigest {
+                expected: 32,
+                actual: 2
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod verifiable_credential_tests {
+    use super::*;
+
+    fn receipt_vc() -> serde_json::Value {
+        let receipt = NotarizationReceipt::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "a".repeat(64),
+            1_700_000_000,
+            42,
+        );
+        let vc = receipt.to_verifiable_credential("did:web:notary.example", "deed.pdf");
+        serde_json::to_value(vc).unwrap()
+    }
+
+    #[test]
+    fn test_vc_lists_w3c_context_first() {
+        let vc = receipt_vc();
+
+        assert_eq!(vc["@context"][0], W3C_CREDENTIALS_CONTEXT);
+        assert_eq!(
+            vc["type"],
+            serde_json::json!(["VerifiableCredential", "NotaryCredential"])
+        );
+        assert_eq!(vc["issuer"], "did:web:notary.example");
+    }
+
+    #[test]
+    fn test_vc_issuance_date_is_iso_8601_utc() {
+        let vc = receipt_vc();
+        let issued = vc["issuanceDate"].as_str().unwrap();
+
+        assert_eq!(issued, "2023-11-14T22:13:20Z");
+        let parsed = chrono::DateTime::parse_from_rfc3339(issued).unwrap();
+        assert_eq!(parsed.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_vc_subject_carries_hash_and_filename() {
+        let vc = receipt_vc();
+        let subject = &vc["credentialSubject"];
+
+        assert_eq!(
+            subject["id"],
+            "urn:uuid:550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(subject["contentHash"], "a".repeat(64));
+        assert_eq!(subject["fileName"], "deed.pdf");
+    }
+
+    #[test]
+    fn test_vc_for_document_uses_notarization_time() {
+        let document = Document::new(
+            b"deed",
+            "deed.pdf",
+            "application/pdf",
+            "0x0000000000000000000000000000000000000123",
+        );
+        let vc = VerifiableCredential::for_document("did:web:notary.example", &document);
+
+        let issued = chrono::DateTime::parse_from_rfc3339(&vc.issuance_date).unwrap();
+        assert_eq!(issued.timestamp(), document.created_at);
+        assert_eq!(document.content_hash, vc.credential_subject.content_hash);
+    }
+}
+
+#[cfg(test)]
+mod signing_tests {
+    use super::*;
+    use dapp::application::NoticeResponse;
+    use dapp::infrastructure::signing::{
+        canonical_receipt_json, verify_receipt_signature, NoticeSigningKey,
+    };
+
+    const KEY: &[u8] = b"notary-key";
+
+    fn receipt() -> NotarizationReceipt {
+        NotarizationReceipt::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "a591a6d40bf420404a011733cfb7b190d62c65bf0bcda32b57b277d9ad9f146e".to_string(),
+            1735862400,
+            12345,
+        )
+    }
+
+    #[test]
+    fn test_signature_is_stable() {
+        // Fixed expected value: any change to the canonical form breaks existing clients
+        let signature = NoticeSigningKey::new(KEY).sign_receipt(&receipt());
+        assert_eq!(
+            signature,
+            "17308aa5f51e5394a93616fbcfa2697bfa6c862b5cde1ab2ecd02539c54b08cc"
+        );
+        assert_eq!(
+            NoticeSigningKey::new(KEY).sign_receipt(&receipt()),
+            signature
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let json = canonical_receipt_json(&receipt());
+        assert!(json.starts_with(r#"{"block_number":12345,"content_hash":"#));
+        assert!(json.ends_with(r#"@1735862400"}"#));
+        assert!(!json.contains(' '));
+
+        let committed = canonical_receipt_json(&receipt().with_committed_at(Some(12000)));
+        assert!(
+            committed.starts_with(r#"{"block_number":12345,"committed_at":12000,"content_hash":"#)
+        );
+    }
+
+    #[test]
+    fn test_signature_verifies() {
+        let signature = NoticeSigningKey::new(KEY).sign_receipt(&receipt());
+        assert!(verify_receipt_signature(&receipt(), KEY, &signature));
+        assert!(!verify_receipt_signature(
+            &receipt(),
+            b"other-key",
+            &signature
+        ));
+        assert!(!verify_receipt_signature(&receipt(), KEY, "not hex"));
+        assert!(!verify_receipt_signature(&receipt(), KEY, &signature[..62]));
+    }
+
+    #[test]
+    fn test_tampered_receipt_fails_verification() {
+        let signature = NoticeSigningKey::new(KEY).sign_receipt(&receipt());
+
+        let tampered: Vec<NotarizationReceipt> = vec![
+            NotarizationReceipt {
+                document_id: "00000000-0000-0000-0000-000000000000".to_string(),
+                ..receipt()
+            },
+            NotarizationReceipt {
+                content_hash: "b".repeat(64),
+                ..receipt()
+            },
+            NotarizationReceipt {
+                notarized_at: 1735862401,
+                ..receipt()
+            },
+            NotarizationReceipt {
+                block_number: 12346,
+                ..receipt()
+            },
+            NotarizationReceipt {
+                proof: "sha256:forged".to_string(),
+                ..receipt()
+            },
+            receipt().with_committed_at(Some(12000)),
+        ];
+
+        for receipt in tampered {
+            assert!(!verify_receipt_signature(&receipt, KEY, &signature));
+        }
+    }
+
+    #[test]
+    fn test_notice_unsigned_without_key() {
+        let notice = NoticeResponse::notarization(receipt()).signed(None);
+        let json = serde_json::to_value(&notice).unwrap();
+        assert!(json.get("signature").is_none());
+
+        let key = NoticeSigningKey::new(KEY);
+        let notice = NoticeResponse::notarization(receipt()).signed(Some(&key));
+        let json = serde_json::to_value(&notice).unwrap();
+        assert_eq!(json["signature"], key.sign_receipt(&receipt()));
+    }
+}
+
+#[cfg(test)]
+mod eth_address_tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_checksummed_addresses() {
+        // EIP-55 reference vectors
+        for address in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let parsed = EthAddress::parse(address).unwrap();
+            assert_eq!(parsed.to_checksum(), address);
+        }
+    }
+
+    #[test]
+    fn test_valid_lowercase_and_uppercase_addresses() {
+        let lower = EthAddress::parse("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        let upper = EthAddress::parse("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap();
+
+        assert_eq!(lower, upper);
+        assert_eq!(lower.as_str(), "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        assert_eq!(
+            EthAddress::parse("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            Err(AddressError::MissingPrefix)
+        );
+        assert_eq!(
+            EthAddress::parse("0x123"),
+            Err(AddressError::InvalidLength(3))
+        );
+        assert_eq!(EthAddress::parse(""), Err(AddressError::MissingPrefix));
+        assert_eq!(
+            EthAddress::parse("0xZZaeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            Err(AddressError::InvalidHex)
+        );
+
+        // One letter's case flipped breaks the checksum
+        assert_eq!(
+            EthAddress::parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(AddressError::InvalidChecksum)
+        );
+    }
+}
+
+#[cfg(test)]
+mod wallet_signature_tests {
+    use super::*;
+    use dapp::domain::{recover_personal_signer, SignatureError};
+
+    // `web3.eth.accounts.sign("Some data", key)` example from the web3.js documentation
+    const MESSAGE: &str = "Some data";
+    const SIGNATURE: &str = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+    const SIGNER: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+    #[test]
+    fn test_recovers_personal_sign_signer() {
+        let signer = recover_personal_signer(MESSAGE, SIGNATURE).unwrap();
+        assert_eq!(signer, EthAddress::parse(SIGNER).unwrap());
+
+        // The raw recovery id works as well as the 27/28 form
+        let raw_v = format!("{}01", &SIGNATURE[..SIGNATURE.len() - 2]);
+        assert_eq!(recover_personal_signer(MESSAGE, &raw_v).unwrap(), signer);
+    }
+
+    #[test]
+    fn test_other_message_recovers_other_signer() {
+        let signer = recover_personal_signer("Other data", SIGNATURE).unwrap();
+        assert_ne!(signer, EthAddress::parse(SIGNER).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_signatures() {
+        assert_eq!(
+            recover_personal_signer(MESSAGE, "0xzz"),
+            Err(SignatureError::InvalidHex)
+        );
+        assert_eq!(
+            recover_personal_signer(MESSAGE, &SIGNATURE[..SIGNATURE.len() - 2]),
+            Err(SignatureError::InvalidLength(64))
+        );
+        let bad_v = format!("{}05", &SIGNATURE[..SIGNATURE.len() - 2]);
+        assert_eq!(
+            recover_personal_signer(MESSAGE, &bad_v),
+            Err(SignatureError::InvalidRecoveryId(5))
+        );
+    }
+}
+
+#[cfg(test)]
+mod mime_detection_tests {
+    use super::*;
+
+    /// Minimal ZIP local file header naming `entry`
+    fn zip_with_entry(entry: &[u8]) -> Vec<u8> {
+        let mut zip = b"PK\x03\x04\x14\x00\x00\x00\x08\x00".to_vec();
+        zip.extend_from_slice(&[0; 16]);
+        zip.extend_from_slice(&(entry.len() as u16).to_le_bytes());
+        zip.extend_from_slice(&[0, 0]);
+        zip.extend_from_slice(entry);
+        zip
+    }
+
+    #[test]
+    fn test_detects_pdf() {
+        assert_eq!(
+            detect_mime(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n"),
+            Some("application/pdf")
+        );
+    }
+
+    #[test]
+    fn test_detects_images() {
+        assert_eq!(
+            detect_mime(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_mime(b"\xff\xd8\xff\xe0\x00\x10JFIF"),
+            Some("image/jpeg")
+        );
+        assert_eq!(detect_mime(b"GIF89a\x01\x00\x01\x00"), Some("image/gif"));
+        assert_eq!(
+            detect_mime(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+    }
+
+    #[test]
+    fn test_detects_office_documents_inside_zip() {
+        assert_eq!(
+            detect_mime(&zip_with_entry(b"word/document.xml")),
+            Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        );
+        assert_eq!(
+            detect_mime(&zip_with_entry(b"xl/workbook.xml")),
+            Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        );
+        assert_eq!(
+            detect_mime(&zip_with_entry(b"ppt/presentation.xml")),
+            Some("application/vnd.openxmlformats-officedocument.presentationml.presentation")
+        );
+        assert_eq!(
+            detect_mime(&zip_with_entry(b"photos/1.jpg")),
+            Some("application/zip")
+        );
+    }
+
+    #[test]
+    fn test_detects_utf8_text() {
+        assert_eq!(
+            detect_mime(b"Hello, notary!\r\n\tIndented"),
+            Some("text/plain")
+        );
+        assert_eq!(detect_mime("Grüße, 公証人".as_bytes()), Some("text/plain"));
+    }
+
+    #[test]
+    fn test_text_cut_inside_multibyte_character() {
+        // "é" straddles the 8 KiB sniffing window
+        let mut content = vec![b'a'; 8191];
+        content.extend_from_slice("é".as_bytes());
+        assert_eq!(detect_mime(&content), Some("text/plain"));
+    }
+
+    #[test]
+    fn test_unknown_content_is_not_detected() {
+        assert_eq!(detect_mime(b""), None);
+        assert_eq!(detect_mime(b"\x00\x01\x02binary"), None);
+        assert_eq!(detect_mime(b"\xc3\x28 invalid utf-8"), None);
+    }
+
+    #[test]
+    fn test_document_records_detected_type() {
+        let doc = Document::new(
+            b"\x89PNG\r\n\x1a\nrest",
+            "scan.bin",
+            "application/octet-stream",
+            "0x0000000000000000000000000000000000000123",
+        );
+
+        assert_eq!(doc.mime_type, "application/octet-stream");
+        assert_eq!(doc.detected_mime_type.as_deref(), Some("image/png"));
+    }
+}
+
+#[cfg(test)]
+mod deposit_tests {
+    use super::*;
+
+    fn amount_word(amount: u128) -> Vec<u8> {
+        let mut word = vec![0u8; 16];
+        word.extend_from_slice(&amount.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_parses_erc20_portal_payload() {
+        let mut payload = vec![0x11; 20];
+        payload.extend_from_slice(&[0x22; 20]);
+        payload.extend_from_slice(&amount_word(1_000));
+        payload.extend_from_slice(b"exec layer data");
+
+        let deposit = Deposit::from_erc20_portal(&payload).unwrap();
+
+        assert_eq!(deposit.token.as_str(), format!("0x{}", "11".repeat(20)));
+        assert_eq!(deposit.depositor.as_str(), format!("0x{}", "22".repeat(20)));
+        assert_eq!(deposit.amount, 1_000);
+    }
+
+    #[test]
+    fn test_parses_ether_portal_payload() {
+        let mut payload = vec![0x22; 20];
+        payload.extend_from_slice(&amount_word(5));
+
+        let deposit = Deposit::from_ether_portal(&payload).unwrap();
+
+        assert_eq!(deposit.token.as_str(), ETHER_TOKEN);
+        assert_eq!(deposit.depositor.as_str(), format!("0x{}", "22".repeat(20)));
+        assert_eq!(deposit.amount, 5);
+    }
+
+    #[test]
+    fn test_rejects_truncated_payload() {
+        assert_eq!(
+            Deposit::from_erc20_portal(&[0u8; 71]),
+            Err(DepositError::TooShort {
+                expected: 72,
+                actual: 71
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_amount_above_u128() {
+        let mut payload = vec![0x11; 40];
+        let mut word = vec![0u8; 32];
+        word[15] = 1;
+        payload.extend_from_slice(&word);
+
+        assert_eq!(
+            Deposit::from_erc20_portal(&payload),
+            Err(DepositError::AmountTooLarge)
+        );
+    }
 }
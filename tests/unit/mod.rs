@@ -1,3 +1,6 @@
+#[path = "../common/mod.rs"]
+mod common;
+
 mod database_tests;
 mod domain_tests;
 mod usecase_tests;
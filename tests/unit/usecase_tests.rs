@@ -1,5 +1,18 @@
-use dapp::application::{NotarizeUseCase, VerifyUseCase};
-use dapp::infrastructure::database::SqliteRepository;
+use super::common::MockDocumentRepository;
+use dapp::application::{
+    AllowDenyList, AllowDenyMode, BalanceError, BalanceUseCase, ChunkedUploadUseCase,
+    CosignOutcome, CosignUseCase, DocumentCursor, EpochUseCase, FetchContentUseCase,
+    LruVerifyCache, MatchedHash, NonceUseCase, NotarizeConfig, NotarizeError, NotarizeOptions,
+    NotarizeRequestBuilder, NotarizeUseCase, RateLimitError, RateLimiter, SearchUseCase,
+    VerifyError, VerifyUseCase, DEFAULT_COSIGN_WINDOW_SECS, MAX_BATCH_VERIFY_SIZE,
+    MAX_DESCRIPTION_LENGTH, MAX_FILENAME_LENGTH, SECONDS_PER_BLOCK,
+};
+use dapp::domain::{
+    compute_blake2b, compute_cid, ContentHash, Deposit, Document, DocumentId, EthAddress,
+    FixedClock,
+};
+use dapp::infrastructure::database::DocumentRepository;
+use dapp::NotaryError;
 
 #[cfg(test)]
 mod notarize_tests {
@@ -7,14 +20,14 @@ mod notarize_tests {
 
     #[test]
     fn test_notarize_new_document_succeeds() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
 
         let result = usecase.execute(
             b"test content",
             "document.pdf",
             "application/pdf",
-            "0x1234567890abcdef",
+            "0x0000000000000000000000001234567890abcdef",
             12345,
         );
 
@@ -26,17 +39,121 @@ mod notarize_tests {
         assert!(receipt.proof.starts_with("sha256:"));
     }
 
+    #[test]
+    fn test_notarize_default_built_request_with_default_config() {
+        use base64::Engine;
+
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let request = NotarizeRequestBuilder::default()
+            .content(b"built with defaults")
+            .file_name("defaults.txt")
+            .build()
+            .unwrap();
+        assert!(request.validate().is_empty());
+
+        let content = base64::engine::general_purpose::STANDARD
+            .decode(&request.content)
+            .unwrap();
+        let receipt = usecase
+            .execute(
+                &content,
+                &request.file_name,
+                &request.mime_type,
+                "0x0000000000000000000000000000000000000123",
+                1,
+            )
+            .unwrap();
+
+        let stored = repo
+            .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+            .unwrap();
+        assert_eq!(stored.mime_type, NotarizeRequestBuilder::DEFAULT_MIME_TYPE);
+    }
+
+    #[test]
+    fn test_notarize_without_timestamp_reads_clock() {
+        let repo = MockDocumentRepository::new();
+        let clock = FixedClock(1_700_000_000);
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default()).with_clock(&clock);
+
+        let receipt = usecase
+            .execute(
+                b"clocked content",
+                "clocked.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                7,
+            )
+            .unwrap();
+
+        assert_eq!(receipt.notarized_at, 1_700_000_000);
+        assert_eq!(
+            receipt.proof,
+            format!("sha256:{}@1700000000", receipt.content_hash)
+        );
+        let stored = repo
+            .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+            .unwrap();
+        assert_eq!(stored.created_at, 1_700_000_000);
+
+        // A timestamp from the input still takes precedence over the clock
+        let options = NotarizeOptions {
+            notarized_at: Some(1_800_000_000),
+            ..NotarizeOptions::default()
+        };
+        let receipt = usecase
+            .execute_with_options(
+                b"block timestamped",
+                "block.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                8,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(receipt.notarized_at, 1_800_000_000);
+    }
+
+    #[test]
+    fn test_notarize_records_cid_when_enabled() {
+        let repo = MockDocumentRepository::new();
+        let submitter = "0x0000000000000000000000001234567890abcdef";
+
+        for (content, enabled) in [(b"with cid".as_slice(), true), (b"without cid", false)] {
+            let config = NotarizeConfig::default().with_generate_ipfs_cid(enabled);
+            NotarizeUseCase::new(&repo, config)
+                .execute(content, "doc.pdf", "application/pdf", submitter, 1)
+                .unwrap();
+
+            let doc = repo.find_by_hash(&Document::hash_content(content)).unwrap();
+            assert_eq!(doc.ipfs_cid, enabled.then(|| compute_cid(content)));
+        }
+    }
+
     #[test]
     fn test_notarize_duplicate_hash_fails() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
 
         // First notarization should succeed
-        let result1 = usecase.execute(b"same content", "file1.txt", "text/plain", "0x123", 100);
+        let result1 = usecase.execute(
+            b"same content",
+            "file1.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
         assert!(result1.is_ok());
 
         // Second notarization with same content should fail
-        let result2 = usecase.execute(b"same content", "file2.txt", "text/plain", "0x456", 101);
+        let result2 = usecase.execute(
+            b"same content",
+            "file2.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000456",
+            101,
+        );
         assert!(result2.is_err());
         let err_msg = result2.unwrap_err().to_string();
         assert!(
@@ -45,12 +162,136 @@ mod notarize_tests {
         );
     }
 
+    #[test]
+    fn test_notarize_reports_save_failure() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let submitter = "0x0000000000000000000000000000000000000123";
+
+        repo.fail_next_save.set(true);
+        let err = usecase
+            .execute(b"content", "a.txt", "text/plain", submitter, 100)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::DatabaseError(_))
+        ));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+
+        // Only the next save fails, so a retry goes through
+        assert!(usecase
+            .execute(b"content", "a.txt", "text/plain", submitter, 101)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_allow_alias_records_filename_and_returns_original_receipt() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let submitter = "0x0000000000000000000000000000000000000123";
+        let options = NotarizeOptions {
+            allow_alias: true,
+            ..NotarizeOptions::default()
+        };
+
+        let original = usecase
+            .execute(b"same content", "a.txt", "text/plain", submitter, 100)
+            .unwrap();
+        for file_name in ["b.txt", "c.txt", "a.txt"] {
+            let receipt = usecase
+                .execute_with_options(
+                    b"same content",
+                    file_name,
+                    "text/plain",
+                    "0x0000000000000000000000000000000000000456",
+                    101,
+                    &options,
+                )
+                .unwrap();
+            assert_eq!(receipt.document_id, original.document_id);
+            assert_eq!(receipt.proof, original.proof);
+        }
+        assert_eq!(repo.count_documents().unwrap(), 1);
+
+        let result = VerifyUseCase::new(&repo)
+            .execute(&original.content_hash)
+            .unwrap();
+        assert_eq!(result.document.unwrap().file_name, "a.txt");
+        assert_eq!(result.aliases, vec!["b.txt", "c.txt"]);
+
+        // New content is notarized normally, and aliases are still checked
+        assert!(usecase
+            .execute_with_options(b"fresh", "d.txt", "text/plain", submitter, 102, &options)
+            .is_ok());
+        let err = usecase
+            .execute_with_options(b"same content", " ", "text/plain", submitter, 103, &options)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::EmptyFilename)
+        ));
+    }
+
+    #[test]
+    fn test_preview_reports_duplicates_without_saving() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let preview = usecase.preview(b"draft").unwrap();
+        assert_eq!(preview.content_hash, Document::hash_content(b"draft"));
+        assert!(!preview.would_duplicate);
+        assert_eq!(repo.count_documents().unwrap(), 0);
+
+        usecase
+            .execute(
+                b"draft",
+                "draft.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+        assert!(usecase.preview(b"draft").unwrap().would_duplicate);
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_notarize_duplicate_matches_error_variant() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let submitter = "0x0000000000000000000000000000000000000123";
+
+        let first = usecase
+            .execute(b"same content", "a.txt", "text/plain", submitter, 100)
+            .unwrap();
+        let err = usecase
+            .execute(b"same content", "b.txt", "text/plain", submitter, 101)
+            .unwrap_err();
+
+        let NotaryError::Notarize(NotarizeError::DuplicateDocument { original }) = &err else {
+            panic!("expected a duplicate, got {:?}", err);
+        };
+        assert_eq!(original.submitted_by, submitter);
+        assert_eq!(original.document_id.as_str(), first.document_id);
+        assert_eq!(original.created_at, first.notarized_at);
+        assert_eq!(
+            err.to_string(),
+            "Document with this content hash already exists"
+        );
+    }
+
     #[test]
     fn test_notarize_empty_content_fails() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
 
-        let result = usecase.execute(b"", "file.txt", "text/plain", "0x123", 100);
+        let result = usecase.execute(
+            b"",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
 
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
@@ -61,10 +302,16 @@ mod notarize_tests {
 
     #[test]
     fn test_notarize_empty_filename_fails() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
 
-        let result = usecase.execute(b"content", "", "text/plain", "0x123", 100);
+        let result = usecase.execute(
+            b"content",
+            "",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
 
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
@@ -73,12 +320,92 @@ mod notarize_tests {
         );
     }
 
+    fn notarize_named(file_name: &str) -> Result<(), NotaryError> {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        usecase
+            .execute(
+                b"content",
+                file_name,
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_notarize_path_traversal_filename_fails() {
+        for name in ["../../etc/passwd", "..\\windows\\system32", "..", "a/b.txt"] {
+            let err = notarize_named(name).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    NotaryError::Notarize(NotarizeError::InvalidFilename(_))
+                ),
+                "{name:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_notarize_null_byte_filename_fails() {
+        let err = notarize_named("report.pdf\0.txt").unwrap_err();
+        assert!(err.to_string().contains("null byte"));
+    }
+
+    #[test]
+    fn test_notarize_control_character_filename_fails() {
+        let err = notarize_named("report\u{1b}[31m.pdf").unwrap_err();
+        assert!(err.to_string().contains("U+001B"));
+    }
+
+    #[test]
+    fn test_notarize_overlong_filename_fails() {
+        let longest = "é".repeat(MAX_FILENAME_LENGTH);
+        assert!(notarize_named(&longest).is_ok());
+
+        let err = notarize_named(&format!("{longest}x")).unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::InvalidFilename(_))
+        ));
+    }
+
+    #[test]
+    fn test_notarize_unicode_filename_succeeds() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let receipt = usecase
+            .execute(
+                b"contract",
+                "contrato ñandú 契約書 📄.pdf",
+                "application/pdf",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+
+        let stored = repo
+            .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+            .unwrap();
+        assert_eq!(stored.file_name, "contrato ñandú 契約書 📄.pdf");
+    }
+
     #[test]
     fn test_notarize_generates_correct_proof_format() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
 
-        let result = usecase.execute(b"test", "file.txt", "text/plain", "0x123", 999);
+        let result = usecase.execute(
+            b"test",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            999,
+        );
 
         assert!(result.is_ok());
         let receipt = result.unwrap();
@@ -89,6 +416,470 @@ mod notarize_tests {
         assert_eq!(parts.len(), 2);
         assert!(parts[0].starts_with("sha256:"));
     }
+
+    #[test]
+    fn test_notarize_without_expiry_never_expires() {
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let verify = VerifyUseCase::new(&repo);
+
+        let receipt = notarize
+            .execute_with_expiry(
+                b"permanent",
+                "file.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+                None,
+            )
+            .unwrap();
+
+        let result = verify.execute(&receipt.content_hash).unwrap();
+        assert!(result.exists);
+        assert!(!result.expired);
+        assert!(result.document.unwrap().expires_at.is_none());
+    }
+
+    #[test]
+    fn test_notarize_with_future_expiry_is_valid() {
+        let repo = MockDocumentRepository::new();
+        let clock = FixedClock(chrono::Utc::now().timestamp());
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default()).with_clock(&clock);
+        let verify = VerifyUseCase::new(&repo);
+
+        // 90-day attestation
+        let expires_at = clock.0 + 90 * 24 * 60 * 60;
+        let receipt = notarize
+            .execute_with_expiry(
+                b"attestation",
+                "file.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+                Some(expires_at),
+            )
+            .unwrap();
+
+        let result = verify.execute(&receipt.content_hash).unwrap();
+        assert!(result.exists);
+        assert!(!result.expired);
+        assert_eq!(result.document.unwrap().expires_at, Some(expires_at));
+    }
+
+    #[test]
+    fn test_notarize_with_past_expiry_fails() {
+        let repo = MockDocumentRepository::new();
+        let clock = FixedClock(1_700_000_000);
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default()).with_clock(&clock);
+
+        let expires_at = clock.0 - 60;
+        let result = usecase.execute_with_expiry(
+            b"stale",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+            Some(expires_at),
+        );
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("future"));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_notarize_with_expiration_blocks_estimates_expiry() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let options = NotarizeOptions {
+            expiration_blocks: Some(7200),
+            ..NotarizeOptions::default()
+        };
+        let receipt = usecase
+            .execute_with_options(
+                b"one day of blocks",
+                "file.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+                &options,
+            )
+            .unwrap();
+
+        let doc = repo
+            .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+            .unwrap();
+        assert_eq!(
+            doc.expires_at,
+            Some(doc.created_at + 7200 * SECONDS_PER_BLOCK)
+        );
+    }
+
+    #[test]
+    fn test_notarize_with_zero_expiration_blocks_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        // Expires at creation time, which already counts as expired
+        let options = NotarizeOptions {
+            expiration_blocks: Some(0),
+            ..NotarizeOptions::default()
+        };
+        let result = usecase.execute_with_options(
+            b"instant",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+            &options,
+        );
+
+        assert!(result.unwrap_err().to_string().contains("future"));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_notarize_with_both_expiry_forms_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let options = NotarizeOptions {
+            expires_at: Some(chrono::Utc::now().timestamp() + 3600),
+            expiration_blocks: Some(300),
+            ..NotarizeOptions::default()
+        };
+        let result = usecase.execute_with_options(
+            b"ambiguous",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+            &options,
+        );
+
+        assert!(result.unwrap_err().to_string().contains("not both"));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_notarize_with_overflowing_expiration_blocks_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let options = NotarizeOptions {
+            expiration_blocks: Some(u64::MAX),
+            ..NotarizeOptions::default()
+        };
+        let result = usecase.execute_with_options(
+            b"forever and a day",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+            &options,
+        );
+
+        assert!(result.unwrap_err().to_string().contains("out of range"));
+    }
+
+    #[test]
+    fn test_notarize_stores_trimmed_description() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        let options = NotarizeOptions {
+            description: Some("  Q3 2024 financial audit report ".to_string()),
+            ..NotarizeOptions::default()
+        };
+        let receipt = usecase
+            .execute_with_options(
+                b"audit report",
+                "audit.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+                &options,
+            )
+            .unwrap();
+
+        let doc = repo
+            .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+            .unwrap();
+        assert_eq!(
+            doc.description.as_deref(),
+            Some("Q3 2024 financial audit report")
+        );
+    }
+
+    #[test]
+    fn test_notarize_records_provided_timestamp() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let timestamp = 1_700_000_000;
+
+        let options = NotarizeOptions {
+            notarized_at: Some(timestamp),
+            ..NotarizeOptions::default()
+        };
+        for content in [&b"first document"[..], &b"second document"[..]] {
+            let receipt = usecase
+                .execute_with_options(
+                    content,
+                    "doc.txt",
+                    "text/plain",
+                    "0x0000000000000000000000000000000000000123",
+                    100,
+                    &options,
+                )
+                .unwrap();
+            assert_eq!(receipt.notarized_at, timestamp);
+
+            let doc = repo
+                .find_by_hash(&ContentHash::from(receipt.content_hash.as_str()))
+                .unwrap();
+            assert_eq!(doc.created_at, timestamp);
+        }
+    }
+
+    #[test]
+    fn test_notarize_with_too_long_description_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        // The limit counts characters, so a multi-byte description at the limit is accepted
+        let at_limit = NotarizeOptions {
+            description: Some("é".repeat(MAX_DESCRIPTION_LENGTH)),
+            ..NotarizeOptions::default()
+        };
+        let notarize = |content: &[u8], options: &NotarizeOptions| {
+            usecase.execute_with_options(
+                content,
+                "file.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+                options,
+            )
+        };
+        assert!(notarize(b"at the limit", &at_limit).is_ok());
+
+        let too_long = NotarizeOptions {
+            description: Some("x".repeat(MAX_DESCRIPTION_LENGTH + 1)),
+            ..NotarizeOptions::default()
+        };
+        let err = notarize(b"over the limit", &too_long).unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::DescriptionTooLong { length, limit })
+                if length == MAX_DESCRIPTION_LENGTH + 1 && limit == MAX_DESCRIPTION_LENGTH
+        ));
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_notarize_allowed_mime_type_succeeds() {
+        let repo = MockDocumentRepository::new();
+        let config = NotarizeConfig::from_allowlist(Some(&["image/png".to_string()]));
+        let usecase = NotarizeUseCase::new(&repo, config);
+
+        let result = usecase.execute(
+            b"png bytes",
+            "logo.png",
+            "image/png",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_notarize_disallowed_mime_type_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        for mime_type in ["application/x-executable", "text/html"] {
+            let result = usecase.execute(
+                b"payload",
+                "file.bin",
+                mime_type,
+                "0x0000000000000000000000000000000000000123",
+                100,
+            );
+            let err_msg = result.unwrap_err().to_string();
+            assert!(err_msg.contains("MIME type not allowed"));
+            assert!(err_msg.contains(mime_type));
+        }
+
+        // A custom allowlist replaces the default one
+        let config = NotarizeConfig::from_allowlist(Some(&["image/png".to_string()]));
+        let usecase = NotarizeUseCase::new(&repo, config);
+        assert!(usecase
+            .execute(
+                b"pdf",
+                "file.pdf",
+                "application/pdf",
+                "0x0000000000000000000000000000000000000123",
+                100
+            )
+            .is_err());
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_notarize_content_exactly_at_limit_succeeds() {
+        let repo = MockDocumentRepository::new();
+        let config = NotarizeConfig::default().with_max_content_bytes(Some(16));
+        let usecase = NotarizeUseCase::new(&repo, config);
+
+        let result = usecase.execute(
+            &[b'a'; 16],
+            "at-limit.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_notarize_content_one_over_limit_fails() {
+        let repo = MockDocumentRepository::new();
+        let config = NotarizeConfig::default().with_max_content_bytes(Some(16));
+        let usecase = NotarizeUseCase::new(&repo, config);
+
+        let result = usecase.execute(
+            &[b'a'; 17],
+            "too-big.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
+        let err_msg = result.unwrap_err().to_string();
+        assert!(err_msg.contains("Content size 17 bytes exceeds limit of 16 bytes"));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_notarize_zero_limit_always_fails() {
+        let repo = MockDocumentRepository::new();
+        let config = NotarizeConfig::default().with_max_content_bytes(Some(0));
+        let usecase = NotarizeUseCase::new(&repo, config);
+
+        let result = usecase.execute(
+            b"x",
+            "tiny.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+            100,
+        );
+        assert!(result.unwrap_err().to_string().contains("exceeds limit"));
+    }
+
+    #[test]
+    fn test_notarize_invalid_submitter_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        for submitter in [
+            "",
+            "not-an-address",
+            "0x123",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+        ] {
+            let result = usecase.execute(b"content", "file.txt", "text/plain", submitter, 100);
+            let err_msg = result.unwrap_err().to_string();
+            assert!(err_msg.contains("Invalid submitter address"));
+        }
+
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+    const BOB: &str = "0x00000000000000000000000000000000000000b0";
+
+    fn access_config(mode: AllowDenyMode, addresses: &[&str]) -> NotarizeConfig {
+        NotarizeConfig::default()
+            .with_submitter_access(Some(AllowDenyList::new(mode, addresses.iter())))
+    }
+
+    #[test]
+    fn test_allowlist_admits_only_listed_submitters() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(
+            &repo,
+            access_config(
+                AllowDenyMode::AllowOnly,
+                &["0x00000000000000000000000000000000000000A1"],
+            ),
+        );
+
+        usecase
+            .execute(b"alice", "a.txt", "text/plain", ALICE, 100)
+            .unwrap();
+        let err = usecase
+            .execute(b"bob", "b.txt", "text/plain", BOB, 100)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::Unauthorized(ref address)) if address == BOB
+        ));
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_denylist_rejects_listed_submitters() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NotarizeUseCase::new(&repo, access_config(AllowDenyMode::DenyAll, &[BOB]));
+
+        usecase
+            .execute(b"alice", "a.txt", "text/plain", ALICE, 100)
+            .unwrap();
+        let err = usecase
+            .execute(b"bob", "b.txt", "text/plain", BOB, 100)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Submitter {BOB} is not allowed to notarize")
+        );
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_empty_access_list_allows_everyone() {
+        let repo = MockDocumentRepository::new();
+
+        for mode in [AllowDenyMode::AllowOnly, AllowDenyMode::DenyAll] {
+            let usecase = NotarizeUseCase::new(&repo, access_config(mode, &[]));
+            let content = format!("{mode:?}");
+            usecase
+                .execute(content.as_bytes(), "a.txt", "text/plain", ALICE, 100)
+                .unwrap();
+        }
+
+        assert_eq!(repo.count_documents().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_denied_submitter_documents_still_verify() {
+        let repo = MockDocumentRepository::new();
+        let receipt = NotarizeUseCase::new(&repo, NotarizeConfig::default())
+            .execute(b"before denial", "a.txt", "text/plain", BOB, 100)
+            .unwrap();
+
+        let restricted = NotarizeUseCase::new(&repo, access_config(AllowDenyMode::DenyAll, &[BOB]));
+        assert!(restricted
+            .execute(b"after denial", "b.txt", "text/plain", BOB, 101)
+            .is_err());
+
+        // Access lists only gate notarization; anyone may still verify
+        let verification = VerifyUseCase::new(&repo)
+            .execute(&receipt.content_hash)
+            .unwrap();
+        assert!(verification.exists);
+    }
 }
 
 #[cfg(test)]
@@ -97,23 +888,162 @@ mod verify_tests {
 
     #[test]
     fn test_verify_existing_document_found() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let notarize = NotarizeUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let verify = VerifyUseCase::new(&repo);
 
         // First, notarize a document
-        let _receipt = notarize
-            .execute(b"content to verify", "test.txt", "text/plain", "0x123", 100)
+        let receipt = notarize
+            .execute(
+                b"content to verify",
+                "test.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+
+        // The verify use case shares the same repository and sees the write
+        let verification = verify.execute(&receipt.content_hash).unwrap();
+        assert!(verification.exists);
+        assert_eq!(verification.document.unwrap().id, receipt.document_id);
+    }
+
+    #[test]
+    fn test_verify_by_blake2b_when_enabled() {
+        let repo = MockDocumentRepository::new();
+        let notarize =
+            NotarizeUseCase::new(&repo, NotarizeConfig::default().with_enable_blake2b(true));
+        let verify = VerifyUseCase::new(&repo);
+
+        let receipt = notarize
+            .execute(
+                b"blake digest",
+                "blake.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+        let blake2b = compute_blake2b(b"blake digest");
+
+        let result = verify.execute_blake2b(&blake2b.to_uppercase()).unwrap();
+        assert!(result.exists);
+        assert_eq!(result.matched_hash, Some(MatchedHash::Blake2b256));
+        let document = result.document.unwrap();
+        assert_eq!(document.id, receipt.document_id);
+        assert_eq!(document.blake2b_hash, Some(blake2b));
+
+        assert!(!verify.execute_blake2b(&"a".repeat(64)).unwrap().exists);
+        assert!(matches!(
+            verify.execute_blake2b("not-a-hash"),
+            Err(NotaryError::Verify(VerifyError::InvalidHashFormat))
+        ));
+    }
+
+    #[test]
+    fn test_blake2b_not_recorded_by_default() {
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+
+        notarize
+            .execute(
+                b"sha only",
+                "sha.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
             .unwrap();
 
-        // Note: This test validates the structure works
-        // In practice, we'd use the same repository instance or a persistent DB
-        // For now, this confirms the use case compiles and runs
+        let result = VerifyUseCase::new(&repo)
+            .execute_blake2b(&compute_blake2b(b"sha only"))
+            .unwrap();
+        assert!(!result.exists);
+    }
+
+    #[test]
+    fn test_verify_accepts_either_digest() {
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let verify = VerifyUseCase::new(&repo);
+
+        let receipt = notarize
+            .execute(
+                b"dual digest",
+                "dual.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+        let keccak = Document::hash_content_keccak(b"dual digest");
+
+        let by_sha = verify.execute(&receipt.content_hash).unwrap();
+        assert_eq!(by_sha.matched_hash, Some(MatchedHash::Sha256));
+
+        let by_keccak = verify.execute(&keccak).unwrap();
+        assert!(by_keccak.exists);
+        assert_eq!(by_keccak.matched_hash, Some(MatchedHash::Keccak256));
+        let document = by_keccak.document.unwrap();
+        assert_eq!(document.id, receipt.document_id);
+        assert_eq!(document.content_hash, receipt.content_hash);
+        assert_eq!(document.content_hash_keccak, Some(keccak));
+        // The receipt stays keyed by SHA-256
+        assert_eq!(
+            by_keccak.receipt.unwrap().content_hash,
+            receipt.content_hash
+        );
+    }
+
+    #[test]
+    fn test_verification_report_includes_declared_and_detected_mime() {
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::allow_all());
+
+        // Mislabelled PDFs are recorded as declared, never rejected
+        let receipt = notarize
+            .execute(
+                b"%PDF-1.7 signed lease",
+                "lease.pdf",
+                "application/octet-stream",
+                "0x0000000000000000000000000000000000000123",
+                100,
+            )
+            .unwrap();
+
+        let verification = VerifyUseCase::new(&repo)
+            .execute(&receipt.content_hash)
+            .unwrap();
+        let report = serde_json::to_value(dapp::application::ReportResponse::from_verification(
+            &verification,
+        ))
+        .unwrap();
+        assert_eq!(report["document"]["mime_type"], "application/octet-stream");
+        assert_eq!(report["document"]["detected_mime_type"], "application/pdf");
+    }
+
+    #[test]
+    fn test_verify_by_id_reports_lookup_failure() {
+        let repo = MockDocumentRepository::new();
+        let usecase = VerifyUseCase::new(&repo);
+        let id = "550e8400-e29b-41d4-a716-446655440000";
+
+        repo.fail_next_find.set(true);
+        let err = usecase.execute_by_id(id).unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Verify(VerifyError::DatabaseError(_))
+        ));
+
+        // Only the next lookup fails
+        assert!(!usecase.execute_by_id(id).unwrap().exists);
     }
 
     #[test]
     fn test_verify_nonexistent_hash_not_found() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = VerifyUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = VerifyUseCase::new(&repo);
 
         let result =
             usecase.execute("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef");
@@ -127,8 +1057,8 @@ mod verify_tests {
 
     #[test]
     fn test_verify_invalid_hash_format_fails() {
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let usecase = VerifyUseCase::new(Box::new(repo));
+        let repo = MockDocumentRepository::new();
+        let usecase = VerifyUseCase::new(&repo);
 
         // Too short
         let result1 = usecase.execute("short");
@@ -147,14 +1077,1317 @@ mod verify_tests {
 
     #[test]
     fn test_verify_returns_complete_metadata() {
-        // We'll implement this with a shared repository pattern
-        // This test validates that all document fields are present
-        let repo = SqliteRepository::new_in_memory().unwrap();
-        let verify_usecase = VerifyUseCase::new(Box::new(repo));
-
-        // For now, just verify the structure exists
-        let result = verify_usecase
-            .execute("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef");
-        assert!(result.is_ok());
+        let repo = MockDocumentRepository::new();
+        let notarize_usecase = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let verify_usecase = VerifyUseCase::new(&repo);
+
+        let receipt = notarize_usecase
+            .execute(
+                b"metadata",
+                "report.pdf",
+                "application/pdf",
+                "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+                7,
+            )
+            .unwrap();
+
+        let result = verify_usecase.execute(&receipt.content_hash).unwrap();
+        let document = result.document.expect("Document should be present");
+
+        assert_eq!(document.content_hash, receipt.content_hash);
+        assert_eq!(document.file_name, "report.pdf");
+        assert_eq!(document.mime_type, "application/pdf");
+        assert_eq!(
+            document.submitted_by,
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+        );
+        assert!(result.receipt.is_some());
+    }
+
+    #[test]
+    fn test_verify_expired_document_still_exists() {
+        let repo = MockDocumentRepository::new();
+        let verify = VerifyUseCase::new(&repo);
+
+        // Stored while valid, expiry has since passed
+        let doc = Document::new(
+            b"expired",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        )
+        .with_expiry(Some(1));
+        repo.save_document(&doc).unwrap();
+
+        let result = verify.execute(doc.content_hash.as_str()).unwrap();
+        assert!(result.exists);
+        assert!(result.expired);
+    }
+
+    #[test]
+    fn test_verify_expiry_boundary_follows_clock() {
+        let repo = MockDocumentRepository::new();
+        let expires_at = 1_700_000_000;
+        let doc = Document::new(
+            b"expires on the second",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        )
+        .with_expiry(Some(expires_at));
+        repo.save_document(&doc).unwrap();
+        let hashes = vec![doc.content_hash.to_string()];
+
+        // Valid until the expiry second, expired from it on
+        for (now, expired) in [(expires_at - 1, false), (expires_at, true)] {
+            let clock = FixedClock(now);
+            let verify = VerifyUseCase::new(&repo).with_clock(&clock);
+
+            let result = verify.execute(doc.content_hash.as_str()).unwrap();
+            assert!(result.exists);
+            assert_eq!(result.expired, expired, "execute at {}", now);
+
+            let batch = verify.execute_batch(&hashes).unwrap();
+            let result = batch[0].as_ref().unwrap();
+            assert_eq!(result.expired, expired, "execute_batch at {}", now);
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_marks_malformed_hashes() {
+        let repo = MockDocumentRepository::new();
+        let notarize = NotarizeUseCase::new(&repo, NotarizeConfig::default());
+        let verify = VerifyUseCase::new(&repo);
+
+        let receipt = notarize
+            .execute(
+                b"batch",
+                "batch.txt",
+                "text/plain",
+                "0x0000000000000000000000000000000000000123",
+                1,
+            )
+            .unwrap();
+
+        let hashes = vec![
+            "short".to_string(),
+            receipt.content_hash.clone(),
+            "f".repeat(64),
+        ];
+        let results = verify.execute_batch(&hashes).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_err());
+        assert!(results[1].as_ref().unwrap().exists);
+        assert!(!results[2].as_ref().unwrap().exists);
+    }
+
+    #[test]
+    fn test_verify_batch_repeated_hashes_share_one_document() {
+        let repo = MockDocumentRepository::new();
+        let doc = Document::new(
+            b"repeated",
+            "file.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
+        repo.save_document(&doc).unwrap();
+
+        let hash = doc.content_hash.to_string();
+        let hashes = vec![hash.clone(), "0".repeat(64), hash];
+        let results = VerifyUseCase::new(&repo).execute_batch(&hashes).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(!results[1].as_ref().unwrap().exists);
+        for index in [0, 2] {
+            let document = results[index].as_ref().unwrap().document.as_ref().unwrap();
+            assert_eq!(*document, doc);
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_rejects_oversized_batch() {
+        let repo = MockDocumentRepository::new();
+        let verify = VerifyUseCase::new(&repo);
+
+        let hashes = vec!["a".repeat(64); MAX_BATCH_VERIFY_SIZE + 1];
+        let err_msg = verify.execute_batch(&hashes).unwrap_err().to_string();
+        assert!(err_msg.contains("exceeds limit"));
+    }
+
+    #[test]
+    fn test_verify_errors_match_error_variant() {
+        let repo = MockDocumentRepository::new();
+        let verify = VerifyUseCase::new(&repo);
+
+        let err = verify.execute("not-a-hash").unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Verify(VerifyError::InvalidHashFormat)
+        ));
+
+        let hashes = vec!["a".repeat(64); MAX_BATCH_VERIFY_SIZE + 1];
+        let err = verify.execute_batch(&hashes).unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Verify(VerifyError::BatchTooLarge { .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod nonce_tests {
+    use super::*;
+
+    #[test]
+    fn test_increasing_nonces_are_accepted() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NonceUseCase::new(&repo);
+
+        assert!(usecase.execute("0xabc", Some(1)).is_ok());
+        assert!(usecase.execute("0xabc", Some(2)).is_ok());
+        assert!(usecase.execute("0xabc", Some(10)).is_ok());
+        assert_eq!(repo.find_last_nonce("0xabc").unwrap(), Some(10));
+    }
+
+    #[test]
+    fn test_replayed_nonce_is_rejected() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NonceUseCase::new(&repo);
+
+        usecase.execute("0xabc", Some(5)).unwrap();
+
+        let err_msg = usecase.execute("0xabc", Some(5)).unwrap_err().to_string();
+        assert!(err_msg.contains("Nonce 5 must be greater than last used nonce 5"));
+    }
+
+    #[test]
+    fn test_out_of_order_nonce_is_rejected() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NonceUseCase::new(&repo);
+
+        usecase.execute("0xabc", Some(7)).unwrap();
+
+        assert!(usecase.execute("0xabc", Some(3)).is_err());
+        assert_eq!(repo.find_last_nonce("0xabc").unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_nonces_are_tracked_per_submitter() {
+        let repo = MockDocumentRepository::new();
+        let usecase = NonceUseCase::new(&repo);
+
+        usecase.execute("0xAbC", Some(4)).unwrap();
+
+        // Addresses compare case-insensitively; other submitters are independent
+        assert!(usecase.execute("0xabc", Some(4)).is_err());
+        assert!(usecase.execute("0xdef", Some(1)).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod verify_cache_tests {
+    use super::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn notarize(repo: &MockDocumentRepository, content: &[u8]) -> String {
+        NotarizeUseCase::new(repo, NotarizeConfig::default())
+            .execute(content, "cached.txt", "text/plain", SUBMITTER, 1)
+            .unwrap()
+            .content_hash
+    }
+
+    #[test]
+    fn test_found_results_are_cached_until_invalidated() {
+        let repo = MockDocumentRepository::new();
+        let cache = LruVerifyCache::new(VerifyUseCase::new(&repo), 8);
+        let hash = notarize(&repo, b"cached content");
+
+        assert!(cache.execute(hash.as_str()).unwrap().exists);
+        assert_eq!(cache.len(), 1);
+
+        // Served from the cache even though the row is gone
+        repo.delete_by_hash(&ContentHash::from(hash.as_str()))
+            .unwrap();
+        assert!(cache.execute(hash.as_str()).unwrap().exists);
+
+        cache.invalidate(&hash).unwrap();
+        assert!(!cache.execute(hash.as_str()).unwrap().exists);
+    }
+
+    #[test]
+    fn test_not_found_results_are_not_cached() {
+        let repo = MockDocumentRepository::new();
+        let cache = LruVerifyCache::new(VerifyUseCase::new(&repo), 8);
+        let hash = Document::new(b"later", "later.txt", "text/plain", SUBMITTER).content_hash;
+
+        assert!(!cache.execute(hash.as_str()).unwrap().exists);
+        assert!(cache.is_empty());
+
+        notarize(&repo, b"later");
+        assert!(cache.execute(hash.as_str()).unwrap().exists);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let repo = MockDocumentRepository::new();
+        let cache = LruVerifyCache::new(VerifyUseCase::new(&repo), 2);
+
+        let first = notarize(&repo, b"first");
+        let second = notarize(&repo, b"second");
+        let third = notarize(&repo, b"third");
+
+        cache.execute(&first).unwrap();
+        cache.execute(&second).unwrap();
+        cache.execute(&third).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // The evicted entry is looked up again from the database
+        repo.delete_by_hash(&ContentHash::from(first.as_str()))
+            .unwrap();
+        assert!(!cache.execute(&first).unwrap().exists);
+    }
+
+    #[test]
+    fn test_invalid_hash_is_still_rejected() {
+        let repo = MockDocumentRepository::new();
+        let cache = LruVerifyCache::new(VerifyUseCase::new(&repo), 0);
+
+        assert!(cache.execute("not-a-hash").is_err());
+    }
+}
+
+#[cfg(test)]
+mod fetch_content_tests {
+    use super::*;
+
+    const OWNER: &str = "0x0000000000000000000000000000000000000123";
+    const OTHER: &str = "0x0000000000000000000000000000000000000456";
+
+    fn notarize(repo: &MockDocumentRepository, config: NotarizeConfig) -> String {
+        NotarizeUseCase::new(repo, config)
+            .execute(b"original bytes", "archive.txt", "text/plain", OWNER, 1)
+            .unwrap()
+            .document_id
+    }
+
+    #[test]
+    fn test_store_content_persists_original_bytes() {
+        let repo = MockDocumentRepository::new();
+        let id = notarize(&repo, NotarizeConfig::default().with_store_content(true));
+
+        assert_eq!(
+            repo.get_content(&DocumentId::from(id.as_str())).unwrap(),
+            b"original bytes"
+        );
+    }
+
+    #[test]
+    fn test_owner_fetches_content() {
+        let repo = MockDocumentRepository::new();
+        let id = notarize(&repo, NotarizeConfig::default().with_store_content(true));
+
+        let stored = FetchContentUseCase::new(&repo).execute(&id, OWNER).unwrap();
+        assert_eq!(stored.content, b"original bytes");
+        assert_eq!(stored.file_name, "archive.txt");
+    }
+
+    #[test]
+    fn test_non_owner_fetch_denied() {
+        let repo = MockDocumentRepository::new();
+        let id = notarize(&repo, NotarizeConfig::default().with_store_content(true));
+
+        let err = FetchContentUseCase::new(&repo)
+            .execute(&id, OTHER)
+            .unwrap_err();
+        assert!(err.to_string().contains("owner"));
+    }
+
+    #[test]
+    fn test_hash_only_by_default() {
+        let repo = MockDocumentRepository::new();
+        let id = notarize(&repo, NotarizeConfig::default());
+
+        let err = FetchContentUseCase::new(&repo)
+            .execute(&id, OWNER)
+            .unwrap_err();
+        assert!(err.to_string().contains("not retained"));
+    }
+}
+
+#[cfg(test)]
+mod tag_tests {
+    use super::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn notarize_tagged(
+        repo: &MockDocumentRepository,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String, NotaryError> {
+        let options = NotarizeOptions {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..NotarizeOptions::default()
+        };
+
+        NotarizeUseCase::new(repo, NotarizeConfig::default())
+            .execute_with_options(
+                content,
+                "tagged.pdf",
+                "application/pdf",
+                SUBMITTER,
+                1,
+                &options,
+            )
+            .map(|receipt| receipt.document_id)
+    }
+
+    #[test]
+    fn test_multiple_tags_are_assigned() {
+        let repo = MockDocumentRepository::new();
+        let id = notarize_tagged(&repo, b"tagged", &["Contract", "acme", " contract "]).unwrap();
+
+        let search = SearchUseCase::new(&repo);
+        for tag in ["contract", "ACME"] {
+            let found = search.find_by_tag(tag, None, 0).unwrap();
+            assert_eq!(found.len(), 1, "tag {}", tag);
+            assert_eq!(found[0].id, id);
+        }
+        assert!(search.find_by_tag("invoice", None, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verification_includes_deduplicated_tags() {
+        let repo = MockDocumentRepository::new();
+        notarize_tagged(&repo, b"lease", &["Contract ", "contract", "legal"]).unwrap();
+        let hash = Document::new(b"lease", "", "", "").content_hash;
+
+        let result = VerifyUseCase::new(&repo).execute(hash.as_str()).unwrap();
+        assert_eq!(result.tags, vec!["contract", "legal"]);
+        assert!(repo
+            .find_tags(&DocumentId::from("unknown"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tag_retrieval_pages_newest_first() {
+        let repo = MockDocumentRepository::new();
+        for i in 0..5 {
+            let content = format!("invoice {}", i);
+            notarize_tagged(&repo, content.as_bytes(), &["invoice"]).unwrap();
+        }
+        notarize_tagged(&repo, b"certificate", &["certificate"]).unwrap();
+
+        let search = SearchUseCase::new(&repo);
+        let first_page = search.find_by_tag("invoice", Some(3), 0).unwrap();
+        let second_page = search.find_by_tag("invoice", Some(3), 3).unwrap();
+        assert_eq!(first_page.len(), 3);
+        assert_eq!(second_page.len(), 2);
+        assert!(first_page
+            .iter()
+            .all(|doc| second_page.iter().all(|other| other.id != doc.id)));
+    }
+
+    #[test]
+    fn test_invalid_tags_reject_notarization() {
+        let repo = MockDocumentRepository::new();
+
+        let err = notarize_tagged(&repo, b"bad tag", &["has space"]).unwrap_err();
+        assert!(err.to_string().contains("Invalid tag"));
+
+        let too_many: Vec<String> = (0..11).map(|i| format!("tag{}", i)).collect();
+        let too_many: Vec<&str> = too_many.iter().map(String::as_str).collect();
+        let err = notarize_tagged(&repo, b"many tags", &too_many).unwrap_err();
+        assert!(err.to_string().contains("exceed limit"));
+
+        // Nothing was stored for the rejected documents
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_empty_tag_lookup_rejected() {
+        let repo = MockDocumentRepository::new();
+        assert!(SearchUseCase::new(&repo)
+            .find_by_tag("  ", None, 0)
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod version_tests {
+    use super::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn notarize_version(
+        repo: &MockDocumentRepository,
+        content: &[u8],
+        supersedes: Option<&str>,
+    ) -> Result<String, NotaryError> {
+        let options = NotarizeOptions {
+            supersedes: supersedes.map(str::to_string),
+            ..NotarizeOptions::default()
+        };
+
+        NotarizeUseCase::new(repo, NotarizeConfig::default())
+            .execute_with_options(
+                content,
+                "contract.pdf",
+                "application/pdf",
+                SUBMITTER,
+                1,
+                &options,
+            )
+            .map(|receipt| receipt.content_hash)
+    }
+
+    #[test]
+    fn test_linear_version_chain() {
+        let repo = MockDocumentRepository::new();
+        let v1 = notarize_version(&repo, b"contract v1", None).unwrap();
+        let v2 = notarize_version(&repo, b"contract v2", Some(&v1)).unwrap();
+        let v3 = notarize_version(&repo, b"contract v3", Some(&v2)).unwrap();
+
+        let versions = repo.find_versions(&ContentHash::from(v1.as_str())).unwrap();
+        let hashes: Vec<&str> = versions.iter().map(|d| d.content_hash.as_str()).collect();
+        assert_eq!(hashes, vec![v1.as_str(), v2.as_str(), v3.as_str()]);
+        assert_eq!(versions[2].supersedes.as_deref(), Some(v2.as_str()));
+
+        // A chain read from the middle starts at that version
+        assert_eq!(
+            repo.find_versions(&ContentHash::from(v2.as_str()))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_superseding_unknown_document_fails() {
+        let repo = MockDocumentRepository::new();
+        let missing = "a".repeat(64);
+
+        let result = notarize_version(&repo, b"orphan", Some(&missing));
+
+        assert!(result.unwrap_err().to_string().contains("not found"));
+        assert_eq!(repo.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_superseded_document_cannot_be_superseded_again() {
+        let repo = MockDocumentRepository::new();
+        let v1 = notarize_version(&repo, b"contract v1", None).unwrap();
+        notarize_version(&repo, b"contract v2", Some(&v1)).unwrap();
+
+        // A second amendment of v1 would fork the chain
+        let result = notarize_version(&repo, b"contract v2 bis", Some(&v1));
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("already superseded"));
+        assert_eq!(repo.count_documents().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_document_cannot_supersede_itself() {
+        let repo = MockDocumentRepository::new();
+        let v1 = notarize_version(&repo, b"contract v1", None).unwrap();
+
+        // Same content hashes to v1, so the request is a duplicate rather than a cycle
+        let result = notarize_version(&repo, b"contract v1", Some(&v1));
+
+        assert!(result.is_err());
+        assert!(
+            repo.find_versions(&ContentHash::from(v1.as_str())).unwrap()[0]
+                .supersedes
+                .is_none()
+        );
+    }
+}
+
+#[cfg(test)]
+mod chunked_upload_tests {
+    use super::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+    const OTHER: &str = "0x0000000000000000000000000000000000000456";
+
+    fn start<'a>(repo: &'a MockDocumentRepository, upload_id: &str) -> ChunkedUploadUseCase<'a> {
+        let usecase = ChunkedUploadUseCase::new(repo, NotarizeConfig::default());
+        usecase
+            .start(upload_id, "scan.pdf", "application/pdf", SUBMITTER, 100)
+            .unwrap();
+        usecase
+    }
+
+    #[test]
+    fn test_assembled_hash_matches_single_shot() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+
+        for (index, chunk) in [b"first ".as_slice(), b"second ", b"third"]
+            .iter()
+            .enumerate()
+        {
+            let session = usecase
+                .append("scan-1", index as u32, chunk, SUBMITTER)
+                .unwrap();
+            assert_eq!(session.chunk_count, index as u32 + 1);
+        }
+
+        let receipt = usecase
+            .finish("scan-1", SUBMITTER, 7, &NotarizeOptions::default())
+            .unwrap();
+
+        let expected = Document::new(b"first second third", "", "", "").content_hash;
+        assert_eq!(expected, receipt.content_hash);
+        assert_eq!(receipt.block_number, 7);
+        assert!(repo.find_upload("scan-1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_gap_rejected() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+        usecase.append("scan-1", 0, b"first", SUBMITTER).unwrap();
+
+        let result = usecase.append("scan-1", 2, b"third", SUBMITTER);
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Expected chunk 1, got 2"));
+        assert_eq!(repo.find_upload("scan-1").unwrap().unwrap().chunk_count, 1);
+    }
+
+    #[test]
+    fn test_duplicate_chunk_rejected() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+        usecase.append("scan-1", 0, b"first", SUBMITTER).unwrap();
+
+        let result = usecase.append("scan-1", 0, b"first again", SUBMITTER);
+
+        assert!(result.unwrap_err().to_string().contains("already received"));
+        assert_eq!(repo.upload_content("scan-1").unwrap(), b"first");
+    }
+
+    #[test]
+    fn test_only_starter_can_continue_upload() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+
+        let result = usecase.append("scan-1", 0, b"hijack", OTHER);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Only the submitter"));
+
+        let result = usecase.finish("scan-1", OTHER, 1, &NotarizeOptions::default());
+        assert!(result.is_err());
+        assert!(repo.find_upload("scan-1").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_upload_id_cannot_be_reused_while_open() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+
+        let result = usecase.start("scan-1", "other.pdf", "application/pdf", SUBMITTER, 100);
+
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_finish_without_chunks_fails() {
+        let repo = MockDocumentRepository::new();
+        let usecase = start(&repo, "scan-1");
+
+        let result = usecase.finish("scan-1", SUBMITTER, 1, &NotarizeOptions::default());
+
+        assert!(result.unwrap_err().to_string().contains("no chunks"));
+    }
+
+    #[test]
+    fn test_assembled_size_is_limited() {
+        let repo = MockDocumentRepository::new();
+        let config = NotarizeConfig::default().with_max_content_bytes(Some(8));
+        let usecase = ChunkedUploadUseCase::new(&repo, config);
+        usecase
+            .start("scan-1", "scan.pdf", "application/pdf", SUBMITTER, 100)
+            .unwrap();
+        usecase.append("scan-1", 0, b"12345", SUBMITTER).unwrap();
+
+        let result = usecase.append("scan-1", 1, b"6789", SUBMITTER);
+
+        assert!(result.unwrap_err().to_string().contains("exceeds limit"));
+    }
+
+    #[test]
+    fn test_finish_of_duplicate_document_consumes_upload() {
+        let repo = MockDocumentRepository::new();
+        NotarizeUseCase::new(&repo, NotarizeConfig::default())
+            .execute(b"same bytes", "scan.pdf", "application/pdf", SUBMITTER, 1)
+            .unwrap();
+
+        let usecase = start(&repo, "scan-1");
+        usecase.append("scan-1", 0, b"same ", SUBMITTER).unwrap();
+        usecase.append("scan-1", 1, b"bytes", SUBMITTER).unwrap();
+
+        let result = usecase.finish("scan-1", SUBMITTER, 2, &NotarizeOptions::default());
+
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+        assert!(repo.find_upload("scan-1").unwrap().is_none());
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+}
+
+#[cfg(test)]
+mod cosign_tests {
+    use super::*;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+    const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+    const BOB: &str = "0x00000000000000000000000000000000000000b2";
+    const MALLORY: &str = "0x00000000000000000000000000000000000000c3";
+
+    /// Block timestamp of the inputs in these tests
+    const NOW: i64 = 1_700_000_000;
+
+    fn usecase(repo: &MockDocumentRepository, window_secs: u64) -> CosignUseCase<'_> {
+        CosignUseCase::new(repo, NotarizeConfig::default(), window_secs)
+    }
+
+    fn request(usecase: &CosignUseCase, content: &[u8], cosigners: &[&str]) -> String {
+        let cosigners: Vec<String> = cosigners.iter().map(|c| c.to_string()).collect();
+        let (pending, _) = usecase
+            .request(
+                content,
+                "agreement.pdf",
+                "application/pdf",
+                SUBMITTER,
+                &NotarizeOptions::default(),
+                &cosigners,
+                NOW,
+            )
+            .unwrap();
+        pending.content_hash
+    }
+
+    #[test]
+    fn test_two_of_two_cosigners_notarize_document() {
+        let repo = MockDocumentRepository::new();
+        let usecase = usecase(&repo, DEFAULT_COSIGN_WINDOW_SECS);
+        let hash = request(&usecase, b"joint agreement", &[ALICE, BOB]);
+
+        // Nothing is notarized until every cosigner approved
+        assert!(repo
+            .find_by_hash(&ContentHash::from(hash.as_str()))
+            .is_err());
+
+        match usecase.cosign(&hash, ALICE, "sig-a", 10, NOW).unwrap() {
+            CosignOutcome::Pending { cosigners, .. } => {
+                assert_eq!(cosigners.iter().filter(|c| c.has_signed()).count(), 1);
+            }
+            other => panic!("expected pending, got {:?}", other),
+        }
+        assert!(repo
+            .find_by_hash(&ContentHash::from(hash.as_str()))
+            .is_err());
+
+        match usecase.cosign(&hash, BOB, "sig-b", 11, NOW).unwrap() {
+            CosignOutcome::Completed {
+                receipt, cosigners, ..
+            } => {
+                assert_eq!(receipt.content_hash, hash);
+                assert_eq!(receipt.block_number, 11);
+                let signatures: Vec<Option<&str>> =
+                    cosigners.iter().map(|c| c.signature.as_deref()).collect();
+                assert_eq!(signatures, vec![Some("sig-a"), Some("sig-b")]);
+            }
+            other => panic!("expected completed, got {:?}", other),
+        }
+
+        let doc = repo
+            .find_by_hash(&ContentHash::from(hash.as_str()))
+            .unwrap();
+        assert_eq!(doc.submitted_by, SUBMITTER);
+        assert!(repo.find_pending_notarization(&hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_partial_cosigning_times_out() {
+        let repo = MockDocumentRepository::new();
+        // A zero window closes as soon as the request is stored
+        let usecase = usecase(&repo, 0);
+        let hash = request(&usecase, b"stalled agreement", &[ALICE, BOB]);
+
+        let result = usecase.cosign(&hash, ALICE, "sig-a", 10, NOW);
+
+        assert!(result.unwrap_err().to_string().contains("window closed"));
+        assert!(repo
+            .find_by_hash(&ContentHash::from(hash.as_str()))
+            .is_err());
+        assert!(repo.find_pending_notarization(&hash).unwrap().is_none());
+        assert!(repo.find_cosigners(&hash).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expired_request_can_be_resubmitted() {
+        let repo = MockDocumentRepository::new();
+        request(&usecase(&repo, 0), b"retry agreement", &[ALICE]);
+
+        let usecase = usecase(&repo, DEFAULT_COSIGN_WINDOW_SECS);
+        let hash = request(&usecase, b"retry agreement", &[ALICE]);
+
+        assert!(matches!(
+            usecase.cosign(&hash, ALICE, "sig-a", 1, NOW).unwrap(),
+            CosignOutcome::Completed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_only_required_cosigners_can_sign_once() {
+        let repo = MockDocumentRepository::new();
+        let usecase = usecase(&repo, DEFAULT_COSIGN_WINDOW_SECS);
+        let hash = request(&usecase, b"guarded agreement", &[ALICE, BOB]);
+
+        let result = usecase.cosign(&hash, MALLORY, "sig-m", 1, NOW);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("not a required cosigner"));
+
+        usecase.cosign(&hash, ALICE, "sig-a", 1, NOW).unwrap();
+        let result = usecase.cosign(&hash, ALICE, "sig-a", 2, NOW);
+        assert!(result.unwrap_err().to_string().contains("already cosigned"));
+
+        assert!(repo
+            .find_by_hash(&ContentHash::from(hash.as_str()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_cosigner_addresses_are_normalized() {
+        let repo = MockDocumentRepository::new();
+        let usecase = usecase(&repo, DEFAULT_COSIGN_WINDOW_SECS);
+        let upper = ALICE.to_uppercase().replacen("0X", "0x", 1);
+        let hash = request(&usecase, b"normalized agreement", &[ALICE, &upper]);
+
+        assert_eq!(repo.find_cosigners(&hash).unwrap().len(), 1);
+        assert!(matches!(
+            usecase.cosign(&hash, &upper, "sig-a", 1, NOW).unwrap(),
+            CosignOutcome::Completed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let repo = MockDocumentRepository::new();
+        let usecase = usecase(&repo, DEFAULT_COSIGN_WINDOW_SECS);
+        let attempt = |content: &[u8], cosigners: &[&str]| {
+            let cosigners: Vec<String> = cosigners.iter().map(|c| c.to_string()).collect();
+            usecase
+                .request(
+                    content,
+                    "agreement.pdf",
+                    "application/pdf",
+                    SUBMITTER,
+                    &NotarizeOptions::default(),
+                    &cosigners,
+                    NOW,
+                )
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(attempt(b"no cosigners", &[]).contains("At least one cosigner"));
+        assert!(attempt(b"bad cosigner", &["0xnope"]).contains("Invalid cosigner"));
+        assert!(attempt(b"", &[ALICE]).contains("Content cannot be empty"));
+
+        request(&usecase, b"pending agreement", &[ALICE]);
+        assert!(attempt(b"pending agreement", &[BOB]).contains("already awaiting"));
+    }
+}
+
+#[cfg(test)]
+mod idempotency_tests {
+    use super::*;
+
+    const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+    const BOB: &str = "0x00000000000000000000000000000000000000b2";
+
+    fn notarize_with_key(
+        repo: &MockDocumentRepository,
+        content: &[u8],
+        submitter: &str,
+        key: Option<&str>,
+        block_number: u64,
+    ) -> Result<dapp::domain::NotarizationReceipt, NotaryError> {
+        let options = NotarizeOptions {
+            idempotency_key: key.map(str::to_string),
+            ..NotarizeOptions::default()
+        };
+
+        NotarizeUseCase::new(repo, NotarizeConfig::default()).execute_with_options(
+            content,
+            "invoice.pdf",
+            "application/pdf",
+            submitter,
+            block_number,
+            &options,
+        )
+    }
+
+    #[test]
+    fn test_retry_returns_original_receipt() {
+        let repo = MockDocumentRepository::new();
+        let first = notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 10).unwrap();
+
+        // The retry lands in a later block but must report the original one
+        let retry = notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 11).unwrap();
+
+        assert_eq!(retry.document_id, first.document_id);
+        assert_eq!(retry.block_number, 10);
+        assert_eq!(retry.proof, first.proof);
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_without_key_still_rejected() {
+        let repo = MockDocumentRepository::new();
+        notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 10).unwrap();
+
+        let result = notarize_with_key(&repo, b"invoice 7", ALICE, None, 11);
+
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_other_submitter_with_same_key_is_rejected_as_duplicate() {
+        let repo = MockDocumentRepository::new();
+        notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 10).unwrap();
+
+        let result = notarize_with_key(&repo, b"invoice 7", BOB, Some("retry-1"), 11);
+
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_key_reused_for_other_content_is_rejected() {
+        let repo = MockDocumentRepository::new();
+        notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 10).unwrap();
+
+        let result = notarize_with_key(&repo, b"invoice 8", ALICE, Some("retry-1"), 11);
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("already used for a different document"));
+        assert_eq!(repo.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_purge_forgets_key() {
+        let repo = MockDocumentRepository::new();
+        let first = notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 10).unwrap();
+
+        repo.delete_by_hash(&ContentHash::from(first.content_hash.as_str()))
+            .unwrap();
+
+        assert!(repo
+            .find_idempotency_key("retry-1", ALICE)
+            .unwrap()
+            .is_none());
+        let again = notarize_with_key(&repo, b"invoice 7", ALICE, Some("retry-1"), 12).unwrap();
+        assert_ne!(again.document_id, first.document_id);
+    }
+}
+
+#[cfg(test)]
+mod epoch_tests {
+    use super::*;
+    use dapp::domain::verify_merkle_proof;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn notarize(repo: &MockDocumentRepository, content: &[u8]) -> String {
+        NotarizeUseCase::new(repo, NotarizeConfig::default())
+            .execute(content, "entry.txt", "text/plain", SUBMITTER, 1)
+            .unwrap()
+            .content_hash
+    }
+
+    #[test]
+    fn test_first_epoch_only_opens() {
+        let repo = MockDocumentRepository::new();
+        let usecase = EpochUseCase::new(&repo);
+
+        assert!(usecase.observe(3).unwrap().is_none());
+        assert_eq!(repo.find_open_epoch().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_epochs_commit_only_their_own_documents() {
+        let repo = MockDocumentRepository::new();
+        let usecase = EpochUseCase::new(&repo);
+
+        usecase.observe(0).unwrap();
+        let first: Vec<String> = (0..5).map(|i| notarize(&repo, &[i])).collect();
+        let sealed = usecase.observe(1).unwrap().unwrap();
+        assert_eq!(sealed.epoch, 0);
+        assert_eq!(sealed.leaf_count, 5);
+
+        let second = notarize(&repo, b"epoch one");
+        let next = usecase.observe(2).unwrap().unwrap();
+        assert_eq!(next.leaf_count, 1);
+
+        for hash in &first {
+            let proof = usecase.inclusion_proof(hash, 0).unwrap();
+            assert_eq!(proof.root, sealed.root);
+            assert!(verify_merkle_proof(&proof.leaf, &proof.path, &proof.root));
+        }
+        let proof = usecase.inclusion_proof(&second, 1).unwrap();
+        assert!(verify_merkle_proof(&proof.leaf, &proof.path, &next.root));
+        assert!(usecase.inclusion_proof(&second, 0).is_err());
+    }
+
+    #[test]
+    fn test_same_epoch_and_empty_epochs_seal_nothing() {
+        let repo = MockDocumentRepository::new();
+        let usecase = EpochUseCase::new(&repo);
+
+        usecase.observe(0).unwrap();
+        assert!(usecase.observe(0).unwrap().is_none());
+        assert!(usecase.observe(1).unwrap().is_none());
+        assert!(repo.find_epoch_root(0).unwrap().is_none());
+        assert_eq!(repo.find_open_epoch().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_proof_survives_purge() {
+        let repo = MockDocumentRepository::new();
+        let usecase = EpochUseCase::new(&repo);
+
+        usecase.observe(0).unwrap();
+        let kept = notarize(&repo, b"kept");
+        let purged = notarize(&repo, b"purged");
+        let sealed = usecase.observe(1).unwrap().unwrap();
+
+        repo.delete_by_hash(&ContentHash::from(purged.as_str()))
+            .unwrap();
+
+        for hash in [&kept, &purged] {
+            let proof = usecase.inclusion_proof(hash, 0).unwrap();
+            assert!(verify_merkle_proof(&proof.leaf, &proof.path, &sealed.root));
+        }
+    }
+}
+
+#[cfg(test)]
+mod list_documents_tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn save(repo: &MockDocumentRepository, content: &str, created_at: i64) -> Document {
+        let mut doc = Document::new(
+            content.as_bytes(),
+            "page.txt",
+            "text/plain",
+            "0x0000000000000000000000000000000000000123",
+        );
+        doc.created_at = created_at;
+        repo.save_document(&doc).unwrap();
+        doc
+    }
+
+    #[test]
+    fn test_cursor_pages_cover_every_document_once_despite_inserts() {
+        let repo = MockDocumentRepository::new();
+        // Groups of five documents share a timestamp
+        let original: HashSet<DocumentId> = (0..500)
+            .map(|i| save(&repo, &format!("doc {}", i), 1_000 + i / 5).id)
+            .collect();
+
+        let usecase = SearchUseCase::new(&repo);
+        let mut seen = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = 0;
+        loop {
+            let page = usecase.list_documents(cursor.as_deref(), Some(37)).unwrap();
+            seen.extend(page.documents.iter().map(|doc| doc.id.clone()));
+            pages += 1;
+
+            // Newer documents arriving mid-iteration sort before the cursor and never appear
+            if pages == 3 {
+                for i in 0..10 {
+                    save(&repo, &format!("late {}", i), 5_000 + i);
+                }
+            }
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next.encode()),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 14);
+        assert_eq!(seen.len(), 500);
+        assert_eq!(seen.iter().cloned().collect::<HashSet<_>>(), original);
+    }
+
+    #[test]
+    fn test_last_full_page_has_no_next_cursor() {
+        let repo = MockDocumentRepository::new();
+        for i in 0..4 {
+            save(&repo, &format!("doc {}", i), 100 + i);
+        }
+
+        let page = SearchUseCase::new(&repo)
+            .list_documents(None, Some(4))
+            .unwrap();
+        assert_eq!(page.documents.len(), 4);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = DocumentCursor {
+            created_at: 1_735_862_400,
+            id: DocumentId::from("550e8400-e29b-41d4-a716-446655440000"),
+        };
+        assert_eq!(DocumentCursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_invalid_cursor_rejected() {
+        let repo = MockDocumentRepository::new();
+        let usecase = SearchUseCase::new(&repo);
+
+        let not_uuid = base64::Engine::encode(
+            &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+            "100:not-a-uuid",
+        );
+        for cursor in ["!!!", "bm8tY29sb24", not_uuid.as_str()] {
+            let err = usecase.list_documents(Some(cursor), None).unwrap_err();
+            assert_eq!(err.to_string(), "Invalid cursor");
+        }
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+
+    const SENDER: &str = "0x0000000000000000000000000000000000000123";
+
+    #[test]
+    fn test_allows_exactly_max_per_block() {
+        let limiter = RateLimiter::new(3);
+        for _ in 0..3 {
+            assert!(limiter.allow(SENDER, 10));
+        }
+    }
+
+    #[test]
+    fn test_rejects_over_limit() {
+        let limiter = RateLimiter::new(3);
+        for _ in 0..3 {
+            limiter.check(SENDER, 10).unwrap();
+        }
+
+        assert_eq!(
+            limiter.check(SENDER, 10),
+            Err(RateLimitError::Exceeded {
+                sender: SENDER.to_string(),
+                max_per_block: 3,
+                block_number: 10,
+            })
+        );
+        // Rejections are not recorded, so the sender stays at the limit
+        assert!(!limiter.allow(SENDER, 10));
+    }
+
+    #[test]
+    fn test_window_slides_with_block_number() {
+        let limiter = RateLimiter::new(1);
+        assert!(limiter.allow(SENDER, 10));
+        assert!(!limiter.allow(SENDER, 10));
+        assert!(limiter.allow(SENDER, 11));
+    }
+
+    #[test]
+    fn test_senders_are_limited_independently() {
+        let limiter = RateLimiter::new(1);
+        assert!(limiter.allow(SENDER, 10));
+        assert!(limiter.allow("0x0000000000000000000000000000000000000456", 10));
+    }
+
+    #[test]
+    fn test_clones_share_counters() {
+        let limiter = RateLimiter::new(1);
+        let clone = limiter.clone();
+        assert!(limiter.allow(SENDER, 10));
+        assert!(!clone.allow(SENDER, 10));
+    }
+}
+
+#[cfg(test)]
+mod balance_tests {
+    use super::*;
+
+    const OWNER: &str = "0x0000000000000000000000000000000000000123";
+    const TOKEN: &str = "0x00000000000000000000000000000000000000f0";
+
+    fn deposit(amount: u128) -> Deposit {
+        Deposit {
+            token: EthAddress::parse(TOKEN).unwrap(),
+            depositor: EthAddress::parse(OWNER).unwrap(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_deposits_accumulate() {
+        let repo = MockDocumentRepository::new();
+        let usecase = BalanceUseCase::new(&repo);
+
+        assert_eq!(usecase.deposit(&deposit(40)).unwrap(), 40);
+        assert_eq!(usecase.deposit(&deposit(2)).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_charge_debits_covered_fee() {
+        let repo = MockDocumentRepository::new();
+        let usecase = BalanceUseCase::new(&repo);
+        usecase.deposit(&deposit(10)).unwrap();
+
+        usecase.ensure_funds(OWNER, TOKEN, 10).unwrap();
+        usecase.charge(OWNER, TOKEN, 10).unwrap();
+
+        assert_eq!(repo.find_balance(OWNER, TOKEN).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_insufficient_balance_reports_shortfall() {
+        let repo = MockDocumentRepository::new();
+        let usecase = BalanceUseCase::new(&repo);
+        usecase.deposit(&deposit(3)).unwrap();
+
+        for result in [
+            usecase.ensure_funds(OWNER, TOKEN, 5),
+            usecase.charge(OWNER, TOKEN, 5),
+        ] {
+            assert!(matches!(
+                result,
+                Err(BalanceError::Insufficient {
+                    required: 5,
+                    available: 3,
+                    ..
+                })
+            ));
+        }
+
+        // A refused charge leaves the balance untouched
+        assert_eq!(repo.find_balance(OWNER, TOKEN).unwrap(), 3);
+    }
+}
+
+#[cfg(test)]
+mod notary_facade_tests {
+    use super::*;
+    use dapp::application::{Config, RepositoryKind};
+    use dapp::Notary;
+
+    const SUBMITTER: &str = "0x0000000000000000000000000000000000000123";
+
+    fn in_memory_notary() -> Notary {
+        let repo = MockDocumentRepository::new();
+        Notary::with_repository(Box::new(repo), Config::default())
+    }
+
+    #[test]
+    fn test_notarize_then_verify_through_facade() {
+        let notary = in_memory_notary();
+
+        let receipt = notary
+            .notarize(b"facade content", "facade.txt", "text/plain", SUBMITTER, 7)
+            .unwrap();
+
+        let by_hash = notary.verify(&receipt.content_hash).unwrap();
+        assert!(by_hash.exists);
+        assert_eq!(by_hash.document.unwrap().id, receipt.document_id);
+
+        let by_id = notary.verify_by_id(&receipt.document_id).unwrap();
+        assert!(by_id.exists);
+
+        let page = notary.list(None, None).unwrap();
+        assert_eq!(page.documents.len(), 1);
+        assert_eq!(
+            page.documents[0].content_hash.as_str(),
+            receipt.content_hash
+        );
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_facade_applies_configured_policy() {
+        let config = Config {
+            max_content_bytes: 4,
+            ..Config::default()
+        };
+        let notary = Notary::with_repository(Box::new(MockDocumentRepository::new()), config);
+
+        let err = notary
+            .notarize(b"too long", "a.txt", "text/plain", SUBMITTER, 1)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NotaryError::Notarize(NotarizeError::ContentTooLarge { .. })
+        ));
+        assert!(!notary.verify(&"a".repeat(64)).unwrap().exists);
+    }
+
+    #[test]
+    fn test_facade_lists_pages() {
+        let notary = in_memory_notary();
+        for i in 0..3 {
+            let content = format!("document {i}");
+            notary
+                .notarize(content.as_bytes(), "doc.txt", "text/plain", SUBMITTER, i)
+                .unwrap();
+        }
+
+        let first = notary.list(None, Some(2)).unwrap();
+        assert_eq!(first.documents.len(), 2);
+        let cursor = first.next_cursor.unwrap().encode();
+
+        let second = notary.list(Some(&cursor), Some(2)).unwrap();
+        assert_eq!(second.documents.len(), 1);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_new_opens_configured_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("notary.db");
+        let config = Config {
+            repository: RepositoryKind::Sqlite(db_path.to_str().unwrap().to_string()),
+            ..Config::default()
+        };
+
+        let receipt = Notary::new(config.clone())
+            .unwrap()
+            .notarize(b"persisted", "p.txt", "text/plain", SUBMITTER, 1)
+            .unwrap();
+
+        // A second facade over the same file sees the earlier notarization
+        let reopened = Notary::new(config).unwrap();
+        assert!(reopened.verify(&receipt.content_hash).unwrap().exists);
     }
 }